@@ -0,0 +1,64 @@
+use arachne::graph::{dag::Dag, filter::filter_by_author, layout::compute_layout};
+use arachne::test_utils::{synthetic_repo, SyntheticSpec};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+const SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+
+fn shapes(commits: usize) -> [(&'static str, SyntheticSpec); 3] {
+    [
+        ("linear", SyntheticSpec::linear(commits)),
+        ("fork_fan", SyntheticSpec::fork_fan(commits)),
+        ("octopus", SyntheticSpec::octopus(commits, 4)),
+    ]
+}
+
+fn trunks() -> Vec<String> {
+    vec!["main".to_string()]
+}
+
+fn bench_dag(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dag_from_repo_data");
+    group.sample_size(10);
+    for commits in SIZES {
+        for (shape, spec) in shapes(commits) {
+            let data = synthetic_repo(&spec);
+            group.bench_with_input(BenchmarkId::new(shape, commits), &data, |b, data| {
+                b.iter(|| Dag::from_repo_data(data))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_layout(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compute_layout");
+    group.sample_size(10);
+    let trunks = trunks();
+    for commits in SIZES {
+        for (shape, spec) in shapes(commits) {
+            let data = synthetic_repo(&spec);
+            let dag = Dag::from_repo_data(&data);
+            group.bench_with_input(BenchmarkId::new(shape, commits), &data, |b, data| {
+                b.iter(|| compute_layout(&dag, data, &trunks))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_filter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("filter_by_author");
+    group.sample_size(10);
+    for commits in SIZES {
+        for (shape, spec) in shapes(commits) {
+            let data = synthetic_repo(&spec);
+            group.bench_with_input(BenchmarkId::new(shape, commits), &data, |b, data| {
+                b.iter(|| filter_by_author(data, "author3"))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_dag, bench_layout, bench_filter);
+criterion_main!(benches);
@@ -0,0 +1,121 @@
+use crate::git::types::CommitInfo;
+use crate::project::Project;
+use std::sync::Arc;
+
+pub struct AggregateRow {
+    pub project_idx: usize,
+    pub commit: Arc<CommitInfo>,
+}
+
+pub struct Aggregate {
+    pub rows: Vec<AggregateRow>,
+    pub projects: Vec<String>,
+    pub columns: Vec<usize>,
+    pub spans: Vec<Option<(usize, usize)>>,
+    pub selected: usize,
+    pub scroll_y: usize,
+}
+
+impl Aggregate {
+    pub fn new(projects: &[Project], members: &[usize]) -> Self {
+        let mut rows: Vec<AggregateRow> = members
+            .iter()
+            .filter_map(|&idx| projects.get(idx).map(|proj| (idx, proj)))
+            .flat_map(|(project_idx, proj)| {
+                proj.repo_data.commits.iter().map(move |c| AggregateRow {
+                    project_idx,
+                    commit: Arc::clone(c),
+                })
+            })
+            .collect();
+        // Stable, so same-second commits keep their project's order
+        rows.sort_by_key(|r| std::cmp::Reverse(r.commit.time));
+        let mut spans = vec![None; projects.len()];
+        for (i, row) in rows.iter().enumerate() {
+            let span: &mut Option<(usize, usize)> = &mut spans[row.project_idx];
+            *span = Some(span.map_or((i, i), |(first, _)| (first, i)));
+        }
+        Self {
+            rows,
+            projects: projects.iter().map(|p| p.name.clone()).collect(),
+            columns: members.to_vec(),
+            spans,
+            selected: 0,
+            scroll_y: 0,
+        }
+    }
+
+    pub fn move_selection(&mut self, down: bool) {
+        if down {
+            if self.selected + 1 < self.rows.len() {
+                self.selected += 1;
+            }
+        } else {
+            self.selected = self.selected.saturating_sub(1);
+        }
+    }
+
+    // It has commits on that row or on both sides of it
+    pub fn spans_row(&self, idx: usize, row: usize) -> bool {
+        self.spans
+            .get(idx)
+            .copied()
+            .flatten()
+            .is_some_and(|(first, last)| first <= row && row <= last)
+    }
+
+    pub fn update_scroll(&mut self, visible_height: usize) {
+        if visible_height == 0 {
+            return;
+        }
+        if self.selected >= self.scroll_y + visible_height {
+            self.scroll_y = self.selected + 1 - visible_height;
+        }
+        if self.selected < self.scroll_y {
+            self.scroll_y = self.selected;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn interleaves_projects_by_time() {
+        let project = |name: &str, commits: Vec<_>| {
+            Project::snapshot(name.to_string(), make_repo_data(commits, vec![]), &[])
+        };
+        let projects = [
+            project("docs", vec![make_commit(5, vec![], 20)]),
+            project(
+                "api",
+                vec![make_commit(3, vec![1], 10), make_commit(1, vec![], 50)],
+            ),
+            project(
+                "web",
+                vec![make_commit(4, vec![2], 30), make_commit(2, vec![], 40)],
+            ),
+        ];
+        // `docs` is outside the workspace
+        let agg = Aggregate::new(&projects, &[1, 2]);
+        let order: Vec<_> = agg
+            .rows
+            .iter()
+            .map(|r| (r.project_idx, r.commit.oid))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                (1, make_oid(3)),
+                (2, make_oid(4)),
+                (2, make_oid(2)),
+                (1, make_oid(1))
+            ]
+        );
+        assert_eq!(agg.spans, vec![None, Some((0, 3)), Some((1, 2))]);
+        assert!(agg.spans_row(1, 2));
+        assert!(!agg.spans_row(2, 3));
+    }
+}
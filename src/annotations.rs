@@ -0,0 +1,109 @@
+use crate::config::{repo_file_name, state_dir, write_atomic};
+use crate::git::types::Oid;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+// Kept in the state dir, never in the repository (unlike git notes)
+#[derive(Default)]
+pub struct Annotations {
+    // None for read-only projects, whose notes are never saved
+    path: Option<PathBuf>,
+    notes: HashMap<Oid, String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct NotesFile {
+    // Sorted so the file diffs cleanly
+    notes: BTreeMap<String, String>,
+}
+
+fn notes_dir() -> PathBuf {
+    state_dir().join("arachne").join("notes")
+}
+
+impl Annotations {
+    pub fn load(repo_name: &str) -> Self {
+        Self::load_from(notes_dir().join(repo_file_name(repo_name, "toml")))
+    }
+
+    pub fn load_from(path: PathBuf) -> Self {
+        let file: NotesFile = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| match toml::from_str(&content) {
+                Ok(file) => Some(file),
+                Err(e) => {
+                    tracing::warn!(path = %path.display(), "notes file unreadable: {e}");
+                    None
+                }
+            })
+            .unwrap_or_default();
+        let notes = file
+            .notes
+            .into_iter()
+            .filter_map(|(sha, text)| {
+                let oid = git2::Oid::from_str(&sha).ok()?;
+                Some((Oid::from_git2(oid), text))
+            })
+            .collect();
+        Self {
+            path: Some(path),
+            notes,
+        }
+    }
+
+    pub fn get(&self, oid: &Oid) -> Option<&str> {
+        self.notes.get(oid).map(String::as_str)
+    }
+
+    pub fn contains(&self, oid: &Oid) -> bool {
+        self.notes.contains_key(oid)
+    }
+
+    pub fn set(&mut self, oid: Oid, text: &str) -> std::io::Result<()> {
+        let text = text.trim();
+        if text.is_empty() {
+            self.notes.remove(&oid);
+        } else {
+            self.notes.insert(oid, text.to_string());
+        }
+        match self.path {
+            Some(ref path) => self.save(path),
+            None => Ok(()),
+        }
+    }
+
+    fn save(&self, path: &Path) -> std::io::Result<()> {
+        let file = NotesFile {
+            notes: self
+                .notes
+                .iter()
+                .map(|(oid, text)| (oid.to_string(), text.clone()))
+                .collect(),
+        };
+        let content = toml::to_string_pretty(&file).map_err(std::io::Error::other)?;
+        write_atomic(path, content.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::make_oid;
+
+    #[test]
+    fn notes_round_trip_through_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(repo_file_name("org/app", "toml"));
+        assert!(path.ends_with("org_app.toml"));
+
+        let mut notes = Annotations::load_from(path.clone());
+        notes.set(make_oid(1), "  regression starts here ").unwrap();
+        notes.set(make_oid(2), "revisit").unwrap();
+        notes.set(make_oid(2), "").unwrap();
+
+        let reloaded = Annotations::load_from(path);
+        assert_eq!(reloaded.get(&make_oid(1)), Some("regression starts here"));
+        assert!(!reloaded.contains(&make_oid(2)));
+    }
+}
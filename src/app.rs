@@ -1,35 +1,89 @@
 const JUST_NOW: &str = "just now";
+const WEIGHED_ROWS: usize = 120;
+const FLASH_DURATION: std::time::Duration = std::time::Duration::from_millis(1500);
+const DEFAULT_DEEPEN: u32 = 100;
 
-use crate::config::Config;
-use crate::data_source::{self, LocalSource, RemoteSource, ViewMode};
+use crate::aggregate::Aggregate;
+use crate::annotations::Annotations;
+use crate::authors::{AuthorFormat, AuthorNames};
+use crate::compare::Comparison;
+use crate::config::{Config, RepoEntry};
+use crate::conventional;
+use crate::data_source::{self, LocalSource, MergeMethod, RemoteSource, ViewMode};
+use crate::dates::DateFormat;
 use crate::error::Result;
 use crate::event::{AppEvent, GitHubData};
-use crate::git::{repo, types::RepoData};
-use crate::graph::image_cache::ImageCache;
-use crate::graph::{dag::Dag, filter::filter_by_author, layout};
+use crate::external::{self, ExternalCommand};
+use crate::git::{
+    blame,
+    diff::{self, ChangedFile, CommitWeight, DiffStat},
+    fetch::{self, FetchOutcome, FetchProgress},
+    head_log::{self, HeadMove},
+    merge::{self, CherryPickOutcome},
+    patch, repo, shallow,
+    signature::{self, TagSignature},
+    stash::{self, StashOp},
+    types::{CommitSource, Oid, RepoData, RepoDelta, RepoUpdate},
+    undo::{self, DeletedRef},
+    worker::RepoWorker,
+};
+use crate::graph::dag::Dag;
+use crate::graph::drift;
+use crate::graph::filter::{author_excluded, author_matches, filter_commits};
+use crate::graph::pixel_renderer::COLS_PER_LANE;
+use crate::graph::types::MAX_LANES;
 use crate::project::{self, Project};
+use crate::remote_cache;
 use crate::screen::{ConfigAction, ConfigScreenState, Screen};
+use crate::script::ScriptCommand;
+use crate::stats;
 use crate::terminal_graphics::GraphicsCapability;
 use crate::ui::{
-    branch_panel::{self, BranchPanel, DisplayEntry, SectionKey},
+    aggregate_view::AggregateView,
+    blame_view::{self, BlameState, BlameView},
+    branch_panel::{self, BranchPanel, DisplayEntry, EntryKind, SectionKey},
     config_screen::ConfigScreen,
-    detail_panel::DetailPanel,
+    conflict_preview::{ConflictPreviewPanel, ConflictPreviewState},
+    deleted_panel::{DeletedPanel, DeletedRefsState, RecentlyDeleted},
+    detail_panel::{self, DetailPanel, StashDetail},
+    format_count,
     graph_view::GraphView,
+    head_timeline::{HeadTimelinePanel, HeadTimelineState},
     header_bar::{HeaderBar, PaneInfo},
-    help_panel::HelpPanel,
-    input::{self, Action, FilterMode},
-    status_bar::StatusBar,
+    health_panel::{HealthPanel, HealthState, LARGEST_FILES},
+    heatmap::{HeatmapPanel, HeatmapState},
+    help_panel::{self, HelpPanel},
+    input::{self, Action, FilterMode, MacroRecorder, MacroStep, PopupKeys},
+    lane_inspect::{LaneBranch, LaneTooltip},
+    list_view::ListView,
+    perf_hud::{PerfHud, PerfStats},
+    pr_merge::{PrMergePanel, PrMergeState},
+    rebase_preview::{RebasePreviewPanel, RebasePreviewState},
+    ref_popover::{RefItem, RefKind, RefPopover, RefPopoverState},
+    release_panel::{ReleasePanel, ReleasesState},
+    render_scrollbar,
+    stash_panel::{StashPanel, StashesState},
+    status_bar::{FilterChip, StatusBar},
     theme::{self, ThemePalette, THEME_NAMES},
+    truncate_with_ellipsis,
 };
+use ansi_to_tui::IntoText;
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
+    text::Text,
     widgets::{Block, Borders},
     Frame,
 };
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
 
+use crate::ui::links::{self, WebLinks};
 use crate::ui::toast::{Notification, NotifyLevel};
 
 #[derive(Debug, Clone, PartialEq)]
@@ -39,131 +93,553 @@ pub enum Panel {
     Detail,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Offline {
+    Manual,
+    Unreachable,
+}
+
+pub struct Prompt {
+    pub label: &'static str,
+    pub text: String,
+    pub kind: PromptKind,
+}
+
+pub enum PromptKind {
+    ExportPatches(Vec<Oid>),
+    Compare { name: String, tip: Oid },
+    RebaseOnto { name: String, tip: Oid },
+    Annotate(Oid),
+    Deepen,
+    NewBranch(Oid),
+    CommitTypes,
+    Workspace,
+}
+
+pub struct Confirm {
+    pub title: &'static str,
+    pub question: String,
+    pub kind: ConfirmKind,
+}
+
+pub enum ConfirmKind {
+    Stash {
+        op: StashOp,
+        index: usize,
+    },
+    MergePr {
+        project_idx: usize,
+        branch: String,
+        number: u64,
+        method: MergeMethod,
+    },
+    DeleteMergedBranch {
+        project_idx: usize,
+        branch: String,
+    },
+    CherryPick {
+        project_idx: usize,
+        oid: Oid,
+    },
+    DeleteBranch {
+        project_idx: usize,
+        branch: String,
+    },
+    DeleteTag {
+        project_idx: usize,
+        tag: String,
+    },
+    PruneBranches {
+        project_idx: usize,
+        branches: Vec<String>,
+    },
+    OpenSubmodule {
+        name: String,
+        workdir: PathBuf,
+    },
+}
+
+// The unfocused pane; switching focus swaps it with active_project
+pub struct SplitPane {
+    pub project: usize,
+}
+
 pub struct App {
     pub config: Config,
     pub projects: Vec<Project>,
     pub active_project: usize,
     pub event_tx: Option<mpsc::UnboundedSender<AppEvent>>,
+    pub shutdown: CancellationToken,
+    pub tasks: TaskTracker,
 
     pub screen: Screen,
     pub active_panel: Panel,
-    pub graph_scroll_y: usize,
-    pub graph_selected: usize,
+    pub visual_anchor: Option<usize>,
+    pub lane_cursor: Option<usize>,
+    pub follow: bool,
+    pub show_trunk_distance: bool,
+    pub list_view: bool,
+    // Only its projects are read, watched and polled; every project when None
+    pub workspace: Option<String>,
+    pub offline: Option<Offline>,
+    // Polls skipped while offline, redone once GitHub is back
+    pub offline_refresh: HashSet<usize>,
+    pub probing: bool,
+    pub manual_refresh: bool,
+    pub tag_milestones: bool,
+    pub show_diffstat: bool,
+    flash: Option<(HashSet<Oid>, std::time::Instant)>,
     pub branch_scroll: usize,
-    pub branch_selected: usize,
+    // For paging
+    branch_page: usize,
 
     pub show_detail: bool,
+    pub detail_oid: Option<Oid>,
+    pub detail_files: Option<Vec<ChangedFile>>,
+    pub detail_file_selected: usize,
+    pub show_diff: bool,
+    pub detail_diff: Option<Text<'static>>,
+    detail_diff_requested: bool,
+    pub detail_diff_scroll: usize,
+    pub detail_message_scroll: usize,
+    detail_message_page: usize,
     pub show_help: bool,
+    // Clamped at the next render
+    pub help_scroll: usize,
+    help_page: usize,
+    pub show_perf_hud: bool,
+    pub perf: PerfStats,
     pub show_forks: bool,
     pub loading_remote: bool,
     pub filter_mode: FilterMode,
     pub filter_text: String,
     pub author_filter_text: String,
+    pub type_filter: Vec<String>,
+    pub prompt: Option<Prompt>,
+    pub script: VecDeque<ScriptCommand>,
+    pub compare: Option<Comparison>,
+    pub aggregate: Option<Aggregate>,
+    pub split: Option<SplitPane>,
+    pub heatmap: Option<HeatmapState>,
+    pub releases: Option<ReleasesState>,
+    pub health: Option<HealthState>,
+    pub stashes: Option<StashesState>,
+    pub head_timeline: Option<HeadTimelineState>,
+    pub blame: Option<BlameState>,
+    pub refs_popover: Option<RefPopoverState>,
+    pub conflict_preview: Option<ConflictPreviewState>,
+    pub rebase_preview: Option<RebasePreviewState>,
+    pub pr_merge: Option<PrMergeState>,
+    pub recently_deleted: Vec<RecentlyDeleted>,
+    pub deleted_refs: Option<DeletedRefsState>,
+    // Drops stale results
+    conflict_preview_oid: Option<Oid>,
     pub collapsed_sections: HashSet<SectionKey>,
     pub notification: Option<Notification>,
+    pub pending_command: Option<ExternalCommand>,
     pub(crate) cached_entries: Vec<DisplayEntry>,
+    status_area: Rect,
 
     pub graphics_cap: GraphicsCapability,
     pub palette: ThemePalette,
+    pub dates: DateFormat,
+    pub authors: AuthorFormat,
     pub confirm_quit: bool,
+    pub macros: MacroRecorder,
+    pub confirm: Option<Confirm>,
     pub should_quit: bool,
 }
 
 impl App {
     pub fn new(config: Config, graphics_cap: GraphicsCapability) -> Self {
         let palette = theme::palette_for_theme(config.theme.as_deref());
+        let dates = DateFormat::from_config(&config);
+        let authors = AuthorFormat::from_config(&config);
+        let list_view = config.list_view;
+        let tag_milestones = config.tag_milestones;
+        let show_diffstat = config.diffstat;
+        let manual_refresh = config.manual_refresh;
+        // A repo adopted from the working directory is in no workspace
+        let workspace = config
+            .workspace
+            .clone()
+            .filter(|_| config.adhoc_repo.is_none());
         Self {
             config,
             projects: Vec::new(),
             active_project: 0,
             event_tx: None,
+            shutdown: CancellationToken::new(),
+            tasks: TaskTracker::new(),
             screen: Screen::Graph,
             active_panel: Panel::Graph,
-            graph_scroll_y: 0,
-            graph_selected: 0,
+            visual_anchor: None,
+            lane_cursor: None,
+            follow: false,
+            show_trunk_distance: false,
+            list_view,
+            tag_milestones,
+            show_diffstat,
+            workspace,
+            offline: None,
+            offline_refresh: HashSet::new(),
+            probing: false,
+            manual_refresh,
+            flash: None,
             branch_scroll: 0,
-            branch_selected: 0,
+            branch_page: 0,
             show_detail: false,
+            detail_oid: None,
+            detail_files: None,
+            detail_file_selected: 0,
+            show_diff: false,
+            detail_diff: None,
+            detail_diff_requested: false,
+            detail_diff_scroll: 0,
+            detail_message_scroll: 0,
+            detail_message_page: 0,
             show_help: false,
+            help_scroll: 0,
+            help_page: 0,
+            show_perf_hud: false,
+            perf: PerfStats::default(),
             show_forks: true,
             loading_remote: false,
             filter_mode: FilterMode::Off,
             filter_text: String::new(),
             author_filter_text: String::new(),
+            type_filter: Vec::new(),
+            prompt: None,
+            script: VecDeque::new(),
+            compare: None,
+            aggregate: None,
+            split: None,
+            heatmap: None,
+            releases: None,
+            health: None,
+            stashes: None,
+            head_timeline: None,
+            blame: None,
+            refs_popover: None,
+            conflict_preview: None,
+            rebase_preview: None,
+            pr_merge: None,
+            recently_deleted: Vec::new(),
+            deleted_refs: None,
+            conflict_preview_oid: None,
             collapsed_sections: HashSet::new(),
             notification: None,
+            pending_command: None,
             cached_entries: Vec::new(),
+            status_area: Rect::default(),
             graphics_cap,
             palette,
+            dates,
+            authors,
             confirm_quit: false,
+            macros: MacroRecorder::default(),
+            confirm: None,
             should_quit: false,
         }
     }
 
     fn apply_theme(&mut self) {
-        self.palette = theme::palette_for_theme(self.config.theme.as_deref());
+        self.set_palette(theme::palette_for_theme(self.config.theme.as_deref()));
+    }
+
+    fn set_palette(&mut self, palette: ThemePalette) {
+        self.palette = palette;
+        // The built-in diff is colored as it's read
+        if self.config.diff_pager.is_none() {
+            self.detail_diff = None;
+            self.detail_diff_requested = false;
+        }
+        for proj in &mut self.projects {
+            proj.line_cache.clear();
+        }
     }
 
     pub fn has_active_notification(&self) -> bool {
         self.notification.is_some()
     }
 
+    pub fn graph_selected(&self) -> usize {
+        self.projects
+            .get(self.active_project)
+            .map_or(0, |p| p.graph_selected)
+    }
+
+    pub fn set_graph_selected(&mut self, row: usize) {
+        if let Some(proj) = self.projects.get_mut(self.active_project) {
+            proj.graph_selected = row;
+        }
+    }
+
+    pub fn graph_scroll_y(&self) -> usize {
+        self.projects
+            .get(self.active_project)
+            .map_or(0, |p| p.graph_scroll_y)
+    }
+
+    pub fn set_graph_scroll_y(&mut self, scroll: usize) {
+        if let Some(proj) = self.projects.get_mut(self.active_project) {
+            proj.graph_scroll_y = scroll;
+        }
+    }
+
+    pub fn branch_selected(&self) -> usize {
+        self.projects
+            .get(self.active_project)
+            .map_or(0, |p| p.branch_selected)
+    }
+
+    pub fn set_branch_selected(&mut self, entry: usize) {
+        if let Some(proj) = self.projects.get_mut(self.active_project) {
+            proj.branch_selected = entry;
+        }
+    }
+
+    // The event loop wakes up to clear the highlight
+    pub fn is_flashing(&self) -> bool {
+        self.flash.is_some()
+    }
+
+    pub fn expire_flash(&mut self) {
+        if self
+            .flash
+            .as_ref()
+            .is_some_and(|(_, since)| since.elapsed() >= FLASH_DURATION)
+        {
+            self.flash = None;
+        }
+    }
+
+    pub fn spawn_task<F>(&self, fut: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let cancel = self.shutdown.clone();
+        self.tasks.spawn(async move {
+            tokio::select! {
+                _ = cancel.cancelled() => {}
+                _ = fut => {}
+            }
+        });
+    }
+
     fn notify(&mut self, level: NotifyLevel, msg: impl Into<String>) {
+        let message = msg.into();
+        tracing::info!(level = ?level, "notify: {message}");
         self.notification = Some(Notification {
-            message: msg.into(),
+            message,
             level,
             created: std::time::Instant::now(),
         });
     }
 
     pub fn load_repos(&mut self) -> Result<()> {
+        if let Some(name) = self.workspace.clone() {
+            if self
+                .config
+                .workspace_members(&name)
+                .is_none_or(|m| m.is_empty())
+            {
+                self.workspace = None;
+                self.notify(
+                    NotifyLevel::Warn,
+                    format!("no repos in workspace \"{name}\", opening every repo"),
+                );
+            }
+        }
         let entries = self.config.resolved_repos();
         for entry in &entries {
-            let path = expand_tilde(&entry.path);
-            let r = repo::open_repo(&path)?;
-            let repo_data = repo::read_repo(&r, self.config.max_commits)?;
-
-            let current_branch = head_branch_name(&repo_data);
-
-            let repo_name = entry
-                .name
-                .clone()
-                .unwrap_or_else(|| repo::detect_repo_name(&r));
-
-            let dag = Dag::from_repo_data(&repo_data);
-            let result = layout::compute_layout(&dag, &repo_data, &self.config.trunk_branches);
-            let time_sorted_indices = project::build_time_sorted_indices(&result.rows);
-            let cached_repo_data = Some(repo_data.clone());
-
-            let remote_source = data_source::init_github_client(&self.config, &repo_name)
-                .map(|client| RemoteSource { client });
-
-            self.projects.push(Project {
-                name: repo_name,
-                local_source: Some(LocalSource { repo: r }),
-                remote_source,
-                active_mode: ViewMode::Local,
-                repo_data,
-                dag,
-                rows: result.rows,
-                branch_index_to_name: result.branch_index_to_name,
-                trunk_count: result.trunk_count,
-                max_lanes: result.max_lanes,
-                current_branch,
-                scroll_x: 0,
-                last_sync: JUST_NOW.to_string(),
-                rate_limit: None,
-                time_sorted_indices,
-                cached_repo_data,
-                github_failures: 0,
-                image_cache: ImageCache::new(),
-            });
+            let proj = self.open_project(&expand_tilde(&entry.path), entry.name.clone())?;
+            let idx = self.projects.len();
+            self.projects.push(proj);
+            // The rest are read when their workspace is opened
+            if self.in_workspace(idx) {
+                self.rebuild_graph(idx);
+            }
+        }
+        if !self.in_workspace(self.active_project) {
+            self.active_project = self.workspace_projects().first().copied().unwrap_or(0);
         }
         self.collapsed_sections = branch_panel::auto_collapse_defaults(&self.projects);
         self.refresh_entries();
         Ok(())
     }
 
+    pub async fn drain_tasks(&self, grace: std::time::Duration) {
+        self.shutdown.cancel();
+        self.tasks.close();
+        if tokio::time::timeout(grace, self.tasks.wait())
+            .await
+            .is_err()
+        {
+            tracing::warn!(
+                pending = self.tasks.len(),
+                "background tasks still running at exit"
+            );
+        }
+    }
+
+    fn open_project(&self, path: &Path, name: Option<String>) -> Result<Project> {
+        let worker = RepoWorker::open(path)?;
+        // A name without an owner/ is only a label; GitHub and the notes
+        // file still go by the detected one
+        let identity = match name.clone().filter(|n| n.contains('/')) {
+            Some(name) => name,
+            None => worker.call(repo::detect_repo_name)?,
+        };
+        let repo_name = name.unwrap_or_else(|| identity.clone());
+
+        let remote_source =
+            data_source::init_github_client(&self.config, &identity).map(|client| RemoteSource {
+                client,
+                cache: remote_cache::cache_path(&identity),
+            });
+
+        let mut proj = Project::loading(repo_name, LocalSource { worker }, remote_source);
+        proj.annotations = Annotations::load(&identity);
+        proj.web_url = links::github_base(&identity);
+        Ok(proj)
+    }
+
+    pub fn workspace_projects(&self) -> Vec<usize> {
+        let all = || (0..self.projects.len()).collect();
+        match self.workspace {
+            Some(ref name) => self.config.workspace_members(name).unwrap_or_else(all),
+            None => all(),
+        }
+    }
+
+    pub fn in_workspace(&self, idx: usize) -> bool {
+        self.workspace_projects().contains(&idx)
+    }
+
+    // Its projects weren't watched while it was closed, so they're read again
+    fn switch_workspace(&mut self, name: Option<String>) {
+        if let Some(ref name) = name {
+            if self.config.workspace_members(name).is_none() {
+                let known: Vec<&str> = self
+                    .config
+                    .workspaces
+                    .iter()
+                    .map(|w| w.name.as_str())
+                    .collect();
+                let hint = if known.is_empty() {
+                    "none are configured".to_string()
+                } else {
+                    format!("try {}", known.join(", "))
+                };
+                self.notify(
+                    NotifyLevel::Warn,
+                    format!("unknown workspace \"{name}\", {hint}"),
+                );
+                return;
+            }
+        }
+        let members = match name {
+            Some(ref name) => self.config.workspace_members(name).unwrap_or_default(),
+            None => (0..self.projects.len()).collect(),
+        };
+        let Some(&first) = members.first() else {
+            self.notify(NotifyLevel::Warn, "that workspace has no configured repos");
+            return;
+        };
+        self.workspace = name;
+        self.aggregate = None;
+        if self
+            .split
+            .as_ref()
+            .is_some_and(|s| !members.contains(&s.project))
+        {
+            self.split = None;
+        }
+        if !members.contains(&self.active_project) {
+            self.close_comparison();
+            self.focus_project(first);
+        }
+        let now = std::time::Instant::now();
+        for &idx in &members {
+            self.projects[idx].shown_at = now;
+            self.projects[idx].parked = false;
+            self.rebuild_graph(idx);
+        }
+        if let Some(ref tx) = self.event_tx {
+            let _ = tx.send(AppEvent::WatchersChanged);
+        }
+        let label = self.workspace.as_deref().unwrap_or("all repos");
+        self.notify(
+            NotifyLevel::Info,
+            format!("workspace: {label} ({} repos)", members.len()),
+        );
+    }
+
+    // True when any was, so the watchers and pollers are restarted without them
+    pub fn park_idle_projects(&mut self, now: std::time::Instant) -> bool {
+        // Nothing runs to be stopped
+        if self.config.park_after_secs == 0 || self.manual_refresh {
+            return false;
+        }
+        let after = std::time::Duration::from_secs(self.config.park_after_secs);
+        let shown = [
+            Some(self.active_project),
+            self.split.as_ref().map(|s| s.project),
+        ];
+        let mut parked = false;
+        for idx in self.workspace_projects() {
+            let Some(proj) = self.projects.get_mut(idx) else {
+                continue;
+            };
+            if shown.contains(&Some(idx)) {
+                proj.shown_at = now;
+            } else if !proj.parked && now.duration_since(proj.shown_at) >= after {
+                tracing::debug!(project = idx, "parked");
+                proj.parked = true;
+                parked = true;
+            }
+        }
+        parked
+    }
+
+    pub fn next_park_at(&self) -> Option<std::time::Instant> {
+        if self.config.park_after_secs == 0 || self.manual_refresh {
+            return None;
+        }
+        let after = std::time::Duration::from_secs(self.config.park_after_secs);
+        let shown = [
+            Some(self.active_project),
+            self.split.as_ref().map(|s| s.project),
+        ];
+        self.workspace_projects()
+            .into_iter()
+            .filter(|idx| !shown.contains(&Some(*idx)))
+            .filter_map(|idx| self.projects.get(idx))
+            .filter(|p| !p.parked)
+            .map(|p| p.shown_at + after)
+            .min()
+    }
+
+    fn unpark(&mut self, idx: usize) {
+        let Some(proj) = self.projects.get_mut(idx) else {
+            return;
+        };
+        proj.shown_at = std::time::Instant::now();
+        if !std::mem::take(&mut proj.parked) {
+            return;
+        }
+        tracing::debug!(project = idx, "resumed");
+        let polled = proj.github_client().is_some();
+        self.rebuild_graph(idx);
+        if let Some(ref tx) = self.event_tx {
+            if polled {
+                let _ = tx.send(AppEvent::GitHubUpdate(idx));
+            }
+            let _ = tx.send(AppEvent::WatchersChanged);
+        }
+    }
+
     fn refresh_entries(&mut self) {
         let active_slice = match self.projects.get(self.active_project) {
             Some(proj) => std::slice::from_ref(proj),
@@ -173,59 +649,170 @@ impl App {
             active_slice,
             &self.filter_text,
             &self.author_filter_text,
+            &self.config.exclude_authors,
             self.show_forks,
+            self.config.stale_fork_cutoff(chrono::Utc::now()),
             &self.collapsed_sections,
         );
         if self.cached_entries.is_empty() {
-            self.branch_selected = 0;
+            self.set_branch_selected(0);
         } else {
-            self.branch_selected = self.branch_selected.min(self.cached_entries.len() - 1);
+            self.set_branch_selected(self.branch_selected().min(self.cached_entries.len() - 1));
         }
     }
 
     pub fn rebuild_graph(&mut self, project_idx: usize) {
-        self.rebuild_graph_inner(project_idx, false);
-        self.refresh_entries();
+        self.read_local(project_idx, true);
     }
 
-    pub fn rebuild_graph_author_only(&mut self, project_idx: usize) {
-        self.rebuild_graph_inner(project_idx, true);
-        self.refresh_entries();
+    // A full read picks up history added behind the known commits, e.g. by deepening
+    #[tracing::instrument(skip(self))]
+    fn read_local(&mut self, project_idx: usize, incremental: bool) {
+        let Some(proj) = self.projects.get(project_idx) else {
+            return;
+        };
+        let Some(local) = proj.local_source.as_ref() else {
+            return;
+        };
+        // Dropped replacements would leave the loaded commits rewired
+        let known_tips = proj
+            .cached_repo_data
+            .as_ref()
+            .filter(|d| incremental && d.replaced.is_empty())
+            .map(RepoData::known_tips);
+        let max_commits = self.config.max_commits;
+        let read = move |r: &git2::Repository, progress: repo::Progress| {
+            repo::refresh_repo(r, known_tips.as_ref(), max_commits, progress)
+                .map_err(|e| e.to_string())
+        };
+        let submitted = match self.event_tx.clone() {
+            Some(tx) => local.worker.submit(move |r| {
+                let result = read(r, &mut |walked| {
+                    let _ = tx.send(AppEvent::LoadProgress {
+                        project_idx,
+                        walked,
+                    });
+                });
+                let _ = tx.send(AppEvent::LocalDataResult {
+                    project_idx,
+                    result,
+                });
+            }),
+            // No event loop yet — read inline
+            None => local
+                .worker
+                .call(move |r| read(r, &mut |_| {}))
+                .map(|result| self.handle_local_data_result(project_idx, result)),
+        };
+        if let Err(e) = submitted {
+            self.notify(NotifyLevel::Error, format!("{e}"));
+        }
     }
 
-    fn rebuild_graph_inner(&mut self, project_idx: usize, author_only: bool) {
-        if let Some(proj) = self.projects.get_mut(project_idx) {
-            let mut data = if author_only {
-                if let Some(ref cached) = proj.cached_repo_data {
-                    cached.clone()
-                } else {
-                    return;
+    pub fn handle_local_data_result(
+        &mut self,
+        project_idx: usize,
+        result: std::result::Result<RepoUpdate, String>,
+    ) {
+        let head_before = self.projects.get_mut(project_idx).and_then(|proj| {
+            proj.loading = false;
+            proj.load_progress = None;
+            proj.cached_repo_data.as_ref().map(|d| d.head)
+        });
+        let baseline = self.refresh_baseline(project_idx);
+        match result {
+            Ok(RepoUpdate::Full(data)) => {
+                if let Some(proj) = self.projects.get_mut(project_idx) {
+                    proj.cached_repo_data = Some(data);
                 }
-            } else if let Some(ref local) = proj.local_source {
-                match repo::read_repo(&local.repo, self.config.max_commits) {
-                    Ok(d) => {
-                        proj.cached_repo_data = Some(d.clone());
-                        d
-                    }
-                    Err(e) => {
-                        self.notify(NotifyLevel::Error, format!("{e}"));
-                        return;
-                    }
+                self.show_cached_data(project_idx);
+            }
+            Ok(RepoUpdate::Delta(delta)) => self.apply_repo_delta(project_idx, delta),
+            Err(e) => self.notify(NotifyLevel::Error, e),
+        }
+        if let Some(before) = baseline {
+            self.announce_refresh(project_idx, &before);
+        }
+        self.report_watched(project_idx);
+        if let Some(proj) = self.projects.get_mut(project_idx) {
+            let head = proj.cached_repo_data.as_ref().and_then(|d| d.head);
+            if let (Some(before), Some(oid)) = (head_before, head) {
+                if before != Some(oid) {
+                    proj.watched_head_moves.push(HeadMove {
+                        oid,
+                        time: chrono::Utc::now(),
+                        message: String::new(),
+                    });
                 }
-            } else {
-                return;
-            };
-
-            proj.current_branch = head_branch_name(&data);
-
-            if !self.author_filter_text.is_empty() {
-                filter_by_author(&mut data, &self.author_filter_text);
             }
+        }
+        if project_idx == self.active_project {
+            self.clamp_selected();
+        }
+        self.refresh_entries();
+        self.run_script();
+    }
+
+    fn apply_repo_delta(&mut self, project_idx: usize, delta: RepoDelta) {
+        let Some(proj) = self.projects.get_mut(project_idx) else {
+            return;
+        };
+        let Some(base) = proj.cached_repo_data.as_mut() else {
+            // Base was replaced (e.g. by a GitHub merge) while the read was in flight
+            self.rebuild_graph(project_idx);
+            return;
+        };
+        let new_commits = delta.new_commits.clone();
+        let trimmed = base.apply_delta(delta, self.config.max_commits);
 
-            proj.repo_data = data;
-            proj.rebuild_layout(&self.config.trunk_branches);
-            proj.last_sync = JUST_NOW.to_string();
+        // The current Dag only matches the base when it shows unfiltered local data
+        let dag_reusable = !trimmed
+            && proj.rows_mode == ViewMode::Local
+            && self.author_filter_text.is_empty()
+            && self.type_filter.is_empty()
+            && self.config.hidden_authors().is_empty();
+        if !dag_reusable {
+            self.show_cached_data(project_idx);
+            return;
         }
+
+        proj.current_branch = head_branch_name(base);
+        proj.repo_data = base.clone();
+        proj.dag.extend(new_commits);
+        proj.relayout(&self.config.trunk_branches);
+        proj.last_sync = JUST_NOW.to_string();
+    }
+
+    pub fn rebuild_graph_author_only(&mut self, project_idx: usize) {
+        self.show_cached_data(project_idx);
+        self.refresh_entries();
+    }
+
+    // The commits are shared with the cache, so nothing is deep-copied
+    fn show_cached_data(&mut self, project_idx: usize) {
+        let hidden = self.config.hidden_authors();
+        let Some(proj) = self.projects.get_mut(project_idx) else {
+            return;
+        };
+        let Some(base) = proj.cached_repo_data.as_ref() else {
+            return;
+        };
+        proj.current_branch = head_branch_name(base);
+        proj.rows_mode = ViewMode::Local;
+        let types = &self.type_filter;
+        proj.repo_data =
+            if self.author_filter_text.is_empty() && types.is_empty() && hidden.is_empty() {
+                base.clone()
+            } else {
+                let query = self.author_filter_text.to_lowercase();
+                filter_commits(base, |c| {
+                    author_matches(c, &query)
+                        && !author_excluded(&c.author, hidden)
+                        && (types.is_empty() || conventional::type_matches(&c.message, types))
+                })
+            };
+        proj.rebuild_layout(&self.config.trunk_branches);
+        proj.last_sync = JUST_NOW.to_string();
     }
 
     pub fn handle_github_result(
@@ -233,23 +820,51 @@ impl App {
         project_idx: usize,
         result: std::result::Result<GitHubData, String>,
     ) {
+        let baseline = self.refresh_baseline(project_idx);
+        // Whether the graph, or only the branch panel, has to be redone
+        let mut graph_changed = false;
+        let mut entries_changed = false;
+        let mut unreachable = false;
         if let Some(proj) = self.projects.get_mut(project_idx) {
             match result {
                 Ok(data) => {
                     proj.github_failures = 0;
                     proj.rate_limit = data.rate_limit;
-                    proj.repo_data.branches.extend(data.branches);
-                    proj.dag.merge_remote(data.commits);
-                    let result = layout::compute_layout(
-                        &proj.dag,
-                        &proj.repo_data,
+                    if let Some(pull_requests) = data.pull_requests {
+                        entries_changed |= proj.pull_requests != pull_requests;
+                        proj.pull_requests = pull_requests;
+                    }
+                    if let Some(protected) = data.protected_branches {
+                        let protected: HashSet<String> = protected.into_iter().collect();
+                        entries_changed |= proj.protected_branches != protected;
+                        proj.protected_branches = protected;
+                    }
+                    if data.codeowners.is_some() {
+                        proj.codeowners = data.codeowners;
+                    }
+                    if data.author_logins.is_some() {
+                        proj.author_logins = data.author_logins;
+                        proj.line_cache.clear();
+                    }
+                    graph_changed = proj.merge_github(
+                        data.branches,
+                        data.commits,
+                        &data.unlisted_forks,
                         &self.config.trunk_branches,
                     );
-                    proj.apply_layout_result(result);
-                    proj.cached_repo_data = None;
+                    entries_changed |= graph_changed;
                     proj.last_sync = JUST_NOW.to_string();
                     self.notification = None;
                 }
+                // Not failures: offline, the poll is redone once GitHub is back
+                Err(e) if data_source::github_unreachable() => {
+                    tracing::info!(project_idx, "github poll unreachable: {e}");
+                    unreachable = true;
+                }
+                // The status bar shows when polling resumes
+                Err(e) if data_source::github_paused_until().is_some() => {
+                    tracing::info!(project_idx, "github poll rate limited: {e}");
+                }
                 Err(e) => {
                     proj.github_failures = proj.github_failures.saturating_add(1);
                     if proj.github_polling_enabled() {
@@ -266,793 +881,7171 @@ impl App {
                 }
             }
         }
-        self.refresh_entries();
+        if unreachable {
+            self.offline_refresh.insert(project_idx);
+            self.note_unreachable();
+        }
+        if !graph_changed {
+            tracing::debug!(
+                project_idx,
+                "github poll brought no new commits or branches"
+            );
+        }
+        if let Some(before) = baseline.filter(|_| graph_changed) {
+            self.announce_refresh(project_idx, &before);
+        }
+        if graph_changed {
+            self.report_watched(project_idx);
+        }
+        // Entries only ever show the active project
+        if entries_changed && project_idx == self.active_project {
+            self.refresh_entries();
+        }
     }
 
-    pub fn handle_event(&mut self, event: AppEvent) {
-        if self.confirm_quit {
-            if let AppEvent::Key(key) = &event {
-                match key.code {
-                    crossterm::event::KeyCode::Char('y') => {
-                        self.should_quit = true;
-                        return;
-                    }
-                    _ => {
-                        self.confirm_quit = false;
+    fn toggle_view_mode(&mut self) {
+        if let Some(proj) = self.projects.get_mut(self.active_project) {
+            match proj.active_mode {
+                ViewMode::Local => {
+                    if proj.remote_source.is_none() {
+                        let msg = if cfg!(feature = "github") {
+                            "no github token \u{2014} set github_token in config or GITHUB_TOKEN env"
+                        } else {
+                            "built without the github feature"
+                        };
+                        self.notify(NotifyLevel::Warn, msg);
                         return;
                     }
+                    proj.active_mode = ViewMode::Remote;
+                    self.load_remote_view(self.active_project);
+                }
+                ViewMode::Remote => {
+                    proj.active_mode = ViewMode::Local;
+                    proj.remote_only.clear();
+                    proj.stale_remote = None;
+                    self.rebuild_graph(self.active_project);
                 }
             }
         }
-        match event {
-            AppEvent::Key(key) => match &mut self.screen {
-                Screen::Config(ref mut state) => {
-                    let action = state.handle_key(key);
-                    self.handle_config_action(action);
-                }
-                Screen::Graph => {
-                    let action = input::map_key(key, self.filter_mode);
-                    self.handle_graph_action(action);
+    }
+
+    fn load_remote_view(&mut self, idx: usize) {
+        let Some(remote) = self
+            .projects
+            .get(idx)
+            .and_then(|p| p.remote_source.as_ref())
+        else {
+            return;
+        };
+        let Some(tx) = self.event_tx.clone() else {
+            return;
+        };
+        let (client, cache) = (remote.client.clone(), remote.cache.clone());
+        let offline = self.offline.is_some();
+        let max = self.config.max_commits;
+        self.loading_remote = true;
+        self.spawn_task(async move {
+            let fetched = if offline {
+                None
+            } else {
+                Some(data_source::load_remote(&client, max).await)
+            };
+            let (result, cached_at) = match fetched {
+                Some(Ok(data)) => {
+                    if let Err(e) = remote_cache::save(&cache, &data, chrono::Utc::now()) {
+                        tracing::warn!("remote cache save failed: {e}");
+                    }
+                    (Ok(data), None)
                 }
-            },
-            AppEvent::GitHubResult {
-                project_idx,
-                result,
-            } => {
-                self.handle_github_result(project_idx, result);
-            }
-            AppEvent::RemoteDataResult {
-                project_idx,
+                Some(Err(e)) if !data_source::github_unreachable() => (Err(e), None),
+                _ => match remote_cache::load(&cache) {
+                    Some(cached) => (Ok(cached.data), Some(cached.fetched_at)),
+                    None => (
+                        Err("GitHub offline and no remote view cached yet".to_string()),
+                        None,
+                    ),
+                },
+            };
+            let _ = tx.send(AppEvent::RemoteDataResult {
+                project_idx: idx,
                 result,
-            } => {
-                self.handle_remote_data_result(project_idx, result);
-            }
-            AppEvent::Resize => {
-                self.graphics_cap.redetect_cell_size();
-                for proj in &mut self.projects {
-                    proj.image_cache.clear(proj.max_lanes);
-                }
-            }
-            _ => {}
+                cached_at,
+            });
+        });
+    }
+
+    fn toggle_offline(&mut self) {
+        if self.offline.is_some() {
+            self.go_online();
+        } else {
+            self.offline = Some(Offline::Manual);
+            self.notify(
+                NotifyLevel::Info,
+                "offline: no GitHub calls, remote views from cache",
+            );
         }
     }
 
-    fn handle_remote_data_result(
-        &mut self,
-        project_idx: usize,
-        result: std::result::Result<RepoData, String>,
-    ) {
-        self.loading_remote = false;
-        if let Some(proj) = self.projects.get_mut(project_idx) {
-            match result {
-                Ok(data) => {
-                    proj.repo_data = data;
-                    proj.rebuild_layout(&self.config.trunk_branches);
-                    proj.last_sync = JUST_NOW.to_string();
-                    self.graph_selected = 0;
-                    self.graph_scroll_y = 0;
-                    self.notification = None;
-                }
-                Err(e) => {
-                    self.notify(NotifyLevel::Error, e);
-                }
+    fn note_unreachable(&mut self) {
+        if self.offline.is_none() {
+            self.offline = Some(Offline::Unreachable);
+            self.notify(
+                NotifyLevel::Warn,
+                "GitHub unreachable, working offline until it's back",
+            );
+        }
+    }
+
+    fn go_online(&mut self) {
+        self.offline = None;
+        let stale: Vec<usize> = self
+            .projects
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.active_mode == ViewMode::Remote && p.stale_remote.is_some())
+            .map(|(idx, _)| idx)
+            .collect();
+        for idx in stale {
+            self.load_remote_view(idx);
+        }
+        let refresh: Vec<usize> = self.offline_refresh.drain().collect();
+        if let Some(ref tx) = self.event_tx {
+            for idx in refresh {
+                let _ = tx.send(AppEvent::GitHubUpdate(idx));
             }
         }
-        self.refresh_entries();
+        self.notify(NotifyLevel::Info, "online: refreshing from GitHub");
     }
 
-    fn handle_config_action(&mut self, action: ConfigAction) {
-        match action {
-            ConfigAction::Close => {
-                self.apply_theme();
-                self.screen = Screen::Graph;
+    // Stops at a step that needs data still loading; called again as results arrive
+    pub fn run_script(&mut self) {
+        while let Some(cmd) = self.script.front() {
+            let waiting = self.loading_remote
+                || self
+                    .projects
+                    .get(self.active_project)
+                    .is_some_and(|p| p.loading);
+            if waiting {
+                return;
             }
-            ConfigAction::Save => {
-                if let Screen::Config(ref state) = self.screen {
-                    let new_config = state.draft.clone();
-                    if let Err(e) = new_config.save() {
-                        self.notify(NotifyLevel::Error, format!("save failed: {e}"));
-                        return;
+            let cmd = cmd.clone();
+            self.script.pop_front();
+            tracing::debug!(?cmd, "running script command");
+            match cmd {
+                ScriptCommand::FilterAuthor(text) => {
+                    self.author_filter_text = text;
+                    for idx in 0..self.projects.len() {
+                        self.rebuild_graph_author_only(idx);
                     }
-                    let was_first_launch = state.first_launch;
-                    self.config = new_config;
-                    self.apply_theme();
-                    self.screen = Screen::Graph;
-                    if was_first_launch {
-                        if let Err(e) = self.load_repos() {
-                            self.notify(NotifyLevel::Error, format!("load repos: {e}"));
-                        }
-                        if let Some(ref tx) = self.event_tx {
-                            let _ = tx.send(AppEvent::ConfigSaved);
-                        }
+                    self.clamp_selected();
+                }
+                ScriptCommand::FilterBranch(text) => {
+                    self.filter_text = text;
+                    self.refresh_entries();
+                }
+                ScriptCommand::SelectSha(prefix) => {
+                    let row = self.projects.get(self.active_project).and_then(|p| {
+                        p.rows
+                            .iter()
+                            .position(|r| r.meta.oid.to_string().starts_with(&prefix))
+                    });
+                    self.select_script_row(row, &prefix);
+                }
+                ScriptCommand::SelectBranch(name) => {
+                    let row = self.projects.get(self.active_project).and_then(|p| {
+                        let tip = p.repo_data.branches.iter().find(|b| b.name == name)?.tip;
+                        p.rows.iter().position(|r| r.meta.oid == tip)
+                    });
+                    self.select_script_row(row, &name);
+                }
+                ScriptCommand::View(mode) => {
+                    let current = self
+                        .projects
+                        .get(self.active_project)
+                        .map(|p| p.active_mode);
+                    if current.is_some_and(|m| m != mode) {
+                        self.toggle_view_mode();
                     }
-                    self.notify(NotifyLevel::Info, "config saved");
                 }
             }
-            ConfigAction::SelectTheme => {
-                if let Screen::Config(ref mut state) = self.screen {
-                    let idx = state.cursor.min(THEME_NAMES.len().saturating_sub(1));
-                    let name = THEME_NAMES[idx];
-                    state.draft.theme = Some(name.to_string());
-                    state.dirty = true;
-                    self.palette = theme::palette_for_theme(Some(name));
-                }
+        }
+    }
+
+    fn select_script_row(&mut self, row: Option<usize>, what: &str) {
+        match row {
+            Some(idx) => {
+                self.set_graph_selected(idx);
+                self.active_panel = Panel::Graph;
+                self.visual_anchor = None;
             }
-            ConfigAction::Quit => {
-                self.should_quit = true;
+            None => self.notify(
+                NotifyLevel::Warn,
+                format!("--cmd: {what} is not in the graph"),
+            ),
+        }
+    }
+
+    fn jump_to_unseen(&mut self) {
+        let Some(proj) = self.projects.get(self.active_project) else {
+            return;
+        };
+        match proj
+            .rows
+            .iter()
+            .rposition(|r| proj.unseen.contains(&r.meta.oid))
+        {
+            Some(idx) => {
+                self.set_graph_selected(idx);
+                self.active_panel = Panel::Graph;
+                self.visual_anchor = None;
             }
-            ConfigAction::QuitConfirm => {
-                self.confirm_quit = true;
+            None => self.notify(NotifyLevel::Info, "no new commits since last view"),
+        }
+    }
+
+    // Skips past spacers in the direction of travel
+    fn select_branch_near(&mut self, target: usize, forward: bool) {
+        let entries = &self.cached_entries;
+        if entries.is_empty() {
+            return;
+        }
+        let selectable = |i: &usize| !entries[*i].is_spacer();
+        let found = if forward {
+            (target..entries.len()).find(selectable)
+        } else {
+            (0..=target.min(entries.len() - 1)).rev().find(selectable)
+        };
+        if let Some(idx) = found {
+            self.set_branch_selected(idx);
+        }
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        match mouse.kind {
+            MouseEventKind::ScrollDown => self.handle_graph_action(Action::ScrollDown),
+            MouseEventKind::ScrollUp => self.handle_graph_action(Action::ScrollUp),
+            MouseEventKind::Down(MouseButton::Left) if mouse.row == self.status_area.y => {
+                if let Some(chip) = self.status_bar().chip_at(self.status_area, mouse.column) {
+                    self.clear_filter(chip);
+                }
             }
-            ConfigAction::AddItem => {
-                if let Screen::Config(ref mut state) = self.screen {
-                    state.add_item();
+            _ => {}
+        }
+    }
+
+    fn clear_filter(&mut self, chip: FilterChip) {
+        match chip {
+            FilterChip::Branch => self.filter_text.clear(),
+            FilterChip::Author => {
+                self.author_filter_text.clear();
+                for idx in 0..self.projects.len() {
+                    self.rebuild_graph_author_only(idx);
                 }
+                self.clamp_selected();
             }
-            ConfigAction::RemoveItem => {
-                if let Screen::Config(ref mut state) = self.screen {
-                    state.remove_item();
+            FilterChip::Type => {
+                self.type_filter.clear();
+                for idx in 0..self.projects.len() {
+                    self.rebuild_graph_author_only(idx);
                 }
+                self.clamp_selected();
+            }
+        }
+        self.refresh_entries();
+    }
+
+    // A filter hiding oid is dropped rather than leaving the selection behind
+    fn jump_to_commit(&mut self, oid: Oid, what: &str) {
+        let find = |app: &App| {
+            app.projects
+                .get(app.active_project)
+                .and_then(|p| p.rows.iter().position(|r| r.meta.oid == oid))
+        };
+        let mut row = find(self);
+        for (chip, label) in [(FilterChip::Author, "author"), (FilterChip::Type, "type")] {
+            if row.is_some() || !self.status_bar().chips().iter().any(|(c, _)| *c == chip) {
+                continue;
+            }
+            self.clear_filter(chip);
+            row = find(self);
+            if row.is_some() {
+                self.notify(
+                    NotifyLevel::Info,
+                    format!("{label} filter cleared to show {what}"),
+                );
             }
-            ConfigAction::None => {}
         }
+        let Some(idx) = row else {
+            self.notify(NotifyLevel::Warn, format!("{what} is not in the graph"));
+            return;
+        };
+        self.set_graph_selected(idx);
+        self.active_panel = Panel::Graph;
+        self.visual_anchor = None;
     }
 
-    fn handle_graph_action(&mut self, action: Action) {
-        if self.notification.is_some()
-            && !matches!(action, Action::None | Action::Quit | Action::ClosePopup)
-        {
-            self.notification = None;
+    fn jump_to_branch_tip(&mut self) {
+        let Some(proj) = self.projects.get(self.active_project) else {
+            return;
+        };
+        let target = match self.active_panel {
+            Panel::Branches => self
+                .cached_entries
+                .get(self.branch_selected())
+                .and_then(|e| Some((e.branch_name()?.to_string(), e.tip_oid()?))),
+            Panel::Graph | Panel::Detail => proj
+                .rows
+                .get(self.graph_selected())
+                .and_then(|r| proj.branch_index_to_name.get(&r.meta.branch_index?))
+                .and_then(|name| {
+                    let tip = proj
+                        .repo_data
+                        .branches
+                        .iter()
+                        .find(|b| &b.name == name)?
+                        .tip;
+                    Some((name.clone(), tip))
+                }),
+        };
+        match target {
+            Some((name, tip)) => self.jump_to_commit(tip, &name),
+            None => self.notify(NotifyLevel::Warn, "select a branch or a commit on one"),
+        }
+    }
+
+    // None for a project off screen
+    fn refresh_baseline(&self, project_idx: usize) -> Option<RefreshBaseline> {
+        if project_idx != self.active_project {
+            return None;
+        }
+        let proj = self.projects.get(project_idx)?;
+        Some(RefreshBaseline {
+            mode: proj.rows_mode,
+            rows: proj.rows.iter().map(|r| r.meta.oid).collect(),
+            tips: branch_tips_by_name(&proj.repo_data),
+        })
+    }
+
+    fn announce_refresh(&mut self, project_idx: usize, before: &RefreshBaseline) {
+        let Some(proj) = self.projects.get_mut(project_idx) else {
             return;
+        };
+        if self.follow {
+            proj.graph_selected = 0;
+            proj.graph_scroll_y = 0;
         }
-        match action {
-            Action::Quit => self.should_quit = true,
-            Action::ScrollDown => match self.active_panel {
-                Panel::Graph | Panel::Detail => {
-                    if let Some(proj) = self.projects.get(self.active_project) {
-                        if self.graph_selected + 1 < proj.rows.len() {
-                            self.graph_selected += 1;
-                        }
-                    }
-                }
-                Panel::Branches => {
-                    if !self.cached_entries.is_empty() {
-                        let mut next = self.branch_selected + 1;
-                        while next < self.cached_entries.len()
-                            && self.cached_entries[next].is_spacer()
-                        {
-                            next += 1;
-                        }
-                        if next < self.cached_entries.len() {
-                            self.branch_selected = next;
-                        }
-                    }
+        // Neither is the first load, or the other view's rows replacing these
+        if before.rows.is_empty() || before.mode != proj.rows_mode {
+            return;
+        }
+        let shown: HashSet<Oid> = proj.rows.iter().map(|r| r.meta.oid).collect();
+        let fresh: HashSet<Oid> = shown.difference(&before.rows).copied().collect();
+        let tips = branch_tips_by_name(&proj.repo_data);
+        let mut moved = 0;
+        let mut created = 0;
+        let mut relabelled = HashSet::new();
+        for (branch, tip) in &tips {
+            match before.tips.get(branch) {
+                Some(old) if old == tip => continue,
+                Some(_) => moved += 1,
+                None => created += 1,
+            }
+            if shown.contains(tip) {
+                relabelled.insert(*tip);
+            }
+        }
+        let gone = before
+            .tips
+            .keys()
+            .filter(|b| !tips.contains_key(*b))
+            .count();
+
+        let count =
+            |n: usize, one: &str, many: &str| format!("{n} {}", if n == 1 { one } else { many });
+        let mut parts = Vec::new();
+        if !fresh.is_empty() {
+            parts.push(format!("+{}", count(fresh.len(), "commit", "commits")));
+        }
+        if moved > 0 {
+            parts.push(count(moved, "branch moved", "branches moved"));
+        }
+        if created > 0 {
+            parts.push(count(created, "new branch", "new branches"));
+        }
+        if gone > 0 {
+            parts.push(count(gone, "branch deleted", "branches deleted"));
+        }
+        if parts.is_empty() {
+            return;
+        }
+        self.notify(NotifyLevel::Info, parts.join(", "));
+        let changed: HashSet<Oid> = fresh.union(&relabelled).copied().collect();
+        if !self.config.reduced_motion && !changed.is_empty() {
+            self.flash = Some((changed, std::time::Instant::now()));
+        }
+    }
+
+    // Off-screen projects too, unlike announce_refresh
+    fn report_watched(&mut self, project_idx: usize) {
+        let Some(proj) = self.projects.get_mut(project_idx) else {
+            return;
+        };
+        let mut moved = Vec::new();
+        for b in &proj.repo_data.branches {
+            let key = (b.source.clone(), b.name.clone());
+            if let Some(seen) = proj.watched_branches.get_mut(&key) {
+                if *seen != b.tip {
+                    *seen = b.tip;
+                    moved.push(format!("{} moved to {}", b.name, &b.tip.to_string()[..7]));
                 }
-            },
-            Action::ScrollUp => match self.active_panel {
-                Panel::Graph | Panel::Detail => {
-                    if self.graph_selected > 0 {
-                        self.graph_selected = self.graph_selected.saturating_sub(1);
+            }
+        }
+        if moved.is_empty() {
+            return;
+        }
+        let message = format!("{}: {}", proj.name, moved.join(", "));
+        self.notify(NotifyLevel::Warn, message);
+    }
+
+    pub fn handle_event(&mut self, event: AppEvent) {
+        if self.confirm_quit {
+            if let AppEvent::Key(key) = &event {
+                match key.code {
+                    crossterm::event::KeyCode::Char('y') => {
+                        self.should_quit = true;
+                        return;
                     }
-                }
-                Panel::Branches => {
-                    if !self.cached_entries.is_empty() && self.branch_selected > 0 {
-                        let mut prev = (self.branch_selected - 1)
-                            .min(self.cached_entries.len().saturating_sub(1));
-                        while prev > 0 && self.cached_entries[prev].is_spacer() {
-                            prev -= 1;
-                        }
-                        if !self.cached_entries[prev].is_spacer() {
-                            self.branch_selected = prev;
-                        }
+                    _ => {
+                        self.confirm_quit = false;
+                        return;
                     }
                 }
-            },
-            Action::ScrollLeft => {
-                if let Some(proj) = self.projects.get_mut(self.active_project) {
-                    proj.scroll_x = proj.scroll_x.saturating_sub(4);
-                }
             }
-            Action::ScrollRight => {
-                if let Some(proj) = self.projects.get_mut(self.active_project) {
-                    proj.scroll_x = proj.scroll_x.saturating_add(4);
+        }
+        if self.confirm.is_some() {
+            if let AppEvent::Key(key) = &event {
+                let confirm = self.confirm.take();
+                if key.code == crossterm::event::KeyCode::Char('y') {
+                    if let Some(confirm) = confirm {
+                        self.run_confirmed(confirm.kind);
+                    }
                 }
+                return;
             }
-            Action::PanelLeft => {
-                self.active_panel = match self.active_panel {
-                    Panel::Detail => Panel::Graph,
-                    Panel::Graph => Panel::Branches,
-                    Panel::Branches => Panel::Branches,
-                };
-            }
-            Action::PanelRight => {
-                self.active_panel = match self.active_panel {
-                    Panel::Branches => Panel::Graph,
-                    Panel::Graph if self.show_detail => Panel::Detail,
-                    Panel::Graph => Panel::Graph,
-                    Panel::Detail => Panel::Detail,
-                };
-            }
-            Action::NextProject => {
-                if !self.projects.is_empty() {
-                    self.active_project = (self.active_project + 1) % self.projects.len();
-                    self.graph_selected = 0;
-                    self.graph_scroll_y = 0;
-                    self.refresh_entries();
+        }
+        match event {
+            AppEvent::Key(key) => match &mut self.screen {
+                Screen::Config(ref mut state) => {
+                    let action = state.handle_key(key);
+                    self.handle_config_action(action);
                 }
-            }
-            Action::PrevProject => {
-                if !self.projects.is_empty() {
-                    self.active_project = if self.active_project == 0 {
-                        self.projects.len() - 1
+                Screen::Graph => {
+                    let popup = if self.refs_popover.is_some() {
+                        Some(PopupKeys::Refs)
                     } else {
-                        self.active_project - 1
+                        self.stashes.as_ref().map(|_| PopupKeys::Stashes)
                     };
-                    self.graph_selected = 0;
-                    self.graph_scroll_y = 0;
-                    self.refresh_entries();
-                }
-            }
-            Action::ToggleViewMode => {
-                if let Some(proj) = self.projects.get_mut(self.active_project) {
-                    match proj.active_mode {
-                        ViewMode::Local => {
-                            if proj.remote_source.is_none() {
+                    let action = popup
+                        .and_then(|popup| input::map_popup_key(key, popup))
+                        .unwrap_or_else(|| input::map_key(key, self.filter_mode));
+                    match self.macros.feed(key, action, self.filter_mode) {
+                        MacroStep::Pass(action) => self.handle_graph_action(action),
+                        MacroStep::Consumed | MacroStep::Started(_) => {}
+                        MacroStep::Stopped(register, len) => self.notify(
+                            NotifyLevel::Info,
+                            format!("recorded {len} actions into @{register}"),
+                        ),
+                        MacroStep::Replay { register, actions } => {
+                            if actions.is_empty() {
                                 self.notify(
                                     NotifyLevel::Warn,
-                                    "no github token \u{2014} set github_token in config or GITHUB_TOKEN env",
+                                    format!("register @{register} is empty"),
                                 );
                                 return;
                             }
-                            proj.active_mode = ViewMode::Remote;
-                            if let Some(ref remote) = proj.remote_source {
-                                if let Some(ref tx) = self.event_tx {
-                                    let client = remote.client.clone();
-                                    let tx = tx.clone();
-                                    let idx = self.active_project;
-                                    let max = self.config.max_commits;
-                                    self.loading_remote = true;
-                                    tokio::spawn(async move {
-                                        let result =
-                                            crate::github::remote_loader::load_remote_repo_data(
-                                                &client, max,
-                                            )
-                                            .await;
-                                        let _ = tx.send(AppEvent::RemoteDataResult {
-                                            project_idx: idx,
-                                            result,
-                                        });
-                                    });
+                            // Or the first replayed action would only dismiss it
+                            self.notification = None;
+                            let total = actions.len();
+                            for (done, action) in actions.into_iter().enumerate() {
+                                self.handle_graph_action(action);
+                                // The answer is the user's to give, not the macro's
+                                if self.confirm.is_some() || self.confirm_quit {
+                                    let left = total - done - 1;
+                                    if left > 0 {
+                                        let s = if left == 1 { "" } else { "s" };
+                                        self.notify(
+                                            NotifyLevel::Warn,
+                                            format!(
+                                                "@{register} stopped at a confirmation, \
+                                                 {left} action{s} skipped"
+                                            ),
+                                        );
+                                    }
+                                    break;
                                 }
                             }
                         }
-                        ViewMode::Remote => {
-                            proj.active_mode = ViewMode::Local;
-                            self.rebuild_graph(self.active_project);
-                        }
                     }
                 }
-            }
-            Action::ToggleDetailPanel => {
-                self.show_detail = !self.show_detail;
-                if !self.show_detail && self.active_panel == Panel::Detail {
-                    self.active_panel = Panel::Graph;
-                }
-                if let Some(proj) = self.projects.get_mut(self.active_project) {
-                    proj.image_cache.clear(proj.max_lanes);
+            },
+            AppEvent::Mouse(mouse) => {
+                if matches!(self.screen, Screen::Graph) {
+                    self.handle_mouse(mouse);
                 }
             }
-            Action::Select => {
-                if self.active_panel == Panel::Branches {
-                    self.toggle_branch_section();
-                } else {
-                    self.show_detail = !self.show_detail;
+            AppEvent::GitHubResult {
+                project_idx,
+                result,
+            } => {
+                self.handle_github_result(project_idx, result);
+            }
+            AppEvent::RemoteDataResult {
+                project_idx,
+                result,
+                cached_at,
+            } => {
+                self.handle_remote_data_result(project_idx, result, cached_at);
+            }
+            AppEvent::GitHubProbed { reachable } => {
+                self.probing = false;
+                if reachable && self.offline == Some(Offline::Unreachable) {
+                    self.go_online();
                 }
             }
-            Action::ToggleForks => {
-                self.show_forks = !self.show_forks;
-                self.refresh_entries();
+            AppEvent::LocalDataResult {
+                project_idx,
+                result,
+            } => {
+                self.handle_local_data_result(project_idx, result);
             }
-            Action::Filter => self.filter_mode = FilterMode::Branch,
-            Action::AuthorFilter => self.filter_mode = FilterMode::Author,
-            Action::FilterChar(c) => match self.filter_mode {
-                FilterMode::Branch => {
-                    self.filter_text.push(c);
-                    self.refresh_entries();
+            AppEvent::LoadProgress {
+                project_idx,
+                walked,
+            } => {
+                if let Some(proj) = self.projects.get_mut(project_idx) {
+                    proj.load_progress = Some(walked);
                 }
-                FilterMode::Author => self.author_filter_text.push(c),
-                FilterMode::Off => {}
+            }
+            AppEvent::CommitFilesResult { oid, result } if self.detail_oid == Some(oid) => {
+                self.detail_files = Some(result.unwrap_or_else(|e| {
+                    // e.g. a fork commit that isn't in the local object db
+                    tracing::debug!(%oid, "changed files unavailable: {e}");
+                    Vec::new()
+                }));
+            }
+            AppEvent::CommitDiffResult { oid, result } if self.detail_oid == Some(oid) => {
+                self.detail_diff = Some(result.unwrap_or_else(Text::raw));
+            }
+            AppEvent::PatchesExported { result } => match result {
+                Ok((count, dir)) => self.notify(
+                    NotifyLevel::Info,
+                    format!("wrote {count} patches to {}", dir.display()),
+                ),
+                Err(e) => self.notify(NotifyLevel::Error, format!("export failed: {e}")),
             },
-            Action::FilterBackspace => match self.filter_mode {
-                FilterMode::Branch => {
-                    self.filter_text.pop();
-                    self.refresh_entries();
-                }
-                FilterMode::Author => {
-                    self.author_filter_text.pop();
+            AppEvent::FetchProgress {
+                project_idx,
+                progress,
+            } => {
+                if let Some(proj) = self.projects.get_mut(project_idx) {
+                    if proj.fetch_progress.is_some() {
+                        proj.fetch_progress = Some(progress);
+                    }
+                }
+            }
+            AppEvent::Fetched {
+                project_idx,
+                result,
+            } => self.handle_fetched(project_idx, result),
+            AppEvent::CloneDeepened {
+                project_idx,
+                result,
+            } => match result {
+                Ok(by) => {
+                    // The old handle still has the previous graft points
+                    let reopened = self
+                        .projects
+                        .get(project_idx)
+                        .and_then(|p| p.local_source.as_ref())
+                        .map(|l| l.worker.reopen());
+                    if let Some(Err(e)) = reopened {
+                        self.notify(NotifyLevel::Error, format!("{e}"));
+                        return;
+                    }
+                    self.notify(
+                        NotifyLevel::Info,
+                        format!("fetched up to {by} more commits of history"),
+                    );
+                    self.read_local(project_idx, false);
+                }
+                Err(e) => {
+                    if let Some(proj) = self.projects.get_mut(project_idx) {
+                        proj.loading = false;
+                    }
+                    self.notify(NotifyLevel::Error, format!("deepen failed: {e}"));
                 }
-                FilterMode::Off => {}
             },
-            Action::FilterConfirm => {
-                let was_author = self.filter_mode == FilterMode::Author;
-                self.filter_mode = FilterMode::Off;
-                if was_author {
-                    for idx in 0..self.projects.len() {
-                        self.rebuild_graph_author_only(idx);
+            AppEvent::ConflictPreviewResult { oid, result } => {
+                if self.conflict_preview_oid != Some(oid) {
+                    return;
+                }
+                match result {
+                    Ok(preview) => {
+                        if let Some(ref mut state) = self.conflict_preview {
+                            state.preview = Some(preview);
+                        }
+                    }
+                    Err(e) => {
+                        self.conflict_preview = None;
+                        self.notify(NotifyLevel::Error, format!("merge preview failed: {e}"));
                     }
-                    self.clamp_selected();
                 }
-                self.refresh_entries();
             }
-            Action::FilterCancel => {
-                match self.filter_mode {
-                    FilterMode::Branch => self.filter_text.clear(),
-                    FilterMode::Author => {
-                        self.author_filter_text.clear();
-                        self.filter_mode = FilterMode::Off;
-                        for idx in 0..self.projects.len() {
-                            self.rebuild_graph_author_only(idx);
-                        }
-                        self.clamp_selected();
-                        self.refresh_entries();
-                        return;
+            AppEvent::CherryPicked {
+                project_idx,
+                oid,
+                result,
+            } => {
+                let short = &oid.to_string()[..7];
+                match result {
+                    Ok(CherryPickOutcome::Picked(picked)) => {
+                        self.notify(
+                            NotifyLevel::Info,
+                            format!("cherry-picked {short} as {}", &picked.to_string()[..7]),
+                        );
+                        self.rebuild_graph(project_idx);
                     }
-                    FilterMode::Off => {}
+                    Ok(CherryPickOutcome::UpToDate) => {
+                        self.notify(NotifyLevel::Info, format!("HEAD already has {short}"))
+                    }
+                    Ok(CherryPickOutcome::Conflicts(paths)) => self.notify(
+                        NotifyLevel::Warn,
+                        format!(
+                            "cherry-pick of {short} aborted, it conflicts in {}",
+                            paths.join(", ")
+                        ),
+                    ),
+                    Err(e) => self.notify(
+                        NotifyLevel::Error,
+                        format!("cherry-picking {short} failed: {e}"),
+                    ),
                 }
-                self.filter_mode = FilterMode::Off;
-                self.refresh_entries();
             }
-            Action::Refresh => {
-                for idx in 0..self.projects.len() {
-                    self.rebuild_graph(idx);
+            AppEvent::PullRequestFound {
+                project_idx,
+                branch,
+                result,
+            } => match result {
+                Ok(Some(pr)) => {
+                    self.pr_merge = Some(PrMergeState {
+                        project_idx,
+                        branch,
+                        pr,
+                        selected: 0,
+                    });
+                }
+                Ok(None) => self.notify(NotifyLevel::Info, format!("no open PR for {branch}")),
+                Err(e) => self.notify(NotifyLevel::Error, format!("PR lookup failed: {e}")),
+            },
+            AppEvent::PullRequestMerged {
+                project_idx,
+                branch,
+                number,
+                result,
+            } => match result {
+                Ok(()) => {
+                    self.notify(NotifyLevel::Info, format!("merged #{number}"));
+                    self.refresh_after_remote_change(project_idx);
+                    self.confirm = Some(Confirm {
+                        title: " Delete branch ",
+                        question: format!("delete {branch} locally and on GitHub? (y/n)"),
+                        kind: ConfirmKind::DeleteMergedBranch {
+                            project_idx,
+                            branch,
+                        },
+                    });
+                }
+                Err(e) => self.notify(NotifyLevel::Error, format!("merging #{number} failed: {e}")),
+            },
+            AppEvent::BranchCreated {
+                project_idx,
+                branch,
+                result,
+            } => match result {
+                Ok(()) => {
+                    self.notify(NotifyLevel::Info, format!("created {branch}"));
+                    self.rebuild_graph(project_idx);
+                }
+                Err(e) => self.notify(NotifyLevel::Error, format!("creating {branch} failed: {e}")),
+            },
+            AppEvent::BranchDeleted {
+                project_idx,
+                branch,
+                remote,
+                result,
+            } => {
+                let place = if remote { "on GitHub" } else { "locally" };
+                // Only a merged PR's branch is deleted on GitHub; its local
+                // copy follows, unless that failed
+                if remote && result.is_ok() {
+                    self.delete_local_branch(project_idx, branch.clone());
+                }
+                match result {
+                    Ok(Some(deleted)) => {
+                        self.remember_deleted(project_idx, deleted);
+                        self.notify(
+                            NotifyLevel::Info,
+                            format!("deleted {branch} {place} \u{2014} U to undo"),
+                        );
+                    }
+                    Ok(None) => self.notify(NotifyLevel::Info, format!("deleted {branch} {place}")),
+                    Err(e) => self.notify(
+                        NotifyLevel::Error,
+                        format!("deleting {branch} {place} failed: {e}"),
+                    ),
                 }
+                self.refresh_after_remote_change(project_idx);
             }
-            Action::Help => self.show_help = !self.show_help,
-            Action::OpenConfig => {
-                let state = ConfigScreenState::new(&self.config);
-                self.screen = Screen::Config(Box::new(state));
+            AppEvent::TagDeleted {
+                project_idx,
+                tag,
+                result,
+            } => {
+                match result {
+                    Ok(deleted) => {
+                        self.remember_deleted(project_idx, deleted);
+                        self.notify(
+                            NotifyLevel::Info,
+                            format!("deleted tag {tag} \u{2014} U to undo"),
+                        );
+                    }
+                    Err(e) => self.notify(
+                        NotifyLevel::Error,
+                        format!("deleting tag {tag} failed: {e}"),
+                    ),
+                }
+                self.refresh_after_remote_change(project_idx);
             }
-            Action::ClosePopup => {
-                if self.show_help {
-                    self.show_help = false;
-                } else if self.show_detail {
-                    self.show_detail = false;
-                    if self.active_panel == Panel::Detail {
-                        self.active_panel = Panel::Graph;
+            AppEvent::BranchesPruned {
+                project_idx,
+                deleted,
+                failed,
+            } => {
+                let count = deleted.len();
+                for deleted in deleted {
+                    self.remember_deleted(project_idx, deleted);
+                }
+                if let Some((branch, e)) = failed.first() {
+                    self.notify(
+                        NotifyLevel::Error,
+                        format!("pruned {count}, {} failed: {branch}: {e}", failed.len()),
+                    );
+                } else {
+                    let noun = if count == 1 { "branch" } else { "branches" };
+                    self.notify(
+                        NotifyLevel::Info,
+                        format!("pruned {count} {noun} \u{2014} U to undo"),
+                    );
+                }
+                self.refresh_after_remote_change(project_idx);
+            }
+            AppEvent::CommitWeightsResult {
+                project_idx,
+                weights,
+            } => {
+                if let Some(proj) = self.projects.get_mut(project_idx) {
+                    proj.commit_weights.extend(weights);
+                }
+            }
+            AppEvent::DiffStatsResult { project_idx, stats } => {
+                if let Some(proj) = self.projects.get_mut(project_idx) {
+                    proj.diff_stats.extend(stats);
+                    proj.line_cache.clear();
+                }
+            }
+            AppEvent::RemoteOnlyFound { project_idx, oids } => {
+                if let Some(proj) = self
+                    .projects
+                    .get_mut(project_idx)
+                    .filter(|p| p.active_mode == ViewMode::Remote)
+                {
+                    proj.remote_only = oids.into_iter().collect();
+                }
+            }
+            AppEvent::TagsVerified {
+                project_idx,
+                results,
+            } => {
+                if let Some(proj) = self.projects.get_mut(project_idx) {
+                    for (name, target, signature) in results {
+                        proj.tag_signatures.insert((name, target), signature);
+                    }
+                    self.refresh_entries();
+                }
+            }
+            AppEvent::LargestFilesResult {
+                project_idx,
+                result,
+            } => {
+                let Some(ref mut state) = self.health else {
+                    return;
+                };
+                if project_idx != self.active_project {
+                    return;
+                }
+                state.largest_files = Some(result);
+            }
+            AppEvent::HeadMovesListed {
+                project_idx,
+                result,
+            } => {
+                let Some(ref mut state) = self.head_timeline else {
+                    return;
+                };
+                if project_idx != self.active_project {
+                    return;
+                }
+                state.loading = false;
+                match result {
+                    Ok(moves) => {
+                        let watched = self
+                            .projects
+                            .get(project_idx)
+                            .map_or(&[][..], |p| &p.watched_head_moves);
+                        state.entries = head_log::merge_watched(moves, watched, start_of_today());
+                        state.selected = state.selected.min(state.entries.len().saturating_sub(1));
+                    }
+                    Err(e) => {
+                        self.head_timeline = None;
+                        self.notify(
+                            NotifyLevel::Error,
+                            format!("reading the reflog failed: {e}"),
+                        );
+                    }
+                }
+            }
+            AppEvent::BlameResult {
+                project_idx,
+                result,
+            } => {
+                let Some(ref mut state) = self.blame else {
+                    return;
+                };
+                if project_idx != self.active_project {
+                    return;
+                }
+                state.loading = false;
+                match result {
+                    Ok(lines) => {
+                        state.lines = lines;
+                        state.move_by(0);
+                    }
+                    Err(e) => {
+                        self.blame = None;
+                        self.notify(NotifyLevel::Error, format!("blame failed: {e}"));
+                    }
+                }
+            }
+            AppEvent::StashesListed {
+                project_idx,
+                result,
+            } => {
+                let Some(ref mut state) = self.stashes else {
+                    return;
+                };
+                if project_idx != self.active_project {
+                    return;
+                }
+                state.loading = false;
+                match result {
+                    Ok(entries) => {
+                        state.selected = state.selected.min(entries.len().saturating_sub(1));
+                        state.entries = entries;
+                    }
+                    Err(e) => {
+                        self.stashes = None;
+                        self.notify(NotifyLevel::Error, format!("listing stashes failed: {e}"));
+                    }
+                }
+            }
+            AppEvent::CheckedOut {
+                project_idx,
+                name,
+                result,
+            } => match result {
+                Ok(()) => {
+                    self.notify(NotifyLevel::Info, format!("checked out {name}"));
+                    self.rebuild_graph(project_idx);
+                }
+                Err(e) => self.notify(NotifyLevel::Error, format!("checkout failed: {e}")),
+            },
+            AppEvent::StashOpDone {
+                project_idx,
+                op,
+                index,
+                dropped,
+                result,
+            } => {
+                match (result, dropped) {
+                    (Ok(_), Some(entry)) => {
+                        self.remember_deleted(
+                            project_idx,
+                            DeletedRef::Stash {
+                                message: entry.message,
+                                oid: entry.oid,
+                            },
+                        );
+                        self.notify(
+                            NotifyLevel::Info,
+                            format!("dropped stash@{{{index}}} \u{2014} U to undo"),
+                        );
+                    }
+                    (Ok(conflicts), None) if conflicts.is_empty() => self.notify(
+                        NotifyLevel::Info,
+                        format!("{} stash@{{{index}}}", op.verb()),
+                    ),
+                    (Ok(conflicts), None) => self.notify(
+                        NotifyLevel::Warn,
+                        format!(
+                            "stash@{{{index}}} applied with conflicts in {}; stash kept",
+                            conflicts.join(", ")
+                        ),
+                    ),
+                    (Err(e), _) => self.notify(
+                        NotifyLevel::Error,
+                        format!("stash {} failed: {e}", op.verb()),
+                    ),
+                }
+                self.rebuild_graph(project_idx);
+                if self.stashes.is_some() && project_idx == self.active_project {
+                    self.list_stashes();
+                }
+            }
+            AppEvent::RefRestored {
+                project_idx,
+                deleted,
+                result,
+            } => {
+                match result {
+                    Ok(()) => {
+                        self.recently_deleted
+                            .retain(|d| d.project_idx != project_idx || d.deleted != deleted);
+                        self.notify(NotifyLevel::Info, format!("restored {}", deleted.label()));
+                    }
+                    Err(e) => self.notify(
+                        NotifyLevel::Error,
+                        format!("restoring {} failed: {e}", deleted.label()),
+                    ),
+                }
+                self.rebuild_graph(project_idx);
+                if self.stashes.is_some() && project_idx == self.active_project {
+                    self.list_stashes();
+                }
+            }
+            AppEvent::Resize => {
+                self.graphics_cap.redetect_cell_size();
+                for proj in &mut self.projects {
+                    proj.image_cache.clear(proj.max_lanes);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_remote_data_result(
+        &mut self,
+        project_idx: usize,
+        result: std::result::Result<RepoData, String>,
+        cached_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) {
+        self.loading_remote = false;
+        if data_source::github_unreachable() {
+            self.note_unreachable();
+        }
+        if let Some(proj) = self.projects.get_mut(project_idx) {
+            match result {
+                Ok(data) => {
+                    proj.remote_only.clear();
+                    let oids: Vec<Oid> = data.commits.iter().map(|c| c.oid).collect();
+                    if let (Some(local), Some(tx)) =
+                        (proj.local_source.as_ref(), self.event_tx.clone())
+                    {
+                        let submitted = local.worker.submit(move |r| {
+                            let oids = oids
+                                .into_iter()
+                                .filter(|oid| r.find_commit(oid.to_git2()).is_err())
+                                .collect();
+                            let _ = tx.send(AppEvent::RemoteOnlyFound { project_idx, oids });
+                        });
+                        if let Err(e) = submitted {
+                            tracing::warn!("remote-only lookup failed: {e}");
+                        }
                     }
+                    proj.repo_data = data;
+                    proj.rows_mode = ViewMode::Remote;
+                    proj.rebuild_layout(&self.config.trunk_branches);
+                    proj.stale_remote = cached_at;
+                    proj.last_sync = match cached_at {
+                        Some(at) => self.dates.date_time(&at),
+                        None => JUST_NOW.to_string(),
+                    };
+                    self.set_graph_selected(0);
+                    self.set_graph_scroll_y(0);
+                    self.notification = None;
+                }
+                Err(e) => {
+                    self.notify(NotifyLevel::Error, e);
                 }
             }
-            Action::None => {}
         }
+        self.refresh_entries();
+        self.run_script();
+    }
+
+    fn handle_config_action(&mut self, action: ConfigAction) {
+        match action {
+            ConfigAction::Close => {
+                self.apply_theme();
+                self.screen = Screen::Graph;
+            }
+            ConfigAction::Save => {
+                if let Screen::Config(ref state) = self.screen {
+                    let new_config = state.draft.clone();
+                    if let Err(e) = new_config.save() {
+                        self.notify(NotifyLevel::Error, format!("save failed: {e}"));
+                        return;
+                    }
+                    let was_first_launch = state.first_launch;
+                    let old_repos = self.config.resolved_repos();
+                    self.config = new_config;
+                    if !was_first_launch {
+                        self.rename_projects(&old_repos);
+                    }
+                    self.apply_theme();
+                    self.screen = Screen::Graph;
+                    if was_first_launch {
+                        if let Err(e) = self.load_repos() {
+                            self.notify(NotifyLevel::Error, format!("load repos: {e}"));
+                        }
+                        if let Some(ref tx) = self.event_tx {
+                            let _ = tx.send(AppEvent::ConfigSaved);
+                        }
+                    }
+                    self.notify(NotifyLevel::Info, "config saved");
+                }
+            }
+            ConfigAction::SelectTheme => {
+                if let Screen::Config(ref mut state) = self.screen {
+                    let idx = state.cursor.min(THEME_NAMES.len().saturating_sub(1));
+                    let name = THEME_NAMES[idx];
+                    state.draft.theme = Some(name.to_string());
+                    state.dirty = true;
+                    self.set_palette(theme::palette_for_theme(Some(name)));
+                }
+            }
+            ConfigAction::Quit => {
+                self.should_quit = true;
+            }
+            ConfigAction::QuitConfirm => {
+                self.confirm_quit = true;
+            }
+            ConfigAction::AddItem => {
+                if let Screen::Config(ref mut state) = self.screen {
+                    state.add_item();
+                }
+            }
+            ConfigAction::RemoveItem => {
+                if let Screen::Config(ref mut state) = self.screen {
+                    state.remove_item();
+                }
+            }
+            ConfigAction::None => {}
+        }
+    }
+
+    // Added, removed or moved repos only take effect on the next launch
+    fn rename_projects(&mut self, old_repos: &[RepoEntry]) {
+        let new_repos = self.config.resolved_repos();
+        for (idx, (old, new)) in old_repos.iter().zip(&new_repos).enumerate() {
+            if old.path != new.path || old.name == new.name {
+                continue;
+            }
+            let Some(proj) = self.projects.get_mut(idx) else {
+                continue;
+            };
+            let name = match (&new.name, &proj.local_source) {
+                (Some(name), _) => name.clone(),
+                (None, Some(local)) => match local.worker.call(repo::detect_repo_name) {
+                    Ok(name) => name,
+                    Err(_) => continue,
+                },
+                (None, None) => continue,
+            };
+            proj.name = name;
+        }
+    }
+
+    fn handle_graph_action(&mut self, action: Action) {
+        if self.notification.is_some()
+            && !matches!(action, Action::None | Action::Quit | Action::ClosePopup)
+        {
+            self.notification = None;
+            return;
+        }
+        if self.show_help {
+            let page = self.help_page.max(1);
+            let scroll = match action {
+                Action::ScrollDown => Some(self.help_scroll.saturating_add(1)),
+                Action::ScrollUp => Some(self.help_scroll.saturating_sub(1)),
+                Action::PageDown => Some(self.help_scroll.saturating_add(page)),
+                Action::PageUp => Some(self.help_scroll.saturating_sub(page)),
+                _ => None,
+            };
+            if let Some(scroll) = scroll {
+                self.help_scroll = scroll;
+                return;
+            }
+        }
+        if let Some(ref mut heatmap) = self.heatmap {
+            let today = chrono::Local::now().date_naive();
+            match action {
+                Action::ScrollDown => heatmap.move_by(1, today),
+                Action::ScrollUp => heatmap.move_by(-1, today),
+                Action::PanelRight => heatmap.move_by(7, today),
+                Action::PanelLeft => heatmap.move_by(-7, today),
+                Action::Select => {
+                    let day = heatmap.selected;
+                    self.jump_to_day(day);
+                }
+                Action::ToggleHeatmap | Action::ClosePopup => self.heatmap = None,
+                Action::Quit => self.should_quit = true,
+                _ => {}
+            }
+            return;
+        }
+        if self.health.is_some() {
+            match action {
+                Action::ShowHealth | Action::ClosePopup => self.health = None,
+                Action::Quit => self.should_quit = true,
+                _ => {}
+            }
+            return;
+        }
+        if let Some(ref mut releases) = self.releases {
+            match action {
+                // Listed newest first, so down is older
+                Action::ScrollDown => releases.selected = releases.selected.saturating_sub(1),
+                Action::ScrollUp if releases.selected + 1 < releases.releases.len() => {
+                    releases.selected += 1;
+                }
+                Action::Select => {
+                    if let Some(release) = releases.releases.get(releases.selected) {
+                        let (name, target) = (release.name.clone(), release.target);
+                        self.jump_to_release(&name, target);
+                    }
+                }
+                Action::ToggleReleases | Action::ClosePopup => self.releases = None,
+                Action::Quit => self.should_quit = true,
+                _ => {}
+            }
+            return;
+        }
+        if let Some(ref mut pr_merge) = self.pr_merge {
+            match action {
+                Action::ScrollDown if pr_merge.selected + 1 < MergeMethod::ALL.len() => {
+                    pr_merge.selected += 1;
+                }
+                Action::ScrollUp => pr_merge.selected = pr_merge.selected.saturating_sub(1),
+                Action::Select => {
+                    if let Some(state) = self.pr_merge.take() {
+                        let method = state.method();
+                        self.confirm = Some(Confirm {
+                            title: " Merge PR ",
+                            question: format!("{} #{}? (y/n)", method.label(), state.pr.number),
+                            kind: ConfirmKind::MergePr {
+                                project_idx: state.project_idx,
+                                branch: state.branch,
+                                number: state.pr.number,
+                                method,
+                            },
+                        });
+                    }
+                }
+                Action::MergePullRequest | Action::ClosePopup => self.pr_merge = None,
+                Action::Quit => self.should_quit = true,
+                _ => {}
+            }
+            return;
+        }
+        if let Some(ref state) = self.rebase_preview {
+            match action {
+                Action::Select => {
+                    let onto = state.onto_tip;
+                    self.rebase_preview = None;
+                    self.jump_to_commit(onto, "new base");
+                }
+                Action::PreviewRebase | Action::ClosePopup => self.rebase_preview = None,
+                Action::Quit => self.should_quit = true,
+                _ => {}
+            }
+            return;
+        }
+        if self.conflict_preview.is_some() {
+            match action {
+                Action::PreviewMerge | Action::ClosePopup | Action::Select => {
+                    self.conflict_preview = None;
+                    self.conflict_preview_oid = None;
+                }
+                Action::Quit => self.should_quit = true,
+                _ => {}
+            }
+            return;
+        }
+        if let Some(ref mut state) = self.deleted_refs {
+            match action {
+                Action::ScrollDown if state.selected + 1 < self.recently_deleted.len() => {
+                    state.selected += 1;
+                }
+                Action::ScrollUp => state.selected = state.selected.saturating_sub(1),
+                Action::Select => {
+                    let selected = state.selected;
+                    self.deleted_refs = None;
+                    self.restore_deleted(selected);
+                }
+                Action::ShowDeleted | Action::ClosePopup => self.deleted_refs = None,
+                Action::Quit => self.should_quit = true,
+                _ => {}
+            }
+            return;
+        }
+        if let Some(ref mut state) = self.head_timeline {
+            match action {
+                Action::ScrollDown if state.selected + 1 < state.entries.len() => {
+                    state.selected += 1;
+                }
+                Action::ScrollUp => state.selected = state.selected.saturating_sub(1),
+                Action::Select => {
+                    let oid = state.entries.get(state.selected).map(|m| m.oid);
+                    self.head_timeline = None;
+                    if let Some(oid) = oid {
+                        self.jump_to_commit(oid, &format!("{:.7}", oid.to_string()));
+                    }
+                }
+                Action::HeadTimeline | Action::ClosePopup => self.head_timeline = None,
+                Action::Quit => self.should_quit = true,
+                _ => {}
+            }
+            return;
+        }
+        if let Some(ref mut state) = self.blame {
+            let page = state.page.max(1) as isize;
+            match action {
+                Action::ScrollDown => state.move_by(1),
+                Action::ScrollUp => state.move_by(-1),
+                Action::PageDown => state.move_by(page),
+                Action::PageUp => state.move_by(-page),
+                Action::JumpTop => state.selected = 0,
+                Action::JumpBottom => state.move_by(isize::MAX),
+                Action::Select => {
+                    let oid = state.lines.get(state.selected).map(|l| l.oid);
+                    if let Some(oid) = oid {
+                        self.blame = None;
+                        self.jump_to_commit(oid, &format!("{:.7}", oid.to_string()));
+                    }
+                }
+                Action::Blame | Action::ClosePopup => self.blame = None,
+                Action::Quit => self.should_quit = true,
+                _ => {}
+            }
+            return;
+        }
+        if let Some(ref mut state) = self.refs_popover {
+            let item = state.refs.get(state.selected).cloned();
+            match action {
+                Action::ScrollDown if state.selected + 1 < state.refs.len() => {
+                    state.selected += 1;
+                }
+                Action::ScrollUp => state.selected = state.selected.saturating_sub(1),
+                Action::Select => {
+                    let oid = state.oid;
+                    self.refs_popover = None;
+                    if let Some(item) = item {
+                        self.jump_to_ref(oid, &item);
+                    }
+                }
+                Action::CheckoutRef => {
+                    self.refs_popover = None;
+                    if let Some(item) = item {
+                        self.checkout(item);
+                    }
+                }
+                Action::RefPopover | Action::ClosePopup => self.refs_popover = None,
+                Action::Quit => self.should_quit = true,
+                _ => {}
+            }
+            return;
+        }
+        if let Some(ref mut stashes) = self.stashes {
+            let selected = stashes.entries.get(stashes.selected).map(|s| s.index);
+            match action {
+                Action::ScrollDown if stashes.selected + 1 < stashes.entries.len() => {
+                    stashes.selected += 1;
+                }
+                Action::ScrollUp => stashes.selected = stashes.selected.saturating_sub(1),
+                Action::Select | Action::StashApply => self.confirm_stash(StashOp::Apply, selected),
+                Action::StashPop => self.confirm_stash(StashOp::Pop, selected),
+                Action::StashDrop => self.confirm_stash(StashOp::Drop, selected),
+                Action::ToggleStashes | Action::ClosePopup => self.stashes = None,
+                Action::Quit => self.should_quit = true,
+                _ => {}
+            }
+            return;
+        }
+        if let Some(ref mut cmp) = self.compare {
+            match action {
+                Action::ScrollDown => cmp.move_selection(true),
+                Action::ScrollUp => cmp.move_selection(false),
+                Action::PanelLeft => cmp.focus = 0,
+                Action::PanelRight => cmp.focus = 1,
+                Action::Compare => self.close_comparison(),
+                Action::ClosePopup if !self.show_help => self.close_comparison(),
+                Action::Quit | Action::Help | Action::ClosePopup => {}
+                _ => return,
+            }
+            if !matches!(action, Action::Quit | Action::Help | Action::ClosePopup) {
+                return;
+            }
+        }
+        if let Some(ref mut agg) = self.aggregate {
+            match action {
+                Action::ScrollDown => agg.move_selection(true),
+                Action::ScrollUp => agg.move_selection(false),
+                Action::Select => {
+                    let target = agg
+                        .rows
+                        .get(agg.selected)
+                        .map(|r| (r.project_idx, r.commit.oid));
+                    self.aggregate = None;
+                    if let Some((idx, oid)) = target {
+                        self.focus_project(idx);
+                        self.jump_to_commit(oid, &format!("{:.7}", oid.to_string()));
+                    }
+                }
+                Action::Aggregate => self.aggregate = None,
+                Action::ClosePopup if !self.show_help => self.aggregate = None,
+                Action::Quit | Action::Help | Action::ClosePopup => {}
+                _ => return,
+            }
+            if !matches!(action, Action::Quit | Action::Help | Action::ClosePopup) {
+                return;
+            }
+        }
+        if let Some(lane) = self.lane_cursor {
+            let lanes = self
+                .projects
+                .get(self.active_project)
+                .map_or(0, |p| p.max_lanes);
+            match action {
+                Action::PanelLeft => self.lane_cursor = Some(lane.saturating_sub(1)),
+                Action::PanelRight => {
+                    self.lane_cursor = Some((lane + 1).min(lanes.saturating_sub(1)))
+                }
+                Action::InspectLanes | Action::ClosePopup => self.lane_cursor = None,
+                _ => {}
+            }
+            if matches!(
+                action,
+                Action::PanelLeft | Action::PanelRight | Action::InspectLanes | Action::ClosePopup
+            ) {
+                return;
+            }
+        }
+        match action {
+            Action::Quit => self.should_quit = true,
+            Action::ScrollDown => match self.active_panel {
+                Panel::Detail if self.show_diff => {
+                    let lines = self.detail_diff.as_ref().map_or(0, |t| t.lines.len());
+                    if self.detail_diff_scroll + 1 < lines {
+                        self.detail_diff_scroll += 1;
+                    }
+                }
+                Panel::Detail if self.detail_files.as_ref().is_some_and(|f| !f.is_empty()) => {
+                    let count = self.detail_files.as_ref().map_or(0, Vec::len);
+                    if self.detail_file_selected + 1 < count {
+                        self.detail_file_selected += 1;
+                    }
+                }
+                Panel::Graph | Panel::Detail => {
+                    if let Some(proj) = self.projects.get(self.active_project) {
+                        if proj.graph_selected + 1 < proj.rows.len() {
+                            self.set_graph_selected(proj.graph_selected + 1);
+                        }
+                    }
+                }
+                Panel::Branches => {
+                    if !self.cached_entries.is_empty() {
+                        let mut next = self.branch_selected() + 1;
+                        while next < self.cached_entries.len()
+                            && self.cached_entries[next].is_spacer()
+                        {
+                            next += 1;
+                        }
+                        if next < self.cached_entries.len() {
+                            self.set_branch_selected(next);
+                        }
+                    }
+                }
+            },
+            Action::ScrollUp => match self.active_panel {
+                Panel::Detail if self.show_diff => {
+                    self.detail_diff_scroll = self.detail_diff_scroll.saturating_sub(1);
+                }
+                Panel::Detail if self.detail_files.as_ref().is_some_and(|f| !f.is_empty()) => {
+                    self.detail_file_selected = self.detail_file_selected.saturating_sub(1);
+                }
+                Panel::Graph | Panel::Detail => {
+                    if self.graph_selected() > 0 {
+                        self.set_graph_selected(self.graph_selected().saturating_sub(1));
+                    }
+                }
+                Panel::Branches => {
+                    if !self.cached_entries.is_empty() && self.branch_selected() > 0 {
+                        let mut prev = (self.branch_selected() - 1)
+                            .min(self.cached_entries.len().saturating_sub(1));
+                        while prev > 0 && self.cached_entries[prev].is_spacer() {
+                            prev -= 1;
+                        }
+                        if !self.cached_entries[prev].is_spacer() {
+                            self.set_branch_selected(prev);
+                        }
+                    }
+                }
+            },
+            Action::PageDown | Action::PageUp | Action::JumpTop | Action::JumpBottom
+                if self.active_panel == Panel::Branches =>
+            {
+                let page = self.branch_page.max(1);
+                let last = self.cached_entries.len().saturating_sub(1);
+                let (target, forward) = match action {
+                    Action::PageDown => ((self.branch_selected() + page).min(last), true),
+                    Action::PageUp => (self.branch_selected().saturating_sub(page), false),
+                    Action::JumpTop => (0, true),
+                    _ => (last, false),
+                };
+                self.select_branch_near(target, forward);
+            }
+            Action::PageDown | Action::PageUp | Action::JumpTop | Action::JumpBottom
+                if self.active_panel == Panel::Detail && !self.show_diff =>
+            {
+                // Clamped to the message's length at the next render
+                let page = self.detail_message_page.max(1);
+                self.detail_message_scroll = match action {
+                    Action::PageDown => self.detail_message_scroll.saturating_add(page),
+                    Action::PageUp => self.detail_message_scroll.saturating_sub(page),
+                    Action::JumpTop => 0,
+                    _ => usize::MAX,
+                };
+            }
+            Action::NextSection if self.active_panel == Panel::Branches => {
+                let next = self
+                    .cached_entries
+                    .iter()
+                    .enumerate()
+                    .skip(self.branch_selected() + 1)
+                    .find(|(_, e)| e.is_header());
+                if let Some((idx, _)) = next {
+                    self.set_branch_selected(idx);
+                }
+            }
+            Action::PrevSection if self.active_panel == Panel::Branches => {
+                let prev = self.cached_entries
+                    [..self.branch_selected().min(self.cached_entries.len())]
+                    .iter()
+                    .rposition(|e| e.is_header());
+                if let Some(idx) = prev {
+                    self.set_branch_selected(idx);
+                }
+            }
+            Action::PageDown
+            | Action::PageUp
+            | Action::JumpTop
+            | Action::JumpBottom
+            | Action::NextSection
+            | Action::PrevSection => {}
+            Action::ScrollLeft => {
+                if let Some(proj) = self.projects.get_mut(self.active_project) {
+                    proj.scroll_x = proj.scroll_x.saturating_sub(4);
+                }
+            }
+            Action::ScrollRight => {
+                if let Some(proj) = self.projects.get_mut(self.active_project) {
+                    proj.scroll_x = proj.scroll_x.saturating_add(4);
+                }
+            }
+            Action::PanelLeft => {
+                self.active_panel = match self.active_panel {
+                    Panel::Detail => Panel::Graph,
+                    Panel::Graph => Panel::Branches,
+                    Panel::Branches => Panel::Branches,
+                };
+            }
+            Action::PanelRight => {
+                self.active_panel = match self.active_panel {
+                    Panel::Branches => Panel::Graph,
+                    Panel::Graph if self.show_detail => Panel::Detail,
+                    Panel::Graph => Panel::Graph,
+                    Panel::Detail => Panel::Detail,
+                };
+            }
+            Action::NextProject | Action::PrevProject if self.split.is_some() => {
+                self.swap_split_focus();
+            }
+            Action::NextProject | Action::PrevProject => {
+                let members = self.workspace_projects();
+                let len = members.len();
+                if len == 0 {
+                    return;
+                }
+                let pos = members
+                    .iter()
+                    .position(|&i| i == self.active_project)
+                    .unwrap_or(0);
+                let next = if action == Action::NextProject {
+                    (pos + 1) % len
+                } else {
+                    (pos + len - 1) % len
+                };
+                self.focus_project(members[next]);
+            }
+            Action::ToggleViewMode => self.toggle_view_mode(),
+            Action::ToggleDetailPanel => {
+                self.show_detail = !self.show_detail;
+                if !self.show_detail && self.active_panel == Panel::Detail {
+                    self.active_panel = Panel::Graph;
+                }
+                if let Some(proj) = self.projects.get_mut(self.active_project) {
+                    proj.image_cache.clear(proj.max_lanes);
+                }
+            }
+            Action::Select => {
+                if self.active_panel == Panel::Branches {
+                    self.toggle_branch_section();
+                } else {
+                    self.show_detail = !self.show_detail;
+                }
+            }
+            Action::ToggleForks => {
+                self.show_forks = !self.show_forks;
+                self.refresh_entries();
+            }
+            Action::Filter => self.filter_mode = FilterMode::Branch,
+            Action::AuthorFilter => self.filter_mode = FilterMode::Author,
+            Action::FilterChar(c) => match self.filter_mode {
+                FilterMode::Branch => {
+                    self.filter_text.push(c);
+                    self.refresh_entries();
+                }
+                FilterMode::Author => self.author_filter_text.push(c),
+                FilterMode::Prompt => {
+                    if let Some(ref mut prompt) = self.prompt {
+                        prompt.text.push(c);
+                    }
+                }
+                FilterMode::Off => {}
+            },
+            Action::FilterBackspace => match self.filter_mode {
+                FilterMode::Branch => {
+                    self.filter_text.pop();
+                    self.refresh_entries();
+                }
+                FilterMode::Author => {
+                    self.author_filter_text.pop();
+                }
+                FilterMode::Prompt => {
+                    if let Some(ref mut prompt) = self.prompt {
+                        prompt.text.pop();
+                    }
+                }
+                FilterMode::Off => {}
+            },
+            Action::FilterConfirm if self.filter_mode == FilterMode::Prompt => {
+                self.filter_mode = FilterMode::Off;
+                if let Some(prompt) = self.prompt.take() {
+                    self.answer_prompt(prompt);
+                }
+            }
+            Action::FilterConfirm => {
+                let was_author = self.filter_mode == FilterMode::Author;
+                self.filter_mode = FilterMode::Off;
+                if was_author {
+                    for idx in 0..self.projects.len() {
+                        self.rebuild_graph_author_only(idx);
+                    }
+                    self.clamp_selected();
+                }
+                self.refresh_entries();
+            }
+            Action::FilterCancel => {
+                match self.filter_mode {
+                    FilterMode::Branch => self.filter_text.clear(),
+                    FilterMode::Author => {
+                        self.filter_mode = FilterMode::Off;
+                        self.clear_filter(FilterChip::Author);
+                        return;
+                    }
+                    FilterMode::Prompt => {
+                        self.prompt = None;
+                        self.filter_mode = FilterMode::Off;
+                        return;
+                    }
+                    FilterMode::Off => {}
+                }
+                self.filter_mode = FilterMode::Off;
+                self.refresh_entries();
+            }
+            Action::ClearFilter => {
+                if let Some((chip, _)) = self.status_bar().chips().pop() {
+                    self.clear_filter(chip);
+                }
+            }
+            Action::ClearChip(n) => {
+                if let Some((chip, _)) = self.status_bar().chips().into_iter().nth(n) {
+                    self.clear_filter(chip);
+                }
+            }
+            Action::Refresh => {
+                // Parked projects and other workspaces are read when shown
+                let shown: Vec<usize> = self
+                    .workspace_projects()
+                    .into_iter()
+                    .filter(|&idx| !self.projects[idx].parked)
+                    .collect();
+                for idx in shown {
+                    if self.manual_refresh {
+                        // No poller asks GitHub either
+                        self.refresh_after_remote_change(idx);
+                    } else {
+                        self.rebuild_graph(idx);
+                    }
+                }
+            }
+            Action::Help => {
+                self.show_help = !self.show_help;
+                self.help_scroll = 0;
+            }
+            Action::TogglePerfHud => self.show_perf_hud = !self.show_perf_hud,
+            Action::OpenDifftool => self.open_difftool(),
+            Action::OpenInEditor => self.open_in_editor(),
+            Action::ToggleDiff => {
+                self.show_diff = !self.show_diff;
+                if self.show_diff {
+                    self.show_detail = true;
+                }
+                if let Some(proj) = self.projects.get_mut(self.active_project) {
+                    proj.image_cache.clear(proj.max_lanes);
+                }
+            }
+            Action::VisualSelect => {
+                if self.active_panel == Panel::Graph {
+                    self.visual_anchor = match self.visual_anchor {
+                        Some(_) => None,
+                        None => Some(self.graph_selected()),
+                    };
+                }
+            }
+            Action::JumpToUnseen => self.jump_to_unseen(),
+            Action::JumpToHead => {
+                let head = self.projects.get(self.active_project).and_then(|p| {
+                    p.repo_data
+                        .head
+                        .or_else(|| p.cached_repo_data.as_ref()?.head)
+                });
+                match head {
+                    Some(oid) => self.jump_to_commit(oid, "HEAD"),
+                    None => self.notify(NotifyLevel::Warn, "no HEAD commit to jump to"),
+                }
+            }
+            Action::JumpToBranchTip => self.jump_to_branch_tip(),
+            Action::InspectLanes if self.list_view => {
+                self.notify(NotifyLevel::Info, "lanes are in the graph view (V)");
+            }
+            Action::InspectLanes => {
+                let lane = self
+                    .projects
+                    .get(self.active_project)
+                    .and_then(|p| p.rows.get(p.graph_selected))
+                    .map(|r| r.layout.commit_lane);
+                if let Some(lane) = lane {
+                    self.active_panel = Panel::Graph;
+                    self.lane_cursor = Some(lane);
+                }
+            }
+            Action::ShowDeleted => {
+                self.recently_deleted.retain(|d| !d.expired());
+                self.deleted_refs = Some(DeletedRefsState { selected: 0 });
+            }
+            Action::NewBranch => self.start_new_branch(),
+            Action::DeleteBranch => self.start_delete_branch(),
+            Action::PruneBranches => self.start_prune(),
+            Action::ToggleFollow => {
+                self.follow = !self.follow;
+                if self.follow {
+                    self.set_graph_selected(0);
+                    self.set_graph_scroll_y(0);
+                    self.visual_anchor = None;
+                } else {
+                    self.flash = None;
+                }
+            }
+            Action::ToggleTrunkDistance => {
+                self.show_trunk_distance = !self.show_trunk_distance;
+                for proj in &mut self.projects {
+                    proj.line_cache.clear();
+                }
+            }
+            Action::ToggleDiffstat => {
+                self.show_diffstat = !self.show_diffstat;
+                for proj in &mut self.projects {
+                    proj.line_cache.clear();
+                }
+            }
+            Action::ToggleMilestones => {
+                self.tag_milestones = !self.tag_milestones;
+                for proj in &mut self.projects {
+                    proj.line_cache.clear();
+                }
+            }
+            Action::FilterByRowAuthor => {
+                let author = self
+                    .projects
+                    .get(self.active_project)
+                    .and_then(|p| p.rows.get(p.graph_selected))
+                    .map(|r| r.meta.author.clone());
+                if let Some(author) = author {
+                    self.toggle_author_filter(author);
+                }
+            }
+            Action::TypeFilter => {
+                self.prompt = Some(Prompt {
+                    label: "commit types",
+                    text: self.type_filter.join(" "),
+                    kind: PromptKind::CommitTypes,
+                });
+                self.filter_mode = FilterMode::Prompt;
+            }
+            Action::ToggleListView => {
+                self.list_view = !self.list_view;
+                self.lane_cursor = None;
+            }
+            Action::ExportPatches => self.start_patch_export(),
+            Action::Compare => self.start_compare(),
+            Action::Aggregate => self.open_aggregate(),
+            Action::Annotate => self.start_annotate(),
+            Action::DeepenClone => self.start_deepen(),
+            Action::FetchRemotes => self.start_fetch(),
+            Action::SwitchWorkspace => {
+                self.prompt = Some(Prompt {
+                    label: "workspace",
+                    text: self.workspace.clone().unwrap_or_default(),
+                    kind: PromptKind::Workspace,
+                });
+                self.filter_mode = FilterMode::Prompt;
+            }
+            Action::PreviewMerge => self.start_conflict_preview(),
+            Action::PreviewRebase => self.start_rebase_preview(),
+            Action::CherryPick => self.start_cherry_pick(),
+            Action::MergePullRequest => self.start_pr_merge(),
+            Action::OpenPullRequest => match self.selected_pull_request_url() {
+                Ok(url) => {
+                    if let Err(e) = external::open_url(&url) {
+                        self.notify(NotifyLevel::Error, format!("opening {url} failed: {e}"));
+                    }
+                }
+                Err(why) => self.notify(NotifyLevel::Warn, why),
+            },
+            Action::WatchBranch => self.toggle_watch(),
+            Action::ToggleStashes => {
+                if self.projects.get(self.active_project).is_none() {
+                    return;
+                }
+                self.stashes = Some(StashesState {
+                    entries: Vec::new(),
+                    selected: 0,
+                    loading: true,
+                });
+                self.list_stashes();
+            }
+            Action::HeadTimeline => self.open_head_timeline(),
+            Action::ToggleOffline => self.toggle_offline(),
+            Action::Blame => self.open_blame(),
+            Action::RefPopover => self.open_ref_popover(),
+            Action::ToggleHeatmap => {
+                let Some(proj) = self.projects.get(self.active_project) else {
+                    return;
+                };
+                let counts = stats::daily_counts(&proj.repo_data, &chrono::Local);
+                let selected = proj
+                    .repo_data
+                    .commits
+                    .iter()
+                    .map(|c| c.time)
+                    .max()
+                    .map(|t| t.with_timezone(&chrono::Local).date_naive())
+                    .unwrap_or_else(|| chrono::Local::now().date_naive());
+                self.heatmap = Some(HeatmapState {
+                    counts,
+                    selected,
+                    author: (!self.author_filter_text.is_empty())
+                        .then(|| self.author_filter_text.clone()),
+                });
+            }
+            Action::ToggleReleases => {
+                let Some(proj) = self.projects.get(self.active_project) else {
+                    return;
+                };
+                let releases = stats::release_timeline(&proj.repo_data);
+                self.releases = Some(ReleasesState {
+                    selected: releases.len().saturating_sub(1),
+                    releases,
+                });
+            }
+            Action::ShowHealth => self.open_health(),
+            Action::ToggleSplit => {
+                if self.split.take().is_none() {
+                    let members = self.workspace_projects();
+                    if members.len() < 2 {
+                        self.notify(NotifyLevel::Warn, "split view needs two projects");
+                        return;
+                    }
+                    let pos = members
+                        .iter()
+                        .position(|&i| i == self.active_project)
+                        .unwrap_or(0);
+                    let project = members[(pos + 1) % members.len()];
+                    self.split = Some(SplitPane { project });
+                    self.unpark(project);
+                }
+                for proj in &mut self.projects {
+                    proj.image_cache.clear(proj.max_lanes);
+                }
+            }
+            Action::OpenConfig => {
+                let state = ConfigScreenState::new(&self.config);
+                self.screen = Screen::Config(Box::new(state));
+            }
+            Action::ClosePopup => {
+                if self.show_help {
+                    self.show_help = false;
+                } else if self.visual_anchor.is_some() {
+                    self.visual_anchor = None;
+                } else if self.show_detail {
+                    self.show_detail = false;
+                    if self.active_panel == Panel::Detail {
+                        self.active_panel = Panel::Graph;
+                    }
+                }
+            }
+            // Only mapped while their popup is open
+            Action::StashApply
+            | Action::StashPop
+            | Action::StashDrop
+            | Action::CheckoutRef
+            | Action::None => {}
+        }
+    }
+
+    fn open_difftool(&mut self) {
+        let Some(proj) = self.projects.get(self.active_project) else {
+            return;
+        };
+        let Some(row) = proj.rows.get(proj.graph_selected) else {
+            return;
+        };
+        let Some(workdir) = proj.local_source.as_ref().and_then(|l| l.worker.workdir()) else {
+            self.notify(NotifyLevel::Warn, "difftool needs a local checkout");
+            return;
+        };
+        let oid = row.meta.oid;
+        let base = proj
+            .dag
+            .nodes
+            .get(&oid)
+            .and_then(|n| n.commit.parents.first())
+            .map(|p| p.to_string())
+            .unwrap_or_else(|| repo::EMPTY_TREE.to_string());
+
+        let mut cmd = ExternalCommand::new("difftool", "git")
+            .cwd(workdir)
+            .arg("difftool")
+            .arg("--no-prompt");
+        if let Some(ref tool) = self.config.difftool {
+            cmd = cmd.arg(format!("--tool={tool}"));
+        }
+        self.pending_command = Some(cmd.arg(base).arg(oid.to_string()));
+    }
+
+    fn open_in_editor(&mut self) {
+        let file = match (&self.active_panel, &self.detail_files) {
+            (Panel::Detail, Some(files)) => files.get(self.detail_file_selected),
+            _ => None,
+        };
+        let (Some(file), Some(oid)) = (file, self.detail_oid) else {
+            self.notify(NotifyLevel::Warn, "select a file in the detail panel");
+            return;
+        };
+        let Some(local) = self
+            .projects
+            .get(self.active_project)
+            .and_then(|p| p.local_source.as_ref())
+        else {
+            return;
+        };
+        let path = file.path.clone();
+        let blob = local
+            .worker
+            .call({
+                let path = path.clone();
+                move |r| diff::blob_at(r, oid, &path)
+            })
+            .and_then(|r| r);
+        let contents = match blob {
+            Ok(Some(contents)) => contents,
+            Ok(None) => {
+                self.notify(
+                    NotifyLevel::Warn,
+                    format!("{path} not found at this commit"),
+                );
+                return;
+            }
+            Err(e) => {
+                self.notify(NotifyLevel::Error, format!("{path}: {e}"));
+                return;
+            }
+        };
+
+        // Keep the file name so the editor picks the right syntax
+        let name = std::path::Path::new(&path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let sha = oid.to_string();
+        let written = tempfile::Builder::new()
+            .prefix(&format!("{}-", &sha[..8]))
+            .suffix(&format!("-{name}"))
+            .tempfile()
+            .and_then(|mut tmp| {
+                use std::io::Write;
+                tmp.write_all(&contents)?;
+                Ok(tmp.into_temp_path())
+            });
+        let temp_path = match written {
+            Ok(p) => p,
+            Err(e) => {
+                self.notify(NotifyLevel::Error, format!("temp file: {e}"));
+                return;
+            }
+        };
+
+        let Some(mut cmd) =
+            ExternalCommand::from_command_line("editor", &crate::external::editor_command())
+        else {
+            return;
+        };
+        cmd = cmd.arg(temp_path.to_string_lossy());
+        cmd.temp_files.push(temp_path);
+        self.pending_command = Some(cmd);
+    }
+
+    // Oldest first, merges left out as git format-patch does
+    fn patch_export_commits(&self) -> Vec<Oid> {
+        let Some(proj) = self.projects.get(self.active_project) else {
+            return Vec::new();
+        };
+        let picked: Vec<_> = match self.active_panel {
+            Panel::Branches => {
+                let tip = self
+                    .cached_entries
+                    .get(self.branch_selected())
+                    .and_then(|e| e.tip_oid());
+                let branch = tip
+                    .and_then(|tip| proj.rows.iter().find(|r| r.meta.oid == tip))
+                    .and_then(|r| r.meta.branch_index);
+                match branch {
+                    Some(idx) => proj
+                        .rows
+                        .iter()
+                        .filter(|r| r.meta.branch_index == Some(idx))
+                        .collect(),
+                    None => Vec::new(),
+                }
+            }
+            Panel::Graph | Panel::Detail => {
+                let anchor = self.visual_anchor.unwrap_or(self.graph_selected());
+                let lo = anchor.min(self.graph_selected());
+                let hi = anchor.max(self.graph_selected());
+                let range: Vec<_> = proj.rows.iter().skip(lo).take(hi - lo + 1).collect();
+                // Only what the newest selected commit builds on, not other
+                // branches' rows that happen to sit in between
+                let in_range: HashSet<Oid> = range.iter().map(|r| r.meta.oid).collect();
+                let mut ancestry = HashSet::new();
+                let mut stack: Vec<Oid> = range.first().map(|r| r.meta.oid).into_iter().collect();
+                while let Some(oid) = stack.pop() {
+                    if !in_range.contains(&oid) || !ancestry.insert(oid) {
+                        continue;
+                    }
+                    if let Some(node) = proj.dag.nodes.get(&oid) {
+                        stack.extend(node.commit.parents.iter().copied());
+                    }
+                }
+                range
+                    .into_iter()
+                    .filter(|r| ancestry.contains(&r.meta.oid))
+                    .collect()
+            }
+        };
+        picked
+            .into_iter()
+            .rev()
+            .filter(|r| !r.meta.is_merge)
+            .map(|r| r.meta.oid)
+            .collect()
+    }
+
+    fn start_patch_export(&mut self) {
+        let Some(workdir) = self
+            .projects
+            .get(self.active_project)
+            .and_then(|p| p.local_source.as_ref())
+            .and_then(|l| l.worker.workdir())
+            .map(|w| w.to_path_buf())
+        else {
+            self.notify(
+                NotifyLevel::Warn,
+                "exporting patches needs a local checkout",
+            );
+            return;
+        };
+        let oids = self.patch_export_commits();
+        if oids.is_empty() {
+            self.notify(NotifyLevel::Warn, "no non-merge commits selected");
+            return;
+        }
+        // Beside the worktree, where the patches won't show up as untracked files
+        let name = workdir.file_name().unwrap_or_default().to_string_lossy();
+        let dir = match workdir.parent() {
+            Some(parent) => parent.join(format!("{name}-patches")),
+            None => PathBuf::new(),
+        };
+        self.prompt = Some(Prompt {
+            label: "export patches to",
+            text: dir.to_string_lossy().to_string(),
+            kind: PromptKind::ExportPatches(oids),
+        });
+        self.filter_mode = FilterMode::Prompt;
+    }
+
+    fn start_compare(&mut self) {
+        let Some(proj) = self.projects.get(self.active_project) else {
+            return;
+        };
+        let picked = match self.active_panel {
+            Panel::Branches => self
+                .cached_entries
+                .get(self.branch_selected())
+                .and_then(|e| Some((e.branch_name()?.to_string(), e.tip_oid()?))),
+            Panel::Graph | Panel::Detail => proj
+                .rows
+                .get(self.graph_selected())
+                .and_then(|r| Some((r.meta.branch_names.first()?.clone(), r.meta.oid))),
+        };
+        let Some((name, tip)) = picked else {
+            self.notify(NotifyLevel::Warn, "select a branch to compare");
+            return;
+        };
+        let branches = &proj.repo_data.branches;
+        let suggestion = match name.split_once('/') {
+            // origin/main -> main
+            Some((_, short)) if branches.iter().any(|b| b.name == short) => short.to_string(),
+            Some(_) => String::new(),
+            None => branches
+                .iter()
+                .find(|b| {
+                    matches!(b.source, CommitSource::Remote(_))
+                        && b.name.split_once('/').is_some_and(|(_, s)| s == name)
+                })
+                .map(|b| b.name.clone())
+                .unwrap_or_default(),
+        };
+        self.prompt = Some(Prompt {
+            label: "compare with",
+            text: suggestion,
+            kind: PromptKind::Compare { name, tip },
+        });
+        self.filter_mode = FilterMode::Prompt;
+    }
+
+    fn selected_pull_request_url(&self) -> std::result::Result<String, &'static str> {
+        let proj = self
+            .projects
+            .get(self.active_project)
+            .ok_or("no project open")?;
+        let pr_for = |name: &str| {
+            let name = name.strip_prefix("origin/").unwrap_or(name);
+            proj.pull_requests
+                .iter()
+                .find(|pr| pr.branch == name)
+                .map(|pr| pr.number)
+        };
+        let number = match self.active_panel {
+            Panel::Branches => {
+                self.cached_entries
+                    .get(self.branch_selected())
+                    .and_then(|e| match e.kind {
+                        EntryKind::PullRequest { number, .. } => Some(number),
+                        _ => e.branch_name().and_then(pr_for),
+                    })
+            }
+            Panel::Graph | Panel::Detail => proj
+                .rows
+                .get(proj.graph_selected)
+                .and_then(|r| r.meta.branch_names.iter().find_map(|n| pr_for(n))),
+        };
+        let number = number.ok_or("no open PR for the selection")?;
+        let base = proj.web_url.as_deref().ok_or("not a GitHub repo")?;
+        Ok(WebLinks {
+            base,
+            branches: &proj.repo_data.branches,
+        }
+        .pull(number))
+    }
+
+    fn toggle_watch(&mut self) {
+        let Some(proj) = self.projects.get(self.active_project) else {
+            return;
+        };
+        let name = match self.active_panel {
+            Panel::Branches => self
+                .cached_entries
+                .get(self.branch_selected())
+                .and_then(|e| e.branch_name()),
+            Panel::Graph | Panel::Detail => proj
+                .rows
+                .get(proj.graph_selected)
+                .and_then(|r| r.meta.branch_names.first())
+                .map(String::as_str),
+        };
+        let Some(branch) = name.and_then(|n| proj.repo_data.branches.iter().find(|b| b.name == n))
+        else {
+            self.notify(NotifyLevel::Warn, "select a branch");
+            return;
+        };
+        let (key, tip) = ((branch.source.clone(), branch.name.clone()), branch.tip);
+        let watched = &mut self.projects[self.active_project].watched_branches;
+        let message = if watched.remove(&key).is_some() {
+            format!("stopped watching {}", key.1)
+        } else {
+            let message = format!("watching {}", key.1);
+            watched.insert(key, tip);
+            message
+        };
+        self.notify(NotifyLevel::Info, message);
+    }
+
+    fn start_pr_merge(&mut self) {
+        let project_idx = self.active_project;
+        let Some(proj) = self.projects.get(project_idx) else {
+            return;
+        };
+        let branch = match self.active_panel {
+            Panel::Branches => self
+                .cached_entries
+                .get(self.branch_selected())
+                .filter(|e| matches!(e.kind, EntryKind::LocalBranch { .. }))
+                .and_then(|e| e.branch_name())
+                .map(str::to_string),
+            Panel::Graph | Panel::Detail => proj.rows.get(proj.graph_selected).and_then(|r| {
+                r.meta
+                    .branch_names
+                    .iter()
+                    .find(|name| {
+                        proj.repo_data
+                            .branches
+                            .iter()
+                            .any(|b| &b.name == *name && b.source == CommitSource::Local)
+                    })
+                    .cloned()
+            }),
+        };
+        let Some(branch) = branch else {
+            self.notify(NotifyLevel::Warn, "select a local branch");
+            return;
+        };
+        if self.offline.is_some() {
+            self.notify(NotifyLevel::Warn, "merging PRs needs GitHub: offline");
+            return;
+        }
+        let (Some(client), Some(tx)) = (proj.github_client().cloned(), self.event_tx.clone())
+        else {
+            self.notify(NotifyLevel::Warn, "merging PRs needs a github_token");
+            return;
+        };
+        self.spawn_task(async move {
+            let result = data_source::find_open_pr(&client, &branch).await;
+            let _ = tx.send(AppEvent::PullRequestFound {
+                project_idx,
+                branch,
+                result,
+            });
+        });
+    }
+
+    fn delete_local_branch(&mut self, project_idx: usize, branch: String) {
+        let Some(local) = self
+            .projects
+            .get(project_idx)
+            .and_then(|p| p.local_source.as_ref())
+        else {
+            return;
+        };
+        let Some(tx) = self.event_tx.clone() else {
+            return;
+        };
+        let submitted = local.worker.submit(move |r| {
+            let result = undo::delete_branch(r, &branch)
+                .map(Some)
+                .map_err(|e| e.to_string());
+            let _ = tx.send(AppEvent::BranchDeleted {
+                project_idx,
+                branch,
+                remote: false,
+                result,
+            });
+        });
+        if let Err(e) = submitted {
+            self.notify(NotifyLevel::Error, format!("{e}"));
+        }
+    }
+
+    fn refresh_after_remote_change(&mut self, project_idx: usize) {
+        self.rebuild_graph(project_idx);
+        if let Some(ref tx) = self.event_tx {
+            if self
+                .projects
+                .get(project_idx)
+                .is_some_and(|p| p.github_client().is_some())
+            {
+                let _ = tx.send(AppEvent::GitHubUpdate(project_idx));
+            }
+        }
+    }
+
+    fn start_rebase_preview(&mut self) {
+        let Some(proj) = self.projects.get(self.active_project) else {
+            return;
+        };
+        let picked = match self.active_panel {
+            Panel::Branches => self
+                .cached_entries
+                .get(self.branch_selected())
+                .and_then(|e| Some((e.branch_name()?.to_string(), e.tip_oid()?))),
+            Panel::Graph | Panel::Detail => proj
+                .rows
+                .get(self.graph_selected())
+                .and_then(|r| Some((r.meta.branch_names.first()?.clone(), r.meta.oid))),
+        };
+        let Some((name, tip)) = picked else {
+            self.notify(NotifyLevel::Warn, "select a branch to preview rebasing");
+            return;
+        };
+        let suggestion = drift::trunk_tips(&proj.repo_data, &self.config.trunk_branches)
+            .into_iter()
+            .map(|(trunk, _)| trunk)
+            .find(|trunk| *trunk != name)
+            .unwrap_or_default();
+        self.prompt = Some(Prompt {
+            label: "rebase onto",
+            text: suggestion,
+            kind: PromptKind::RebaseOnto { name, tip },
+        });
+        self.filter_mode = FilterMode::Prompt;
+    }
+
+    fn start_conflict_preview(&mut self) {
+        let Some(proj) = self.projects.get(self.active_project) else {
+            return;
+        };
+        let picked = match self.active_panel {
+            Panel::Branches => self
+                .cached_entries
+                .get(self.branch_selected())
+                .and_then(|e| Some((e.branch_name()?.to_string(), e.tip_oid()?))),
+            Panel::Graph | Panel::Detail => proj.rows.get(proj.graph_selected).map(|r| {
+                let name = r.meta.branch_names.first().cloned();
+                let short = r.meta.oid.to_string()[..7].to_string();
+                (name.unwrap_or(short), r.meta.oid)
+            }),
+        };
+        let Some((target, oid)) = picked else {
+            self.notify(NotifyLevel::Warn, "select a branch or commit to preview");
+            return;
+        };
+        let (Some(local), Some(tx)) = (proj.local_source.as_ref(), self.event_tx.clone()) else {
+            return;
+        };
+        let submitted = local.worker.submit(move |r| {
+            let result = merge::preview_onto_head(r, oid).map_err(|e| e.to_string());
+            let _ = tx.send(AppEvent::ConflictPreviewResult { oid, result });
+        });
+        if let Err(e) = submitted {
+            self.notify(NotifyLevel::Error, format!("{e}"));
+            return;
+        }
+        self.conflict_preview = Some(ConflictPreviewState {
+            target,
+            preview: None,
+        });
+        self.conflict_preview_oid = Some(oid);
+    }
+
+    fn start_cherry_pick(&mut self) {
+        if self.active_panel == Panel::Branches {
+            return;
+        }
+        let Some(proj) = self.projects.get(self.active_project) else {
+            return;
+        };
+        if proj.local_source.is_none() {
+            self.notify(NotifyLevel::Warn, "cherry-picking needs a local checkout");
+            return;
+        }
+        let Some(row) = proj.rows.get(proj.graph_selected) else {
+            return;
+        };
+        let oid = row.meta.oid;
+        let summary = truncate_with_ellipsis(&row.meta.message, 40);
+        let question = format!(
+            "cherry-pick {} \"{summary}\" onto {}? (y/n)",
+            &oid.to_string()[..7],
+            proj.current_branch
+        );
+        self.confirm = Some(Confirm {
+            title: " Cherry-pick ",
+            question,
+            kind: ConfirmKind::CherryPick {
+                project_idx: self.active_project,
+                oid,
+            },
+        });
+    }
+
+    fn open_health(&mut self) {
+        let project_idx = self.active_project;
+        let trunk_branches = &self.config.trunk_branches;
+        let Some(proj) = self.projects.get_mut(project_idx) else {
+            return;
+        };
+        let tips: Vec<Oid> = proj.repo_data.branches.iter().map(|b| b.tip).collect();
+        proj.ensure_tip_stats(&tips, trunk_branches);
+        let trunks = drift::trunk_tips(&proj.repo_data, trunk_branches);
+        let health = stats::health(
+            &proj.repo_data,
+            &trunks,
+            &proj.tip_stats,
+            chrono::Utc::now(),
+        );
+        let local = proj.local_source.as_ref().zip(self.event_tx.clone());
+        self.health = Some(HealthState {
+            project: proj.name.clone(),
+            health,
+            largest_files: local
+                .is_none()
+                .then(|| Err("needs a local checkout".to_string())),
+        });
+        let Some((local, tx)) = local else {
+            return;
+        };
+        let submitted = local.worker.submit(move |r| {
+            let result = diff::largest_files(r, LARGEST_FILES).map_err(|e| e.to_string());
+            let _ = tx.send(AppEvent::LargestFilesResult {
+                project_idx,
+                result,
+            });
+        });
+        if let Err(e) = submitted {
+            self.notify(NotifyLevel::Error, format!("{e}"));
+        }
+    }
+
+    fn open_head_timeline(&mut self) {
+        let project_idx = self.active_project;
+        let Some(proj) = self.projects.get(project_idx) else {
+            return;
+        };
+        let since = start_of_today();
+        let local = proj.local_source.as_ref().zip(self.event_tx.clone());
+        self.head_timeline = Some(HeadTimelineState {
+            entries: head_log::merge_watched(Vec::new(), &proj.watched_head_moves, since),
+            selected: 0,
+            loading: local.is_some(),
+        });
+        let Some((local, tx)) = local else {
+            return;
+        };
+        let submitted = local.worker.submit(move |r| {
+            let result = head_log::head_moves(r, since).map_err(|e| e.to_string());
+            let _ = tx.send(AppEvent::HeadMovesListed {
+                project_idx,
+                result,
+            });
+        });
+        if let Err(e) = submitted {
+            self.notify(NotifyLevel::Error, format!("{e}"));
+        }
+    }
+
+    fn open_blame(&mut self) {
+        let file = match (&self.active_panel, &self.detail_files) {
+            (Panel::Detail, Some(files)) => files.get(self.detail_file_selected),
+            _ => None,
+        };
+        let (Some(file), Some(oid)) = (file, self.detail_oid) else {
+            self.notify(NotifyLevel::Warn, "select a file in the detail panel");
+            return;
+        };
+        let project_idx = self.active_project;
+        let Some(local) = self
+            .projects
+            .get(project_idx)
+            .and_then(|p| p.local_source.as_ref())
+        else {
+            self.notify(NotifyLevel::Warn, "blame needs a local checkout");
+            return;
+        };
+        let Some(tx) = self.event_tx.clone() else {
+            return;
+        };
+        let path = file.path.clone();
+        self.blame = Some(BlameState::new(path.clone(), oid));
+        let submitted = local.worker.submit(move |r| {
+            let result = blame::blame_file(r, oid, &path).map_err(|e| e.to_string());
+            let _ = tx.send(AppEvent::BlameResult {
+                project_idx,
+                result,
+            });
+        });
+        if let Err(e) = submitted {
+            self.blame = None;
+            self.notify(NotifyLevel::Error, format!("{e}"));
+        }
+    }
+
+    fn open_ref_popover(&mut self) {
+        let Some(proj) = self.projects.get(self.active_project) else {
+            return;
+        };
+        let oid = match self.active_panel {
+            Panel::Branches => self
+                .cached_entries
+                .get(self.branch_selected())
+                .and_then(|e| e.tip_oid()),
+            Panel::Graph | Panel::Detail => proj.rows.get(proj.graph_selected).map(|r| r.meta.oid),
+        };
+        let Some(oid) = oid else {
+            return;
+        };
+        let data = &proj.repo_data;
+        let branches = data
+            .branches
+            .iter()
+            .filter(|b| b.tip == oid && !b.name.ends_with("/HEAD"))
+            .map(|b| RefItem {
+                name: b.name.clone(),
+                kind: RefKind::Branch {
+                    source: b.source.clone(),
+                    is_head: b.is_head,
+                },
+            });
+        let tags = data
+            .tags
+            .iter()
+            .filter(|t| t.target == oid)
+            .map(|t| RefItem {
+                name: t.name.clone(),
+                kind: RefKind::Tag,
+            });
+        let refs: Vec<RefItem> = branches.chain(tags).collect();
+        if refs.is_empty() {
+            self.notify(NotifyLevel::Info, "no branches or tags on this commit");
+            return;
+        }
+        self.refs_popover = Some(RefPopoverState::new(oid, refs));
+    }
+
+    fn jump_to_ref(&mut self, oid: Oid, item: &RefItem) {
+        let find = |entries: &[DisplayEntry]| {
+            entries.iter().position(|e| match item.kind {
+                RefKind::Tag => {
+                    matches!(e.kind, EntryKind::Tag { target, .. } if target == oid)
+                        && e.label.trim() == format!("({})", item.name)
+                }
+                RefKind::Branch { .. } => {
+                    e.branch_name() == Some(item.name.as_str()) && e.tip_oid() == Some(oid)
+                }
+            })
+        };
+        let mut found = find(&self.cached_entries);
+        if found.is_none() {
+            self.collapsed_sections.retain(|key| match &item.kind {
+                RefKind::Branch {
+                    source: CommitSource::Local,
+                    ..
+                } => !matches!(key, SectionKey::Local(_) | SectionKey::Prunable(_)),
+                RefKind::Branch {
+                    source: CommitSource::Remote(remote),
+                    ..
+                } => !matches!(key, SectionKey::Remote(_, r) if r == remote),
+                RefKind::Branch {
+                    source: CommitSource::Fork(owner),
+                    ..
+                } => {
+                    !matches!(key, SectionKey::Fork(_, o) if o == owner)
+                        && !matches!(key, SectionKey::InactiveForks(_))
+                }
+                RefKind::Tag => !matches!(key, SectionKey::Tags(_)),
+            });
+            self.refresh_entries();
+            found = find(&self.cached_entries);
+        }
+        match found {
+            Some(idx) => {
+                self.set_branch_selected(idx);
+                self.active_panel = Panel::Branches;
+            }
+            None => self.notify(
+                NotifyLevel::Warn,
+                format!("{} isn't listed in the branch panel", item.name),
+            ),
+        }
+    }
+
+    fn checkout(&mut self, item: RefItem) {
+        let Some(refname) = item.refname() else {
+            self.notify(
+                NotifyLevel::Warn,
+                "fork branches only exist on GitHub; fetch them to check out",
+            );
+            return;
+        };
+        let project_idx = self.active_project;
+        let Some(local) = self
+            .projects
+            .get(project_idx)
+            .and_then(|p| p.local_source.as_ref())
+        else {
+            self.notify(NotifyLevel::Warn, "checkout needs a local checkout");
+            return;
+        };
+        let Some(tx) = self.event_tx.clone() else {
+            return;
+        };
+        let submitted = local.worker.submit(move |r| {
+            let result = repo::checkout_ref(r, &refname).map_err(|e| e.to_string());
+            let _ = tx.send(AppEvent::CheckedOut {
+                project_idx,
+                name: item.name,
+                result,
+            });
+        });
+        if let Err(e) = submitted {
+            self.notify(NotifyLevel::Error, format!("{e}"));
+        }
+    }
+
+    fn list_stashes(&mut self) {
+        let project_idx = self.active_project;
+        let Some(local) = self
+            .projects
+            .get(project_idx)
+            .and_then(|p| p.local_source.as_ref())
+        else {
+            self.stashes = None;
+            self.notify(NotifyLevel::Warn, "stashes need a local checkout");
+            return;
+        };
+        let Some(tx) = self.event_tx.clone() else {
+            return;
+        };
+        let submitted = local.worker.submit(move |r| {
+            let result = stash::list_stashes(r).map_err(|e| e.to_string());
+            let _ = tx.send(AppEvent::StashesListed {
+                project_idx,
+                result,
+            });
+        });
+        if let Err(e) = submitted {
+            self.notify(NotifyLevel::Error, format!("{e}"));
+        }
+    }
+
+    fn remember_deleted(&mut self, project_idx: usize, deleted: DeletedRef) {
+        self.recently_deleted.retain(|d| !d.expired());
+        self.recently_deleted.insert(
+            0,
+            RecentlyDeleted {
+                project_idx,
+                deleted,
+                at: std::time::Instant::now(),
+            },
+        );
+    }
+
+    fn restore_deleted(&mut self, index: usize) {
+        let Some(entry) = self.recently_deleted.get(index) else {
+            return;
+        };
+        if entry.expired() {
+            self.notify(NotifyLevel::Warn, "too late to undo that deletion");
+            return;
+        }
+        let (project_idx, deleted) = (entry.project_idx, entry.deleted.clone());
+        let Some(local) = self
+            .projects
+            .get(project_idx)
+            .and_then(|p| p.local_source.as_ref())
+        else {
+            return;
+        };
+        let Some(tx) = self.event_tx.clone() else {
+            return;
+        };
+        let submitted = local.worker.submit(move |r| {
+            let result = undo::restore(r, &deleted).map_err(|e| e.to_string());
+            let _ = tx.send(AppEvent::RefRestored {
+                project_idx,
+                deleted,
+                result,
+            });
+        });
+        if let Err(e) = submitted {
+            self.notify(NotifyLevel::Error, format!("{e}"));
+        }
+    }
+
+    fn confirm_stash(&mut self, op: StashOp, index: Option<usize>) {
+        let Some(index) = index else {
+            return;
+        };
+        let verb = op.verb();
+        let title = match op {
+            StashOp::Apply => " Apply stash ",
+            StashOp::Pop => " Pop stash ",
+            StashOp::Drop => " Drop stash ",
+        };
+        self.confirm = Some(Confirm {
+            title,
+            question: format!("{verb} stash@{{{index}}}? (y/n)"),
+            kind: ConfirmKind::Stash { op, index },
+        });
+    }
+
+    // One already open is just focused
+    fn confirm_open_submodule(&mut self, name: String, workdir: Option<PathBuf>) {
+        let Some(workdir) = workdir else {
+            self.notify(
+                NotifyLevel::Warn,
+                format!("submodule {name} isn't checked out"),
+            );
+            return;
+        };
+        if let Some(idx) = self.project_at(&workdir) {
+            self.focus_project(idx);
+            return;
+        }
+        self.confirm = Some(Confirm {
+            title: " Open submodule ",
+            question: format!("open {name} as a project? (y/n)"),
+            kind: ConfirmKind::OpenSubmodule { name, workdir },
+        });
+    }
+
+    fn project_at(&self, workdir: &Path) -> Option<usize> {
+        self.projects.iter().position(|p| {
+            p.local_source
+                .as_ref()
+                .and_then(|l| l.worker.workdir())
+                .is_some_and(|w| w == workdir)
+        })
+    }
+
+    fn run_confirmed(&mut self, kind: ConfirmKind) {
+        match kind {
+            ConfirmKind::OpenSubmodule { name, workdir } => {
+                let proj = match self.open_project(&workdir, None) {
+                    Ok(proj) => proj,
+                    Err(e) => {
+                        self.notify(
+                            NotifyLevel::Error,
+                            format!("opening submodule {name} failed: {e}"),
+                        );
+                        return;
+                    }
+                };
+                // Workspaces only list configured repos; leaving one reads
+                // the repos it skipped
+                if self.workspace.is_some() {
+                    self.switch_workspace(None);
+                }
+                let idx = self.projects.len();
+                self.projects.push(proj);
+                self.rebuild_graph(idx);
+                self.focus_project(idx);
+                if let Some(ref tx) = self.event_tx {
+                    let _ = tx.send(AppEvent::WatchersChanged);
+                }
+            }
+            ConfirmKind::MergePr {
+                project_idx,
+                branch,
+                number,
+                method,
+            } => {
+                let client = self
+                    .projects
+                    .get(project_idx)
+                    .and_then(|p| p.github_client().cloned());
+                let (Some(client), Some(tx)) = (client, self.event_tx.clone()) else {
+                    return;
+                };
+                self.spawn_task(async move {
+                    let result = data_source::merge_pr(&client, number, method).await;
+                    let _ = tx.send(AppEvent::PullRequestMerged {
+                        project_idx,
+                        branch,
+                        number,
+                        result,
+                    });
+                });
+            }
+            ConfirmKind::DeleteMergedBranch {
+                project_idx,
+                branch,
+            } => {
+                let client = self
+                    .projects
+                    .get(project_idx)
+                    .and_then(|p| p.github_client().cloned());
+                let (Some(client), Some(tx)) = (client, self.event_tx.clone()) else {
+                    return;
+                };
+                // The local copy goes once GitHub's has, see BranchDeleted
+                self.spawn_task(async move {
+                    let result = data_source::delete_remote_branch(&client, &branch)
+                        .await
+                        .map(|()| None);
+                    let _ = tx.send(AppEvent::BranchDeleted {
+                        project_idx,
+                        branch,
+                        remote: true,
+                        result,
+                    });
+                });
+            }
+            ConfirmKind::CherryPick { project_idx, oid } => {
+                let Some(local) = self
+                    .projects
+                    .get(project_idx)
+                    .and_then(|p| p.local_source.as_ref())
+                else {
+                    return;
+                };
+                let Some(tx) = self.event_tx.clone() else {
+                    return;
+                };
+                let submitted = local.worker.submit(move |r| {
+                    let result = merge::cherry_pick(r, oid).map_err(|e| e.to_string());
+                    let _ = tx.send(AppEvent::CherryPicked {
+                        project_idx,
+                        oid,
+                        result,
+                    });
+                });
+                if let Err(e) = submitted {
+                    self.notify(NotifyLevel::Error, format!("{e}"));
+                }
+            }
+            ConfirmKind::DeleteBranch {
+                project_idx,
+                branch,
+            } => {
+                let has_local = self
+                    .projects
+                    .get(project_idx)
+                    .is_some_and(|p| p.local_source.is_some());
+                if !has_local {
+                    self.notify(
+                        NotifyLevel::Warn,
+                        "deleting branches needs a local checkout",
+                    );
+                    return;
+                }
+                self.delete_local_branch(project_idx, branch);
+            }
+            ConfirmKind::DeleteTag { project_idx, tag } => {
+                let Some(local) = self
+                    .projects
+                    .get(project_idx)
+                    .and_then(|p| p.local_source.as_ref())
+                else {
+                    self.notify(NotifyLevel::Warn, "deleting tags needs a local checkout");
+                    return;
+                };
+                let Some(tx) = self.event_tx.clone() else {
+                    return;
+                };
+                let submitted = local.worker.submit(move |r| {
+                    let result = undo::delete_tag(r, &tag).map_err(|e| e.to_string());
+                    let _ = tx.send(AppEvent::TagDeleted {
+                        project_idx,
+                        tag,
+                        result,
+                    });
+                });
+                if let Err(e) = submitted {
+                    self.notify(NotifyLevel::Error, format!("{e}"));
+                }
+            }
+            ConfirmKind::PruneBranches {
+                project_idx,
+                branches,
+            } => {
+                let Some(local) = self
+                    .projects
+                    .get(project_idx)
+                    .and_then(|p| p.local_source.as_ref())
+                else {
+                    self.notify(NotifyLevel::Warn, "pruning needs a local checkout");
+                    return;
+                };
+                let Some(tx) = self.event_tx.clone() else {
+                    return;
+                };
+                let submitted = local.worker.submit(move |r| {
+                    let mut deleted = Vec::new();
+                    let mut failed = Vec::new();
+                    for branch in branches {
+                        match undo::delete_branch(r, &branch) {
+                            Ok(ref_) => deleted.push(ref_),
+                            Err(e) => failed.push((branch, e.to_string())),
+                        }
+                    }
+                    let _ = tx.send(AppEvent::BranchesPruned {
+                        project_idx,
+                        deleted,
+                        failed,
+                    });
+                });
+                if let Err(e) = submitted {
+                    self.notify(NotifyLevel::Error, format!("{e}"));
+                }
+            }
+            ConfirmKind::Stash { op, index } => {
+                let project_idx = self.active_project;
+                let Some(local) = self
+                    .projects
+                    .get(project_idx)
+                    .and_then(|p| p.local_source.as_ref())
+                else {
+                    return;
+                };
+                let Some(tx) = self.event_tx.clone() else {
+                    return;
+                };
+                let submitted = local.worker.submit(move |r| {
+                    let dropped = if op == StashOp::Drop {
+                        stash::list_stashes(r)
+                            .ok()
+                            .and_then(|list| list.into_iter().find(|s| s.index == index))
+                    } else {
+                        None
+                    };
+                    let result = stash::run_stash_op(r, op, index).map_err(|e| e.to_string());
+                    let _ = tx.send(AppEvent::StashOpDone {
+                        project_idx,
+                        op,
+                        index,
+                        dropped,
+                        result,
+                    });
+                });
+                if let Err(e) = submitted {
+                    self.notify(NotifyLevel::Error, format!("{e}"));
+                }
+            }
+        }
+    }
+
+    fn start_annotate(&mut self) {
+        if self.active_panel == Panel::Branches {
+            return;
+        }
+        let Some(proj) = self.projects.get(self.active_project) else {
+            return;
+        };
+        let Some(row) = proj.rows.get(proj.graph_selected) else {
+            return;
+        };
+        let oid = row.meta.oid;
+        self.prompt = Some(Prompt {
+            label: "note",
+            text: proj.annotations.get(&oid).unwrap_or_default().to_string(),
+            kind: PromptKind::Annotate(oid),
+        });
+        self.filter_mode = FilterMode::Prompt;
+    }
+
+    fn start_prune(&mut self) {
+        let Some(proj) = self.projects.get(self.active_project) else {
+            return;
+        };
+        let branches = proj.prunable_branches();
+        let question = match branches.as_slice() {
+            [] => {
+                self.notify(NotifyLevel::Info, "no branches with a gone upstream");
+                return;
+            }
+            [branch] => format!("delete {branch}, whose upstream is gone? (y/n)"),
+            [first, rest @ ..] => format!(
+                "delete {first} and {} more whose upstream is gone? (y/n)",
+                rest.len()
+            ),
+        };
+        self.confirm = Some(Confirm {
+            title: " Prune branches ",
+            question,
+            kind: ConfirmKind::PruneBranches {
+                project_idx: self.active_project,
+                branches,
+            },
+        });
+    }
+
+    fn start_new_branch(&mut self) {
+        let Some(proj) = self.projects.get(self.active_project) else {
+            return;
+        };
+        if proj.local_source.is_none() {
+            self.notify(
+                NotifyLevel::Warn,
+                "creating branches needs a local checkout",
+            );
+            return;
+        }
+        let oid = match self.active_panel {
+            Panel::Branches => self
+                .cached_entries
+                .get(self.branch_selected())
+                .and_then(|e| e.tip_oid()),
+            Panel::Graph | Panel::Detail => proj.rows.get(proj.graph_selected).map(|r| r.meta.oid),
+        };
+        let Some(oid) = oid else {
+            self.notify(NotifyLevel::Warn, "select a commit to branch from");
+            return;
+        };
+        self.prompt = Some(Prompt {
+            label: "new branch",
+            text: String::new(),
+            kind: PromptKind::NewBranch(oid),
+        });
+        self.filter_mode = FilterMode::Prompt;
+    }
+
+    // The checked-out one is refused, as git branch -d does
+    fn start_delete_branch(&mut self) {
+        let tag = match self.active_panel {
+            Panel::Branches => self
+                .cached_entries
+                .get(self.branch_selected())
+                .filter(|e| matches!(e.kind, EntryKind::Tag { .. }))
+                .map(|e| {
+                    let label = e.label.trim();
+                    label[1..label.len() - 1].to_string()
+                }),
+            Panel::Graph | Panel::Detail => None,
+        };
+        if let Some(tag) = tag {
+            self.confirm = Some(Confirm {
+                title: " Delete tag ",
+                question: format!("delete tag {tag}? (y/n)"),
+                kind: ConfirmKind::DeleteTag {
+                    project_idx: self.active_project,
+                    tag,
+                },
+            });
+            return;
+        }
+        let picked = match self.active_panel {
+            Panel::Branches => {
+                self.cached_entries
+                    .get(self.branch_selected())
+                    .and_then(|e| match e.kind {
+                        EntryKind::LocalBranch { is_head, .. } => {
+                            Some((e.branch_name()?.to_string(), is_head))
+                        }
+                        _ => None,
+                    })
+            }
+            Panel::Graph | Panel::Detail => None,
+        };
+        let Some((branch, is_head)) = picked else {
+            self.notify(
+                NotifyLevel::Warn,
+                "select a local branch or tag in the branch panel to delete",
+            );
+            return;
+        };
+        if is_head {
+            self.notify(
+                NotifyLevel::Warn,
+                format!("{branch} is checked out and can't be deleted"),
+            );
+            return;
+        }
+        self.confirm = Some(Confirm {
+            title: " Delete branch ",
+            question: format!("delete {branch}? (y/n)"),
+            kind: ConfirmKind::DeleteBranch {
+                project_idx: self.active_project,
+                branch,
+            },
+        });
+    }
+
+    fn start_deepen(&mut self) {
+        let Some(proj) = self.projects.get(self.active_project) else {
+            return;
+        };
+        if proj.local_source.is_none() {
+            self.notify(NotifyLevel::Warn, "deepening needs a local checkout");
+            return;
+        }
+        let shallow = proj.cached_repo_data.as_ref().map(|d| &d.shallow);
+        if shallow.is_none_or(|s| s.is_empty()) {
+            self.notify(NotifyLevel::Info, "not a shallow clone");
+            return;
+        }
+        self.prompt = Some(Prompt {
+            label: "deepen clone by",
+            text: DEFAULT_DEEPEN.to_string(),
+            kind: PromptKind::Deepen,
+        });
+        self.filter_mode = FilterMode::Prompt;
+    }
+
+    fn start_fetch(&mut self) {
+        let project_idx = self.active_project;
+        let Some(proj) = self.projects.get_mut(project_idx) else {
+            return;
+        };
+        let Some(local) = proj.local_source.as_ref() else {
+            self.notify(NotifyLevel::Warn, "fetching needs a local checkout");
+            return;
+        };
+        let Some(tx) = self.event_tx.clone() else {
+            return;
+        };
+        if proj.fetch_progress.is_some() {
+            self.notify(NotifyLevel::Info, "already fetching");
+            return;
+        }
+        let progress_tx = tx.clone();
+        let submitted = local.worker.submit(move |r| {
+            let result = fetch::fetch_all(r, &mut |progress| {
+                let _ = progress_tx.send(AppEvent::FetchProgress {
+                    project_idx,
+                    progress,
+                });
+            })
+            .map_err(|e| e.to_string());
+            let _ = tx.send(AppEvent::Fetched {
+                project_idx,
+                result,
+            });
+        });
+        match submitted {
+            Ok(()) => {
+                proj.fetch_progress = Some(FetchProgress {
+                    remote: String::new(),
+                    received: 0,
+                    total: 0,
+                })
+            }
+            Err(e) => self.notify(NotifyLevel::Error, format!("fetch failed: {e}")),
+        }
+    }
+
+    fn handle_fetched(
+        &mut self,
+        project_idx: usize,
+        result: std::result::Result<FetchOutcome, String>,
+    ) {
+        let Some(proj) = self.projects.get_mut(project_idx) else {
+            return;
+        };
+        proj.fetch_progress = None;
+        let outcome = match result {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                self.notify(NotifyLevel::Error, format!("fetch failed: {e}"));
+                return;
+            }
+        };
+        match (outcome.fetched.is_empty(), outcome.failed.first()) {
+            (true, None) => self.notify(NotifyLevel::Info, "no remotes to fetch"),
+            (false, None) => self.notify(
+                NotifyLevel::Info,
+                format!("fetched {}", outcome.fetched.join(", ")),
+            ),
+            (_, Some((remote, e))) => {
+                self.notify(NotifyLevel::Warn, format!("fetching {remote} failed: {e}"))
+            }
+        }
+        if !outcome.fetched.is_empty() {
+            self.read_local(project_idx, true);
+        }
+    }
+
+    fn jump_to_day(&mut self, day: chrono::NaiveDate) {
+        let Some(proj) = self.projects.get(self.active_project) else {
+            return;
+        };
+        let first = proj
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.meta.time.with_timezone(&chrono::Local).date_naive() == day)
+            .min_by_key(|(_, r)| r.meta.time)
+            .map(|(i, _)| i);
+        let Some(idx) = first else {
+            self.notify(NotifyLevel::Warn, format!("no commits on {day}"));
+            return;
+        };
+        self.set_graph_selected(idx);
+        self.active_panel = Panel::Graph;
+        self.heatmap = None;
+    }
+
+    fn jump_to_release(&mut self, name: &str, target: Oid) {
+        let row = self
+            .projects
+            .get(self.active_project)
+            .and_then(|p| p.rows.iter().position(|r| r.meta.oid == target));
+        let Some(idx) = row else {
+            self.notify(NotifyLevel::Warn, format!("{name} is not in the graph"));
+            return;
+        };
+        self.set_graph_selected(idx);
+        self.active_panel = Panel::Graph;
+        self.releases = None;
+    }
+
+    fn swap_split_focus(&mut self) {
+        let Some(ref mut pane) = self.split else {
+            return;
+        };
+        if let Some(proj) = self.projects.get_mut(self.active_project) {
+            proj.mark_seen();
+        }
+        std::mem::swap(&mut pane.project, &mut self.active_project);
+        self.visual_anchor = None;
+        self.refresh_entries();
+    }
+
+    // In a split, focuses the other pane instead when it shows idx
+    fn focus_project(&mut self, idx: usize) {
+        if idx == self.active_project || idx >= self.projects.len() {
+            return;
+        }
+        if self.split.as_ref().is_some_and(|s| s.project == idx) {
+            self.swap_split_focus();
+            return;
+        }
+        self.projects[self.active_project].mark_seen();
+        self.active_project = idx;
+        self.unpark(idx);
+        self.visual_anchor = None;
+        self.refresh_entries();
+    }
+
+    fn open_aggregate(&mut self) {
+        let members = self.workspace_projects();
+        if members.len() < 2 {
+            self.notify(NotifyLevel::Warn, "the aggregate view needs two projects");
+            return;
+        }
+        self.aggregate = Some(Aggregate::new(&self.projects, &members));
+    }
+
+    fn close_comparison(&mut self) {
+        self.compare = None;
+        // Kitty images of the two sides stay on screen otherwise
+        for proj in &mut self.projects {
+            proj.image_cache.clear(proj.max_lanes);
+        }
+    }
+
+    fn answer_prompt(&mut self, prompt: Prompt) {
+        match prompt.kind {
+            PromptKind::Annotate(oid) => {
+                let Some(proj) = self.projects.get_mut(self.active_project) else {
+                    return;
+                };
+                if let Err(e) = proj.annotations.set(oid, &prompt.text) {
+                    self.notify(NotifyLevel::Error, format!("note not saved: {e}"));
+                }
+            }
+            PromptKind::Compare { name, tip } => {
+                let Some(proj) = self.projects.get_mut(self.active_project) else {
+                    return;
+                };
+                let other = prompt.text.trim();
+                let data = &proj.repo_data;
+                let Some(other_tip) = ref_tip(data, other) else {
+                    self.notify(
+                        NotifyLevel::Warn,
+                        format!("no branch or tag named {other:?}"),
+                    );
+                    return;
+                };
+                self.compare = Some(Comparison::new(
+                    data,
+                    (&name, tip),
+                    (other, other_tip),
+                    &self.config.trunk_branches,
+                ));
+                proj.image_cache.clear(proj.max_lanes);
+            }
+            PromptKind::RebaseOnto { name, tip } => {
+                let Some(proj) = self.projects.get(self.active_project) else {
+                    return;
+                };
+                // The shown data lacks the commits and refs the filters hide
+                let filtered = !self.author_filter_text.is_empty()
+                    || !self.type_filter.is_empty()
+                    || !self.config.hidden_authors().is_empty();
+                let unfiltered = proj.cached_repo_data.as_ref().filter(|_| filtered);
+                let onto = prompt.text.trim();
+                let Some(onto_tip) = ref_tip(unfiltered.unwrap_or(&proj.repo_data), onto) else {
+                    self.notify(
+                        NotifyLevel::Warn,
+                        format!("no branch or tag named {onto:?}"),
+                    );
+                    return;
+                };
+                let unfiltered_dag;
+                let dag = match unfiltered {
+                    Some(data) => {
+                        unfiltered_dag = Dag::from_repo_data(data);
+                        &unfiltered_dag
+                    }
+                    None => &proj.dag,
+                };
+                let plan = drift::rebase_plan(dag, tip, onto_tip);
+                let commits = plan
+                    .replayed
+                    .iter()
+                    .filter_map(|oid| Some(dag.nodes.get(oid)?.commit.clone()))
+                    .collect();
+                self.rebase_preview = Some(RebasePreviewState {
+                    branch: name,
+                    onto: onto.to_string(),
+                    onto_tip,
+                    plan,
+                    commits,
+                    onto_row: proj.rows.iter().position(|r| r.meta.oid == onto_tip),
+                });
+            }
+            PromptKind::CommitTypes => {
+                let types: Vec<String> = prompt
+                    .text
+                    .split([' ', ',', '+'])
+                    .filter(|t| !t.is_empty())
+                    .map(str::to_lowercase)
+                    .collect();
+                if let Some(unknown) = types
+                    .iter()
+                    .find(|t| *t != "breaking" && !conventional::TYPES.contains(&t.as_str()))
+                {
+                    self.notify(
+                        NotifyLevel::Warn,
+                        format!(
+                            "unknown commit type {unknown:?}, try {} or breaking",
+                            conventional::TYPES.join(", ")
+                        ),
+                    );
+                    return;
+                }
+                self.type_filter = types;
+                for idx in 0..self.projects.len() {
+                    self.rebuild_graph_author_only(idx);
+                }
+                self.clamp_selected();
+            }
+            PromptKind::Workspace => {
+                let name = prompt.text.trim();
+                self.switch_workspace((!name.is_empty()).then(|| name.to_string()));
+            }
+            PromptKind::NewBranch(oid) => {
+                let branch = prompt.text.trim().to_string();
+                if branch.is_empty() {
+                    return;
+                }
+                let project_idx = self.active_project;
+                let Some(local) = self
+                    .projects
+                    .get(project_idx)
+                    .and_then(|p| p.local_source.as_ref())
+                else {
+                    return;
+                };
+                let Some(tx) = self.event_tx.clone() else {
+                    return;
+                };
+                let submitted = local.worker.submit(move |r| {
+                    let result = repo::create_branch(r, &branch, oid).map_err(|e| e.to_string());
+                    let _ = tx.send(AppEvent::BranchCreated {
+                        project_idx,
+                        branch,
+                        result,
+                    });
+                });
+                if let Err(e) = submitted {
+                    self.notify(NotifyLevel::Error, format!("{e}"));
+                }
+            }
+            PromptKind::Deepen => {
+                let by = match prompt.text.trim().parse::<u32>() {
+                    Ok(by) if by > 0 => by,
+                    _ => {
+                        self.notify(
+                            NotifyLevel::Warn,
+                            format!("{:?} is not a commit count", prompt.text.trim()),
+                        );
+                        return;
+                    }
+                };
+                let project_idx = self.active_project;
+                let (Some(proj), Some(tx)) =
+                    (self.projects.get_mut(project_idx), self.event_tx.clone())
+                else {
+                    return;
+                };
+                let Some(local) = proj.local_source.as_ref() else {
+                    return;
+                };
+                let submitted = local.worker.submit(move |r| {
+                    let result = shallow::deepen(r, by).map(|()| by);
+                    let _ = tx.send(AppEvent::CloneDeepened {
+                        project_idx,
+                        result,
+                    });
+                });
+                match submitted {
+                    Ok(()) => proj.loading = true,
+                    Err(e) => self.notify(NotifyLevel::Error, format!("deepen failed: {e}")),
+                }
+            }
+            PromptKind::ExportPatches(oids) => {
+                let Some(local) = self
+                    .projects
+                    .get(self.active_project)
+                    .and_then(|p| p.local_source.as_ref())
+                else {
+                    return;
+                };
+                if prompt.text.trim().is_empty() {
+                    self.notify(NotifyLevel::Warn, "export needs a directory");
+                    return;
+                }
+                let mut dir = expand_tilde(std::path::Path::new(prompt.text.trim()));
+                if dir.is_relative() {
+                    if let Some(workdir) = local.worker.workdir() {
+                        dir = workdir.join(dir);
+                    }
+                }
+                let Some(tx) = self.event_tx.clone() else {
+                    return;
+                };
+                let submitted = local.worker.submit(move |r| {
+                    let result = patch::export_patches(r, &oids, &dir)
+                        .map(|files| (files.len(), dir))
+                        .map_err(|e| e.to_string());
+                    let _ = tx.send(AppEvent::PatchesExported { result });
+                });
+                if let Err(e) = submitted {
+                    self.notify(NotifyLevel::Error, format!("export failed: {e}"));
+                    return;
+                }
+                self.visual_anchor = None;
+            }
+        }
+    }
+
+    pub fn sync_lane_overflow(&mut self) {
+        let Some(proj) = self
+            .projects
+            .iter_mut()
+            .find(|p| p.lane_overflow && !p.lane_overflow_reported)
+        else {
+            return;
+        };
+        proj.lane_overflow_reported = true;
+        let name = proj.name.clone();
+        self.notify(
+            NotifyLevel::Warn,
+            format!("{name}: over {MAX_LANES} lanes \u{2014} extra branches share the last one"),
+        );
+    }
+
+    pub fn sync_trunk_distances(&mut self) {
+        if !self.show_trunk_distance {
+            return;
+        }
+        let mut shown = vec![self.active_project];
+        if let Some(ref pane) = self.split {
+            shown.push(pane.project);
+        }
+        for idx in shown {
+            if let Some(proj) = self.projects.get_mut(idx) {
+                proj.ensure_trunk_distances(&self.config.trunk_branches);
+            }
+        }
+    }
+
+    pub fn sync_commit_weights(&mut self) {
+        let project_idx = self.active_project;
+        let Some(proj) = self.projects.get_mut(project_idx) else {
+            return;
+        };
+        let oids: Vec<Oid> = proj
+            .rows
+            .iter()
+            .skip(proj.graph_scroll_y)
+            .take(WEIGHED_ROWS)
+            .map(|r| r.meta.oid)
+            .filter(|oid| !proj.commit_weights.contains_key(oid))
+            .collect();
+        if oids.is_empty() {
+            return;
+        }
+        let (Some(local), Some(tx)) = (proj.local_source.as_ref(), self.event_tx.clone()) else {
+            return;
+        };
+        for oid in &oids {
+            proj.commit_weights.insert(*oid, CommitWeight::default());
+        }
+        let submitted = local.worker.submit(move |r| {
+            // Remote-only commits aren't in the local odb; they stay unmarked
+            let weights = oids
+                .into_iter()
+                .filter_map(|oid| Some((oid, diff::commit_weight(r, oid).ok()?)))
+                .collect();
+            let _ = tx.send(AppEvent::CommitWeightsResult {
+                project_idx,
+                weights,
+            });
+        });
+        if let Err(e) = submitted {
+            tracing::warn!("commit weight request failed: {e}");
+        }
+    }
+
+    pub fn sync_diff_stats(&mut self) {
+        if !self.show_diffstat {
+            return;
+        }
+        let project_idx = self.active_project;
+        let Some(proj) = self.projects.get_mut(project_idx) else {
+            return;
+        };
+        let oids: Vec<Oid> = proj
+            .rows
+            .iter()
+            .skip(proj.graph_scroll_y)
+            .take(WEIGHED_ROWS)
+            .map(|r| r.meta.oid)
+            .filter(|oid| !proj.diff_stats.contains_key(oid))
+            .collect();
+        if oids.is_empty() {
+            return;
+        }
+        let (Some(local), Some(tx)) = (proj.local_source.as_ref(), self.event_tx.clone()) else {
+            return;
+        };
+        for oid in &oids {
+            proj.diff_stats.insert(*oid, DiffStat::default());
+        }
+        let submitted = local.worker.submit(move |r| {
+            let stats = oids
+                .into_iter()
+                .filter_map(|oid| Some((oid, diff::diff_stat(r, oid).ok()?)))
+                .collect();
+            let _ = tx.send(AppEvent::DiffStatsResult { project_idx, stats });
+        });
+        if let Err(e) = submitted {
+            tracing::warn!("diff stat request failed: {e}");
+        }
+    }
+
+    pub fn sync_tag_signatures(&mut self) {
+        let project_idx = self.active_project;
+        let Some(proj) = self.projects.get_mut(project_idx) else {
+            return;
+        };
+        let mut names: Vec<&str> = self
+            .cached_entries
+            .iter()
+            .filter(|e| matches!(e.kind, EntryKind::Tag { .. }))
+            .map(|e| e.label.trim().trim_start_matches('(').trim_end_matches(')'))
+            .collect();
+        if self.show_detail {
+            if let Some(row) = proj.rows.get(proj.graph_selected) {
+                names.extend(row.meta.tag_names.iter().map(String::as_str));
+            }
+        }
+        let wanted: Vec<(String, Oid)> = proj
+            .repo_data
+            .tags
+            .iter()
+            .filter(|t| t.signed && names.contains(&t.name.as_str()))
+            .map(|t| (t.name.clone(), t.target))
+            .filter(|key| !proj.tag_signatures.contains_key(key))
+            .collect();
+        if wanted.is_empty() {
+            return;
+        }
+        let (Some(local), Some(tx)) = (proj.local_source.as_ref(), self.event_tx.clone()) else {
+            return;
+        };
+        for key in &wanted {
+            proj.tag_signatures
+                .insert(key.clone(), TagSignature::Pending);
+        }
+        let submitted = local.worker.submit(move |r| {
+            let results = wanted
+                .into_iter()
+                .map(|(name, target)| {
+                    let signature = signature::verify_tag(r, &name);
+                    (name, target, signature)
+                })
+                .collect();
+            let _ = tx.send(AppEvent::TagsVerified {
+                project_idx,
+                results,
+            });
+        });
+        if let Err(e) = submitted {
+            tracing::warn!("tag verification request failed: {e}");
+        }
+    }
+
+    pub fn sync_detail(&mut self) {
+        if !self.show_detail {
+            return;
+        }
+        let Some(proj) = self.projects.get(self.active_project) else {
+            return;
+        };
+        let Some(oid) = proj.rows.get(proj.graph_selected).map(|r| r.meta.oid) else {
+            return;
+        };
+        let (Some(local), Some(tx)) = (proj.local_source.as_ref(), self.event_tx.clone()) else {
+            return;
+        };
+        if self.detail_oid != Some(oid) {
+            self.detail_oid = Some(oid);
+            self.detail_files = None;
+            self.detail_file_selected = 0;
+            self.detail_diff = None;
+            self.detail_diff_requested = false;
+            self.detail_diff_scroll = 0;
+            self.detail_message_scroll = 0;
+            let tx = tx.clone();
+            let submitted = local.worker.submit(move |r| {
+                let result = diff::changed_files(r, oid).map_err(|e| e.to_string());
+                let _ = tx.send(AppEvent::CommitFilesResult { oid, result });
+            });
+            if let Err(e) = submitted {
+                tracing::warn!("changed files request failed: {e}");
+            }
+        }
+
+        if !self.show_diff || self.detail_diff_requested {
+            return;
+        }
+        let Some(line) = self.config.diff_pager.as_deref() else {
+            self.detail_diff_requested = true;
+            let palette = self.palette.clone();
+            let submitted = local.worker.submit(move |r| {
+                let result = diff::file_patches(r, oid)
+                    .map(|files| detail_panel::diff_text(&files, &palette))
+                    .map_err(|e| e.to_string());
+                let _ = tx.send(AppEvent::CommitDiffResult { oid, result });
+            });
+            if let Err(e) = submitted {
+                tracing::warn!("diff request failed: {e}");
+            }
+            return;
+        };
+        let Some(mut pager) = ExternalCommand::from_command_line("diff pager", line) else {
+            return;
+        };
+        if let Some(workdir) = local.worker.workdir() {
+            // So delta and friends pick up the repo's git config
+            pager = pager.cwd(workdir);
+        }
+        self.detail_diff_requested = true;
+        // Runs on the repo worker thread: the pager blocks it briefly, but
+        // keeps the terminal-owning UI thread free
+        let submitted = local.worker.submit(move |r| {
+            let result = diff::patch_text(r, oid)
+                .map_err(|e| e.to_string())
+                .and_then(|patch| {
+                    external::pipe_through(&pager, patch)
+                        .map_err(|e| format!("{}: {e}", pager.label))
+                })
+                .and_then(|out| out.into_text().map_err(|e| e.to_string()));
+            let _ = tx.send(AppEvent::CommitDiffResult { oid, result });
+        });
+        if let Err(e) = submitted {
+            tracing::warn!("diff request failed: {e}");
+        }
+    }
+
+    pub fn finish_external(
+        &mut self,
+        cmd: &ExternalCommand,
+        result: std::io::Result<std::process::ExitStatus>,
+    ) {
+        // The screen was handed over, so any kitty images are gone
+        for proj in &mut self.projects {
+            proj.image_cache.clear(proj.max_lanes);
+        }
+        match result {
+            Ok(status) if status.success() => {}
+            Ok(status) => self.notify(
+                NotifyLevel::Warn,
+                format!("{} exited with {status}", cmd.label),
+            ),
+            Err(e) => self.notify(
+                NotifyLevel::Error,
+                format!("{} ({}): {e}", cmd.label, cmd.program),
+            ),
+        }
+    }
+
+    pub fn flush_kitty_if_needed(
+        &mut self,
+        backend: &mut impl std::io::Write,
+    ) -> std::io::Result<()> {
+        if !self.graphics_cap.is_kitty() {
+            return Ok(());
+        }
+        let compare_sides = self.compare.iter_mut().flat_map(|c| c.sides.iter_mut());
+        // Not `any`: every cache's dirty flag must be taken
+        let any_dirty = self
+            .projects
+            .iter_mut()
+            .chain(compare_sides)
+            .fold(false, |dirty, p| p.image_cache.take_dirty() | dirty);
+        if any_dirty {
+            write!(
+                backend,
+                "{}",
+                crate::kitty_protocol::delete_all_kitty_images()
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn cleanup_kitty(&self, backend: &mut impl std::io::Write) -> std::io::Result<()> {
+        if self.graphics_cap.is_kitty() {
+            write!(
+                backend,
+                "{}",
+                crate::kitty_protocol::delete_all_kitty_images()
+            )?;
+            backend.flush()?;
+        }
+        Ok(())
+    }
+
+    fn clamp_selected(&mut self) {
+        if let Some(proj) = self.projects.get(self.active_project) {
+            if !proj.rows.is_empty() && self.graph_selected() >= proj.rows.len() {
+                self.set_graph_selected(proj.rows.len() - 1);
+            }
+        }
+    }
+
+    fn toggle_author_filter(&mut self, name: String) {
+        let selected = self
+            .projects
+            .get(self.active_project)
+            .and_then(|p| p.rows.get(p.graph_selected))
+            .map(|r| r.meta.oid);
+        if self.author_filter_text == name {
+            self.author_filter_text.clear();
+        } else {
+            self.author_filter_text = name;
+        }
+        for idx in 0..self.projects.len() {
+            self.rebuild_graph_author_only(idx);
+        }
+        let row = self
+            .projects
+            .get(self.active_project)
+            .and_then(|p| p.rows.iter().position(|r| Some(r.meta.oid) == selected));
+        match row {
+            Some(row) => self.set_graph_selected(row),
+            None => self.clamp_selected(),
+        }
+        self.refresh_entries();
+    }
+
+    fn toggle_branch_section(&mut self) {
+        if let Some(entry) = self.cached_entries.get(self.branch_selected()) {
+            if let Some(key) = entry.section_key() {
+                let key = key.clone();
+                if self.collapsed_sections.contains(&key) {
+                    self.collapsed_sections.remove(&key);
+                } else {
+                    self.collapsed_sections.insert(key);
+                }
+                self.refresh_entries();
+            } else if let branch_panel::EntryKind::Author { ref name } = entry.kind {
+                self.toggle_author_filter(name.clone());
+            } else if let branch_panel::EntryKind::Submodule {
+                ref name,
+                ref workdir,
+                ..
+            } = entry.kind
+            {
+                let (name, workdir) = (name.clone(), workdir.clone());
+                self.confirm_open_submodule(name, workdir);
+            } else if let Some(tip) = entry.tip_oid() {
+                if let Some(proj) = self.projects.get(self.active_project) {
+                    if let Some(idx) = proj.rows.iter().position(|r| r.meta.oid == tip) {
+                        self.set_graph_selected(idx);
+                    }
+                }
+            }
+        }
+    }
+
+    // ── Render dispatch ─────────────────────────────────────────────
+
+    pub fn render(&mut self, frame: &mut Frame) {
+        let size = frame.area();
+
+        let bg_style = Style::default().bg(self.palette.app_bg);
+        for y in size.y..size.bottom() {
+            for x in size.x..size.right() {
+                frame.buffer_mut()[(x, y)].set_style(bg_style);
+            }
+        }
+
+        match &self.screen {
+            Screen::Graph => self.render_graph_screen(frame, size),
+            Screen::Config(state) => {
+                let widget = ConfigScreen {
+                    state,
+                    palette: &self.palette,
+                };
+                frame.render_widget(widget, size);
+            }
+        }
+    }
+
+    fn render_graph_screen(&mut self, frame: &mut Frame, size: Rect) {
+        let vert = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ])
+            .split(size);
+
+        let header_area = vert[0];
+        let body_area = vert[1];
+        let status_area = vert[2];
+        self.status_area = status_area;
+
+        self.render_header(frame, header_area);
+
+        let panel_w = self.branch_panel_width(size.width);
+        let detail_w: u16 = if self.show_detail { 50 } else { 0 };
+
+        let mut body_constraints = vec![
+            Constraint::Length(panel_w),
+            Constraint::Min(1), // graph
+        ];
+        if self.show_detail {
+            body_constraints.push(Constraint::Length(detail_w));
+        }
+
+        let body_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(body_constraints)
+            .split(body_area);
+
+        let branch_area = body_chunks[0];
+        let mut graph_area = body_chunks[1];
+
+        if self.compare.is_some() {
+            graph_area = body_area;
+            self.render_comparison(frame, body_area);
+        } else if self.aggregate.is_some() {
+            graph_area = body_area;
+            self.render_aggregate(frame, body_area);
+        } else {
+            self.render_bordered_branch_panel(frame, branch_area);
+            self.render_bordered_graph_panel(frame, graph_area);
+
+            if self.show_detail && body_chunks.len() >= 3 {
+                let detail_area = body_chunks[2];
+                self.render_bordered_detail_panel(frame, detail_area);
+            }
+        }
+
+        self.dismiss_stale_notifications();
+        self.render_status_bar(frame, status_area);
+        if self.show_perf_hud {
+            self.render_perf_hud(frame, graph_area);
+        }
+        if let Some(ref mut state) = self.blame {
+            state.update_scroll(blame_view::visible_lines(size));
+        }
+        self.help_page = help_panel::visible_rows(size);
+        self.help_scroll = self
+            .help_scroll
+            .min(help_panel::BINDINGS.len().saturating_sub(self.help_page));
+        self.render_overlays(frame, size);
+    }
+
+    fn render_perf_hud(&self, frame: &mut Frame, area: Rect) {
+        let proj = self.projects.get(self.active_project);
+        let hud = PerfHud {
+            stats: &self.perf,
+            layout_time: proj.map(|p| p.layout_time).unwrap_or_default(),
+            image_cache: if self.graphics_cap.is_kitty() {
+                proj.map(|p| p.image_cache.stats())
+            } else {
+                None
+            },
+            github_requests: proj
+                .and_then(|p| p.github_client())
+                .map(|c| c.request_count()),
+            palette: &self.palette,
+        };
+        let inner = Rect {
+            x: area.x.saturating_add(1),
+            y: area.y.saturating_add(1),
+            width: area.width.saturating_sub(2),
+            height: area.height.saturating_sub(2),
+        };
+        frame.render_widget(hud, inner);
+    }
+
+    fn render_bordered_branch_panel(&mut self, frame: &mut Frame, area: Rect) {
+        let is_active = self.active_panel == Panel::Branches;
+        let border_color = if is_active {
+            self.palette.active_panel_border
+        } else {
+            self.palette.inactive_panel_border
+        };
+        let title_color = if is_active {
+            self.palette.accent
+        } else {
+            self.palette.panel_label
+        };
+
+        let block = Block::default()
+            .title(" Branches ")
+            .title_style(
+                Style::default()
+                    .fg(title_color)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(border_color));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        // Away from the panel, it follows the graph selection to the
+        // branches and tags on that commit
+        let linked = if is_active {
+            None
+        } else {
+            self.projects
+                .get(self.active_project)
+                .and_then(|p| p.rows.get(p.graph_selected))
+                .map(|r| r.meta.oid)
+        };
+        // Quick actions take the bottom line while the panel has the focus
+        let footer = is_active && inner.height > 3;
+        let visible_height = inner.height as usize - usize::from(footer);
+        self.branch_page = visible_height;
+        if visible_height > 0 {
+            let target = linked
+                .and_then(|oid| branch_panel::linked_entry(&self.cached_entries, oid))
+                .unwrap_or(self.branch_selected());
+            self.branch_scroll = scroll_into_view(self.branch_scroll, target, visible_height);
+        }
+
+        // Only the branches in view are measured, so hundreds stay cheap
+        let shown: Vec<Oid> = self
+            .cached_entries
+            .iter()
+            .skip(self.branch_scroll)
+            .take(visible_height)
+            .filter(|e| e.branch_name().is_some())
+            .filter_map(|e| e.tip_oid())
+            .collect();
+        let mut tip_stats = HashMap::new();
+        for proj in &mut self.projects {
+            proj.ensure_tip_stats(&shown, &self.config.trunk_branches);
+            tip_stats.extend(
+                shown
+                    .iter()
+                    .filter_map(|tip| Some((*tip, *proj.tip_stats.get(tip)?))),
+            );
+        }
+
+        let branch_panel = BranchPanel {
+            entries: &self.cached_entries,
+            selected: self.branch_selected(),
+            scroll: self.branch_scroll,
+            focused: is_active,
+            linked,
+            tip_stats: &tip_stats,
+            footer,
+            github: self
+                .projects
+                .get(self.active_project)
+                .is_some_and(|p| p.github_client().is_some()),
+            palette: &self.palette,
+        };
+        frame.render_widget(branch_panel, inner);
+        let track = Rect {
+            x: area.right().saturating_sub(1),
+            width: 1,
+            ..inner
+        };
+        render_scrollbar(
+            frame.buffer_mut(),
+            track,
+            self.cached_entries.len(),
+            self.branch_scroll,
+            &self.palette,
+        );
+    }
+
+    fn render_bordered_graph_panel(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(other) = self.split.as_ref().map(|p| p.project) else {
+            self.render_graph_pane(frame, area, self.active_project);
+            return;
+        };
+        let halves = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+        // Panes keep project order on screen; Tab moves the focus, not them
+        let (left, right) = if self.active_project < other {
+            (self.active_project, other)
+        } else {
+            (other, self.active_project)
+        };
+        self.render_graph_pane(frame, halves[0], left);
+        self.render_graph_pane(frame, halves[1], right);
+    }
+
+    fn render_graph_pane(&mut self, frame: &mut Frame, area: Rect, project_idx: usize) {
+        let focused = project_idx == self.active_project;
+        let is_active = focused && self.active_panel == Panel::Graph;
+        let border_color = if is_active {
+            self.palette.active_panel_border
+        } else {
+            self.palette.inactive_panel_border
+        };
+
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(border_color));
+        if self.split.is_some() {
+            let name = self
+                .projects
+                .get(project_idx)
+                .map(|p| p.name.as_str())
+                .unwrap_or_default();
+            let (marker, color) = if focused {
+                ("\u{25b6} ", self.palette.accent)
+            } else {
+                ("", self.palette.panel_label)
+            };
+            block = block
+                .title(format!(" {marker}{name} "))
+                .title_style(Style::default().fg(color).add_modifier(Modifier::BOLD));
+        }
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let visible_height = (inner.height as usize).saturating_sub(1);
+        let rows_len = self.projects.get(project_idx).map_or(0, |p| p.rows.len());
+        let (selected, scroll_y) = if focused {
+            self.ensure_scroll_bounds(visible_height);
+            (self.graph_selected(), self.graph_scroll_y())
+        } else {
+            let Some(proj) = self.projects.get_mut(project_idx) else {
+                return;
+            };
+            // The pane's project may have been rebuilt with fewer rows
+            proj.graph_selected = proj.graph_selected.min(rows_len.saturating_sub(1));
+            proj.graph_scroll_y =
+                scroll_into_view(proj.graph_scroll_y, proj.graph_selected, visible_height);
+            (proj.graph_selected, proj.graph_scroll_y)
+        };
+
+        let highlighted: HashSet<_> = if focused {
+            self.get_highlighted_oids(&self.cached_entries)
+        } else {
+            HashSet::new()
+        };
+        if focused {
+            self.perf.rows_rendered = rows_len.saturating_sub(scroll_y).min(visible_height);
+        }
+
+        let render_params = self.graphics_cap.render_params();
+        if self.list_view && self.graphics_cap.is_kitty() {
+            self.clear_kitty_images_in_area(frame, inner);
+        }
+
+        if let Some(proj) = self.projects.get_mut(project_idx) {
+            if proj.loading {
+                let label = format!("{} {}", proj.name, project::LOADING_LABEL);
+                let w = (label.chars().count() as u16).min(inner.width);
+                let x = inner.x + (inner.width - w) / 2;
+                let y = inner.y + inner.height / 2;
+                frame.buffer_mut().set_stringn(
+                    x,
+                    y,
+                    &label,
+                    w as usize,
+                    Style::default().fg(self.palette.dim_text),
+                );
+                return;
+            }
+            let palette = match proj.active_mode {
+                ViewMode::Remote => self.palette.with_remote_tint(),
+                ViewMode::Local => self.palette.clone(),
+            };
+            if self.list_view {
+                ListView {
+                    rows: &proj.rows,
+                    scroll_y,
+                    selected,
+                    highlighted_oids: &highlighted,
+                    is_active,
+                    authors: AuthorNames {
+                        format: &self.authors,
+                        logins: proj.author_logins.as_ref(),
+                    },
+                    palette: &palette,
+                }
+                .render_into(inner, frame.buffer_mut());
+            } else {
+                let lane_cursor = self
+                    .lane_cursor
+                    .filter(|_| focused)
+                    .map(|lane| lane.min(proj.max_lanes.saturating_sub(1)));
+                follow_selection_x(proj, selected, lane_cursor, inner.width as usize);
+                let graph_view = GraphView {
+                    rows: &proj.rows,
+                    scroll_y,
+                    scroll_x: proj.scroll_x,
+                    lane_scroll: proj.lane_scroll,
+                    selected,
+                    highlighted_oids: &highlighted,
+                    marked_oids: None,
+                    unseen_oids: Some(&proj.unseen),
+                    remote_only: Some(&proj.remote_only),
+                    annotations: Some(&proj.annotations),
+                    weights: Some(&proj.commit_weights),
+                    large_file_bytes: self.config.large_file_bytes(),
+                    diff_stats: Some(&proj.diff_stats).filter(|_| self.show_diffstat),
+                    trunk_distances: proj
+                        .trunk_distances
+                        .as_ref()
+                        .filter(|_| self.show_trunk_distance),
+                    shallow: Some(&proj.repo_data.shallow),
+                    detached: Some(&proj.detached),
+                    unreleased: Some(&proj.unreleased),
+                    authors: AuthorNames {
+                        format: &self.authors,
+                        logins: proj.author_logins.as_ref(),
+                    },
+                    milestones: self.tag_milestones,
+                    is_active,
+                    trunk_count: proj.trunk_count,
+                    max_lanes: proj.max_lanes,
+                    lane_overflow: proj.lane_overflow,
+                    lane_cursor,
+                    palette: &palette,
+                    branch_index_to_name: &proj.branch_index_to_name,
+                    links: proj
+                        .web_url
+                        .as_deref()
+                        .filter(|_| self.config.hyperlinks)
+                        .map(|base| WebLinks {
+                            base,
+                            branches: &proj.repo_data.branches,
+                        }),
+                    graphics_cap: &self.graphics_cap,
+                    image_cache: &mut proj.image_cache,
+                    line_cache: &mut proj.line_cache,
+                    render_params: render_params.as_ref(),
+                };
+                graph_view.render_into(inner, frame.buffer_mut());
+                if let Some(lane) = lane_cursor {
+                    let tooltip = LaneTooltip {
+                        lane,
+                        anchor_x: inner.x
+                            + 1
+                            + (lane.saturating_sub(proj.lane_scroll) as u16) * COLS_PER_LANE,
+                        branch: lane_branch(proj, scroll_y, lane, &palette),
+                        palette: &self.palette,
+                    };
+                    frame.render_widget(tooltip, inner);
+                }
+            }
+        }
+        // On the right border, below the lane header
+        let track = Rect {
+            x: area.right().saturating_sub(1),
+            y: inner.y + 1,
+            width: 1,
+            height: visible_height as u16,
+        };
+        render_scrollbar(frame.buffer_mut(), track, rows_len, scroll_y, &self.palette);
+    }
+
+    fn render_aggregate(&mut self, frame: &mut Frame, area: Rect) {
+        if self.graphics_cap.is_kitty() {
+            self.clear_kitty_images_in_area(frame, area);
+        }
+        let Some(agg) = self.aggregate.as_mut() else {
+            return;
+        };
+        let block = Block::default()
+            .title(format!(
+                " All projects \u{00b7} {} commits (experimental) ",
+                agg.rows.len()
+            ))
+            .title_style(
+                Style::default()
+                    .fg(self.palette.accent)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.palette.active_panel_border));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        agg.update_scroll(inner.height as usize);
+        let (rows_len, scroll_y) = (agg.rows.len(), agg.scroll_y);
+        AggregateView {
+            aggregate: agg,
+            palette: &self.palette,
+        }
+        .render_into(inner, frame.buffer_mut());
+        let track = Rect {
+            x: area.right().saturating_sub(1),
+            width: 1,
+            ..inner
+        };
+        render_scrollbar(frame.buffer_mut(), track, rows_len, scroll_y, &self.palette);
+    }
+
+    fn render_comparison(&mut self, frame: &mut Frame, area: Rect) {
+        let halves = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+        let render_params = self.graphics_cap.render_params();
+        let Some(cmp) = self.compare.as_mut() else {
+            return;
+        };
+        // Borders plus the lane header
+        cmp.update_scroll((area.height as usize).saturating_sub(3));
+        let only = [cmp.only_on(0), cmp.only_on(1)];
+        let Comparison {
+            sides,
+            selected,
+            scroll_y,
+            focus,
+            shared,
+        } = cmp;
+        let no_highlights = HashSet::new();
+        for (side, proj) in sides.iter_mut().enumerate() {
+            let is_active = *focus == side;
+            let (border_color, title_color) = if is_active {
+                (self.palette.active_panel_border, self.palette.accent)
+            } else {
+                (self.palette.inactive_panel_border, self.palette.panel_label)
+            };
+            let block = Block::default()
+                .title(format!(" {} \u{00b7} {} only here ", proj.name, only[side]))
+                .title_style(
+                    Style::default()
+                        .fg(title_color)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(border_color));
+            let inner = block.inner(halves[side]);
+            frame.render_widget(block, halves[side]);
+
+            let graph_view = GraphView {
+                rows: &proj.rows,
+                scroll_y: scroll_y[side],
+                scroll_x: proj.scroll_x,
+                lane_scroll: 0,
+                selected: selected[side],
+                highlighted_oids: &no_highlights,
+                marked_oids: Some(shared),
+                unseen_oids: None,
+                remote_only: None,
+                annotations: None,
+                weights: None,
+                large_file_bytes: u64::MAX,
+                diff_stats: None,
+                trunk_distances: None,
+                shallow: Some(&proj.repo_data.shallow),
+                detached: Some(&proj.detached),
+                unreleased: None,
+                authors: AuthorNames {
+                    format: &self.authors,
+                    logins: proj.author_logins.as_ref(),
+                },
+                milestones: self.tag_milestones,
+                is_active,
+                trunk_count: proj.trunk_count,
+                max_lanes: proj.max_lanes,
+                lane_overflow: proj.lane_overflow,
+                lane_cursor: None,
+                palette: &self.palette,
+                branch_index_to_name: &proj.branch_index_to_name,
+                links: None,
+                graphics_cap: &self.graphics_cap,
+                image_cache: &mut proj.image_cache,
+                line_cache: &mut proj.line_cache,
+                render_params: render_params.as_ref(),
+            };
+            graph_view.render_into(inner, frame.buffer_mut());
+            let track = Rect {
+                x: halves[side].right().saturating_sub(1),
+                y: inner.y + 1,
+                width: 1,
+                height: inner.height.saturating_sub(1),
+            };
+            render_scrollbar(
+                frame.buffer_mut(),
+                track,
+                proj.rows.len(),
+                scroll_y[side],
+                &self.palette,
+            );
+        }
+    }
+
+    fn render_bordered_detail_panel(&mut self, frame: &mut Frame, area: Rect) {
+        let is_active = self.active_panel == Panel::Detail;
+        let border_color = if is_active {
+            self.palette.active_panel_border
+        } else {
+            self.palette.inactive_panel_border
+        };
+        let title_color = if is_active {
+            self.palette.accent
+        } else {
+            self.palette.panel_label
+        };
+
+        let block = Block::default()
+            .title(" Detail ")
+            .title_style(
+                Style::default()
+                    .fg(title_color)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(border_color));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        if let Some(&branch_panel::EntryKind::Stash {
+            index,
+            base,
+            ref message,
+        }) = self
+            .cached_entries
+            .get(self.branch_selected())
+            .filter(|_| self.active_panel == Panel::Branches)
+            .map(|e| &e.kind)
+        {
+            let base_summary = self
+                .projects
+                .get(self.active_project)
+                .and_then(|proj| proj.rows.iter().find(|r| r.meta.oid == base))
+                .and_then(|r| r.meta.message.lines().next());
+            let detail = StashDetail {
+                index,
+                base,
+                base_summary,
+                message,
+                palette: &self.palette,
+            };
+            frame.render_widget(detail, inner);
+            return;
+        }
+
+        if let Some(proj) = self.projects.get(self.active_project) {
+            if let Some(row) = proj.rows.get(proj.graph_selected) {
+                let files = match self.detail_oid {
+                    Some(oid) if oid == row.meta.oid => self.detail_files.as_deref(),
+                    _ => None,
+                };
+                let detail_current = self.detail_oid == Some(row.meta.oid);
+                let detail = DetailPanel {
+                    meta: &row.meta,
+                    files,
+                    diff: self
+                        .show_diff
+                        .then_some(self.detail_diff.as_ref().filter(|_| detail_current)),
+                    diff_scroll: self.detail_diff_scroll,
+                    message_scroll: self.detail_message_scroll,
+                    upstreams: proj
+                        .repo_data
+                        .branches
+                        .iter()
+                        .filter(|b| b.tip == row.meta.oid)
+                        .filter_map(|b| Some((b.name.as_str(), b.upstream.as_ref()?)))
+                        .collect(),
+                    tag_signatures: row
+                        .meta
+                        .tag_names
+                        .iter()
+                        .filter_map(|name| Some((name.as_str(), proj.tag_signature(name)?)))
+                        .collect(),
+                    note: proj.annotations.get(&row.meta.oid),
+                    owners: match (&proj.codeowners, files) {
+                        (Some(owners), Some(files)) => {
+                            owners.owners_for(files.iter().map(|f| f.path.as_str()))
+                        }
+                        _ => Vec::new(),
+                    },
+                    large_file_bytes: self.config.large_file_bytes(),
+                    hyperlinks: self.config.hyperlinks,
+                    links: proj
+                        .web_url
+                        .as_deref()
+                        .filter(|_| self.config.hyperlinks)
+                        .map(|base| WebLinks {
+                            base,
+                            branches: &proj.repo_data.branches,
+                        }),
+                    file_selected: self.detail_file_selected,
+                    focused: is_active,
+                    dates: &self.dates,
+                    palette: &self.palette,
+                };
+                let (rows, lines) = detail.message_layout(inner);
+                frame.render_widget(detail, inner);
+                self.detail_message_page = rows;
+                self.detail_message_scroll = self
+                    .detail_message_scroll
+                    .min(lines.len().saturating_sub(rows));
+            }
+        }
+    }
+
+    fn render_header(&self, frame: &mut Frame, area: Rect) {
+        let proj = self.projects.get(self.active_project);
+        let mut shown = vec![self.active_project];
+        if let Some(ref pane) = self.split {
+            shown.push(pane.project);
+            shown.sort_unstable();
+        }
+        let infos: Vec<PaneInfo<'_>> = shown
+            .iter()
+            .filter_map(|&idx| self.projects.get(idx))
+            .map(|p| PaneInfo {
+                name: &p.name,
+                branch: &p.current_branch,
+                commit_count: p.rows.len(),
+            })
+            .collect();
+        let focused_pane = shown
+            .iter()
+            .position(|&idx| idx == self.active_project)
+            .unwrap_or(0);
+        let last_sync = proj.map(|p| p.last_sync.as_str()).unwrap_or("never");
+        let view_mode = proj.map(|p| &p.active_mode);
+        let members = self.workspace_projects();
+        let header = HeaderBar {
+            panes: &infos,
+            focused_pane,
+            last_sync,
+            author_filter: &self.author_filter_text,
+            view_mode,
+            stale: proj.is_some_and(|p| p.stale_remote.is_some()),
+            stats: proj.map(|p| &p.stats),
+            project_count: members.len(),
+            active_project_idx: members
+                .iter()
+                .position(|&idx| idx == self.active_project)
+                .unwrap_or(0),
+            workspace: self.workspace.as_deref(),
+            palette: &self.palette,
+        };
+        frame.render_widget(header, area);
+    }
+
+    fn branch_panel_width(&self, term_w: u16) -> u16 {
+        let max_w = branch_panel::max_entry_width(&self.cached_entries);
+        let tw = term_w as usize;
+        (max_w + 4 + branch_panel::TIP_STAT_W).clamp(22, (tw / 3).max(22)) as u16
+    }
+
+    fn dismiss_stale_notifications(&mut self) {
+        if let Some(ref n) = self.notification {
+            if n.created.elapsed() > std::time::Duration::from_secs(n.level.ttl_secs()) {
+                self.notification = None;
+            }
+        }
+    }
+
+    fn walk_progress(&self, walked: usize) -> String {
+        match self.config.max_commits {
+            0 => format!("walked {} commits", format_count(walked)),
+            max => format!(
+                "walked {}/{} commits",
+                format_count(walked),
+                format_count(max)
+            ),
+        }
+    }
+
+    fn status_bar(&self) -> StatusBar<'_> {
+        let active = self.projects.get(self.active_project);
+        let loading_message = if self.loading_remote {
+            Some("loading remote data...".to_string())
+        } else if let Some(walked) = active.and_then(|p| p.load_progress) {
+            Some(self.walk_progress(walked))
+        } else if let Some(fetch) = active.and_then(|p| p.fetch_progress.as_ref()) {
+            Some(match fetch.total {
+                0 => "fetching remotes...".to_string(),
+                total => format!(
+                    "fetching {} {}/{} objects",
+                    fetch.remote,
+                    format_count(fetch.received),
+                    format_count(total)
+                ),
+            })
+        } else if active.is_some_and(|p| p.loading) {
+            Some("loading repository...".to_string())
+        } else {
+            None
+        };
+        let commit_count = active.map(|p| p.rows.len()).unwrap_or(0);
+        let branch_count = active.map(|p| p.repo_data.branches.len()).unwrap_or(0);
+        StatusBar {
+            branch_name: active.map(|p| p.current_branch.as_str()).unwrap_or(""),
+            last_sync: active.map(|p| p.last_sync.as_str()).unwrap_or("never"),
+            filter_mode: self.filter_mode,
+            filter_text: &self.filter_text,
+            author_filter_text: &self.author_filter_text,
+            type_filter: &self.type_filter,
+            prompt: self.prompt.as_ref().map(|p| (p.label, p.text.as_str())),
+            loading_message,
+            recording: self.macros.recording(),
+            github_paused_until: data_source::github_paused_until()
+                .map(|t| self.dates.time(t.with_timezone(&chrono::Local).time())),
+            offline: self.offline.map(|offline| match offline {
+                Offline::Manual => "offline",
+                Offline::Unreachable => "offline: GitHub unreachable",
+            }),
+            following: self.follow,
+            manual_refresh: self.manual_refresh,
+            commit_count,
+            selected_row: self.graph_selected(),
+            branch_count,
+            palette: &self.palette,
+        }
+    }
+
+    fn render_status_bar(&self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(self.status_bar(), area);
+    }
+
+    fn render_overlays(&self, frame: &mut Frame, size: Rect) {
+        if self.show_help {
+            if self.graphics_cap.is_kitty() {
+                self.clear_kitty_images_in_area(frame, size);
+            }
+            frame.render_widget(
+                HelpPanel {
+                    scroll: self.help_scroll,
+                    palette: &self.palette,
+                },
+                size,
+            );
+        }
+        if let Some(ref state) = self.releases {
+            if self.graphics_cap.is_kitty() {
+                self.clear_kitty_images_in_area(frame, size);
+            }
+            frame.render_widget(
+                ReleasePanel {
+                    state,
+                    dates: &self.dates,
+                    palette: &self.palette,
+                },
+                size,
+            );
+        }
+        if let Some(ref state) = self.health {
+            if self.graphics_cap.is_kitty() {
+                self.clear_kitty_images_in_area(frame, size);
+            }
+            frame.render_widget(
+                HealthPanel {
+                    state,
+                    dates: &self.dates,
+                    palette: &self.palette,
+                },
+                size,
+            );
+        }
+        if let Some(ref state) = self.heatmap {
+            if self.graphics_cap.is_kitty() {
+                self.clear_kitty_images_in_area(frame, size);
+            }
+            frame.render_widget(
+                HeatmapPanel {
+                    state,
+                    today: chrono::Local::now().date_naive(),
+                    dates: &self.dates,
+                    palette: &self.palette,
+                },
+                size,
+            );
+        }
+        if let Some(ref state) = self.stashes {
+            if self.graphics_cap.is_kitty() {
+                self.clear_kitty_images_in_area(frame, size);
+            }
+            frame.render_widget(
+                StashPanel {
+                    state,
+                    palette: &self.palette,
+                },
+                size,
+            );
+        }
+        if let Some(ref state) = self.head_timeline {
+            if self.graphics_cap.is_kitty() {
+                self.clear_kitty_images_in_area(frame, size);
+            }
+            frame.render_widget(
+                HeadTimelinePanel {
+                    state,
+                    dates: &self.dates,
+                    palette: &self.palette,
+                },
+                size,
+            );
+        }
+        if let Some(ref state) = self.blame {
+            if self.graphics_cap.is_kitty() {
+                self.clear_kitty_images_in_area(frame, size);
+            }
+            frame.render_widget(
+                BlameView {
+                    state,
+                    palette: &self.palette,
+                },
+                size,
+            );
+        }
+        if let Some(ref state) = self.refs_popover {
+            if self.graphics_cap.is_kitty() {
+                self.clear_kitty_images_in_area(frame, size);
+            }
+            frame.render_widget(
+                RefPopover {
+                    state,
+                    palette: &self.palette,
+                },
+                size,
+            );
+        }
+        if let Some(ref state) = self.deleted_refs {
+            if self.graphics_cap.is_kitty() {
+                self.clear_kitty_images_in_area(frame, size);
+            }
+            frame.render_widget(
+                DeletedPanel {
+                    entries: &self.recently_deleted,
+                    state,
+                    palette: &self.palette,
+                },
+                size,
+            );
+        }
+        if let Some(ref state) = self.pr_merge {
+            if self.graphics_cap.is_kitty() {
+                self.clear_kitty_images_in_area(frame, size);
+            }
+            frame.render_widget(
+                PrMergePanel {
+                    state,
+                    palette: &self.palette,
+                },
+                size,
+            );
+        }
+        if let Some(ref state) = self.rebase_preview {
+            if self.graphics_cap.is_kitty() {
+                self.clear_kitty_images_in_area(frame, size);
+            }
+            frame.render_widget(
+                RebasePreviewPanel {
+                    state,
+                    palette: &self.palette,
+                },
+                size,
+            );
+        }
+        if let Some(ref state) = self.conflict_preview {
+            if self.graphics_cap.is_kitty() {
+                self.clear_kitty_images_in_area(frame, size);
+            }
+            frame.render_widget(
+                ConflictPreviewPanel {
+                    state,
+                    palette: &self.palette,
+                },
+                size,
+            );
+        }
+        if self.confirm_quit {
+            self.render_confirm_dialog(frame, size, " Quit ", "Discard changes? (y/n)");
+        }
+        if let Some(ref confirm) = self.confirm {
+            self.render_confirm_dialog(frame, size, confirm.title, &confirm.question);
+        }
+        if let Some(ref n) = self.notification {
+            frame.render_widget(
+                crate::ui::toast::Toast {
+                    notification: n,
+                    palette: &self.palette,
+                },
+                size,
+            );
+        }
+    }
+
+    fn render_confirm_dialog(&self, frame: &mut Frame, size: Rect, title: &str, question: &str) {
+        use ratatui::text::{Line, Span};
+        let dialog_area = crate::ui::centered_rect(30, 15, size);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.palette.warn_fg))
+            .title(title.to_string())
+            .title_style(
+                Style::default()
+                    .fg(self.palette.warn_fg)
+                    .add_modifier(Modifier::BOLD),
+            );
+        let inner = block.inner(dialog_area);
+        frame.render_widget(ratatui::widgets::Clear, dialog_area);
+        frame.render_widget(block, dialog_area);
+        if inner.height >= 2 && inner.width >= 10 {
+            let msg = Line::from(Span::styled(
+                question.to_string(),
+                Style::default().fg(self.palette.content_fg),
+            ));
+            frame.buffer_mut().set_line(
+                inner.x + 1,
+                inner.y + 1,
+                &msg,
+                inner.width.saturating_sub(2),
+            );
+        }
+    }
+
+    fn clear_kitty_images_in_area(&self, frame: &mut Frame, area: Rect) {
+        use ratatui::layout::Position;
+        let del = crate::kitty_protocol::delete_all_kitty_images();
+        if let Some(cell) = frame.buffer_mut().cell_mut(Position::new(area.x, area.y)) {
+            cell.set_symbol(&del);
+        }
+    }
+
+    fn ensure_scroll_bounds(&mut self, visible_height: usize) {
+        if let Some(proj) = self.projects.get_mut(self.active_project) {
+            proj.graph_scroll_y =
+                scroll_into_view(proj.graph_scroll_y, proj.graph_selected, visible_height);
+        }
+    }
+
+    fn get_highlighted_oids(&self, entries: &[DisplayEntry]) -> HashSet<crate::git::types::Oid> {
+        let mut set = HashSet::new();
+        if self.active_panel == Panel::Branches {
+            if let Some(entry) = entries.get(self.branch_selected()) {
+                if let Some(tip) = entry.tip_oid() {
+                    set.insert(tip);
+                }
+            }
+        }
+        if let (Some(anchor), Some(proj)) =
+            (self.visual_anchor, self.projects.get(self.active_project))
+        {
+            let lo = anchor.min(self.graph_selected());
+            let hi = anchor.max(self.graph_selected());
+            set.extend(
+                proj.rows
+                    .iter()
+                    .skip(lo)
+                    .take(hi - lo + 1)
+                    .map(|r| r.meta.oid),
+            );
+        }
+        if let Some((ref fresh, _)) = self.flash {
+            set.extend(fresh);
+        }
+        set
+    }
+}
+
+struct RefreshBaseline {
+    mode: ViewMode,
+    rows: HashSet<Oid>,
+    tips: HashMap<(CommitSource, String), Oid>,
+}
+
+// A branch or, failing that, a tag
+fn ref_tip(data: &RepoData, name: &str) -> Option<Oid> {
+    data.branches
+        .iter()
+        .find(|b| b.name == name)
+        .map(|b| b.tip)
+        .or_else(|| data.tags.iter().find(|t| t.name == name).map(|t| t.target))
+}
+
+// Keyed by where it lives so forks' same-named branches stay apart
+fn branch_tips_by_name(data: &RepoData) -> HashMap<(CommitSource, String), Oid> {
+    data.branches
+        .iter()
+        .map(|b| ((b.source.clone(), b.name.clone()), b.tip))
+        .collect()
+}
+
+const MIN_TEXT_WIDTH: usize = 8;
+
+fn lane_branch<'a>(
+    proj: &'a Project,
+    top: usize,
+    lane: usize,
+    palette: &ThemePalette,
+) -> Option<LaneBranch<'a>> {
+    let index = (*proj.rows.get(top)?.layout.lane_branches.get(lane)?)?;
+    let name = proj.branch_index_to_name.get(&index)?;
+    let tip = proj
+        .repo_data
+        .branches
+        .iter()
+        .find(|b| b.name == *name)
+        .and_then(|b| proj.rows.iter().find(|r| r.meta.oid == b.tip));
+    Some(LaneBranch {
+        name,
+        color: palette.branch_color_by_identity(index, proj.trunk_count),
+        tip: tip.map(|r| &r.meta),
+        commits: proj
+            .rows
+            .iter()
+            .filter(|r| r.meta.branch_index == Some(index))
+            .count(),
+    })
+}
+
+// Brings the text back to its start when the selection lands on branch labels
+fn follow_selection_x(proj: &mut Project, selected: usize, lane: Option<usize>, width: usize) {
+    let Some(row) = proj.rows.get(selected) else {
+        return;
+    };
+    if proj.x_followed != Some(row.meta.oid) {
+        proj.x_followed = Some(row.meta.oid);
+        if !row.meta.branch_names.is_empty() {
+            proj.scroll_x = 0;
+        }
+    }
+    let lanes_shown = width.saturating_sub(1 + MIN_TEXT_WIDTH) / COLS_PER_LANE as usize;
+    proj.lane_scroll = if proj.max_lanes <= lanes_shown {
+        0
+    } else {
+        let lane_scroll = proj.lane_scroll.min(proj.max_lanes - lanes_shown.max(1));
+        let lane = lane.unwrap_or(row.layout.commit_lane);
+        scroll_into_view(lane_scroll, lane, lanes_shown.max(1))
+    };
+}
+
+fn scroll_into_view(scroll: usize, selected: usize, visible: usize) -> usize {
+    if visible == 0 {
+        scroll
+    } else if selected >= scroll + visible {
+        selected - visible + 1
+    } else if selected < scroll {
+        selected
+    } else {
+        scroll
+    }
+}
+
+fn start_of_today() -> chrono::DateTime<chrono::Utc> {
+    let midnight = chrono::Local::now()
+        .date_naive()
+        .and_time(chrono::NaiveTime::MIN);
+    midnight
+        .and_local_timezone(chrono::Local)
+        .earliest()
+        .map_or_else(chrono::Utc::now, |t| t.with_timezone(&chrono::Utc))
+}
+
+pub fn head_branch_name(data: &RepoData) -> String {
+    data.branches
+        .iter()
+        .find(|b| b.is_head)
+        .map(|b| b.name.clone())
+        .unwrap_or_else(|| "detached".to_string())
+}
+
+pub fn expand_tilde(path: &std::path::Path) -> std::path::PathBuf {
+    let s = path.to_string_lossy();
+    if let Some(rest) = s.strip_prefix("~/") {
+        if let Ok(home) = std::env::var("HOME") {
+            return std::path::PathBuf::from(home).join(rest);
+        }
+    }
+    path.to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WorkspaceEntry;
+    use crate::test_utils::sample_repo;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use ratatui::{backend::TestBackend, Terminal};
+    use std::path::PathBuf;
+
+    const WIDTH: u16 = 100;
+    const HEIGHT: u16 = 20;
+
+    fn sample_app() -> App {
+        let config = Config::default();
+        let project = Project::snapshot("demo".to_string(), sample_repo(), &config.trunk_branches);
+        let mut app = App::new(config, GraphicsCapability::Unsupported);
+        app.projects.push(project);
+        app.refresh_entries();
+        app
+    }
+
+    fn press(app: &mut App, c: char) {
+        let key = KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        app.handle_event(AppEvent::Key(key));
+    }
+
+    fn render(app: &mut App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+        terminal.draw(|f| app.render(f)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let mut out = String::new();
+        for y in 0..buffer.area.height {
+            let row: String = (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect();
+            out.push_str(row.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+
+    // Run with UPDATE_SNAPSHOTS=1 to write new or changed snapshots
+    fn assert_snapshot(name: &str, actual: &str) {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/snapshots")
+            .join(format!("{name}.snap"));
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, actual).unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+            panic!(
+                "no snapshot at {}; run with UPDATE_SNAPSHOTS=1",
+                path.display()
+            )
+        });
+        if expected != actual {
+            let line = expected
+                .lines()
+                .zip(actual.lines())
+                .position(|(e, a)| e != a)
+                .unwrap_or(expected.lines().count().min(actual.lines().count()));
+            panic!(
+                "snapshot {name} differs from line {}\n--- expected\n{expected}\n--- actual\n{actual}",
+                line + 1
+            );
+        }
+    }
+
+    #[test]
+    fn graph_screen() {
+        let mut app = sample_app();
+        assert_snapshot("graph", &render(&mut app));
+    }
+
+    #[test]
+    fn detail_panel() {
+        let mut app = sample_app();
+        press(&mut app, 'j');
+        press(&mut app, 'd');
+        // The commit's absolute time moves with the clock; pin its shape
+        app.dates = DateFormat::default();
+        let stamp = app.dates.date_time(&app.projects[0].rows[1].meta.time);
+        let screen = render(&mut app).replace(&stamp, "YYYY-MM-DD hh:mm");
+        assert_snapshot("detail", &screen);
+    }
+
+    #[test]
+    fn detail_message_wraps_and_pages() {
+        let mut app = sample_app();
+        press(&mut app, 'd');
+        app.active_panel = Panel::Detail;
+        let body = "word ".repeat(200);
+        app.projects[0].rows[0].meta.message = format!("Long subject\n\n{body}");
+        let key = |code| AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        let screen = render(&mut app);
+        assert!(screen.contains("Long subject"));
+        let wrapped = screen.lines().filter(|l| l.contains("word word")).count();
+        assert!(wrapped > 1, "wrapped, not truncated:\n{screen}");
+
+        app.handle_event(key(KeyCode::PageDown));
+        let screen = render(&mut app);
+        let page = app.detail_message_page;
+        assert_eq!(app.detail_message_scroll, page);
+        assert!(!screen.contains("Long subject"));
+
+        app.handle_event(key(KeyCode::End));
+        render(&mut app);
+        let last = app.detail_message_scroll;
+        assert!(last > page);
+        app.handle_event(key(KeyCode::PageDown));
+        render(&mut app);
+        assert_eq!(app.detail_message_scroll, last);
+        app.handle_event(key(KeyCode::Home));
+        assert_eq!(app.detail_message_scroll, 0);
+    }
+
+    #[test]
+    fn stash_in_the_branch_panel_shows_its_base_and_message() {
+        use crate::test_utils::make_oid;
+        let config = Config::default();
+        let mut data = sample_repo();
+        data.stashes.push(crate::git::stash::StashEntry {
+            index: 0,
+            message: "On main: half-done login copy".to_string(),
+            oid: make_oid(9),
+            base: make_oid(2),
+        });
+        let project = Project::snapshot("demo".to_string(), data, &config.trunk_branches);
+        let mut app = App::new(config, GraphicsCapability::Unsupported);
+        app.projects.push(project);
+        app.refresh_entries();
+        app.show_detail = true;
+        app.active_panel = Panel::Branches;
+        let stash = app
+            .cached_entries
+            .iter()
+            .position(|e| matches!(e.kind, branch_panel::EntryKind::Stash { .. }))
+            .unwrap();
+        app.set_branch_selected(stash);
+
+        let screen = render(&mut app);
+        assert!(screen.contains("Stash stash@{0}"), "{screen}");
+        assert!(screen.contains("On main: half-done login copy"));
+        assert!(
+            screen.contains("Bump version to 1.1"),
+            "base subject:\n{screen}"
+        );
+        assert!(app
+            .get_highlighted_oids(&app.cached_entries)
+            .contains(&make_oid(2)));
+
+        let key = |code| AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        app.handle_event(key(KeyCode::Enter));
+        assert_eq!(
+            app.projects[0].rows[app.graph_selected()].meta.oid,
+            make_oid(2)
+        );
+    }
+
+    #[test]
+    fn focused_branch_panel_lists_quick_actions_for_the_selected_entry() {
+        let mut app = sample_app();
+        let footer = |app: &mut App| {
+            let screen = render(app);
+            let lines: Vec<_> = screen.lines().collect();
+            // The panel's bottom line, above its border and the status bar
+            lines[lines.len() - 3].to_string()
+        };
+        assert!(!footer(&mut app).contains("Enter"), "only while focused");
+
+        app.active_panel = Panel::Branches;
+        let select = |app: &mut App, name: &str| {
+            let row = app
+                .cached_entries
+                .iter()
+                .position(|e| e.branch_name() == Some(name));
+            app.set_branch_selected(row.unwrap());
+        };
+        select(&mut app, "feature/login");
+        let line = footer(&mut app);
+        assert!(line.contains("Enter jump  B refs"), "{line}");
+        let actions =
+            branch_panel::quick_actions(&app.cached_entries[app.branch_selected()].kind, false);
+        assert!(actions.contains(&("D", "delete")));
+        assert!(actions.contains(&("E", "rebase")));
+        assert!(!actions.contains(&("P", "merge PR")), "no GitHub");
+
+        select(&mut app, "main");
+        let actions =
+            branch_panel::quick_actions(&app.cached_entries[app.branch_selected()].kind, true);
+        assert!(!actions.contains(&("D", "delete")), "HEAD can't be deleted");
+        assert!(actions.contains(&("P", "merge PR")));
+
+        // B opens the refs on the selected entry's tip, not the graph's row
+        app.set_branch_selected(
+            app.cached_entries
+                .iter()
+                .position(|e| matches!(e.kind, EntryKind::Tag { .. }))
+                .unwrap(),
+        );
+        press(&mut app, 'B');
+        let popover = app.refs_popover.as_ref().unwrap();
+        assert!(popover.refs.iter().any(|r| r.name == "v1.0"));
+    }
+
+    #[test]
+    fn branches_are_created_at_commits_and_deleted_after_confirming() {
+        let mut app = sample_app();
+        let warning = |app: &mut App| app.notification.take().map(|n| n.message);
+        press(&mut app, 'n');
+        assert!(app.prompt.is_none());
+        assert_eq!(
+            warning(&mut app).as_deref(),
+            Some("creating branches needs a local checkout")
+        );
+        press(&mut app, 'D');
+        assert_eq!(
+            warning(&mut app).as_deref(),
+            Some("select a local branch or tag in the branch panel to delete")
+        );
+
+        app.active_panel = Panel::Branches;
+        let select = |app: &mut App, name: &str| {
+            let row = app.cached_entries.iter().position(|e| {
+                matches!(e.kind, EntryKind::LocalBranch { .. }) && e.branch_name() == Some(name)
+            });
+            app.set_branch_selected(row.unwrap());
+        };
+        select(&mut app, "main");
+        press(&mut app, 'D');
+        assert!(app.confirm.is_none());
+        assert_eq!(
+            warning(&mut app).as_deref(),
+            Some("main is checked out and can't be deleted")
+        );
+
+        select(&mut app, "feature/login");
+        press(&mut app, 'D');
+        let question = app.confirm.as_ref().map(|c| c.question.clone());
+        assert_eq!(question.as_deref(), Some("delete feature/login? (y/n)"));
+        press(&mut app, 'y');
+        assert!(app.confirm.is_none());
+        assert_eq!(
+            warning(&mut app).as_deref(),
+            Some("deleting branches needs a local checkout")
+        );
+
+        let tag = app
+            .cached_entries
+            .iter()
+            .position(|e| matches!(e.kind, EntryKind::Tag { .. }));
+        app.set_branch_selected(tag.unwrap());
+        press(&mut app, 'D');
+        let question = app.confirm.as_ref().map(|c| c.question.clone());
+        assert_eq!(question.as_deref(), Some("delete tag v1.0? (y/n)"));
+        press(&mut app, 'y');
+        assert_eq!(
+            warning(&mut app).as_deref(),
+            Some("deleting tags needs a local checkout")
+        );
+        app.handle_event(AppEvent::TagDeleted {
+            project_idx: 0,
+            tag: "v1.0".to_string(),
+            result: Ok(DeletedRef::Tag {
+                name: "v1.0".to_string(),
+                target: crate::test_utils::make_oid(3),
+            }),
+        });
+        assert_eq!(
+            warning(&mut app).as_deref(),
+            Some("deleted tag v1.0 \u{2014} U to undo")
+        );
+        assert_eq!(app.recently_deleted[0].deleted.label(), "tag v1.0");
+
+        app.handle_event(AppEvent::BranchCreated {
+            project_idx: 0,
+            branch: "topic".to_string(),
+            result: Err("a reference with that name already exists".to_string()),
+        });
+        assert_eq!(
+            warning(&mut app).as_deref(),
+            Some("creating topic failed: a reference with that name already exists")
+        );
+    }
+
+    #[test]
+    fn rebase_preview_lists_replayed_commits_and_jumps_to_the_new_base() {
+        use crate::test_utils::make_oid;
+        let mut app = sample_app();
+        let key = |code| AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        app.active_panel = Panel::Branches;
+        let row = app
+            .cached_entries
+            .iter()
+            .position(|e| e.branch_name() == Some("feature/login"));
+        app.set_branch_selected(row.unwrap());
+        press(&mut app, 'E');
+        let prompt = app.prompt.as_ref().map(|p| p.text.clone());
+        assert_eq!(prompt.as_deref(), Some("main"));
+        app.handle_event(key(KeyCode::Enter));
+        let preview = app.rebase_preview.as_ref().unwrap();
+        assert!(preview.plan.replayed.is_empty());
+        assert!(render(&mut app).contains("main already has every commit of feature/login"));
+        press(&mut app, 'E');
+        assert!(app.rebase_preview.is_none());
+
+        press(&mut app, 'E');
+        app.prompt.as_mut().unwrap().text.clear();
+        for c in "fix/empty-config".chars() {
+            press(&mut app, c);
+        }
+        app.handle_event(key(KeyCode::Enter));
+        let preview = app.rebase_preview.as_ref().unwrap();
+        assert_eq!(
+            preview.plan.replayed,
+            vec![make_oid(3), make_oid(4), make_oid(5)]
+        );
+        assert_eq!(preview.commits.len(), 3);
+        assert!(render(&mut app).contains("3 commits replayed onto fix/empty-config"));
+
+        app.handle_event(key(KeyCode::Enter));
+        assert!(app.rebase_preview.is_none());
+        assert_eq!(app.active_panel, Panel::Graph);
+        let selected = app.graph_selected();
+        assert_eq!(app.projects[0].rows[selected].meta.oid, make_oid(7));
+
+        // Commits the author filter hides are still replayed
+        let data = app.projects[0].repo_data.clone();
+        app.projects[0].cached_repo_data = Some(data);
+        app.author_filter_text = "bob".to_string();
+        app.rebuild_graph_author_only(0);
+        app.active_panel = Panel::Branches;
+        app.set_branch_selected(row.unwrap());
+        press(&mut app, 'E');
+        app.prompt.as_mut().unwrap().text = "fix/empty-config".to_string();
+        app.handle_event(key(KeyCode::Enter));
+        let preview = app.rebase_preview.as_ref().unwrap();
+        assert_eq!(
+            preview.plan.replayed,
+            vec![make_oid(3), make_oid(4), make_oid(5)]
+        );
+        assert_eq!(preview.plan.base, Some(make_oid(6)));
+    }
+
+    #[test]
+    fn cherry_pick_needs_a_checkout_and_reports_conflicts() {
+        use crate::test_utils::make_oid;
+        let mut app = sample_app();
+        press(&mut app, 'Y');
+        assert!(app.confirm.is_none());
+        assert_eq!(
+            app.notification.take().map(|n| n.message).as_deref(),
+            Some("cherry-picking needs a local checkout")
+        );
+
+        let oid = make_oid(7);
+        let short = oid.to_string()[..7].to_string();
+        app.handle_event(AppEvent::CherryPicked {
+            project_idx: 0,
+            oid,
+            result: Ok(CherryPickOutcome::Conflicts(vec![
+                "a.txt".to_string(),
+                "b.txt".to_string(),
+            ])),
+        });
+        let toast = app.notification.take().unwrap();
+        assert!(matches!(toast.level, NotifyLevel::Warn));
+        assert_eq!(
+            toast.message,
+            format!("cherry-pick of {short} aborted, it conflicts in a.txt, b.txt")
+        );
+        app.handle_event(AppEvent::CherryPicked {
+            project_idx: 0,
+            oid,
+            result: Ok(CherryPickOutcome::UpToDate),
+        });
+        assert_eq!(
+            app.notification.take().map(|n| n.message),
+            Some(format!("HEAD already has {short}"))
+        );
+    }
+
+    #[test]
+    fn submodule_in_the_branch_panel_opens_as_a_project() {
+        use crate::git::submodule::SubmoduleInfo;
+        use crate::test_utils::make_oid;
+        let dir = tempfile::tempdir().unwrap();
+        let checkout = dir.path().join("vendor-lib");
+        git2::Repository::init(&checkout).unwrap();
+        let config = Config::default();
+        let mut data = sample_repo();
+        data.submodules = vec![
+            SubmoduleInfo {
+                name: "docs".to_string(),
+                path: "docs".to_string(),
+                pinned: None,
+                workdir: None,
+            },
+            SubmoduleInfo {
+                name: "vendor-lib".to_string(),
+                path: "vendor-lib".to_string(),
+                pinned: Some(make_oid(9)),
+                workdir: Some(checkout.clone()),
+            },
+        ];
+        let project = Project::snapshot("demo".to_string(), data, &config.trunk_branches);
+        let mut app = App::new(config, GraphicsCapability::Unsupported);
+        app.projects.push(project);
+        app.refresh_entries();
+        app.active_panel = Panel::Branches;
+        let labels: Vec<_> = app
+            .cached_entries
+            .iter()
+            .filter(|e| matches!(e.kind, branch_panel::EntryKind::Submodule { .. }))
+            .map(|e| e.label.trim())
+            .collect();
+        let sha = make_oid(9).to_string();
+        assert_eq!(
+            labels,
+            [
+                "------- docs".to_string(),
+                format!("{} vendor-lib", &sha[..7])
+            ]
+        );
+
+        let key = |code| AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        let select = |app: &mut App, name: &str| {
+            let row = app.cached_entries.iter().position(|e| {
+                matches!(e.kind, branch_panel::EntryKind::Submodule { name: ref n, .. } if n == name)
+            });
+            app.set_branch_selected(row.unwrap());
+        };
+        select(&mut app, "docs");
+        app.handle_event(key(KeyCode::Enter));
+        assert_eq!(
+            app.notification.take().map(|n| n.message),
+            Some("submodule docs isn't checked out".to_string())
+        );
+
+        select(&mut app, "vendor-lib");
+        app.handle_event(key(KeyCode::Enter));
+        assert!(app.confirm.is_some());
+        app.workspace = Some("core".to_string());
+        app.projects[0].parked = true;
+        press(&mut app, 'y');
+        assert!(app.workspace.is_none());
+        assert!(!app.projects[0].parked);
+        assert_eq!(
+            app.notification.take().map(|n| n.message),
+            Some("workspace: all repos (1 repos)".to_string())
+        );
+        assert_eq!(app.projects.len(), 2);
+        assert_eq!(app.active_project, 1);
+        assert_eq!(app.projects[1].name, "vendor-lib");
+
+        // Opened once; selecting it again goes back to that project
+        app.focus_project(0);
+        app.active_panel = Panel::Branches;
+        select(&mut app, "vendor-lib");
+        app.handle_event(key(KeyCode::Enter));
+        assert!(app.confirm.is_none());
+        assert_eq!(app.projects.len(), 2);
+        assert_eq!(app.active_project, 1);
     }
 
-    pub fn flush_kitty_if_needed(
-        &mut self,
-        backend: &mut impl std::io::Write,
-    ) -> std::io::Result<()> {
-        if !self.graphics_cap.is_kitty() {
-            return Ok(());
+    #[test]
+    fn reduced_motion_announces_followed_commits() {
+        let arrive = |app: &mut App| {
+            let mut data = sample_repo();
+            let mut commit = (*data.commits[0]).clone();
+            commit.oid = crate::test_utils::make_oid(8);
+            commit.parents = vec![data.commits[0].oid];
+            data.commits.insert(0, std::sync::Arc::new(commit));
+            data.branches[0].tip = crate::test_utils::make_oid(8);
+            app.handle_local_data_result(0, Ok(RepoUpdate::Full(data)));
+        };
+        let mut app = sample_app();
+        app.follow = true;
+        arrive(&mut app);
+        assert!(app.is_flashing());
+        assert_eq!(
+            app.notification.as_ref().map(|n| n.message.as_str()),
+            Some("+1 commit, 1 branch moved")
+        );
+
+        let mut app = sample_app();
+        app.config.reduced_motion = true;
+        app.follow = true;
+        arrive(&mut app);
+        assert!(!app.is_flashing());
+        assert_eq!(
+            app.notification.as_ref().map(|n| n.message.as_str()),
+            Some("+1 commit, 1 branch moved")
+        );
+    }
+
+    #[test]
+    fn refresh_flashes_rows_whose_branch_labels_changed() {
+        use crate::test_utils::make_oid;
+        let mut app = sample_app();
+        app.set_graph_selected(2);
+        // Nothing changed: no news
+        app.handle_local_data_result(0, Ok(RepoUpdate::Full(sample_repo())));
+        assert!(app.notification.is_none());
+        assert!(!app.is_flashing());
+
+        let mut data = sample_repo();
+        data.branches[1].tip = make_oid(4);
+        data.branches.push(crate::git::types::BranchInfo {
+            name: "release".to_string(),
+            tip: make_oid(3),
+            is_head: false,
+            source: CommitSource::Local,
+            upstream: None,
+        });
+        app.handle_local_data_result(0, Ok(RepoUpdate::Full(data)));
+        assert_eq!(
+            app.notification.take().map(|n| n.message),
+            Some("1 branch moved, 1 new branch".to_string())
+        );
+        let flashed = app.flash.as_ref().map(|(oids, _)| oids.clone());
+        assert_eq!(flashed, Some(HashSet::from([make_oid(4), make_oid(3)])));
+        // Not following, so the selection stays put
+        assert_eq!(app.graph_selected(), 2);
+
+        // Back from the remote view: its rows aren't what changed
+        app.flash = None;
+        app.projects[0].rows_mode = ViewMode::Remote;
+        let mut data = sample_repo();
+        data.branches.truncate(1);
+        app.handle_local_data_result(0, Ok(RepoUpdate::Full(data)));
+        assert!(app.notification.is_none());
+        assert!(!app.is_flashing());
+        assert_eq!(app.projects[0].rows_mode, ViewMode::Local);
+    }
+
+    #[test]
+    fn list_view_keeps_the_selection() {
+        let mut app = sample_app();
+        press(&mut app, 'j');
+        press(&mut app, 'V');
+        press(&mut app, 'j');
+        assert_snapshot("list_view", &render(&mut app));
+        press(&mut app, 'V');
+        assert_eq!(app.graph_selected(), 2);
+        assert!(!app.list_view);
+    }
+
+    #[test]
+    fn lane_inspect_moves_across_lanes() {
+        let mut app = sample_app();
+        press(&mut app, 'j');
+        press(&mut app, 'j');
+        let lane = app.projects[0].rows[2].layout.commit_lane;
+        press(&mut app, 'L');
+        assert_eq!(app.lane_cursor, Some(lane));
+        press(&mut app, 'h');
+        assert_eq!(app.lane_cursor, Some(lane - 1));
+        assert_eq!(app.active_panel, Panel::Graph);
+        assert_snapshot("lane_inspect", &render(&mut app));
+
+        // Stops at the last lane; j/k still move the selection
+        for _ in 0..10 {
+            press(&mut app, 'l');
         }
-        let any_dirty = self.projects.iter_mut().any(|p| p.image_cache.take_dirty());
-        if any_dirty {
-            write!(
-                backend,
-                "{}",
-                crate::kitty_protocol::delete_all_kitty_images()
-            )?;
+        assert_eq!(app.lane_cursor, Some(app.projects[0].max_lanes - 1));
+        press(&mut app, 'k');
+        assert_eq!(app.graph_selected(), 1);
+        app.handle_event(AppEvent::Key(KeyEvent::new(
+            KeyCode::Esc,
+            KeyModifiers::NONE,
+        )));
+        assert_eq!(app.lane_cursor, None);
+    }
+
+    #[test]
+    fn trunk_distance_column() {
+        let mut app = sample_app();
+        press(&mut app, 'o');
+        app.sync_trunk_distances();
+        assert_snapshot("trunk_distance", &render(&mut app));
+    }
+
+    #[test]
+    fn remote_view_marks_commits_missing_locally() {
+        let mut app = sample_app();
+        let fix = crate::test_utils::make_oid(7);
+        app.handle_event(AppEvent::RemoteOnlyFound {
+            project_idx: 0,
+            oids: vec![fix],
+        });
+        // Stale once back in the local view
+        assert!(app.projects[0].remote_only.is_empty());
+
+        app.projects[0].active_mode = ViewMode::Remote;
+        app.handle_event(AppEvent::RemoteOnlyFound {
+            project_idx: 0,
+            oids: vec![fix],
+        });
+        let screen = render(&mut app);
+        let marked: Vec<&str> = screen.lines().filter(|l| l.contains('\u{25c7}')).collect();
+        assert_eq!(marked.len(), 1);
+        assert!(marked[0].contains("Guard against empty"));
+    }
+
+    #[test]
+    fn row_author_toggles_the_author_filter() {
+        let mut app = sample_app();
+        let data = app.projects[0].repo_data.clone();
+        app.projects[0].cached_repo_data = Some(data);
+        // "Remember the last user name", by bob
+        app.set_graph_selected(2);
+        let oid = app.projects[0].rows[2].meta.oid;
+
+        press(&mut app, 'w');
+        assert_eq!(app.author_filter_text, "bob");
+        assert!(app.projects[0].rows.iter().all(|r| r.meta.author == "bob"));
+        assert_eq!(app.projects[0].rows[app.graph_selected()].meta.oid, oid);
+
+        press(&mut app, 'w');
+        assert!(app.author_filter_text.is_empty());
+        assert_eq!(app.projects[0].rows.len(), 7);
+        assert_eq!(app.projects[0].rows[app.graph_selected()].meta.oid, oid);
+    }
+
+    #[test]
+    fn prune_offers_branches_whose_upstream_is_gone() {
+        let mut app = sample_app();
+        press(&mut app, 'X');
+        assert!(app.confirm.is_none());
+        assert_eq!(
+            app.notification.take().unwrap().message,
+            "no branches with a gone upstream"
+        );
+
+        let upstream = |name: &str, gone| {
+            Some(crate::git::types::Upstream {
+                name: name.to_string(),
+                gone,
+            })
+        };
+        let mut data = sample_repo();
+        data.branches[0].upstream = upstream("origin/main", true);
+        data.branches[1].upstream = upstream("origin/feature/login", false);
+        data.branches[2].upstream = upstream("origin/fix/empty-config", true);
+        app.projects[0] = Project::snapshot("demo".to_string(), data, &app.config.trunk_branches);
+        app.refresh_entries();
+
+        // HEAD stays under Local even with its upstream gone
+        let labels: Vec<&str> = app.cached_entries.iter().map(|e| e.label.trim()).collect();
+        let prunable = labels.iter().position(|l| l.ends_with("Prunable")).unwrap();
+        assert_eq!(labels[prunable + 1], "fix/empty-config");
+        assert!(labels[..prunable].contains(&"\u{25b8} main"));
+
+        press(&mut app, 'X');
+        let confirm = app.confirm.as_ref().unwrap();
+        assert_eq!(
+            confirm.question,
+            "delete fix/empty-config, whose upstream is gone? (y/n)"
+        );
+
+        app.confirm = None;
+        app.handle_event(AppEvent::BranchesPruned {
+            project_idx: 0,
+            deleted: vec![DeletedRef::Branch {
+                name: "fix/empty-config".to_string(),
+                tip: crate::test_utils::make_oid(7),
+                upstream: None,
+            }],
+            failed: Vec::new(),
+        });
+        assert_eq!(app.recently_deleted.len(), 1);
+        assert_eq!(
+            app.notification.as_ref().unwrap().message,
+            "pruned 1 branch \u{2014} U to undo"
+        );
+    }
+
+    #[test]
+    fn type_filter_splices_over_other_commits() {
+        let mut app = sample_app();
+        let mut data = sample_repo();
+        for (i, message) in [
+            (2, "fix(login): Remember the last user name"),
+            (4, "revert: Guard against empty config"),
+            (6, "chore: Initial commit"),
+        ] {
+            std::sync::Arc::make_mut(&mut data.commits[i]).message = message.to_string();
         }
-        Ok(())
+        app.projects[0].cached_repo_data = Some(data);
+        let key = |code| AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        let answer = |app: &mut App, text: &str| {
+            press(app, 'C');
+            for c in text.chars() {
+                press(app, c);
+            }
+            app.handle_event(key(KeyCode::Enter));
+        };
+
+        answer(&mut app, "fix,chore");
+        assert_eq!(app.type_filter, vec!["fix", "chore"]);
+        let commits = &app.projects[0].repo_data.commits;
+        assert_eq!(app.projects[0].rows.len(), 2);
+        // Bob's login form and 1.0, between the two, are skipped over
+        assert_eq!(commits[0].parents, vec![commits[1].oid]);
+        assert!(render(&mut app).contains("type: fix,chore"));
+
+        // The prompt starts from the current types
+        answer(&mut app, " docz");
+        assert!(app
+            .notification
+            .take()
+            .unwrap()
+            .message
+            .contains("\"docz\""));
+        assert_eq!(app.type_filter, vec!["fix", "chore"]);
+
+        app.handle_event(key(KeyCode::Backspace));
+        assert!(app.type_filter.is_empty());
+        assert_eq!(app.projects[0].rows.len(), 7);
     }
 
-    pub fn cleanup_kitty(&self, backend: &mut impl std::io::Write) -> std::io::Result<()> {
-        if self.graphics_cap.is_kitty() {
-            write!(
-                backend,
-                "{}",
-                crate::kitty_protocol::delete_all_kitty_images()
-            )?;
-            backend.flush()?;
+    #[test]
+    fn milestones_toggle_off_the_graph_rows() {
+        let mut app = sample_app();
+        let release_row = |app: &mut App| {
+            let screen = render(app);
+            let row = screen.lines().find(|l| l.contains("Release 1.0")).unwrap();
+            row.to_string()
+        };
+        assert!(release_row(&mut app).contains("\u{2500} \u{2691} v1.0"));
+        press(&mut app, 'G');
+        assert!(!release_row(&mut app).contains("v1.0"));
+        press(&mut app, 'G');
+        assert!(release_row(&mut app).contains("\u{2691} v1.0"));
+    }
+
+    #[test]
+    fn aggregate_interleaves_projects_and_jumps_into_one() {
+        let mut app = sample_app();
+        let key = |code| AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        press(&mut app, 'W');
+        assert!(app.aggregate.is_none());
+        app.notification.take();
+
+        let mut data = sample_repo();
+        data.commits.truncate(1);
+        let newest = Oid::from_bytes([0xee; 20]);
+        let commit = std::sync::Arc::make_mut(&mut data.commits[0]);
+        commit.oid = newest;
+        commit.parents.clear();
+        commit.message = "web: ship the form".to_string();
+        commit.time -= chrono::Duration::seconds(30);
+        data.branches.clear();
+        data.tags.clear();
+        app.projects.push(Project::snapshot(
+            "web".to_string(),
+            data,
+            &app.config.trunk_branches,
+        ));
+
+        press(&mut app, 'W');
+        let screen = render(&mut app);
+        assert!(screen.contains("All projects"));
+        let web_row = screen.lines().position(|l| l.contains("web: ")).unwrap();
+        assert!(web_row > 1, "older than the demo's newest commit");
+        while app.aggregate.as_ref().unwrap().rows[app.aggregate.as_ref().unwrap().selected]
+            .project_idx
+            != 1
+        {
+            press(&mut app, 'j');
         }
-        Ok(())
+        app.handle_event(key(KeyCode::Enter));
+        assert!(app.aggregate.is_none());
+        assert_eq!(app.active_project, 1);
+        assert_eq!(app.projects[1].rows[app.graph_selected()].meta.oid, newest);
     }
 
-    fn clamp_selected(&mut self) {
-        if let Some(proj) = self.projects.get(self.active_project) {
-            if !proj.rows.is_empty() && self.graph_selected >= proj.rows.len() {
-                self.graph_selected = proj.rows.len() - 1;
-            }
+    #[test]
+    fn ref_popover_lists_every_ref_and_jumps_to_it() {
+        use crate::git::types::BranchInfo;
+        use crate::test_utils::make_oid;
+        let config = Config::default();
+        let mut data = sample_repo();
+        for (name, source) in [
+            ("origin/main", CommitSource::Remote("origin".to_string())),
+            ("release/1.1", CommitSource::Local),
+        ] {
+            data.branches.push(BranchInfo {
+                name: name.to_string(),
+                tip: make_oid(1),
+                is_head: false,
+                source,
+                upstream: None,
+            });
         }
+        let project = Project::snapshot("demo".to_string(), data, &config.trunk_branches);
+        let mut app = App::new(config, GraphicsCapability::Unsupported);
+        app.projects.push(project);
+        app.refresh_entries();
+        let key = |code| AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE));
+
+        press(&mut app, 'B');
+        let names: Vec<_> = app
+            .refs_popover
+            .as_ref()
+            .unwrap()
+            .refs
+            .iter()
+            .map(|r| r.name.as_str())
+            .collect();
+        assert_eq!(names, ["main", "release/1.1", "origin/main"]);
+        assert!(render(&mut app).contains("Refs on"));
+        press(&mut app, 'c');
+        assert_eq!(
+            app.notification.take().map(|n| n.message),
+            Some("checkout needs a local checkout".to_string())
+        );
+
+        press(&mut app, 'B');
+        press(&mut app, 'j');
+        app.handle_event(key(KeyCode::Enter));
+        assert!(app.refs_popover.is_none());
+        assert_eq!(app.active_panel, Panel::Branches);
+        let entry = &app.cached_entries[app.branch_selected()];
+        assert_eq!(entry.branch_name(), Some("release/1.1"));
+
+        // A tag in a collapsed section opens it
+        app.active_panel = Panel::Graph;
+        app.collapsed_sections.insert(SectionKey::Tags(0));
+        app.refresh_entries();
+        let tagged = app.projects[0]
+            .rows
+            .iter()
+            .position(|r| r.meta.oid == make_oid(3));
+        app.set_graph_selected(tagged.unwrap());
+        press(&mut app, 'B');
+        app.handle_event(key(KeyCode::Enter));
+        assert_eq!(
+            app.cached_entries[app.branch_selected()].label.trim(),
+            "(v1.0)"
+        );
     }
 
-    fn toggle_branch_section(&mut self) {
-        if let Some(entry) = self.cached_entries.get(self.branch_selected) {
-            if let Some(key) = entry.section_key() {
-                let key = key.clone();
-                if self.collapsed_sections.contains(&key) {
-                    self.collapsed_sections.remove(&key);
-                } else {
-                    self.collapsed_sections.insert(key);
-                }
-                self.refresh_entries();
-            } else if let branch_panel::EntryKind::Author { ref name } = entry.kind {
-                let name = name.clone();
-                if self.author_filter_text == name {
-                    self.author_filter_text.clear();
-                } else {
-                    self.author_filter_text = name;
-                }
-                for idx in 0..self.projects.len() {
-                    self.rebuild_graph_author_only(idx);
-                }
-                self.clamp_selected();
-                self.refresh_entries();
-            } else if let Some(tip) = entry.tip_oid() {
-                if let Some(proj) = self.projects.get(self.active_project) {
-                    if let Some(idx) = proj.rows.iter().position(|r| r.meta.oid == tip) {
-                        self.graph_selected = idx;
-                    }
-                }
-            }
+    #[test]
+    fn each_project_keeps_its_own_selection() {
+        let mut app = sample_app();
+        app.projects.push(Project::snapshot(
+            "web".to_string(),
+            sample_repo(),
+            &app.config.trunk_branches,
+        ));
+        let key = |code| AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        for _ in 0..3 {
+            press(&mut app, 'j');
         }
+        app.handle_event(key(KeyCode::Tab));
+        assert_eq!(app.active_project, 1);
+        assert_eq!(app.graph_selected(), 0);
+        press(&mut app, 'j');
+
+        app.handle_event(key(KeyCode::Tab));
+        assert_eq!(app.active_project, 0);
+        assert_eq!(app.graph_selected(), 3);
+        assert_eq!(app.projects[1].graph_selected, 1);
     }
 
-    // ── Render dispatch ─────────────────────────────────────────────
+    #[test]
+    fn workspace_limits_which_projects_are_open() {
+        let mut app = sample_app();
+        let key = |code| AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        for name in ["api", "web"] {
+            app.projects.push(Project::snapshot(
+                name.to_string(),
+                sample_repo(),
+                &app.config.trunk_branches,
+            ));
+        }
+        app.config.repos = ["demo", "api", "web"]
+            .map(|name| RepoEntry {
+                path: PathBuf::from(format!("~/src/{name}")),
+                name: Some(name.to_string()),
+            })
+            .to_vec();
+        app.config.workspaces = vec![WorkspaceEntry {
+            name: "backend".to_string(),
+            repos: vec!["~/src/web".to_string(), "demo".to_string()],
+        }];
 
-    pub fn render(&mut self, frame: &mut Frame) {
-        let size = frame.area();
+        press(&mut app, 'g');
+        for c in "infra".chars() {
+            press(&mut app, c);
+        }
+        app.handle_event(key(KeyCode::Enter));
+        let warning = app.notification.take().unwrap().message;
+        assert_eq!(warning, "unknown workspace \"infra\", try backend");
+        assert_eq!(app.workspace, None);
 
-        let bg_style = Style::default().bg(self.palette.app_bg);
-        for y in size.y..size.bottom() {
-            for x in size.x..size.right() {
-                frame.buffer_mut()[(x, y)].set_style(bg_style);
-            }
+        press(&mut app, 'g');
+        for c in "backend".chars() {
+            press(&mut app, c);
         }
+        app.handle_event(key(KeyCode::Enter));
+        app.notification.take();
+        assert_eq!(app.workspace.as_deref(), Some("backend"));
+        assert_eq!(app.workspace_projects(), vec![0, 2]);
+        app.handle_event(key(KeyCode::Tab));
+        assert_eq!(app.active_project, 2, "api is outside the workspace");
+        let screen = render(&mut app);
+        assert!(screen
+            .lines()
+            .next()
+            .unwrap()
+            .contains("backend \u{2503} web"));
 
-        match &self.screen {
-            Screen::Graph => self.render_graph_screen(frame, size),
-            Screen::Config(state) => {
-                let widget = ConfigScreen {
-                    state,
-                    palette: &self.palette,
-                };
-                frame.render_widget(widget, size);
-            }
+        // Blank opens every project again
+        press(&mut app, 'g');
+        for _ in "backend".chars() {
+            app.handle_event(key(KeyCode::Backspace));
         }
+        app.handle_event(key(KeyCode::Enter));
+        app.notification.take();
+        assert_eq!(app.workspace, None);
+        app.handle_event(key(KeyCode::BackTab));
+        assert_eq!(app.active_project, 1);
     }
 
-    fn render_graph_screen(&mut self, frame: &mut Frame, size: Rect) {
-        let vert = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(1),
-                Constraint::Min(1),
-                Constraint::Length(1),
-            ])
-            .split(size);
+    #[test]
+    fn off_screen_projects_park_until_shown() {
+        let mut app = sample_app();
+        let key = |code| AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        app.projects.push(Project::snapshot(
+            "web".to_string(),
+            sample_repo(),
+            &app.config.trunk_branches,
+        ));
+        app.config.park_after_secs = 60;
+        let now = std::time::Instant::now();
+        let due = app.next_park_at().unwrap();
+        assert!(!app.park_idle_projects(now));
 
-        let header_area = vert[0];
-        let body_area = vert[1];
-        let status_area = vert[2];
+        assert!(app.park_idle_projects(due));
+        assert!(app.projects[1].parked);
+        assert!(!app.projects[0].parked, "the active project stays watched");
+        assert_eq!(app.next_park_at(), None);
 
-        self.render_header(frame, header_area);
+        app.handle_event(key(KeyCode::Tab));
+        assert!(!app.projects[1].parked);
+        assert!(app.next_park_at().is_some_and(|at| at > due));
 
-        let panel_w = self.branch_panel_width(size.width);
-        let detail_w: u16 = if self.show_detail { 50 } else { 0 };
+        app.config.park_after_secs = 0;
+        assert!(!app.park_idle_projects(due + std::time::Duration::from_secs(3600)));
+    }
 
-        let mut body_constraints = vec![
-            Constraint::Length(panel_w),
-            Constraint::Min(1), // graph
-        ];
-        if self.show_detail {
-            body_constraints.push(Constraint::Length(detail_w));
-        }
+    #[test]
+    fn manual_refresh_parks_nothing_and_says_r_refreshes() {
+        let mut app = sample_app();
+        app.projects.push(Project::snapshot(
+            "web".to_string(),
+            sample_repo(),
+            &app.config.trunk_branches,
+        ));
+        app.config.park_after_secs = 60;
+        app.manual_refresh = true;
+        assert_eq!(app.next_park_at(), None);
+        let later = std::time::Instant::now() + std::time::Duration::from_secs(3600);
+        assert!(!app.park_idle_projects(later));
+        assert!(!app.projects[1].parked);
+        assert!(render(&mut app).contains("(r to refresh)"));
 
-        let body_chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(body_constraints)
-            .split(body_area);
+        app.manual_refresh = false;
+        assert!(!render(&mut app).contains("(r to refresh)"));
+    }
 
-        let branch_area = body_chunks[0];
-        let graph_area = body_chunks[1];
+    #[test]
+    fn builtin_diff_shows_hunks_in_theme_colors() {
+        use crate::git::diff::{DiffLine, FilePatch, FileStatus, Hunk};
+        let mut app = sample_app();
+        press(&mut app, 'p');
+        assert!(app.show_diff, "no diff_pager needed");
+        assert!(app.notification.is_none());
+
+        let line = |origin, content: &str| DiffLine {
+            origin,
+            content: content.to_string(),
+        };
+        let files = [FilePatch {
+            path: "src/lib.rs".to_string(),
+            status: FileStatus::Modified,
+            binary: false,
+            hunks: vec![Hunk {
+                header: "@@ -1,2 +1,2 @@ fn main".to_string(),
+                lines: vec![line(' ', "\tkeep"), line('-', "old"), line('+', "new")],
+            }],
+        }];
+        let text = detail_panel::diff_text(&files, &app.palette);
+        let rows: Vec<String> = text.lines.iter().map(|l| l.to_string()).collect();
+        assert_eq!(
+            rows,
+            [
+                "M src/lib.rs",
+                "@@ -1,2 +1,2 @@ fn main",
+                "     keep",
+                "-old",
+                "+new"
+            ]
+        );
+        assert_eq!(text.lines[3].style.fg, Some(app.palette.diff_del));
+        assert_eq!(text.lines[4].style.fg, Some(app.palette.diff_add));
+
+        app.detail_oid = Some(app.projects[0].rows[app.graph_selected()].meta.oid);
+        app.detail_diff = Some(text);
+        app.active_panel = Panel::Detail;
+        let screen = render(&mut app);
+        assert!(screen.contains("+new"));
+    }
+
+    #[test]
+    fn changed_files_show_lines_added_and_removed() {
+        use crate::git::diff::{ChangedFile, DiffStat, FileStatus};
+        let mut app = sample_app();
+        let file = |path: &str, stat| ChangedFile {
+            path: path.to_string(),
+            status: FileStatus::Modified,
+            size: 10,
+            lfs: None,
+            stat,
+        };
+        app.detail_oid = Some(app.projects[0].rows[app.graph_selected()].meta.oid);
+        app.detail_files = Some(vec![
+            file(
+                "src/login.rs",
+                Some(DiffStat {
+                    insertions: 12,
+                    deletions: 3,
+                }),
+            ),
+            file("logo.png", None),
+        ]);
+        app.show_detail = true;
+        let screen = render(&mut app);
+        assert!(screen.contains("Files (2) +12 -3"));
+        let row = screen.lines().find(|l| l.contains("src/login.rs")).unwrap();
+        assert!(row.contains("+12 -3"));
+        assert!(screen
+            .lines()
+            .any(|l| l.contains("logo.png") && l.contains("bin")));
+    }
+
+    #[test]
+    fn health_summarizes_the_project() {
+        let mut app = sample_app();
+        press(&mut app, 'O');
+        let screen = render(&mut app);
+        assert!(screen.contains("Health: demo"));
+        assert!(screen.contains("Default branch  main"));
+        assert!(screen.contains("Last release    v1.0"));
+        assert!(screen.contains("needs a local checkout"));
+        press(&mut app, 'O');
+        assert!(app.health.is_none());
+    }
+
+    #[test]
+    fn head_timeline_jumps_to_where_head_was() {
+        let mut app = sample_app();
+        let key = |code| AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        let proj = &mut app.projects[0];
+        let oid = proj.rows[2].meta.oid;
+        proj.watched_head_moves.push(HeadMove {
+            oid,
+            time: chrono::Utc::now(),
+            message: String::new(),
+        });
+        press(&mut app, 'J');
+        let screen = render(&mut app);
+        assert!(screen.contains("HEAD today (1)"));
+        assert!(screen.contains("moved outside the reflog"));
+        app.handle_event(key(KeyCode::Enter));
+        assert!(app.head_timeline.is_none());
+        assert_eq!(app.graph_selected(), 2);
+    }
+
+    #[test]
+    fn blame_jumps_to_the_commit_behind_a_line() {
+        let mut app = sample_app();
+        let key = |code| AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        press(&mut app, 'b');
+        let toast = app.notification.take().unwrap();
+        assert!(toast.message.contains("select a file in the detail panel"));
+
+        let rows = &app.projects[0].rows;
+        let (newer, older) = (rows[1].meta.oid, rows[2].meta.oid);
+        let line = |oid, author: &str, content: &str| blame::BlameLine {
+            oid,
+            author: author.to_string(),
+            time: chrono::Utc::now(),
+            content: content.to_string(),
+        };
+        app.blame = Some(BlameState::new("src/lib.rs".to_string(), newer));
+        app.handle_event(AppEvent::BlameResult {
+            project_idx: 0,
+            result: Ok(vec![
+                line(newer, "alice", "fn main() {"),
+                line(older, "bob", "\tprintln!();"),
+                line(older, "bob", "}"),
+            ]),
+        });
+        let screen = render(&mut app);
+        assert!(screen.contains("Blame: src/lib.rs"));
+        assert!(screen.contains("alice"));
+        // A run of lines from one commit names it once
+        assert_eq!(screen.matches("bob").count(), 1);
+
+        press(&mut app, 'j');
+        app.handle_event(key(KeyCode::Enter));
+        assert!(app.blame.is_none());
+        assert_eq!(app.graph_selected(), 2);
+    }
+
+    #[test]
+    fn diffstat_sparkline_fills_by_lines_changed() {
+        let mut app = sample_app();
+        let release = app.projects[0]
+            .rows
+            .iter()
+            .find(|r| r.meta.message.contains("Release 1.0"))
+            .unwrap()
+            .meta
+            .oid;
+        app.projects[0].diff_stats.insert(
+            release,
+            DiffStat {
+                insertions: 40,
+                deletions: 2,
+            },
+        );
+        let release_row = |app: &mut App| {
+            let screen = render(app);
+            let row = screen.lines().find(|l| l.contains("Release 1.0")).unwrap();
+            row.to_string()
+        };
+        assert!(!release_row(&mut app).contains('\u{25b0}'));
+        press(&mut app, 'I');
+        assert!(release_row(&mut app).contains("\u{25b0}\u{25b0}\u{25b0}\u{25b1}\u{25b1}"));
+        // Not read yet: an empty gauge
+        assert!(render(&mut app).contains("\u{25b1}\u{25b1}\u{25b1}\u{25b1}\u{25b1}"));
+    }
+
+    #[test]
+    fn conventional_prefix_renders_as_badge() {
+        let mut app = sample_app();
+        let mut data = sample_repo();
+        std::sync::Arc::make_mut(&mut data.commits[1]).message =
+            "feat(ui)!: Bump version to 1.1".to_string();
+        app.projects[0] = Project::snapshot("demo".to_string(), data, &app.config.trunk_branches);
+        let screen = render(&mut app);
+        assert!(screen.contains("feat(ui)! Bump version to 1.1"));
+
+        let badge = app.palette.commit_type_color("feat");
+        let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+        terminal.draw(|f| app.render(f)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let colors: Vec<_> = buffer
+            .content()
+            .windows(5)
+            .filter(|w| w.iter().map(|c| c.symbol()).collect::<String>() == "feat(")
+            .map(|w| w[0].fg)
+            .collect();
+        assert_eq!(colors, vec![badge]);
+    }
+
+    #[test]
+    fn head_jump_drops_author_filter_hiding_head() {
+        let mut app = sample_app();
+        let data = app.projects[0].repo_data.clone();
+        app.projects[0].cached_repo_data = Some(data);
+        app.author_filter_text = "bob".to_string();
+        app.rebuild_graph_author_only(0);
+        app.set_graph_selected(1);
+
+        press(&mut app, 'H');
+        assert!(app.author_filter_text.is_empty());
+        let head = crate::test_utils::make_oid(1);
+        assert_eq!(app.projects[0].rows[app.graph_selected()].meta.oid, head);
+
+        // "Add login form" is drawn on feature/login
+        app.notification = None;
+        app.set_graph_selected(3);
+        press(&mut app, 'T');
+        let tip = crate::test_utils::make_oid(5);
+        assert_eq!(app.projects[0].rows[app.graph_selected()].meta.oid, tip);
+    }
 
-        self.render_bordered_branch_panel(frame, branch_area);
-        self.render_bordered_graph_panel(frame, graph_area);
+    #[test]
+    fn help_overlay() {
+        let mut app = sample_app();
+        press(&mut app, '?');
+        assert_snapshot("help", &render(&mut app));
 
-        if self.show_detail && body_chunks.len() >= 3 {
-            let detail_area = body_chunks[2];
-            self.render_bordered_detail_panel(frame, detail_area);
+        // Scrolls to the last binding and no further
+        let key = |code| AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        for _ in 0..20 {
+            app.handle_event(key(KeyCode::PageDown));
+            render(&mut app);
         }
+        let screen = render(&mut app);
+        assert!(screen.contains("Quit / Close"), "{screen}");
+        let last = app.help_scroll;
+        press(&mut app, 'j');
+        render(&mut app);
+        assert_eq!(app.help_scroll, last);
+        press(&mut app, 'k');
+        assert_eq!(app.help_scroll, last - 1);
+        assert!(app.show_help);
+    }
 
-        self.dismiss_stale_notifications();
-        self.render_status_bar(frame, status_area);
-        self.render_overlays(frame, size);
+    #[test]
+    fn config_screen() {
+        let mut app = sample_app();
+        press(&mut app, 'c');
+        assert_snapshot("config", &render(&mut app));
     }
 
-    fn render_bordered_branch_panel(&mut self, frame: &mut Frame, area: Rect) {
-        let is_active = self.active_panel == Panel::Branches;
-        let border_color = if is_active {
-            self.palette.active_panel_border
-        } else {
-            self.palette.inactive_panel_border
+    #[test]
+    fn repos_tab_edits_name_after_tab() {
+        let mut app = sample_app();
+        press(&mut app, 'c');
+        let key = |code| AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        app.handle_event(key(KeyCode::Enter));
+        app.handle_event(key(KeyCode::Tab));
+        for c in "Demo app".chars() {
+            press(&mut app, c);
+        }
+        assert!(render(&mut app).contains(".  Demo app\u{258c}"));
+        app.handle_event(key(KeyCode::Enter));
+        let Screen::Config(state) = &app.screen else {
+            panic!("config screen closed");
         };
-        let title_color = if is_active {
-            self.palette.accent
-        } else {
-            self.palette.panel_label
+        let repos = &state.draft.repos;
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].path, app.config.repo_path);
+        assert_eq!(repos[0].name.as_deref(), Some("Demo app"));
+
+        let old_repos = app.config.resolved_repos();
+        app.config = state.draft.clone();
+        app.rename_projects(&old_repos);
+        assert_eq!(app.projects[0].name, "Demo app");
+    }
+
+    #[test]
+    fn theme_tab_previews_theme_under_cursor() {
+        let mut app = sample_app();
+        press(&mut app, 'c');
+        for _ in 0..2 {
+            let tab = KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE);
+            app.handle_event(AppEvent::Key(tab));
+        }
+        press(&mut app, 'j');
+        assert_snapshot("theme_preview", &render(&mut app));
+        // Browsing doesn't pick the theme
+        let Screen::Config(state) = &app.screen else {
+            panic!("config screen closed");
         };
+        assert_eq!(state.draft.theme, app.config.theme);
+    }
 
-        let block = Block::default()
-            .title(" Branches ")
-            .title_style(
-                Style::default()
-                    .fg(title_color)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(border_color));
-        let inner = block.inner(area);
-        frame.render_widget(block, area);
+    #[test]
+    fn startup_script_waits_for_data() {
+        let mut app = sample_app();
+        let data = app.projects[0].repo_data.clone();
+        app.projects[0].cached_repo_data = Some(data);
+        app.projects[0].loading = true;
+        app.script = crate::script::parse("select sha:0500; filter branch:fix; select sha:ff")
+            .unwrap()
+            .into();
+        app.run_script();
+        assert_eq!(app.script.len(), 3);
+
+        app.projects[0].loading = false;
+        app.run_script();
+        assert!(app.script.is_empty());
+        assert_eq!(app.filter_text, "fix");
+        // The missing commit leaves the earlier selection alone
+        let selected = &app.projects[0].rows[app.graph_selected()];
+        assert_eq!(selected.meta.oid, crate::test_utils::make_oid(5));
+        assert!(app.notification.is_some());
+
+        app.script = crate::script::parse("filter author:bob").unwrap().into();
+        app.run_script();
+        let commits = &app.projects[0].repo_data.commits;
+        assert!(!commits.is_empty() && commits.iter().all(|c| c.author == "bob"));
+    }
+
+    #[test]
+    fn status_bar_reports_walk_progress() {
+        let mut app = sample_app();
+        app.config.max_commits = 0;
+        app.projects[0].loading = true;
+        app.handle_event(AppEvent::LoadProgress {
+            project_idx: 0,
+            walked: 120_000,
+        });
+        assert!(render(&mut app).contains("walked 120k commits"));
+
+        app.config.max_commits = 1_500_000;
+        assert!(render(&mut app).contains("walked 120k/1.5M commits"));
+
+        let data = app.projects[0].repo_data.clone();
+        app.handle_local_data_result(0, Ok(RepoUpdate::Full(data)));
+        assert_eq!(app.projects[0].load_progress, None);
+        assert!(!render(&mut app).contains("walked"));
+    }
+
+    #[test]
+    fn status_bar_shows_when_rate_limited_requests_resume() {
+        let app = sample_app();
+        let mut bar = app.status_bar();
+        bar.github_paused_until = Some(app.dates.time(chrono::NaiveTime::MIN));
+        let area = Rect::new(0, 0, 100, 1);
+        let mut buf = ratatui::buffer::Buffer::empty(area);
+        ratatui::widgets::Widget::render(bar, area, &mut buf);
+        let text: String = buf.content.iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("GitHub rate limited until 00:00"), "{text}");
+    }
+
+    #[test]
+    fn offline_shows_the_cached_remote_view_as_stale() {
+        let mut app = sample_app();
+        press(&mut app, 'Z');
+        assert_eq!(app.offline, Some(Offline::Manual));
+        app.notification.take();
+        // Only an unreachable GitHub comes back by itself
+        app.handle_event(AppEvent::GitHubProbed { reachable: true });
+        assert_eq!(app.offline, Some(Offline::Manual));
+
+        app.projects[0].active_mode = ViewMode::Remote;
+        let data = app.projects[0].repo_data.clone();
+        app.handle_event(AppEvent::RemoteDataResult {
+            project_idx: 0,
+            result: Ok(data),
+            cached_at: Some(chrono::Utc::now() - chrono::Duration::hours(3)),
+        });
+        let screen = render(&mut app);
+        assert!(screen.contains("[Remote, stale]"));
+        assert!(screen.contains(" offline "));
+
+        app.offline = Some(Offline::Unreachable);
+        app.offline_refresh.insert(0);
+        assert!(render(&mut app).contains("offline: GitHub unreachable"));
+        app.handle_event(AppEvent::GitHubProbed { reachable: false });
+        assert_eq!(app.offline, Some(Offline::Unreachable));
+        app.handle_event(AppEvent::GitHubProbed { reachable: true });
+        assert_eq!(app.offline, None);
+        assert!(app.offline_refresh.is_empty());
+        assert!(!render(&mut app).contains("offline"));
+    }
+
+    #[test]
+    fn horizontal_scroll_follows_selection() {
+        let mut app = sample_app();
+        let proj = &mut app.projects[0];
+        let lane_of = |proj: &Project, row: usize| proj.rows[row].layout.commit_lane;
+        let (branch_row, trunk_row) = (2, 1);
+        assert!(lane_of(proj, branch_row) > lane_of(proj, trunk_row));
+
+        // Room for every lane: nothing scrolls
+        follow_selection_x(proj, branch_row, None, 80);
+        assert_eq!(proj.lane_scroll, 0);
+
+        // One lane's room: the selected commit's lane is the one shown
+        let narrow = 1 + MIN_TEXT_WIDTH + COLS_PER_LANE as usize;
+        follow_selection_x(proj, branch_row, None, narrow);
+        assert_eq!(proj.lane_scroll, lane_of(proj, branch_row));
+        follow_selection_x(proj, trunk_row, None, narrow);
+        assert_eq!(proj.lane_scroll, lane_of(proj, trunk_row));
+
+        // Landing on a labelled row brings its label back; scrolling the
+        // text on that row afterwards is left alone
+        assert!(!proj.rows[branch_row].meta.branch_names.is_empty());
+        proj.scroll_x = 8;
+        follow_selection_x(proj, branch_row, None, 80);
+        assert_eq!(proj.scroll_x, 0);
+        proj.scroll_x = 4;
+        follow_selection_x(proj, branch_row, None, 80);
+        assert_eq!(proj.scroll_x, 4);
+    }
+
+    #[test]
+    fn branch_panel_pages_and_jumps_between_sections() {
+        let mut app = sample_app();
+        press(&mut app, 'h');
+        render(&mut app);
+        let entries = &app.cached_entries;
+        let last = entries.iter().rposition(|e| !e.is_spacer()).unwrap();
+        let headers: Vec<usize> = (0..entries.len())
+            .filter(|&i| entries[i].is_header())
+            .collect();
+        let key = |code| AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE));
+
+        app.handle_event(key(KeyCode::End));
+        assert_eq!(app.branch_selected(), last);
+        app.handle_event(key(KeyCode::Home));
+        assert_eq!(app.branch_selected(), 0);
+
+        assert!(headers.len() >= 2, "{headers:?}");
+        press(&mut app, ']');
+        assert_eq!(app.branch_selected(), headers[1]);
+        press(&mut app, ']');
+        press(&mut app, '[');
+        assert_eq!(app.branch_selected(), headers[1]);
+
+        // A page is the panel's height, here longer than the list
+        app.handle_event(key(KeyCode::PageUp));
+        assert_eq!(app.branch_selected(), 0);
+        app.handle_event(key(KeyCode::PageDown));
+        assert_eq!(app.branch_selected(), last);
+    }
+
+    #[test]
+    fn filter_chips_clear_on_click_and_backspace() {
+        let mut app = sample_app();
+        let data = app.projects[0].repo_data.clone();
+        app.projects[0].cached_repo_data = Some(data);
+        app.filter_text = "feat".to_string();
+        app.author_filter_text = "alice".to_string();
+        app.rebuild_graph_author_only(0);
+        let screen = render(&mut app);
+        assert!(screen.contains("/feat \u{2715}"), "{screen}");
+        assert!(screen.contains("author: alice \u{2715}"), "{screen}");
+        let filtered = app.projects[0].rows.len();
+
+        // Click the author chip; clicks only arrive once `mouse` is turned on
+        assert!(!app.config.mouse);
+        let bar = app.status_area;
+        let status = screen.lines().last().unwrap();
+        let col = status.find("author:").unwrap();
+        let col = status[..col].chars().count() as u16;
+        app.handle_event(AppEvent::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: bar.x + col,
+            row: bar.y,
+            modifiers: KeyModifiers::NONE,
+        }));
+        assert!(app.author_filter_text.is_empty());
+        assert_eq!(app.filter_text, "feat");
+        assert!(app.projects[0].rows.len() > filtered);
+
+        app.handle_event(AppEvent::Key(KeyEvent::new(
+            KeyCode::Backspace,
+            KeyModifiers::NONE,
+        )));
+        assert!(app.filter_text.is_empty());
+        assert!(!render(&mut app).contains('\u{2715}'));
+
+        // A number key clears that chip, here the middle one
+        app.filter_text = "feat".to_string();
+        app.author_filter_text = "alice".to_string();
+        app.type_filter = vec!["fix".to_string()];
+        app.rebuild_graph_author_only(0);
+        assert!(render(&mut app).contains(" 2 author: alice \u{2715} "));
+        press(&mut app, '2');
+        assert!(app.author_filter_text.is_empty());
+        assert_eq!(app.filter_text, "feat");
+        assert_eq!(app.type_filter, ["fix"]);
+        let screen = render(&mut app);
+        assert!(screen.contains(" 1 /feat \u{2715} "), "{screen}");
+        assert!(screen.contains(" 2 type: fix \u{2715} "), "{screen}");
+        press(&mut app, '3');
+        assert_eq!(app.filter_text, "feat");
+    }
+
+    #[test]
+    fn shallow_graft_points_are_labelled() {
+        let mut app = sample_app();
+        assert!(!render(&mut app).contains("history truncated"));
+        press(&mut app, 'S');
+        let toast = app.notification.take().map(|n| n.message);
+        assert_eq!(toast.as_deref(), Some("deepening needs a local checkout"));
+
+        let root = crate::test_utils::make_oid(6);
+        app.projects[0].repo_data.shallow.insert(root);
+        // A load would relayout, dropping lines built before
+        app.projects[0].line_cache.clear();
+        let screen = render(&mut app);
+        let line = screen
+            .lines()
+            .find(|l| l.contains("Initial commit"))
+            .unwrap();
+        assert!(line.contains("\u{22ef} history truncated"), "{screen}");
+    }
 
-        let visible_height = inner.height as usize;
-        if visible_height > 0 {
-            if self.branch_selected >= self.branch_scroll + visible_height {
-                self.branch_scroll = self.branch_selected - visible_height + 1;
-            }
-            if self.branch_selected < self.branch_scroll {
-                self.branch_scroll = self.branch_selected;
-            }
-        }
+    #[test]
+    fn fetch_progress_shows_in_the_status_bar_until_done() {
+        use crate::git::fetch::{FetchOutcome, FetchProgress};
+        let mut app = sample_app();
+        press(&mut app, 'i');
+        assert_eq!(
+            app.notification.take().map(|n| n.message),
+            Some("fetching needs a local checkout".to_string())
+        );
 
-        let branch_panel = BranchPanel {
-            entries: &self.cached_entries,
-            selected: self.branch_selected,
-            scroll: self.branch_scroll,
-            focused: is_active,
-            palette: &self.palette,
+        let progress = |received, total| AppEvent::FetchProgress {
+            project_idx: 0,
+            progress: FetchProgress {
+                remote: "origin".to_string(),
+                received,
+                total,
+            },
         };
-        frame.render_widget(branch_panel, inner);
+        // Progress for a fetch that isn't running is ignored
+        app.handle_event(progress(1, 2));
+        assert!(app.projects[0].fetch_progress.is_none());
+
+        app.projects[0].fetch_progress = Some(FetchProgress {
+            remote: String::new(),
+            received: 0,
+            total: 0,
+        });
+        assert!(render(&mut app).contains("fetching remotes..."));
+        app.handle_event(progress(1200, 4500));
+        assert!(render(&mut app).contains("fetching origin 1k/4k objects"));
+
+        app.handle_event(AppEvent::Fetched {
+            project_idx: 0,
+            result: Ok(FetchOutcome {
+                fetched: vec!["origin".to_string()],
+                failed: vec![("mirror".to_string(), "unreachable".to_string())],
+            }),
+        });
+        assert!(app.projects[0].fetch_progress.is_none());
+        assert_eq!(
+            app.notification.take().map(|n| n.message),
+            Some("fetching mirror failed: unreachable".to_string())
+        );
+        assert!(!render(&mut app).contains("fetching"));
     }
 
-    fn render_bordered_graph_panel(&mut self, frame: &mut Frame, area: Rect) {
-        let is_active = self.active_panel == Panel::Graph;
-        let border_color = if is_active {
-            self.palette.active_panel_border
-        } else {
-            self.palette.inactive_panel_border
+    #[test]
+    fn graph_rows_show_authors_as_configured() {
+        let mut app = sample_app();
+        app.authors = crate::authors::AuthorFormat {
+            style: crate::authors::AuthorStyle::Username,
+            aliases: [("carol@contractor.dev".to_string(), "ext-carol".to_string())].into(),
         };
+        app.projects[0].author_logins =
+            Some([("alice@example.com".to_string(), "alice-gh".to_string())].into());
+        let screen = render(&mut app);
+        let row = |message: &str| {
+            screen
+                .lines()
+                .find(|l| l.contains(message))
+                .unwrap()
+                .to_string()
+        };
+        assert!(row("Bump version").contains("alice-gh"), "{screen}");
+        // No login known: the name stays
+        assert!(row("Add login form").contains("bob"));
+        assert!(row("Guard against").contains("ext-carol"));
+    }
 
-        let block = Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(border_color));
-        let inner = block.inner(area);
-        frame.render_widget(block, area);
+    #[test]
+    fn commits_past_the_newest_tag_are_banded_as_unreleased() {
+        use crate::test_utils::make_oid;
+        let mut app = sample_app();
+        let expected: HashSet<_> = [1, 2, 5, 4].into_iter().map(make_oid).collect();
+        assert_eq!(app.projects[0].unreleased, expected);
 
-        let visible_height = (inner.height as usize).saturating_sub(1);
-        self.ensure_scroll_bounds(visible_height);
+        let screen = render(&mut app);
+        // The selected merge commit shows the cursor instead
+        let banded: Vec<_> = screen.lines().filter(|l| l.contains('\u{2506}')).collect();
+        assert_eq!(banded.len(), 3, "{screen}");
+        assert!(
+            banded.iter().all(|l| !l.contains("Release 1.0")),
+            "{screen}"
+        );
+    }
 
-        let highlighted: HashSet<_> = self.get_highlighted_oids(&self.cached_entries);
+    #[test]
+    fn commits_only_a_tag_reaches_are_marked_detached() {
+        use crate::git::types::{CommitInfo, TagInfo};
+        use crate::test_utils::make_oid;
+        let config = Config::default();
+        let mut data = sample_repo();
+        let mut orphan = CommitInfo::clone(&data.commits[1]);
+        orphan.oid = make_oid(8);
+        orphan.parents = vec![make_oid(2)];
+        orphan.message = "Experiment kept by a tag".to_string();
+        data.commits.insert(0, std::sync::Arc::new(orphan));
+        data.tags.push(TagInfo {
+            target: make_oid(8),
+            name: "try/cache".to_string(),
+            ..data.tags[0].clone()
+        });
+        let project = Project::snapshot("demo".to_string(), data, &config.trunk_branches);
+        let mut app = App::new(config, GraphicsCapability::Unsupported);
+        app.projects.push(project);
+        app.refresh_entries();
 
-        let render_params = self.graphics_cap.render_params();
+        assert_eq!(app.projects[0].detached, HashSet::from([make_oid(8)]));
+        let screen = render(&mut app);
+        let detached: Vec<_> = screen.lines().filter(|l| l.contains("detached")).collect();
+        assert_eq!(detached.len(), 1, "{screen}");
+        assert!(detached[0].contains("Experiment"), "{screen}");
+    }
 
-        if let Some(proj) = self.projects.get_mut(self.active_project) {
-            let palette = match proj.active_mode {
-                ViewMode::Remote => self.palette.with_remote_tint(),
-                ViewMode::Local => self.palette.clone(),
-            };
-            let graph_view = GraphView {
-                rows: &proj.rows,
-                scroll_y: self.graph_scroll_y,
-                scroll_x: proj.scroll_x,
-                selected: self.graph_selected,
-                highlighted_oids: &highlighted,
-                is_active,
-                trunk_count: proj.trunk_count,
-                max_lanes: proj.max_lanes,
-                palette: &palette,
-                branch_index_to_name: &proj.branch_index_to_name,
-                graphics_cap: &self.graphics_cap,
-                image_cache: &mut proj.image_cache,
-                render_params: render_params.as_ref(),
-            };
-            graph_view.render_into(inner, frame.buffer_mut());
-        }
+    #[test]
+    fn unchanged_github_poll_keeps_layout() {
+        use crate::git::types::{BranchInfo, CommitSource};
+        use crate::test_utils::{make_commit, make_oid};
+
+        let mut app = sample_app();
+        let fork = CommitSource::Fork("octocat".to_string());
+        let poll = || GitHubData {
+            rate_limit: Some(4999),
+            branches: vec![BranchInfo {
+                name: "octocat/main".to_string(),
+                tip: make_oid(8),
+                is_head: false,
+                source: fork.clone(),
+                upstream: None,
+            }],
+            commits: vec![crate::git::types::CommitInfo {
+                source: fork.clone(),
+                ..make_commit(8, vec![1], 60)
+            }],
+            unlisted_forks: Vec::new(),
+            pull_requests: Some(Vec::new()),
+            protected_branches: None,
+            codeowners: None,
+            author_logins: None,
+        };
+        let rows = app.projects[0].rows.len();
+        app.handle_github_result(0, Ok(poll()));
+        assert_eq!(app.projects[0].rows.len(), rows + 1);
+        let branches = app.projects[0].repo_data.branches.len();
+
+        // A relayout would bring them back
+        app.projects[0].rows.clear();
+        app.handle_github_result(0, Ok(poll()));
+        assert!(app.projects[0].rows.is_empty());
+        assert_eq!(app.projects[0].repo_data.branches.len(), branches);
     }
 
-    fn render_bordered_detail_panel(&self, frame: &mut Frame, area: Rect) {
-        let is_active = self.active_panel == Panel::Detail;
-        let border_color = if is_active {
-            self.palette.active_panel_border
-        } else {
-            self.palette.inactive_panel_border
+    #[test]
+    fn github_polls_merge_branches_by_source_and_name() {
+        use crate::git::types::{BranchInfo, CommitSource};
+        use crate::test_utils::{make_commit, make_oid};
+
+        let mut app = sample_app();
+        let fork = |owner: &str| CommitSource::Fork(owner.to_string());
+        let branch = |owner: &str, name: &str, tip: u8| BranchInfo {
+            name: format!("{owner}/{name}"),
+            tip: make_oid(tip),
+            is_head: false,
+            source: fork(owner),
+            upstream: None,
         };
-        let title_color = if is_active {
-            self.palette.accent
-        } else {
-            self.palette.panel_label
+        let poll = |branches: Vec<BranchInfo>, tips: &[u8], unlisted: &[&str]| GitHubData {
+            rate_limit: None,
+            branches,
+            unlisted_forks: unlisted.iter().map(|owner| fork(owner)).collect(),
+            commits: tips
+                .iter()
+                .map(|&tip| crate::git::types::CommitInfo {
+                    source: fork("octocat"),
+                    ..make_commit(tip, vec![1], 60 - tip as i64)
+                })
+                .collect(),
+            pull_requests: None,
+            protected_branches: None,
+            codeowners: None,
+            author_logins: None,
+        };
+        let fork_branches = |app: &App| {
+            let mut names: Vec<(String, Oid)> = app.projects[0]
+                .repo_data
+                .branches
+                .iter()
+                .filter(|b| matches!(b.source, CommitSource::Fork(_)))
+                .map(|b| (b.name.clone(), b.tip))
+                .collect();
+            names.sort();
+            names
         };
 
-        let block = Block::default()
-            .title(" Detail ")
-            .title_style(
-                Style::default()
-                    .fg(title_color)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(border_color));
-        let inner = block.inner(area);
-        frame.render_widget(block, area);
+        app.handle_github_result(
+            0,
+            Ok(poll(
+                vec![
+                    branch("octocat", "main", 8),
+                    branch("octocat", "wip", 9),
+                    branch("hubot", "main", 10),
+                ],
+                &[8, 9, 10],
+                &[],
+            )),
+        );
+        assert_eq!(fork_branches(&app).len(), 3);
 
-        if let Some(proj) = self.projects.get(self.active_project) {
-            if let Some(row) = proj.rows.get(self.graph_selected) {
-                let detail = DetailPanel {
-                    meta: &row.meta,
-                    focused: is_active,
-                    palette: &self.palette,
-                };
-                frame.render_widget(detail, inner);
-            }
-        }
+        // main moved, wip was deleted, hubot's listing failed this time
+        app.handle_github_result(
+            0,
+            Ok(poll(vec![branch("octocat", "main", 11)], &[11], &["hubot"])),
+        );
+        assert_eq!(
+            fork_branches(&app),
+            vec![
+                ("hubot/main".to_string(), make_oid(10)),
+                ("octocat/main".to_string(), make_oid(11)),
+            ]
+        );
+
+        // hubot's fork is gone and octocat's listing came back empty
+        app.handle_github_result(0, Ok(poll(Vec::new(), &[], &[])));
+        assert!(fork_branches(&app).is_empty());
     }
 
-    fn render_header(&self, frame: &mut Frame, area: Rect) {
-        let proj = self.projects.get(self.active_project);
-        let info = proj.map(|p| PaneInfo {
-            name: &p.name,
-            branch: &p.current_branch,
-            commit_count: p.rows.len(),
-        });
-        let infos: Vec<PaneInfo<'_>> = info.into_iter().collect();
-        let last_sync = proj.map(|p| p.last_sync.as_str()).unwrap_or("never");
-        let view_mode = proj.map(|p| &p.active_mode);
-        let project_count = self.projects.len();
-        let header = HeaderBar {
-            panes: &infos,
-            last_sync,
-            author_filter: &self.author_filter_text,
-            view_mode,
-            project_count,
-            active_project_idx: self.active_project,
-            palette: &self.palette,
+    #[test]
+    fn forks_without_recent_commits_fold_together() {
+        use crate::git::types::{BranchInfo, CommitInfo, CommitSource};
+        use crate::test_utils::{make_commit, make_oid};
+
+        let mut app = sample_app();
+        let fork_branch = |owner: &str, tip: u8, secs_ago: i64| {
+            let source = CommitSource::Fork(owner.to_string());
+            let branch = BranchInfo {
+                name: format!("{owner}/main"),
+                tip: make_oid(tip),
+                is_head: false,
+                source: source.clone(),
+                upstream: None,
+            };
+            let commit = CommitInfo {
+                source,
+                ..make_commit(tip, vec![6], secs_ago)
+            };
+            (branch, commit)
         };
-        frame.render_widget(header, area);
+        let (old, old_commit) = fork_branch("octocat", 8, 400 * 24 * 3600);
+        let (fresh, fresh_commit) = fork_branch("hubot", 9, 3600);
+        app.handle_github_result(
+            0,
+            Ok(GitHubData {
+                rate_limit: None,
+                branches: vec![old, fresh],
+                commits: vec![old_commit, fresh_commit],
+                unlisted_forks: Vec::new(),
+                pull_requests: None,
+                protected_branches: None,
+                codeowners: None,
+                author_logins: None,
+            }),
+        );
+        let labels: Vec<&str> = app
+            .cached_entries
+            .iter()
+            .map(|e| e.label.trim_start())
+            .collect();
+        let inactive = labels
+            .iter()
+            .position(|l| l.ends_with("Inactive forks"))
+            .expect("inactive forks section");
+        assert!(labels.iter().any(|l| l.ends_with("Fork: hubot")));
+        assert!(!labels.iter().any(|l| l.ends_with("Fork: octocat")));
+        assert_eq!(labels[inactive + 1], "octocat/main");
+
+        app.config.stale_fork_months = 0;
+        app.refresh_entries();
+        assert!(app
+            .cached_entries
+            .iter()
+            .any(|e| e.label.ends_with("Fork: octocat")));
     }
 
-    fn branch_panel_width(&self, term_w: u16) -> u16 {
-        let max_w = branch_panel::max_entry_width(&self.cached_entries);
-        let tw = term_w as usize;
-        (max_w + 4).clamp(22, (tw / 3).max(22)) as u16
+    #[test]
+    fn toast() {
+        let mut app = sample_app();
+        app.notify(NotifyLevel::Warn, "github polling disabled for demo");
+        assert_snapshot("toast", &render(&mut app));
     }
 
-    fn dismiss_stale_notifications(&mut self) {
-        if let Some(ref n) = self.notification {
-            if n.created.elapsed() > std::time::Duration::from_secs(n.level.ttl_secs()) {
-                self.notification = None;
+    #[test]
+    fn pr_merge_picks_a_method_and_asks_before_merging() {
+        use crate::data_source::PullRequestInfo;
+        let mut app = sample_app();
+        app.active_panel = Panel::Graph;
+        press(&mut app, 'P');
+        assert_eq!(
+            app.notification.take().map(|n| n.message),
+            Some("merging PRs needs a github_token".to_string())
+        );
+
+        app.handle_event(AppEvent::PullRequestFound {
+            project_idx: 0,
+            branch: "main".to_string(),
+            result: Ok(Some(PullRequestInfo {
+                number: 12,
+                title: "login".to_string(),
+            })),
+        });
+        press(&mut app, 'j');
+        let key = |code| AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        app.handle_event(key(KeyCode::Enter));
+        assert!(app.pr_merge.is_none());
+        let confirm = app.confirm.as_ref().unwrap();
+        assert_eq!(confirm.question, "squash and merge #12? (y/n)");
+        assert!(matches!(
+            confirm.kind,
+            ConfirmKind::MergePr {
+                number: 12,
+                method: MergeMethod::Squash,
+                ..
             }
-        }
+        ));
+        press(&mut app, 'n');
+        assert!(app.confirm.is_none());
+
+        app.handle_event(AppEvent::PullRequestMerged {
+            project_idx: 0,
+            branch: "main".to_string(),
+            number: 12,
+            result: Err("409 conflict".to_string()),
+        });
+        assert!(app.confirm.is_none());
+        assert_eq!(
+            app.notification.take().map(|n| n.message),
+            Some("merging #12 failed: 409 conflict".to_string())
+        );
+
+        app.handle_event(AppEvent::PullRequestMerged {
+            project_idx: 0,
+            branch: "main".to_string(),
+            number: 12,
+            result: Ok(()),
+        });
+        let confirm = app.confirm.as_ref().unwrap();
+        assert_eq!(confirm.question, "delete main locally and on GitHub? (y/n)");
+        assert!(matches!(
+            confirm.kind,
+            ConfirmKind::DeleteMergedBranch { .. }
+        ));
     }
 
-    fn render_status_bar(&self, frame: &mut Frame, area: Rect) {
-        let active = self.projects.get(self.active_project);
-        let loading_msg = if self.loading_remote {
-            Some("loading remote data...")
-        } else {
-            None
+    #[test]
+    fn merged_branch_stays_local_until_github_deletes_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap());
+        let head = repo
+            .commit(Some("HEAD"), &sig, &sig, "init", &tree.unwrap(), &[])
+            .unwrap();
+        repo.branch("feature", &repo.find_commit(head).unwrap(), false)
+            .unwrap();
+
+        let mut app = App::new(Config::default(), GraphicsCapability::Unsupported);
+        let project = app
+            .open_project(dir.path(), Some("demo/app".to_string()))
+            .unwrap();
+        app.projects.push(project);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        app.event_tx = Some(tx);
+        let deleted = |result| AppEvent::BranchDeleted {
+            project_idx: 0,
+            branch: "feature".to_string(),
+            remote: true,
+            result,
         };
-        let commit_count = active.map(|p| p.rows.len()).unwrap_or(0);
-        let branch_count = active.map(|p| p.repo_data.branches.len()).unwrap_or(0);
-        let status = StatusBar {
-            branch_name: active.map(|p| p.current_branch.as_str()).unwrap_or(""),
-            last_sync: active.map(|p| p.last_sync.as_str()).unwrap_or("never"),
-            filter_mode: self.filter_mode,
-            filter_text: &self.filter_text,
-            author_filter_text: &self.author_filter_text,
-            loading_message: loading_msg,
-            commit_count,
-            branch_count,
-            palette: &self.palette,
+        let has_feature = |app: &App| {
+            let worker = &app.projects[0].local_source.as_ref().unwrap().worker;
+            worker
+                .call(|r| r.find_branch("feature", git2::BranchType::Local).is_ok())
+                .unwrap()
         };
-        frame.render_widget(status, area);
+
+        app.handle_event(deleted(Err("403".to_string())));
+        assert!(has_feature(&app));
+        assert_eq!(
+            app.notification.take().map(|n| n.message),
+            Some("deleting feature on GitHub failed: 403".to_string())
+        );
+
+        app.handle_event(deleted(Ok(None)));
+        assert!(!has_feature(&app));
+        let local = std::iter::from_fn(|| rx.try_recv().ok()).any(|e| {
+            matches!(
+                e,
+                AppEvent::BranchDeleted {
+                    remote: false,
+                    result: Ok(_),
+                    ..
+                }
+            )
+        });
+        assert!(local);
+
+        let mut app = sample_app();
+        app.handle_event(AppEvent::BranchDeleted {
+            project_idx: 0,
+            branch: "feature/login".to_string(),
+            remote: true,
+            result: Ok(None),
+        });
+        assert_eq!(
+            app.notification.take().map(|n| n.message),
+            Some("deleted feature/login on GitHub".to_string())
+        );
     }
 
-    fn render_overlays(&self, frame: &mut Frame, size: Rect) {
-        if self.show_help {
-            if self.graphics_cap.is_kitty() {
-                self.clear_kitty_images_in_area(frame, size);
-            }
-            frame.render_widget(
-                HelpPanel {
-                    palette: &self.palette,
-                },
-                size,
-            );
+    #[test]
+    fn split_panes_keep_their_own_selection_and_scroll() {
+        let mut app = sample_app();
+        press(&mut app, 's');
+        assert_eq!(
+            app.notification.take().map(|n| n.message),
+            Some("split view needs two projects".to_string())
+        );
+        let config = Config::default();
+        app.projects.push(Project::snapshot(
+            "infra".to_string(),
+            sample_repo(),
+            &config.trunk_branches,
+        ));
+        app.active_panel = Panel::Graph;
+        press(&mut app, 's');
+        assert_eq!(app.split.as_ref().map(|s| s.project), Some(1));
+        let screen = render(&mut app);
+        assert!(screen.contains("\u{25b6} demo") && !screen.contains("\u{25b6} infra"));
+
+        press(&mut app, 'j');
+        press(&mut app, 'j');
+        press(&mut app, '>');
+        let key = |code| AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        app.handle_event(key(KeyCode::Tab));
+        assert_eq!(app.active_project, 1);
+        assert_eq!(app.split.as_ref().map(|s| s.project), Some(0));
+        press(&mut app, 'j');
+        let pane = |app: &App, i: usize| {
+            let proj = &app.projects[i];
+            (proj.graph_selected, proj.scroll_x)
+        };
+        assert_eq!(pane(&app, 0), (2, 4));
+        assert_eq!(pane(&app, 1), (1, 0));
+        assert!(render(&mut app).contains("\u{25b6} infra"));
+
+        app.handle_event(key(KeyCode::BackTab));
+        assert_eq!(app.active_project, 0);
+        press(&mut app, 'k');
+        assert_eq!(pane(&app, 0).0, 1);
+        assert_eq!(pane(&app, 1).0, 1);
+
+        press(&mut app, 's');
+        assert!(app.split.is_none());
+        assert_eq!(app.active_project, 0);
+        assert!(!render(&mut app).contains('\u{25b6}'));
+    }
+
+    #[tokio::test]
+    async fn quitting_saves_the_session_and_lets_tasks_flush() {
+        use crate::config::write_atomic;
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = sample_app();
+        press(&mut app, 'j');
+        press(&mut app, 'q');
+        assert!(app.should_quit);
+
+        let session = dir.path().join("session.toml");
+        crate::session::save_to(&app, &session).unwrap();
+        let saved: crate::session::SessionState =
+            toml::from_str(&std::fs::read_to_string(&session).unwrap()).unwrap();
+        assert_eq!(saved.projects[0].graph_selected, 1);
+
+        let cache = dir.path().join("cache.json");
+        let (cancel, path) = (app.shutdown.clone(), cache.clone());
+        app.tasks.spawn(async move {
+            cancel.cancelled().await;
+            write_atomic(&path, b"{}").unwrap();
+        });
+        app.spawn_task(std::future::pending());
+        let started = std::time::Instant::now();
+        app.drain_tasks(std::time::Duration::from_secs(5)).await;
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+        assert!(app.tasks.is_empty());
+        assert_eq!(std::fs::read(&cache).unwrap(), b"{}");
+        let mut names: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["cache.json", "session.toml"]);
+    }
+
+    #[test]
+    fn repos_load_behind_placeholders_and_fill_in() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        for name in ["svc", "infra"] {
+            let repo = git2::Repository::init(dir.path().join(name)).unwrap();
+            let sig = git2::Signature::now("test", "test@example.com").unwrap();
+            let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap());
+            repo.commit(Some("HEAD"), &sig, &sig, name, &tree.unwrap(), &[])
+                .unwrap();
+            config.repos.push(RepoEntry {
+                path: dir.path().join(name),
+                name: Some(format!("acme/{name}")),
+            });
         }
-        if self.confirm_quit {
-            use ratatui::text::{Line, Span};
-            let dialog_area = crate::ui::centered_rect(30, 15, size);
-            let block = Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(self.palette.warn_fg))
-                .title(" Quit ")
-                .title_style(
-                    Style::default()
-                        .fg(self.palette.warn_fg)
-                        .add_modifier(Modifier::BOLD),
-                );
-            let inner = block.inner(dialog_area);
-            frame.render_widget(ratatui::widgets::Clear, dialog_area);
-            frame.render_widget(block, dialog_area);
-            if inner.height >= 2 && inner.width >= 10 {
-                let msg = Line::from(Span::styled(
-                    "Discard changes? (y/n)",
-                    Style::default().fg(self.palette.content_fg),
-                ));
-                frame.buffer_mut().set_line(
-                    inner.x + 1,
-                    inner.y + 1,
-                    &msg,
-                    inner.width.saturating_sub(2),
-                );
+        let mut app = App::new(config, GraphicsCapability::Unsupported);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        app.event_tx = Some(tx);
+        app.load_repos().unwrap();
+        assert!(app.projects.iter().all(|p| p.loading && p.rows.is_empty()));
+        assert!(render(&mut app).contains(project::LOADING_LABEL));
+
+        // Both reads finish on their own workers before the UI handles either
+        let mut results = Vec::new();
+        while results.len() < 2 {
+            let event = rx.blocking_recv().unwrap();
+            if matches!(event, AppEvent::LocalDataResult { .. }) {
+                results.push(event);
             }
         }
-        if let Some(ref n) = self.notification {
-            frame.render_widget(
-                crate::ui::toast::Toast {
-                    notification: n,
-                    palette: &self.palette,
-                },
-                size,
-            );
+        for event in results {
+            app.handle_event(event);
         }
+        let loaded: Vec<_> = app
+            .projects
+            .iter()
+            .map(|p| (p.loading, p.rows.len()))
+            .collect();
+        assert_eq!(loaded, [(false, 1), (false, 1)]);
+        assert!(!render(&mut app).contains(project::LOADING_LABEL));
     }
 
-    fn clear_kitty_images_in_area(&self, frame: &mut Frame, area: Rect) {
-        use ratatui::layout::Position;
-        let del = crate::kitty_protocol::delete_all_kitty_images();
-        if let Some(cell) = frame.buffer_mut().cell_mut(Position::new(area.x, area.y)) {
-            cell.set_symbol(&del);
-        }
+    #[test]
+    fn lane_overflow_warns_once_per_overflow() {
+        use crate::test_utils::{synthetic_repo, SyntheticSpec};
+        let spec = SyntheticSpec {
+            width: MAX_LANES + 8,
+            fork_rate: 1000,
+            merge_rate: 0,
+            ..SyntheticSpec::linear(4 * MAX_LANES)
+        };
+        let config = Config::default();
+        let wide = Project::snapshot(
+            "wide".to_string(),
+            synthetic_repo(&spec),
+            &config.trunk_branches,
+        );
+        let mut app = sample_app();
+        app.projects.push(wide);
+        assert!(!app.projects[0].lane_overflow && app.projects[1].lane_overflow);
+        assert_eq!(app.projects[1].max_lanes, MAX_LANES);
+
+        app.sync_lane_overflow();
+        assert_eq!(
+            app.notification.take().map(|n| n.message),
+            Some(format!(
+                "wide: over {MAX_LANES} lanes \u{2014} extra branches share the last one"
+            ))
+        );
+        app.sync_lane_overflow();
+        assert!(app.notification.is_none());
+
+        // A layout that fits re-arms the warning
+        let wide = &mut app.projects[1];
+        wide.repo_data = sample_repo();
+        wide.rebuild_layout(&config.trunk_branches);
+        assert!(!wide.lane_overflow);
+        wide.repo_data = synthetic_repo(&spec);
+        wide.rebuild_layout(&config.trunk_branches);
+        app.sync_lane_overflow();
+        assert!(app.notification.take().is_some());
+
+        let palette = &app.palette;
+        let trunks = palette.trunk_colors.len();
+        let wrapped = palette.branch_color_by_identity(trunks, trunks + 1);
+        assert_ne!(wrapped, palette.trunk_colors[0]);
+        assert_ne!(wrapped, palette.branch_colors[0]);
     }
 
-    fn ensure_scroll_bounds(&mut self, visible_height: usize) {
-        if visible_height == 0 {
-            return;
-        }
-        if self.graph_selected >= self.graph_scroll_y + visible_height {
-            self.graph_scroll_y = self.graph_selected - visible_height + 1;
-        }
-        if self.graph_selected < self.graph_scroll_y {
-            self.graph_scroll_y = self.graph_selected;
-        }
+    #[test]
+    fn follow_mode_pins_the_selection_to_new_commits() {
+        use crate::test_utils::make_oid;
+        let arrive = |app: &mut App, oid: u8| {
+            let mut data = sample_repo();
+            let mut commit = (*data.commits[0]).clone();
+            commit.oid = make_oid(oid);
+            commit.parents = vec![data.commits[0].oid];
+            data.commits.insert(0, std::sync::Arc::new(commit));
+            data.branches[0].tip = make_oid(oid);
+            app.handle_local_data_result(0, Ok(RepoUpdate::Full(data)));
+        };
+        let mut app = sample_app();
+        app.active_panel = Panel::Graph;
+        app.set_graph_selected(3);
+        press(&mut app, 'F');
+        assert!(app.follow);
+        assert_eq!(app.graph_selected(), 0);
+        assert!(render(&mut app).contains(" following "));
+
+        press(&mut app, 'j');
+        press(&mut app, 'j');
+        arrive(&mut app, 8);
+        app.notification = None;
+        assert_eq!(app.graph_selected(), 0);
+        assert_eq!(app.projects[0].graph_scroll_y, 0);
+        assert_eq!(app.projects[0].rows[0].meta.oid, make_oid(8));
+        let flashed = app.flash.as_ref().map(|(oids, _)| oids.clone());
+        assert_eq!(flashed, Some(HashSet::from([make_oid(8)])));
+
+        press(&mut app, 'F');
+        assert!(!app.follow && !app.is_flashing());
+        press(&mut app, 'j');
+        arrive(&mut app, 9);
+        assert_eq!(app.graph_selected(), 1);
     }
 
-    fn get_highlighted_oids(&self, entries: &[DisplayEntry]) -> HashSet<crate::git::types::Oid> {
-        let mut set = HashSet::new();
-        if self.active_panel == Panel::Branches {
-            if let Some(entry) = entries.get(self.branch_selected) {
-                if let Some(tip) = entry.tip_oid() {
-                    set.insert(tip);
-                }
-            }
+    #[test]
+    fn watched_branches_report_moves_and_prs_open_from_their_branch() {
+        use crate::data_source::{PullRequestStatus, ReviewState};
+        use crate::test_utils::make_oid;
+        let mut app = sample_app();
+        app.active_panel = Panel::Branches;
+        let select = |app: &mut App, pick: &dyn Fn(&DisplayEntry) -> bool| {
+            let row = app.cached_entries.iter().position(pick).unwrap();
+            app.set_branch_selected(row);
+        };
+        let toast = |app: &mut App| app.notification.take().map(|n| n.message);
+
+        select(&mut app, &|e| e.branch_name() == Some("feature/login"));
+        press(&mut app, '!');
+        assert_eq!(toast(&mut app), Some("watching feature/login".to_string()));
+        let mut data = sample_repo();
+        data.branches[1].tip = make_oid(4);
+        app.handle_local_data_result(0, Ok(RepoUpdate::Full(data.clone())));
+        let short = &make_oid(4).to_string()[..7];
+        assert_eq!(
+            toast(&mut app),
+            Some(format!("demo: feature/login moved to {short}"))
+        );
+        // Reported once per move
+        app.handle_local_data_result(0, Ok(RepoUpdate::Full(data)));
+        assert_eq!(toast(&mut app), None);
+        select(&mut app, &|e| e.branch_name() == Some("feature/login"));
+        press(&mut app, '!');
+        assert_eq!(
+            toast(&mut app),
+            Some("stopped watching feature/login".to_string())
+        );
+        assert!(app.projects[0].watched_branches.is_empty());
+
+        assert_eq!(
+            app.selected_pull_request_url(),
+            Err("no open PR for the selection")
+        );
+        app.projects[0].pull_requests = vec![PullRequestStatus {
+            number: 7,
+            branch: "feature/login".to_string(),
+            draft: false,
+            review: ReviewState::Pending,
+            head: None,
+        }];
+        assert_eq!(app.selected_pull_request_url(), Err("not a GitHub repo"));
+        app.projects[0].web_url = Some("https://github.com/acme/demo".to_string());
+        assert_eq!(
+            app.selected_pull_request_url().as_deref(),
+            Ok("https://github.com/acme/demo/pull/7")
+        );
+        app.refresh_entries();
+        select(&mut app, &|e| {
+            matches!(e.kind, EntryKind::PullRequest { .. })
+        });
+        assert_eq!(
+            app.selected_pull_request_url().as_deref(),
+            Ok("https://github.com/acme/demo/pull/7")
+        );
+    }
+
+    #[test]
+    fn stash_popup_keys_act_on_the_stash_not_the_graph() {
+        use crate::git::stash::StashEntry;
+        use crate::test_utils::make_oid;
+        let mut app = sample_app();
+        let stash = |index| StashEntry {
+            index,
+            message: format!("WIP {index}"),
+            oid: make_oid(8 + index as u8),
+            base: make_oid(1),
+        };
+        app.stashes = Some(StashesState {
+            entries: vec![stash(0), stash(1)],
+            selected: 0,
+            loading: false,
+        });
+        let (diff, detail) = (app.show_diff, app.show_detail);
+        press(&mut app, 'j');
+        for (c, question) in [
+            ('a', "apply stash@{1}? (y/n)"),
+            ('p', "pop stash@{1}? (y/n)"),
+            ('d', "drop stash@{1}? (y/n)"),
+        ] {
+            press(&mut app, c);
+            let confirm = app.confirm.take().unwrap();
+            assert_eq!(confirm.question, question);
         }
-        set
+        assert_eq!((app.show_diff, app.show_detail), (diff, detail));
+        assert_eq!(app.filter_mode, FilterMode::Off);
+        assert!(app.stashes.is_some());
     }
-}
 
-pub fn head_branch_name(data: &RepoData) -> String {
-    data.branches
-        .iter()
-        .find(|b| b.is_head)
-        .map(|b| b.name.clone())
-        .unwrap_or_else(|| "detached".to_string())
-}
+    #[test]
+    fn patch_export_keeps_to_the_ancestry_and_out_of_the_worktree() {
+        use crate::test_utils::make_oid;
+        let mut app = sample_app();
+        app.active_panel = Panel::Graph;
+        let row = |app: &App, val| {
+            app.projects[0]
+                .rows
+                .iter()
+                .position(|r| r.meta.oid == make_oid(val))
+                .unwrap()
+        };
+        // feature/login's tip down to Release 1.0, across fix/empty-config's commit
+        app.visual_anchor = Some(row(&app, 5));
+        app.set_graph_selected(row(&app, 3));
+        assert!(row(&app, 7) > row(&app, 5) && row(&app, 7) < row(&app, 3));
+        assert_eq!(
+            app.patch_export_commits(),
+            [make_oid(3), make_oid(4), make_oid(5)]
+        );
 
-pub fn expand_tilde(path: &std::path::Path) -> std::path::PathBuf {
-    let s = path.to_string_lossy();
-    if let Some(rest) = s.strip_prefix("~/") {
-        if let Ok(home) = std::env::var("HOME") {
-            return std::path::PathBuf::from(home).join(rest);
+        let dir = tempfile::tempdir().unwrap();
+        let workdir = dir.path().join("demo");
+        let repo = git2::Repository::init(&workdir).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap());
+        repo.commit(Some("HEAD"), &sig, &sig, "init", &tree.unwrap(), &[])
+            .unwrap();
+        let mut app = App::new(Config::default(), GraphicsCapability::Unsupported);
+        let project = app
+            .open_project(&workdir, Some("acme/demo".to_string()))
+            .unwrap();
+        app.projects.push(project);
+        app.rebuild_graph(0);
+        app.active_panel = Panel::Graph;
+        press(&mut app, 'x');
+        let prompt = app.prompt.as_mut().unwrap();
+        let expected = dir.path().join("demo-patches");
+        assert_eq!(prompt.text, expected.to_string_lossy());
+
+        prompt.text.clear();
+        let key = |code| AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        app.handle_event(key(KeyCode::Enter));
+        assert_eq!(
+            app.notification.take().map(|n| n.message),
+            Some("export needs a directory".to_string())
+        );
+    }
+
+    #[test]
+    fn macro_replay_stops_at_a_confirmation() {
+        use crate::git::stash::StashEntry;
+        use crate::test_utils::make_oid;
+        let mut app = sample_app();
+        let stash = |index| StashEntry {
+            index,
+            message: format!("WIP {index}"),
+            oid: make_oid(8 + index as u8),
+            base: make_oid(1),
+        };
+        app.stashes = Some(StashesState {
+            entries: vec![stash(0), stash(1)],
+            selected: 0,
+            loading: false,
+        });
+        // The confirmation's own n isn't recorded
+        for c in ['Q', 'a', 'p', 'n', 'j', 'Q'] {
+            press(&mut app, c);
         }
+        app.notification = None;
+        press(&mut app, 'k');
+        assert_eq!(app.stashes.as_ref().unwrap().selected, 0);
+
+        press(&mut app, '@');
+        press(&mut app, 'a');
+        let confirm = app.confirm.as_ref().unwrap();
+        assert_eq!(confirm.question, "pop stash@{0}? (y/n)");
+        assert_eq!(app.stashes.as_ref().unwrap().selected, 0);
+        assert_eq!(
+            app.notification.take().map(|n| n.message),
+            Some("@a stopped at a confirmation, 1 action skipped".to_string())
+        );
+        press(&mut app, 'n');
+        assert!(app.confirm.is_none());
     }
-    path.to_path_buf()
 }
@@ -0,0 +1,128 @@
+use crate::config::Config;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AuthorStyle {
+    #[default]
+    Name,
+    Initials,
+    Username,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AuthorFormat {
+    pub style: AuthorStyle,
+    pub aliases: BTreeMap<String, String>,
+}
+
+impl AuthorFormat {
+    pub fn from_config(config: &Config) -> Self {
+        let style = match config.author_display.as_deref() {
+            None | Some("name") => AuthorStyle::Name,
+            Some("initials") => AuthorStyle::Initials,
+            Some("username") => AuthorStyle::Username,
+            Some(other) => {
+                tracing::warn!(
+                    author_display = other,
+                    "author_display must be \"name\", \"initials\" or \"username\""
+                );
+                AuthorStyle::Name
+            }
+        };
+        Self {
+            style,
+            aliases: config.author_aliases.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct AuthorNames<'a> {
+    pub format: &'a AuthorFormat,
+    pub logins: Option<&'a HashMap<String, String>>,
+}
+
+impl<'a> AuthorNames<'a> {
+    // An alias wins over the style; an unknown login falls back to the name
+    pub fn label(&self, name: &'a str, email: &str) -> Cow<'a, str> {
+        let aliases = &self.format.aliases;
+        if let Some(alias) = aliases.get(email).or_else(|| aliases.get(name)) {
+            return Cow::Owned(alias.clone());
+        }
+        match self.format.style {
+            AuthorStyle::Name => Cow::Borrowed(name),
+            AuthorStyle::Initials => Cow::Owned(initials(name)),
+            AuthorStyle::Username => self
+                .logins
+                .and_then(|logins| logins.get(email))
+                .cloned()
+                .or_else(|| noreply_login(email))
+                .map_or(Cow::Borrowed(name), Cow::Owned),
+        }
+    }
+}
+
+fn initials(name: &str) -> String {
+    name.split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .take(3)
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+fn noreply_login(email: &str) -> Option<String> {
+    let local = email.strip_suffix("@users.noreply.github.com")?;
+    let login = local.split_once('+').map_or(local, |(_, login)| login);
+    (!login.is_empty()).then(|| login.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shows_names_initials_or_usernames_with_aliases_first() {
+        let logins = HashMap::from([("alice@example.com".to_string(), "alice-gh".to_string())]);
+        let names = |style| {
+            let format = AuthorFormat {
+                style,
+                aliases: BTreeMap::from([
+                    ("bot@ci.dev".to_string(), "ci".to_string()),
+                    ("Robert Tables".to_string(), "bobby".to_string()),
+                ]),
+            };
+            let names = AuthorNames {
+                format: &format,
+                logins: Some(&logins),
+            };
+            [
+                ("Alice Liddell", "alice@example.com"),
+                ("Carol de la Cruz", "42+carol@users.noreply.github.com"),
+                ("Dan", "dan@example.com"),
+                ("Robert Tables", "bob@example.com"),
+                ("Build Bot", "bot@ci.dev"),
+            ]
+            .map(|(name, email)| names.label(name, email).into_owned())
+        };
+
+        assert_eq!(
+            names(AuthorStyle::Name),
+            ["Alice Liddell", "Carol de la Cruz", "Dan", "bobby", "ci"]
+        );
+        assert_eq!(
+            names(AuthorStyle::Initials),
+            ["AL", "CDL", "D", "bobby", "ci"]
+        );
+        assert_eq!(
+            names(AuthorStyle::Username),
+            ["alice-gh", "carol", "Dan", "bobby", "ci"]
+        );
+
+        let config = Config {
+            author_display: Some("nicknames".to_string()),
+            ..Config::default()
+        };
+        assert_eq!(AuthorFormat::from_config(&config).style, AuthorStyle::Name);
+    }
+}
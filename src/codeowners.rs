@@ -0,0 +1,132 @@
+// The last rule matching a path decides its owners, as on GitHub
+#[derive(Clone, Debug, Default)]
+pub struct CodeOwners {
+    rules: Vec<Rule>,
+}
+
+#[derive(Clone, Debug)]
+struct Rule {
+    glob: String,
+    owners: Vec<String>,
+}
+
+impl CodeOwners {
+    pub fn parse(text: &str) -> Self {
+        let rules = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut words = line.split_whitespace();
+                let pattern = words.next()?;
+                let owners = words
+                    .take_while(|w| !w.starts_with('#'))
+                    .map(str::to_string)
+                    .collect();
+                Some(Rule {
+                    glob: anchor(pattern),
+                    owners,
+                })
+            })
+            .collect();
+        Self { rules }
+    }
+
+    pub fn owners_of(&self, path: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| {
+                glob_match(rule.glob.as_bytes(), path.as_bytes())
+                    || glob_match(format!("{}/**", rule.glob).as_bytes(), path.as_bytes())
+            })
+            .map_or(&[], |rule| &rule.owners)
+    }
+
+    pub fn owners_for<'a>(&self, paths: impl IntoIterator<Item = &'a str>) -> Vec<&str> {
+        let mut out: Vec<&str> = Vec::new();
+        for path in paths {
+            for owner in self.owners_of(path) {
+                if !out.contains(&owner.as_str()) {
+                    out.push(owner);
+                }
+            }
+        }
+        out
+    }
+}
+
+// Without an inner / a pattern matches at any depth; a trailing / means the contents
+fn anchor(pattern: &str) -> String {
+    let dir_only = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    let mut glob = match trimmed.strip_prefix('/') {
+        Some(rooted) => rooted.to_string(),
+        None if trimmed.contains('/') => trimmed.to_string(),
+        None => format!("**/{trimmed}"),
+    };
+    if dir_only {
+        glob.push_str("/**");
+    }
+    glob
+}
+
+// * and ? stay within a path segment, ** crosses them
+fn glob_match(glob: &[u8], path: &[u8]) -> bool {
+    match glob {
+        [] => path.is_empty(),
+        [b'*', b'*', b'/', rest @ ..] => {
+            // Zero or more leading directories
+            glob_match(rest, path)
+                || path
+                    .iter()
+                    .enumerate()
+                    .any(|(i, &c)| c == b'/' && glob_match(rest, &path[i + 1..]))
+        }
+        [b'*', b'*', rest @ ..] => (0..=path.len()).any(|i| glob_match(rest, &path[i..])),
+        [b'*', rest @ ..] => {
+            let segment = path.iter().position(|&c| c == b'/').unwrap_or(path.len());
+            (0..=segment).any(|i| glob_match(rest, &path[i..]))
+        }
+        [b'?', rest @ ..] => match path {
+            [c, tail @ ..] => *c != b'/' && glob_match(rest, tail),
+            [] => false,
+        },
+        [c, rest @ ..] => match path {
+            [p, tail @ ..] => p == c && glob_match(rest, tail),
+            [] => false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_matching_rule_owns_the_path() {
+        let owners = CodeOwners::parse(
+            "# default owners\n\
+             *       @org/core\n\
+             *.md    @org/docs # docs team\n\
+             /src/ui/ @org/frontend @alice\n\
+             docs/**/api.md @bob\n\
+             /vendor\n",
+        );
+        assert_eq!(owners.owners_of("Cargo.toml"), ["@org/core"]);
+        assert_eq!(
+            owners.owners_of("src/ui/README.md"),
+            ["@org/frontend", "@alice"]
+        );
+        assert_eq!(owners.owners_of("notes/todo.md"), ["@org/docs"]);
+        assert_eq!(owners.owners_of("docs/v1/http/api.md"), ["@bob"]);
+        assert_eq!(owners.owners_of("lib/src/ui/app.rs"), ["@org/core"]);
+        // Unowned by the later rule without owners
+        assert!(owners.owners_of("vendor/lib/a.c").is_empty());
+
+        assert_eq!(
+            owners.owners_for(["src/ui/app.rs", "main.rs", "src/ui/mod.rs"]),
+            vec!["@org/frontend", "@alice", "@org/core"]
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use crate::git::types::{Oid, RepoData};
+use crate::graph::filter::reachable_from;
+use crate::project::Project;
+use std::collections::HashSet;
+
+pub struct Comparison {
+    pub sides: [Project; 2],
+    pub selected: [usize; 2],
+    pub scroll_y: [usize; 2],
+    pub focus: usize,
+    pub shared: HashSet<Oid>,
+}
+
+impl Comparison {
+    pub fn new(
+        data: &RepoData,
+        left: (&str, Oid),
+        right: (&str, Oid),
+        trunk_branches: &[String],
+    ) -> Self {
+        let sides = [left, right].map(|(name, tip)| {
+            Project::snapshot(name.to_string(), reachable_from(data, tip), trunk_branches)
+        });
+        let left_oids: HashSet<Oid> = sides[0].rows.iter().map(|r| r.meta.oid).collect();
+        let shared = sides[1]
+            .rows
+            .iter()
+            .map(|r| r.meta.oid)
+            .filter(|oid| left_oids.contains(oid))
+            .collect();
+        let mut cmp = Self {
+            sides,
+            selected: [0, 0],
+            scroll_y: [0, 0],
+            focus: 0,
+            shared,
+        };
+        cmp.align();
+        cmp
+    }
+
+    // Selects the focused commit on the other side, or the closest in time
+    pub fn only_on(&self, side: usize) -> usize {
+        self.sides[side].rows.len() - self.shared.len()
+    }
+
+    pub fn move_selection(&mut self, down: bool) {
+        let f = self.focus;
+        if down {
+            if self.selected[f] + 1 < self.sides[f].rows.len() {
+                self.selected[f] += 1;
+            }
+        } else {
+            self.selected[f] = self.selected[f].saturating_sub(1);
+        }
+        self.align();
+    }
+
+    // Scrolls the other side so its aligned commit sits on the same row
+    pub fn align(&mut self) {
+        let (f, o) = (self.focus, 1 - self.focus);
+        let Some(row) = self.sides[f].rows.get(self.selected[f]) else {
+            return;
+        };
+        let oid = row.meta.oid;
+        let exact = if self.shared.contains(&oid) {
+            self.sides[o].rows.iter().position(|r| r.meta.oid == oid)
+        } else {
+            None
+        };
+        if let Some(target) =
+            exact.or_else(|| self.sides[o].find_closest_by_time_binary(&row.meta.time))
+        {
+            self.selected[o] = target;
+        }
+    }
+
+    pub fn update_scroll(&mut self, visible_height: usize) {
+        if visible_height == 0 {
+            return;
+        }
+        let (f, o) = (self.focus, 1 - self.focus);
+        if self.selected[f] >= self.scroll_y[f] + visible_height {
+            self.scroll_y[f] = self.selected[f] + 1 - visible_height;
+        }
+        if self.selected[f] < self.scroll_y[f] {
+            self.scroll_y[f] = self.selected[f];
+        }
+        let offset = self.selected[f] - self.scroll_y[f];
+        self.scroll_y[o] = self.selected[o].saturating_sub(offset);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::types::{BranchInfo, CommitSource};
+    use crate::test_utils::*;
+
+    fn branch(name: &str, tip: u8) -> BranchInfo {
+        BranchInfo {
+            name: name.to_string(),
+            tip: make_oid(tip),
+            is_head: false,
+            source: CommitSource::Local,
+            upstream: None,
+        }
+    }
+
+    // main: 1 <- 2 <- 3 <- 5, origin/main: 1 <- 2 <- 4
+    fn diverged() -> RepoData {
+        make_repo_data(
+            vec![
+                make_commit(5, vec![3], 10),
+                make_commit(4, vec![2], 20),
+                make_commit(3, vec![2], 30),
+                make_commit(2, vec![1], 40),
+                make_commit(1, vec![], 50),
+            ],
+            vec![branch("main", 5), branch("origin/main", 4)],
+        )
+    }
+
+    fn selected_oid(cmp: &Comparison, side: usize) -> Oid {
+        cmp.sides[side].rows[cmp.selected[side]].meta.oid
+    }
+
+    #[test]
+    fn counts_shared_and_unique_commits() {
+        let cmp = Comparison::new(
+            &diverged(),
+            ("main", make_oid(5)),
+            ("origin/main", make_oid(4)),
+            &[],
+        );
+        assert_eq!(cmp.shared, HashSet::from([make_oid(1), make_oid(2)]));
+        assert_eq!(cmp.only_on(0), 2);
+        assert_eq!(cmp.only_on(1), 1);
+    }
+
+    #[test]
+    fn follows_by_oid_then_by_time() {
+        let mut cmp = Comparison::new(
+            &diverged(),
+            ("main", make_oid(5)),
+            ("origin/main", make_oid(4)),
+            &[],
+        );
+        // 5 is only on main; 4 is the closest in time
+        assert_eq!(selected_oid(&cmp, 1), make_oid(4));
+
+        cmp.move_selection(true);
+        cmp.move_selection(true);
+        assert_eq!(selected_oid(&cmp, 0), make_oid(2));
+        assert_eq!(selected_oid(&cmp, 1), make_oid(2));
+
+        cmp.update_scroll(1);
+        assert_eq!(cmp.scroll_y, [2, 1]);
+    }
+}
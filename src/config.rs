@@ -1,10 +1,13 @@
+use chrono::{DateTime, Months, Utc};
 use figment::{
     providers::{Env, Format, Serialized, Toml},
     Figment,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepoEntry {
@@ -12,6 +15,13 @@ pub struct RepoEntry {
     pub name: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceEntry {
+    pub name: String,
+    #[serde(default)]
+    pub repos: Vec<String>,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ProfileEntry {
     pub name: String,
@@ -89,6 +99,59 @@ pub struct Config {
     pub active_profile: Option<String>,
     #[serde(default)]
     pub profiles: Vec<ProfileEntry>,
+    #[serde(default)]
+    pub workspaces: Vec<WorkspaceEntry>,
+    #[serde(default)]
+    pub workspace: Option<String>,
+    // 0 keeps them running
+    #[serde(default = "default_park_after_secs")]
+    pub park_after_secs: u64,
+    // git's diff.tool when unset
+    #[serde(default)]
+    pub difftool: Option<String>,
+    #[serde(default)]
+    pub diff_pager: Option<String>,
+    #[serde(default)]
+    pub exclude_authors: Vec<String>,
+    #[serde(default)]
+    pub hide_excluded_authors: bool,
+    #[serde(default = "default_large_file_mb")]
+    pub large_file_mb: u64,
+    // 0 keeps every fork in its own section
+    #[serde(default = "default_stale_fork_months")]
+    pub stale_fork_months: u32,
+    // Off by default, as it takes over the terminal's text selection
+    #[serde(default)]
+    pub mouse: bool,
+    #[serde(default = "default_hyperlinks")]
+    pub hyperlinks: bool,
+    // LC_TIME or LANG when unset
+    #[serde(default)]
+    pub date_locale: Option<String>,
+    // The locale's own clock when unset
+    #[serde(default)]
+    pub clock: Option<String>,
+    #[serde(default)]
+    pub week_start: Option<String>,
+    #[serde(default)]
+    pub author_display: Option<String>,
+    #[serde(default)]
+    pub author_aliases: BTreeMap<String, String>,
+    // No kitty graph images and no transient highlights
+    #[serde(default)]
+    pub reduced_motion: bool,
+    #[serde(default)]
+    pub list_view: bool,
+    #[serde(default = "default_tag_milestones")]
+    pub tag_milestones: bool,
+    // It diffs every commit scrolled past
+    #[serde(default)]
+    pub diffstat: bool,
+    #[serde(default)]
+    pub manual_refresh: bool,
+    // Never written back
+    #[serde(skip)]
+    pub adhoc_repo: Option<PathBuf>,
 }
 
 impl fmt::Debug for Config {
@@ -107,10 +170,52 @@ impl fmt::Debug for Config {
             .field("theme", &self.theme)
             .field("active_profile", &self.active_profile)
             .field("profiles_count", &self.profiles.len())
+            .field("workspaces", &self.workspaces)
+            .field("workspace", &self.workspace)
+            .field("park_after_secs", &self.park_after_secs)
+            .field("difftool", &self.difftool)
+            .field("diff_pager", &self.diff_pager)
+            .field("exclude_authors", &self.exclude_authors)
+            .field("hide_excluded_authors", &self.hide_excluded_authors)
+            .field("large_file_mb", &self.large_file_mb)
+            .field("stale_fork_months", &self.stale_fork_months)
+            .field("mouse", &self.mouse)
+            .field("hyperlinks", &self.hyperlinks)
+            .field("date_locale", &self.date_locale)
+            .field("clock", &self.clock)
+            .field("week_start", &self.week_start)
+            .field("author_display", &self.author_display)
+            .field("author_aliases", &self.author_aliases)
+            .field("reduced_motion", &self.reduced_motion)
+            .field("list_view", &self.list_view)
+            .field("tag_milestones", &self.tag_milestones)
+            .field("diffstat", &self.diffstat)
+            .field("manual_refresh", &self.manual_refresh)
+            .field("adhoc_repo", &self.adhoc_repo)
             .finish()
     }
 }
 
+fn default_park_after_secs() -> u64 {
+    600
+}
+
+fn default_large_file_mb() -> u64 {
+    5
+}
+
+fn default_stale_fork_months() -> u32 {
+    6
+}
+
+fn default_hyperlinks() -> bool {
+    true
+}
+
+fn default_tag_milestones() -> bool {
+    true
+}
+
 fn default_trunk_branches() -> Vec<String> {
     vec![
         "development".to_string(),
@@ -132,6 +237,28 @@ impl Default for Config {
             theme: None,
             active_profile: None,
             profiles: Vec::new(),
+            workspaces: Vec::new(),
+            workspace: None,
+            park_after_secs: default_park_after_secs(),
+            difftool: None,
+            diff_pager: None,
+            exclude_authors: Vec::new(),
+            hide_excluded_authors: false,
+            large_file_mb: default_large_file_mb(),
+            stale_fork_months: default_stale_fork_months(),
+            mouse: false,
+            hyperlinks: default_hyperlinks(),
+            date_locale: None,
+            clock: None,
+            week_start: None,
+            author_display: None,
+            author_aliases: BTreeMap::new(),
+            reduced_motion: false,
+            list_view: false,
+            tag_milestones: default_tag_milestones(),
+            diffstat: false,
+            manual_refresh: false,
+            adhoc_repo: None,
         }
     }
 }
@@ -169,6 +296,12 @@ impl Config {
     }
 
     pub fn resolved_repos(&self) -> Vec<RepoEntry> {
+        if let Some(ref path) = self.adhoc_repo {
+            return vec![RepoEntry {
+                path: path.clone(),
+                name: None,
+            }];
+        }
         if self.repos.is_empty() {
             vec![RepoEntry {
                 path: self.repo_path.clone(),
@@ -179,26 +312,48 @@ impl Config {
         }
     }
 
+    // Positions in resolved_repos
+    pub fn workspace_members(&self, name: &str) -> Option<Vec<usize>> {
+        let workspace = self.workspaces.iter().find(|w| w.name == name)?;
+        let listed = |entry: &RepoEntry| {
+            workspace
+                .repos
+                .iter()
+                .any(|r| entry.name.as_deref() == Some(r.as_str()) || entry.path == Path::new(r))
+        };
+        let members = self
+            .resolved_repos()
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| listed(entry))
+            .map(|(i, _)| i)
+            .collect();
+        Some(members)
+    }
+
+    pub fn hidden_authors(&self) -> &[String] {
+        if self.hide_excluded_authors {
+            &self.exclude_authors
+        } else {
+            &[]
+        }
+    }
+
+    pub fn large_file_bytes(&self) -> u64 {
+        self.large_file_mb.saturating_mul(1024 * 1024)
+    }
+
+    pub fn stale_fork_cutoff(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if self.stale_fork_months == 0 {
+            return None;
+        }
+        now.checked_sub_months(Months::new(self.stale_fork_months))
+    }
+
     pub fn save(&self) -> std::io::Result<()> {
-        let dir = config_dir().join("arachne");
-        std::fs::create_dir_all(&dir)?;
-        let path = dir.join("config.toml");
+        let path = config_dir().join("arachne").join("config.toml");
         let content = toml::to_string_pretty(self).map_err(std::io::Error::other)?;
-        #[cfg(unix)]
-        {
-            use std::io::Write;
-            use std::os::unix::fs::OpenOptionsExt;
-            let mut file = std::fs::OpenOptions::new()
-                .write(true)
-                .create(true)
-                .truncate(true)
-                .mode(0o600)
-                .open(&path)?;
-            file.write_all(content.as_bytes())?;
-            Ok(())
-        }
-        #[cfg(not(unix))]
-        std::fs::write(path, content)
+        write_atomic(&path, content.as_bytes())
     }
 
     pub fn config_file_exists() -> bool {
@@ -243,3 +398,40 @@ pub fn config_dir() -> PathBuf {
         })
         .unwrap_or_else(|| PathBuf::from("."))
 }
+
+// The temp file is 0600 on unix, which keeps the token private
+pub fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(dir)?;
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+    tmp.write_all(contents)?;
+    tmp.as_file().sync_all()?;
+    tmp.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+pub fn repo_file_name(repo_name: &str, ext: &str) -> String {
+    let safe: String = repo_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{safe}.{ext}")
+}
+
+pub fn state_dir() -> PathBuf {
+    std::env::var("XDG_STATE_HOME")
+        .ok()
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var("HOME")
+                .ok()
+                .map(|h| PathBuf::from(h).join(".local").join("state"))
+        })
+        .unwrap_or_else(|| PathBuf::from("."))
+}
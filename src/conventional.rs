@@ -0,0 +1,89 @@
+// Anything else before a colon is left as written
+pub const TYPES: &[&str] = &[
+    "feat", "fix", "chore", "revert", "docs", "refactor", "perf", "test", "build", "ci", "style",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Conventional<'a> {
+    pub kind: &'a str,
+    pub scope: Option<&'a str>,
+    pub breaking: bool,
+    pub description: &'a str,
+}
+
+pub fn parse(summary: &str) -> Option<Conventional<'_>> {
+    let (head, description) = summary.split_once(": ")?;
+    let (head, breaking) = match head.strip_suffix('!') {
+        Some(head) => (head, true),
+        None => (head, false),
+    };
+    let (kind, scope) = match head.split_once('(') {
+        Some((kind, rest)) => {
+            let scope = rest.strip_suffix(')')?;
+            if scope.is_empty() || scope.contains([' ', '(', ')']) {
+                return None;
+            }
+            (kind, Some(scope))
+        }
+        None => (head, None),
+    };
+    let description = description.trim_start();
+    if !TYPES.contains(&kind) || description.is_empty() {
+        return None;
+    }
+    Some(Conventional {
+        kind,
+        scope,
+        breaking,
+        description,
+    })
+}
+
+// breaking takes any type marked with !
+pub fn type_matches(summary: &str, types: &[String]) -> bool {
+    parse(summary).is_some_and(|c| {
+        types
+            .iter()
+            .any(|t| t == c.kind || (c.breaking && t == "breaking"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_type_scope_and_breaking_mark() {
+        assert_eq!(
+            parse("feat(ui)!: drop the old theme"),
+            Some(Conventional {
+                kind: "feat",
+                scope: Some("ui"),
+                breaking: true,
+                description: "drop the old theme",
+            })
+        );
+        let fix = parse("fix: guard against empty config").unwrap();
+        assert_eq!((fix.kind, fix.scope, fix.breaking), ("fix", None, false));
+        assert_eq!(
+            parse("revert!: undo the cache").map(|c| c.breaking),
+            Some(true)
+        );
+
+        let types = ["fix".to_string(), "breaking".to_string()];
+        assert!(type_matches("fix(ci): pin the toolchain", &types));
+        assert!(type_matches("feat!: drop v1", &types));
+        assert!(!type_matches("feat: add v2", &types));
+
+        for plain in [
+            "Merge branch 'feature/login'",
+            "note: not a known type",
+            "feat:missing space",
+            "feat(): empty scope",
+            "fix(a b): spaced scope",
+            "feat: ",
+        ] {
+            assert_eq!(parse(plain), None, "{plain}");
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use crate::config::{state_dir, Config};
+use crossterm::{
+    cursor::Show,
+    event::DisableMouseCapture,
+    execute,
+    terminal::{disable_raw_mode, LeaveAlternateScreen},
+};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+const MAX_RECENT_EVENTS: usize = 50;
+
+static RECENT_EVENTS: Mutex<EventLog> = Mutex::new(EventLog::new());
+static CONFIG_SUMMARY: Mutex<String> = Mutex::new(String::new());
+
+struct EventLog {
+    entries: VecDeque<String>,
+}
+
+impl EventLog {
+    const fn new() -> Self {
+        Self {
+            entries: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, entry: String) {
+        if self.entries.len() >= MAX_RECENT_EVENTS {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &String> {
+        self.entries.iter()
+    }
+}
+
+pub fn record_event(kind: &str) {
+    if let Ok(mut log) = RECENT_EVENTS.lock() {
+        log.push(format!(
+            "{} {kind}",
+            chrono::Local::now().format("%H:%M:%S%.3f")
+        ));
+    }
+}
+
+// Config's Debug impl redacts tokens
+pub fn set_config_summary(config: &Config) {
+    if let Ok(mut summary) = CONFIG_SUMMARY.lock() {
+        *summary = format!("{config:#?}");
+    }
+}
+
+fn crash_dir() -> PathBuf {
+    state_dir().join("arachne").join("crashes")
+}
+
+pub fn build_report(
+    message: &str,
+    location: &str,
+    backtrace: &str,
+    events: &[String],
+    config: &str,
+) -> String {
+    let mut out = String::new();
+    out.push_str(&format!(
+        "arachne {} crash report\n",
+        env!("CARGO_PKG_VERSION")
+    ));
+    out.push_str(&format!("time: {}\n", chrono::Local::now().to_rfc3339()));
+    out.push_str(&format!(
+        "os: {} {}\n\n",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    ));
+    out.push_str(&format!("panic: {message}\nat: {location}\n\n"));
+    out.push_str("── recent events (oldest first) ──\n");
+    for e in events {
+        out.push_str(e);
+        out.push('\n');
+    }
+    out.push_str("\n── config ──\n");
+    out.push_str(config);
+    out.push_str("\n\n── backtrace ──\n");
+    out.push_str(backtrace);
+    out.push('\n');
+    out
+}
+
+fn write_report(report: &str) -> std::io::Result<PathBuf> {
+    let dir = crash_dir();
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!(
+        "crash-{}.txt",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    std::fs::write(&path, report)?;
+    Ok(path)
+}
+
+fn panic_message(info: &std::panic::PanicHookInfo<'_>) -> String {
+    if let Some(s) = info.payload().downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = info.payload().downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    // Workers and tasks catch their panics and the UI keeps running, so
+    // only the UI thread's may take the terminal down
+    let ui_thread = std::thread::current().id();
+    std::panic::set_hook(Box::new(move |info| {
+        let on_ui = std::thread::current().id() == ui_thread;
+        if on_ui {
+            let _ = disable_raw_mode();
+            let _ = execute!(
+                std::io::stdout(),
+                DisableMouseCapture,
+                LeaveAlternateScreen,
+                Show
+            );
+        }
+
+        let message = panic_message(info);
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+            .unwrap_or_else(|| "unknown".to_string());
+        tracing::error!(%location, "panic: {message}");
+
+        let backtrace = std::backtrace::Backtrace::force_capture().to_string();
+        let events: Vec<String> = RECENT_EVENTS
+            .lock()
+            .map(|log| log.iter().cloned().collect())
+            .unwrap_or_default();
+        let config = CONFIG_SUMMARY.lock().map(|s| s.clone()).unwrap_or_default();
+
+        let report = build_report(&message, &location, &backtrace, &events, &config);
+        match write_report(&report) {
+            Ok(path) if on_ui => {
+                eprintln!("arachne crashed: {message}");
+                eprintln!("crash report written to {}", path.display());
+            }
+            Ok(path) => tracing::error!(report = %path.display(), "panic off the UI thread"),
+            Err(_) if on_ui => default_hook(info),
+            Err(_) => {}
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_log_keeps_most_recent() {
+        let mut log = EventLog::new();
+        for i in 0..(MAX_RECENT_EVENTS + 5) {
+            log.push(format!("event {i}"));
+        }
+        let entries: Vec<_> = log.iter().collect();
+        assert_eq!(entries.len(), MAX_RECENT_EVENTS);
+        assert_eq!(entries[0], "event 5");
+    }
+
+    #[test]
+    fn report_redacts_token() {
+        let config = Config {
+            github_token: Some("ghp_secret".to_string()),
+            ..Default::default()
+        };
+        let report = build_report(
+            "boom",
+            "src/app.rs:1:1",
+            "<bt>",
+            &["12:00:00.000 key".to_string()],
+            &format!("{config:#?}"),
+        );
+        assert!(report.contains("panic: boom"));
+        assert!(report.contains("12:00:00.000 key"));
+        assert!(report.contains("[REDACTED]"));
+        assert!(!report.contains("ghp_secret"));
+    }
+}
@@ -1,6 +1,100 @@
+use crate::codeowners::CodeOwners;
 use crate::config::Config;
-use crate::github::client::GitHubClient;
-use git2::Repository;
+use crate::git::types::{BranchInfo, CommitInfo, CommitSource, Oid, RepoData};
+use crate::git::worker::RepoWorker;
+use std::collections::HashMap;
+
+#[cfg(feature = "github")]
+pub use crate::github::client::GitHubClient;
+
+// Can't be constructed, so remote paths type-check but never run
+#[cfg(not(feature = "github"))]
+#[derive(Clone)]
+pub struct GitHubClient {
+    never: std::convert::Infallible,
+}
+
+#[cfg(not(feature = "github"))]
+impl GitHubClient {
+    pub fn request_count(&self) -> u32 {
+        match self.never {}
+    }
+
+    pub async fn is_reachable(&self) -> bool {
+        match self.never {}
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ForkNetwork {
+    pub branches: Vec<BranchInfo>,
+    pub commits: Vec<CommitInfo>,
+    // Forks whose branch listing failed, so their known branches stay
+    pub unlisted: Vec<CommitSource>,
+    pub rate_limit: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PullRequestInfo {
+    pub number: u64,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewState {
+    Approved,
+    ChangesRequested,
+    Pending,
+}
+
+impl ReviewState {
+    // Each reviewer's latest verdict counts; one request for changes outweighs approvals
+    pub fn from_reviews<'a>(
+        reviews: impl IntoIterator<Item = (&'a str, Option<ReviewState>)>,
+    ) -> Self {
+        let mut latest: HashMap<&str, Option<ReviewState>> = HashMap::new();
+        for (reviewer, verdict) in reviews {
+            latest.insert(reviewer, verdict);
+        }
+        let verdicts: Vec<_> = latest.into_values().flatten().collect();
+        if verdicts.contains(&ReviewState::ChangesRequested) {
+            ReviewState::ChangesRequested
+        } else if verdicts.contains(&ReviewState::Approved) {
+            ReviewState::Approved
+        } else {
+            ReviewState::Pending
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullRequestStatus {
+    pub number: u64,
+    pub branch: String,
+    pub draft: bool,
+    pub review: ReviewState,
+    pub head: Option<Oid>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeMethod {
+    Merge,
+    Squash,
+    Rebase,
+}
+
+impl MergeMethod {
+    pub const ALL: [MergeMethod; 3] =
+        [MergeMethod::Merge, MergeMethod::Squash, MergeMethod::Rebase];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MergeMethod::Merge => "merge commit",
+            MergeMethod::Squash => "squash and merge",
+            MergeMethod::Rebase => "rebase and merge",
+        }
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ViewMode {
@@ -9,13 +103,15 @@ pub enum ViewMode {
 }
 
 pub struct LocalSource {
-    pub repo: Repository,
+    pub worker: RepoWorker,
 }
 
 pub struct RemoteSource {
     pub client: GitHubClient,
+    pub cache: std::path::PathBuf,
 }
 
+#[cfg(feature = "github")]
 pub fn init_github_client(config: &Config, repo_name: &str) -> Option<GitHubClient> {
     let token = config.github_token.as_ref()?;
     if token.is_empty() {
@@ -28,3 +124,192 @@ pub fn init_github_client(config: &Config, repo_name: &str) -> Option<GitHubClie
         None
     }
 }
+
+#[cfg(not(feature = "github"))]
+pub fn init_github_client(_config: &Config, _repo_name: &str) -> Option<GitHubClient> {
+    None
+}
+
+pub fn github_paused_until() -> Option<chrono::DateTime<chrono::Utc>> {
+    #[cfg(feature = "github")]
+    {
+        crate::github::queue::RequestQueue::shared().paused_until()
+    }
+    #[cfg(not(feature = "github"))]
+    None
+}
+
+pub fn github_unreachable() -> bool {
+    #[cfg(feature = "github")]
+    {
+        crate::github::queue::RequestQueue::shared().unreachable()
+    }
+    #[cfg(not(feature = "github"))]
+    false
+}
+
+pub async fn fetch_network(client: &GitHubClient) -> std::result::Result<ForkNetwork, String> {
+    #[cfg(feature = "github")]
+    {
+        crate::github::network::fetch_network_detached(client).await
+    }
+    #[cfg(not(feature = "github"))]
+    match client.never {}
+}
+
+pub async fn fetch_pull_requests(
+    client: &GitHubClient,
+) -> std::result::Result<Vec<PullRequestStatus>, String> {
+    #[cfg(feature = "github")]
+    {
+        client
+            .fetch_pull_requests()
+            .await
+            .map_err(|e| e.to_string())
+    }
+    #[cfg(not(feature = "github"))]
+    match client.never {}
+}
+
+pub async fn fetch_protected_branches(
+    client: &GitHubClient,
+) -> std::result::Result<Vec<String>, String> {
+    #[cfg(feature = "github")]
+    {
+        client
+            .fetch_protected_branches()
+            .await
+            .map_err(|e| e.to_string())
+    }
+    #[cfg(not(feature = "github"))]
+    match client.never {}
+}
+
+pub async fn fetch_codeowners(client: &GitHubClient) -> std::result::Result<CodeOwners, String> {
+    #[cfg(feature = "github")]
+    {
+        let text = client.fetch_codeowners().await.map_err(|e| e.to_string())?;
+        Ok(text.as_deref().map(CodeOwners::parse).unwrap_or_default())
+    }
+    #[cfg(not(feature = "github"))]
+    match client.never {}
+}
+
+pub async fn fetch_author_logins(
+    client: &GitHubClient,
+) -> std::result::Result<HashMap<String, String>, String> {
+    #[cfg(feature = "github")]
+    {
+        client
+            .fetch_author_logins()
+            .await
+            .map_err(|e| e.to_string())
+    }
+    #[cfg(not(feature = "github"))]
+    match client.never {}
+}
+
+pub async fn load_remote(
+    client: &GitHubClient,
+    max_commits: usize,
+) -> std::result::Result<RepoData, String> {
+    #[cfg(feature = "github")]
+    {
+        crate::github::remote_loader::load_remote_repo_data(&client.for_user(), max_commits).await
+    }
+    #[cfg(not(feature = "github"))]
+    {
+        let _ = max_commits;
+        match client.never {}
+    }
+}
+
+pub async fn find_open_pr(
+    client: &GitHubClient,
+    branch: &str,
+) -> std::result::Result<Option<PullRequestInfo>, String> {
+    #[cfg(feature = "github")]
+    {
+        client
+            .for_user()
+            .find_open_pr(branch)
+            .await
+            .map_err(|e| e.to_string())
+    }
+    #[cfg(not(feature = "github"))]
+    {
+        let _ = branch;
+        match client.never {}
+    }
+}
+
+pub async fn merge_pr(
+    client: &GitHubClient,
+    number: u64,
+    method: MergeMethod,
+) -> std::result::Result<(), String> {
+    #[cfg(feature = "github")]
+    {
+        client
+            .for_user()
+            .merge_pr(number, method)
+            .await
+            .map_err(|e| e.to_string())
+    }
+    #[cfg(not(feature = "github"))]
+    {
+        let _ = (number, method);
+        match client.never {}
+    }
+}
+
+pub async fn delete_remote_branch(
+    client: &GitHubClient,
+    branch: &str,
+) -> std::result::Result<(), String> {
+    #[cfg(feature = "github")]
+    {
+        client
+            .for_user()
+            .delete_branch(branch)
+            .await
+            .map_err(|e| e.to_string())
+    }
+    #[cfg(not(feature = "github"))]
+    {
+        let _ = branch;
+        match client.never {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latest_verdict_per_reviewer_decides_review_state() {
+        use ReviewState::*;
+        assert_eq!(ReviewState::from_reviews([]), Pending);
+        assert_eq!(
+            ReviewState::from_reviews([("ann", Some(Approved)), ("bob", Some(Approved))]),
+            Approved
+        );
+        assert_eq!(
+            ReviewState::from_reviews([("ann", Some(Approved)), ("bob", Some(ChangesRequested))]),
+            ChangesRequested
+        );
+        // Bob re-reviewed after his changes were made
+        assert_eq!(
+            ReviewState::from_reviews([
+                ("bob", Some(ChangesRequested)),
+                ("ann", Some(Approved)),
+                ("bob", Some(Approved)),
+            ]),
+            Approved
+        );
+        assert_eq!(
+            ReviewState::from_reviews([("ann", Some(Approved)), ("ann", None)]),
+            Pending
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use crate::config::Config;
+use chrono::{DateTime, Datelike, Days, Locale, NaiveDate, NaiveTime, Utc, Weekday};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DateFormat {
+    pub locale: Locale,
+    pub hour12: bool,
+    pub week_start: Weekday,
+}
+
+impl Default for DateFormat {
+    fn default() -> Self {
+        Self {
+            locale: Locale::POSIX,
+            hour12: false,
+            week_start: Weekday::Mon,
+        }
+    }
+}
+
+impl DateFormat {
+    // Values that don't parse fall back with a warning
+    pub fn from_config(config: &Config) -> Self {
+        let locale = match config.date_locale.as_deref() {
+            Some(name) => parse_locale(name).unwrap_or_else(|| {
+                tracing::warn!(locale = name, "unknown date_locale, using POSIX");
+                Locale::POSIX
+            }),
+            None => ["LC_ALL", "LC_TIME", "LANG"]
+                .iter()
+                .filter_map(|var| std::env::var(var).ok())
+                .find(|value| !value.is_empty())
+                .and_then(|value| parse_locale(&value))
+                .unwrap_or(Locale::POSIX),
+        };
+        let hour12 = match config.clock.as_deref() {
+            Some("12h") => true,
+            Some("24h") => false,
+            other => {
+                if let Some(clock) = other {
+                    tracing::warn!(clock, "clock must be \"12h\" or \"24h\"");
+                }
+                // A locale on a 24-hour clock writes 1 PM as 13
+                !NaiveDate::default()
+                    .and_hms_opt(13, 0, 0)
+                    .unwrap_or_default()
+                    .and_utc()
+                    .format_localized("%X", locale)
+                    .to_string()
+                    .contains("13")
+            }
+        };
+        let week_start = match config.week_start.as_deref() {
+            Some(day) => day.parse().unwrap_or_else(|_| {
+                tracing::warn!(week_start = day, "unknown week_start, using Monday");
+                Weekday::Mon
+            }),
+            None => Weekday::Mon,
+        };
+        Self {
+            locale,
+            hour12,
+            week_start,
+        }
+    }
+
+    pub fn date(&self, day: NaiveDate) -> String {
+        if self.locale == Locale::POSIX {
+            return day.format("%Y-%m-%d").to_string();
+        }
+        day.format_localized("%x", self.locale).to_string()
+    }
+
+    pub fn day(&self, day: NaiveDate) -> String {
+        format!("{} {}", self.weekday(day), self.date(day))
+    }
+
+    pub fn weekday(&self, day: NaiveDate) -> String {
+        day.format_localized("%a", self.locale).to_string()
+    }
+
+    pub fn month(&self, day: NaiveDate) -> String {
+        day.format_localized("%b", self.locale).to_string()
+    }
+
+    pub fn time(&self, time: NaiveTime) -> String {
+        if !self.hour12 {
+            return time.format("%H:%M").to_string();
+        }
+        // Some locales have no AM/PM names; the hour alone is what's left
+        // Only dates and zoned date-times take a locale
+        NaiveDate::default()
+            .and_time(time)
+            .and_utc()
+            .format_localized("%-I:%M %p", self.locale)
+            .to_string()
+            .trim_end()
+            .to_string()
+    }
+
+    pub fn date_time(&self, time: &DateTime<Utc>) -> String {
+        let local = time.with_timezone(&chrono::Local);
+        format!(
+            "{} {}",
+            self.date(local.date_naive()),
+            self.time(local.time())
+        )
+    }
+
+    pub fn week_of(&self, day: NaiveDate) -> NaiveDate {
+        let into_week =
+            (day.weekday().num_days_from_monday() + 7 - self.week_start.num_days_from_monday()) % 7;
+        day - Days::new(into_week as u64)
+    }
+}
+
+// C and POSIX are the POSIX locale
+fn parse_locale(name: &str) -> Option<Locale> {
+    let name = name.split(['.', '@']).next().unwrap_or_default();
+    match name {
+        "C" | "POSIX" => Some(Locale::POSIX),
+        name => Locale::try_from(name).ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_by_locale_clock_and_week_start() {
+        let day = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let afternoon = NaiveTime::from_hms_opt(14, 5, 0).unwrap();
+
+        let iso = DateFormat::default();
+        assert_eq!(iso.day(day), "Fri 2026-10-16");
+        assert_eq!(iso.time(afternoon), "14:05");
+        assert_eq!(
+            iso.week_of(day),
+            NaiveDate::from_ymd_opt(2026, 10, 12).unwrap()
+        );
+
+        let config = |locale: &str, clock: Option<&str>, week_start: Option<&str>| Config {
+            date_locale: Some(locale.to_string()),
+            clock: clock.map(str::to_string),
+            week_start: week_start.map(str::to_string),
+            ..Config::default()
+        };
+        let us = DateFormat::from_config(&config("en_US.UTF-8", None, Some("sunday")));
+        assert!(us.hour12);
+        assert_eq!(us.date(day), "10/16/2026");
+        assert_eq!(us.time(afternoon), "2:05 PM");
+        assert_eq!(
+            us.week_of(day),
+            NaiveDate::from_ymd_opt(2026, 10, 11).unwrap()
+        );
+
+        let de = DateFormat::from_config(&config("de_DE", None, None));
+        assert!(!de.hour12);
+        assert_eq!(de.day(day), "Fr 16.10.2026");
+        assert_eq!(de.month(day), "Okt");
+        let de12 = DateFormat::from_config(&config("de_DE", Some("12h"), None));
+        assert_eq!(de12.time(afternoon), "2:05");
+
+        let unknown = DateFormat::from_config(&config("xx_XX", None, None));
+        assert_eq!(unknown.locale, Locale::POSIX);
+    }
+}
@@ -5,9 +5,17 @@ pub enum ArachneError {
     #[error("git error: {0}")]
     Git(#[from] git2::Error),
 
+    #[cfg(feature = "github")]
     #[error("github error: {0}")]
     GitHub(String),
 
+    #[cfg(feature = "github")]
+    #[error(
+        "GitHub rate limit reached, requests paused until {}",
+        .0.with_timezone(&chrono::Local).format("%H:%M")
+    )]
+    RateLimited(chrono::DateTime<chrono::Utc>),
+
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -16,6 +24,9 @@ pub enum ArachneError {
 
     #[error("not a git repository: {0}")]
     NotARepo(String),
+
+    #[error("repo worker thread stopped")]
+    WorkerGone,
 }
 
 pub type Result<T> = std::result::Result<T, ArachneError>;
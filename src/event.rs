@@ -1,16 +1,38 @@
-use crate::git::types::{BranchInfo, CommitInfo, RepoData};
-use crossterm::event::KeyEvent;
+use crate::codeowners::CodeOwners;
+use crate::data_source::{PullRequestInfo, PullRequestStatus};
+use crate::git::blame::BlameLine;
+use crate::git::diff::{ChangedFile, CommitWeight, DiffStat};
+use crate::git::fetch::{FetchOutcome, FetchProgress};
+use crate::git::head_log::HeadMove;
+use crate::git::merge::{CherryPickOutcome, ConflictPreview};
+use crate::git::signature::TagSignature;
+use crate::git::stash::{StashEntry, StashOp};
+use crate::git::types::{BranchInfo, CommitInfo, CommitSource, Oid, RepoData, RepoUpdate};
+use crate::git::undo::DeletedRef;
+use crossterm::event::{KeyEvent, MouseEvent};
+use ratatui::text::Text;
+use std::collections::HashMap;
+use std::path::PathBuf;
 
 #[derive(Debug)]
 pub struct GitHubData {
     pub rate_limit: Option<u32>,
     pub branches: Vec<BranchInfo>,
     pub commits: Vec<CommitInfo>,
+    pub unlisted_forks: Vec<CommitSource>,
+    // None when the listing failed; the previous one is kept
+    pub pull_requests: Option<Vec<PullRequestStatus>>,
+    pub protected_branches: Option<Vec<String>>,
+    // Only fetched until the first success
+    pub codeowners: Option<CodeOwners>,
+    // Like `codeowners`, only fetched until the first success
+    pub author_logins: Option<HashMap<String, String>>,
 }
 
 #[derive(Debug)]
 pub enum AppEvent {
     Key(KeyEvent),
+    Mouse(MouseEvent),
     Resize,
     FsChanged(usize),
     GitHubUpdate(usize),
@@ -21,6 +43,176 @@ pub enum AppEvent {
     RemoteDataResult {
         project_idx: usize,
         result: std::result::Result<RepoData, String>,
+        cached_at: Option<chrono::DateTime<chrono::Utc>>,
+    },
+    GitHubProbed {
+        reachable: bool,
+    },
+    LocalDataResult {
+        project_idx: usize,
+        result: std::result::Result<RepoUpdate, String>,
+    },
+    LoadProgress {
+        project_idx: usize,
+        walked: usize,
+    },
+    CommitFilesResult {
+        oid: Oid,
+        result: std::result::Result<Vec<ChangedFile>, String>,
+    },
+    CommitDiffResult {
+        oid: Oid,
+        result: std::result::Result<Text<'static>, String>,
+    },
+    PatchesExported {
+        result: std::result::Result<(usize, PathBuf), String>,
+    },
+    FetchProgress {
+        project_idx: usize,
+        progress: FetchProgress,
+    },
+    Fetched {
+        project_idx: usize,
+        result: std::result::Result<FetchOutcome, String>,
+    },
+    CloneDeepened {
+        project_idx: usize,
+        result: std::result::Result<u32, String>,
+    },
+    ConflictPreviewResult {
+        oid: Oid,
+        result: std::result::Result<ConflictPreview, String>,
+    },
+    CherryPicked {
+        project_idx: usize,
+        oid: Oid,
+        result: std::result::Result<CherryPickOutcome, String>,
+    },
+    PullRequestFound {
+        project_idx: usize,
+        branch: String,
+        result: std::result::Result<Option<PullRequestInfo>, String>,
+    },
+    PullRequestMerged {
+        project_idx: usize,
+        branch: String,
+        number: u64,
+        result: std::result::Result<(), String>,
+    },
+    BranchCreated {
+        project_idx: usize,
+        branch: String,
+        result: std::result::Result<(), String>,
+    },
+    BranchDeleted {
+        project_idx: usize,
+        branch: String,
+        remote: bool,
+        result: std::result::Result<Option<DeletedRef>, String>,
+    },
+    TagDeleted {
+        project_idx: usize,
+        tag: String,
+        result: std::result::Result<DeletedRef, String>,
+    },
+    BranchesPruned {
+        project_idx: usize,
+        deleted: Vec<DeletedRef>,
+        failed: Vec<(String, String)>,
+    },
+    CommitWeightsResult {
+        project_idx: usize,
+        weights: Vec<(Oid, CommitWeight)>,
+    },
+    DiffStatsResult {
+        project_idx: usize,
+        stats: Vec<(Oid, DiffStat)>,
+    },
+    RemoteOnlyFound {
+        project_idx: usize,
+        oids: Vec<Oid>,
+    },
+    TagsVerified {
+        project_idx: usize,
+        results: Vec<(String, Oid, TagSignature)>,
+    },
+    LargestFilesResult {
+        project_idx: usize,
+        result: std::result::Result<Vec<(String, u64)>, String>,
+    },
+    HeadMovesListed {
+        project_idx: usize,
+        result: std::result::Result<Vec<HeadMove>, String>,
+    },
+    BlameResult {
+        project_idx: usize,
+        result: std::result::Result<Vec<BlameLine>, String>,
+    },
+    CheckedOut {
+        project_idx: usize,
+        name: String,
+        result: std::result::Result<(), String>,
+    },
+    StashesListed {
+        project_idx: usize,
+        result: std::result::Result<Vec<StashEntry>, String>,
+    },
+    StashOpDone {
+        project_idx: usize,
+        op: StashOp,
+        index: usize,
+        dropped: Option<StashEntry>,
+        result: std::result::Result<Vec<String>, String>,
+    },
+    RefRestored {
+        project_idx: usize,
+        deleted: DeletedRef,
+        result: std::result::Result<(), String>,
     },
     ConfigSaved,
+    WatchersChanged,
+}
+
+impl AppEvent {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppEvent::Key(_) => "key",
+            AppEvent::Mouse(_) => "mouse",
+            AppEvent::Resize => "resize",
+            AppEvent::FsChanged(_) => "fs_changed",
+            AppEvent::GitHubUpdate(_) => "github_update",
+            AppEvent::GitHubResult { .. } => "github_result",
+            AppEvent::RemoteDataResult { .. } => "remote_data_result",
+            AppEvent::GitHubProbed { .. } => "github_probed",
+            AppEvent::LocalDataResult { .. } => "local_data_result",
+            AppEvent::LoadProgress { .. } => "load_progress",
+            AppEvent::CommitFilesResult { .. } => "commit_files_result",
+            AppEvent::CommitDiffResult { .. } => "commit_diff_result",
+            AppEvent::PatchesExported { .. } => "patches_exported",
+            AppEvent::FetchProgress { .. } => "fetch_progress",
+            AppEvent::Fetched { .. } => "fetched",
+            AppEvent::CloneDeepened { .. } => "clone_deepened",
+            AppEvent::ConflictPreviewResult { .. } => "conflict_preview_result",
+            AppEvent::CherryPicked { .. } => "cherry_picked",
+            AppEvent::PullRequestFound { .. } => "pull_request_found",
+            AppEvent::PullRequestMerged { .. } => "pull_request_merged",
+            AppEvent::BranchCreated { .. } => "branch_created",
+            AppEvent::BranchDeleted { .. } => "branch_deleted",
+            AppEvent::TagDeleted { .. } => "tag_deleted",
+            AppEvent::BranchesPruned { .. } => "branches_pruned",
+            AppEvent::CommitWeightsResult { .. } => "commit_weights_result",
+            AppEvent::DiffStatsResult { .. } => "diff_stats_result",
+            AppEvent::RemoteOnlyFound { .. } => "remote_only_found",
+            AppEvent::TagsVerified { .. } => "tags_verified",
+            AppEvent::LargestFilesResult { .. } => "largest_files_result",
+            AppEvent::HeadMovesListed { .. } => "head_moves_listed",
+            AppEvent::BlameResult { .. } => "blame_result",
+            AppEvent::CheckedOut { .. } => "checked_out",
+            AppEvent::StashesListed { .. } => "stashes_listed",
+            AppEvent::StashOpDone { .. } => "stash_op_done",
+            AppEvent::RefRestored { .. } => "ref_restored",
+            AppEvent::ConfigSaved => "config_saved",
+            AppEvent::WatchersChanged => "watchers_changed",
+        }
+    }
 }
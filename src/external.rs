@@ -0,0 +1,159 @@
+use crossterm::{
+    cursor::{Hide, Show},
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus, Stdio};
+
+#[derive(Debug)]
+pub struct ExternalCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    pub cwd: Option<PathBuf>,
+    pub label: &'static str,
+    // Removed once the command is dropped
+    pub temp_files: Vec<tempfile::TempPath>,
+}
+
+impl ExternalCommand {
+    pub fn new(label: &'static str, program: impl Into<String>) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+            cwd: None,
+            label,
+            temp_files: Vec::new(),
+        }
+    }
+
+    // No shell quoting, so paths with spaces need a wrapper script
+    pub fn from_command_line(label: &'static str, line: &str) -> Option<Self> {
+        let mut words = line.split_whitespace();
+        let mut cmd = Self::new(label, words.next()?);
+        cmd.args = words.map(str::to_string).collect();
+        Some(cmd)
+    }
+
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn cwd(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cwd = Some(dir.into());
+        self
+    }
+}
+
+// The caller must stop reading input first and redraw everything after
+pub fn run_suspended(
+    backend: &mut impl Write,
+    cmd: &ExternalCommand,
+    mouse: bool,
+) -> std::io::Result<ExitStatus> {
+    execute!(backend, DisableMouseCapture, LeaveAlternateScreen, Show)?;
+    disable_raw_mode()?;
+
+    let mut command = Command::new(&cmd.program);
+    command.args(&cmd.args);
+    if let Some(ref dir) = cmd.cwd {
+        command.current_dir(dir);
+    }
+    tracing::info!(program = %cmd.program, args = ?cmd.args, "running external command");
+    let status = command.status();
+
+    enable_raw_mode()?;
+    execute!(backend, EnterAlternateScreen, Hide)?;
+    if mouse {
+        execute!(backend, EnableMouseCapture)?;
+    }
+    status
+}
+
+pub fn pipe_through(cmd: &ExternalCommand, input: Vec<u8>) -> std::io::Result<Vec<u8>> {
+    let mut command = Command::new(&cmd.program);
+    command
+        .args(&cmd.args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(ref dir) = cmd.cwd {
+        command.current_dir(dir);
+    }
+    let mut child = command.spawn()?;
+    // Feed stdin from another thread so a pager that writes before it has
+    // read everything can't deadlock against a full stdout pipe
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+    let output = child.wait_with_output()?;
+    match writer.join() {
+        Ok(Ok(())) => {}
+        // The filter may stop reading early (e.g. `head`); its output still counts
+        Ok(Err(e)) if e.kind() == std::io::ErrorKind::BrokenPipe => {}
+        Ok(Err(e)) => return Err(e),
+        Err(_) => return Err(std::io::Error::other("stdin writer panicked")),
+    }
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().next().unwrap_or_default();
+        return Err(std::io::Error::other(format!(
+            "{} exited with {}: {reason}",
+            cmd.program, output.status
+        )));
+    }
+    Ok(output.stdout)
+}
+
+// Detached: the browser outlives neither the terminal nor its input
+pub fn open_url(url: &str) -> std::io::Result<()> {
+    let program = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    let mut child = Command::new(program)
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+pub fn editor_command() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_line_splits_program_and_args() {
+        let cmd = ExternalCommand::from_command_line("editor", "code  -w --new").unwrap();
+        assert_eq!(cmd.program, "code");
+        assert_eq!(cmd.args, vec!["-w", "--new"]);
+        assert!(ExternalCommand::from_command_line("editor", "   ").is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pipe_through_filters_stdin() {
+        let cmd = ExternalCommand::new("pager", "tr").arg("a-z").arg("A-Z");
+        let out = pipe_through(&cmd, b"diff --git\n".to_vec()).unwrap();
+        assert_eq!(out, b"DIFF --GIT\n");
+
+        let failing = ExternalCommand::new("pager", "false");
+        assert!(pipe_through(&failing, Vec::new()).is_err());
+    }
+}
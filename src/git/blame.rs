@@ -0,0 +1,97 @@
+use crate::error::Result;
+use crate::git::types::Oid;
+use chrono::{DateTime, TimeZone, Utc};
+use git2::{BlameOptions, Repository};
+use std::path::Path;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlameLine {
+    pub oid: Oid,
+    pub author: String,
+    pub time: DateTime<Utc>,
+    pub content: String,
+}
+
+// A file the commit deleted is blamed as of its first parent
+pub fn blame_file(repo: &Repository, oid: Oid, path: &str) -> Result<Vec<BlameLine>> {
+    let commit = repo.find_commit(oid.to_git2())?;
+    let mut candidates = vec![commit.clone()];
+    candidates.extend(commit.parents().next());
+    let Some((at, blob)) = candidates.into_iter().find_map(|c| {
+        let entry = c.tree().ok()?.get_path(Path::new(path)).ok()?;
+        let blob = entry.to_object(repo).ok()?.peel_to_blob().ok()?;
+        Some((c.id(), blob))
+    }) else {
+        return Err(git2::Error::from_str(&format!("{path} not found at this commit")).into());
+    };
+    if blob.is_binary() {
+        return Err(git2::Error::from_str(&format!("{path} is a binary file")).into());
+    }
+
+    let mut opts = BlameOptions::new();
+    opts.newest_commit(at);
+    let blame = repo.blame_file(Path::new(path), Some(&mut opts))?;
+    let content = String::from_utf8_lossy(blob.content());
+    let mut out = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        let Some(hunk) = blame.get_line(i + 1) else {
+            continue;
+        };
+        let sig = hunk.final_signature();
+        out.push(BlameLine {
+            oid: Oid::from_git2(hunk.final_commit_id()),
+            author: sig.name().unwrap_or_default().to_string(),
+            time: Utc
+                .timestamp_opt(sig.when().seconds(), 0)
+                .single()
+                .unwrap_or_default(),
+            content: line.to_string(),
+        });
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit_file(repo: &Repository, path: &str, content: &str, author: &str) -> git2::Oid {
+        std::fs::write(repo.workdir().unwrap().join(path), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now(author, "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, content, &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn blames_each_line_on_the_commit_that_last_changed_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let first = commit_file(&repo, "a.txt", "one\ntwo\n", "alice");
+        let second = commit_file(&repo, "a.txt", "one\nTWO\nthree\n", "bob");
+
+        let lines = blame_file(&repo, Oid::from_git2(second), "a.txt").unwrap();
+        let blamed: Vec<_> = lines
+            .iter()
+            .map(|l| (l.oid, l.author.as_str(), l.content.as_str()))
+            .collect();
+        assert_eq!(
+            blamed,
+            vec![
+                (Oid::from_git2(first), "alice", "one"),
+                (Oid::from_git2(second), "bob", "TWO"),
+                (Oid::from_git2(second), "bob", "three"),
+            ]
+        );
+
+        // As of the first commit, the later one doesn't exist yet
+        let lines = blame_file(&repo, Oid::from_git2(first), "a.txt").unwrap();
+        assert!(lines.iter().all(|l| l.oid == Oid::from_git2(first)));
+        assert!(blame_file(&repo, Oid::from_git2(first), "missing.txt").is_err());
+    }
+}
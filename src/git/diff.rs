@@ -0,0 +1,425 @@
+use crate::error::Result;
+use crate::git::types::Oid;
+use git2::{
+    Delta, Diff, DiffDelta, DiffFormat, ObjectType, Patch, Repository, TreeWalkMode, TreeWalkResult,
+};
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileStatus {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    Other,
+}
+
+impl FileStatus {
+    fn of(delta: &DiffDelta<'_>) -> Self {
+        match delta.status() {
+            Delta::Added => FileStatus::Added,
+            Delta::Modified => FileStatus::Modified,
+            Delta::Deleted => FileStatus::Deleted,
+            Delta::Renamed => FileStatus::Renamed,
+            _ => FileStatus::Other,
+        }
+    }
+
+    pub fn marker(self) -> char {
+        match self {
+            FileStatus::Added => 'A',
+            FileStatus::Modified => 'M',
+            FileStatus::Deleted => 'D',
+            FileStatus::Renamed => 'R',
+            FileStatus::Other => '?',
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ChangedFile {
+    // The new path for renames, the old one for deletes
+    pub path: String,
+    pub status: FileStatus,
+    // For LFS files, the size of the pointer
+    pub size: u64,
+    pub lfs: Option<LfsPointer>,
+    // None for a binary file
+    pub stat: Option<DiffStat>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LfsPointer {
+    pub oid: String,
+    pub size: u64,
+}
+
+// Pointer files are about 130 bytes; anything bigger is real content
+const MAX_POINTER_SIZE: u64 = 1024;
+
+pub fn parse_lfs_pointer(content: &[u8]) -> Option<LfsPointer> {
+    let text = std::str::from_utf8(content).ok()?;
+    let mut lines = text.lines();
+    if !lines.next()?.starts_with("version https://git-lfs") {
+        return None;
+    }
+    let (mut oid, mut size) = (None, None);
+    for line in lines {
+        if let Some(rest) = line.strip_prefix("oid ") {
+            oid = Some(rest.to_string());
+        } else if let Some(rest) = line.strip_prefix("size ") {
+            size = rest.parse().ok();
+        }
+    }
+    Some(LfsPointer {
+        oid: oid?,
+        size: size?,
+    })
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CommitWeight {
+    pub lfs_files: usize,
+    // LFS objects counted at full size
+    pub largest: u64,
+}
+
+pub fn commit_weight(repo: &Repository, oid: Oid) -> Result<CommitWeight> {
+    let mut weight = CommitWeight::default();
+    for file in changed_files(repo, oid)? {
+        if file.status == FileStatus::Deleted {
+            continue;
+        }
+        let size = match file.lfs {
+            Some(ref pointer) => {
+                weight.lfs_files += 1;
+                pointer.size
+            }
+            None => file.size,
+        };
+        weight.largest = weight.largest.max(size);
+    }
+    Ok(weight)
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DiffStat {
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+pub fn diff_stat(repo: &Repository, oid: Oid) -> Result<DiffStat> {
+    let stats = first_parent_diff(repo, oid)?.stats()?;
+    Ok(DiffStat {
+        insertions: stats.insertions(),
+        deletions: stats.deletions(),
+    })
+}
+
+fn first_parent_diff(repo: &Repository, oid: Oid) -> Result<Diff<'_>> {
+    let commit = repo.find_commit(oid.to_git2())?;
+    let tree = commit.tree()?;
+    let parent_tree = match commit.parents().next() {
+        Some(parent) => Some(parent.tree()?),
+        None => None,
+    };
+    let mut diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+    diff.find_similar(None)?;
+    Ok(diff)
+}
+
+pub fn changed_files(repo: &Repository, oid: Oid) -> Result<Vec<ChangedFile>> {
+    let diff = first_parent_diff(repo, oid)?;
+    let odb = repo.odb()?;
+    diff.deltas()
+        .enumerate()
+        .map(|(idx, delta)| {
+            let status = FileStatus::of(&delta);
+            let file = if status == FileStatus::Deleted {
+                delta.old_file()
+            } else {
+                delta.new_file()
+            };
+            // Submodule entries point at commits, not blobs: no size
+            let size = odb
+                .read_header(file.id())
+                .map(|(len, _)| len as u64)
+                .unwrap_or(0);
+            let lfs = (size <= MAX_POINTER_SIZE)
+                .then(|| repo.find_blob(file.id()).ok())
+                .flatten()
+                .and_then(|blob| parse_lfs_pointer(blob.content()));
+            let stat = match Patch::from_diff(&diff, idx)? {
+                Some(_) if delta.flags().is_binary() => None,
+                Some(patch) => {
+                    let (_, insertions, deletions) = patch.line_stats()?;
+                    Some(DiffStat {
+                        insertions,
+                        deletions,
+                    })
+                }
+                None => None,
+            };
+            Ok(ChangedFile {
+                path: file
+                    .path()
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                status,
+                size,
+                lfs,
+                stat,
+            })
+        })
+        .collect()
+}
+
+pub fn patch_text(repo: &Repository, oid: Oid) -> Result<Vec<u8>> {
+    let diff = first_parent_diff(repo, oid)?;
+    let mut out = Vec::new();
+    diff.print(DiffFormat::Patch, |_, _, line| {
+        // Content lines come without their +/-/space marker
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            out.push(line.origin() as u8);
+        }
+        out.extend_from_slice(line.content());
+        true
+    })?;
+    Ok(out)
+}
+
+// origin is + for an added line, - for a removed one and a space for context
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiffLine {
+    pub origin: char,
+    // Without its line break
+    pub content: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Hunk {
+    // With the enclosing function if git found one
+    pub header: String,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Clone, Debug)]
+pub struct FilePatch {
+    pub path: String,
+    pub status: FileStatus,
+    pub binary: bool,
+    pub hunks: Vec<Hunk>,
+}
+
+pub fn file_patches(repo: &Repository, oid: Oid) -> Result<Vec<FilePatch>> {
+    let diff = first_parent_diff(repo, oid)?;
+    let mut files = Vec::with_capacity(diff.deltas().len());
+    for idx in 0..diff.deltas().len() {
+        let Some(patch) = Patch::from_diff(&diff, idx)? else {
+            continue;
+        };
+        let delta = patch.delta();
+        let status = FileStatus::of(&delta);
+        let file = if status == FileStatus::Deleted {
+            delta.old_file()
+        } else {
+            delta.new_file()
+        };
+        let mut hunks = Vec::with_capacity(patch.num_hunks());
+        for h in 0..patch.num_hunks() {
+            let (hunk, line_count) = patch.hunk(h)?;
+            let mut lines = Vec::with_capacity(line_count);
+            for l in 0..line_count {
+                let line = patch.line_in_hunk(h, l)?;
+                // Skips the "no newline at end of file" markers
+                if !matches!(line.origin(), '+' | '-' | ' ') {
+                    continue;
+                }
+                lines.push(DiffLine {
+                    origin: line.origin(),
+                    content: String::from_utf8_lossy(line.content())
+                        .trim_end_matches(['\n', '\r'])
+                        .to_string(),
+                });
+            }
+            hunks.push(Hunk {
+                header: String::from_utf8_lossy(hunk.header())
+                    .trim_end()
+                    .to_string(),
+                lines,
+            });
+        }
+        files.push(FilePatch {
+            path: file
+                .path()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            status,
+            binary: delta.flags().is_binary(),
+            hunks,
+        });
+    }
+    Ok(files)
+}
+
+pub fn largest_files(repo: &Repository, limit: usize) -> Result<Vec<(String, u64)>> {
+    let tree = repo.head()?.peel_to_tree()?;
+    let odb = repo.odb()?;
+    let mut files = Vec::new();
+    tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() != Some(ObjectType::Blob) {
+            return TreeWalkResult::Ok;
+        }
+        let Ok((len, _)) = odb.read_header(entry.id()) else {
+            return TreeWalkResult::Ok;
+        };
+        let size = (len as u64 <= MAX_POINTER_SIZE)
+            .then(|| repo.find_blob(entry.id()).ok())
+            .flatten()
+            .and_then(|blob| parse_lfs_pointer(blob.content()))
+            .map_or(len as u64, |pointer| pointer.size);
+        files.push((format!("{dir}{}", entry.name().unwrap_or_default()), size));
+        TreeWalkResult::Ok
+    })?;
+    files.sort_by(|(a, a_size), (b, b_size)| b_size.cmp(a_size).then_with(|| a.cmp(b)));
+    files.truncate(limit);
+    Ok(files)
+}
+
+// A file deleted by oid is read from its first parent
+pub fn blob_at(repo: &Repository, oid: Oid, path: &str) -> Result<Option<Vec<u8>>> {
+    let commit = repo.find_commit(oid.to_git2())?;
+    let mut trees = vec![commit.tree()?];
+    if let Some(parent) = commit.parents().next() {
+        trees.push(parent.tree()?);
+    }
+    for tree in trees {
+        if let Ok(entry) = tree.get_path(Path::new(path)) {
+            let blob = entry.to_object(repo)?.peel_to_blob()?;
+            return Ok(Some(blob.content().to_vec()));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit_file(repo: &Repository, path: &str, contents: Option<&str>) -> Oid {
+        let workdir = repo.workdir().unwrap();
+        let mut index = repo.index().unwrap();
+        match contents {
+            Some(c) => {
+                std::fs::write(workdir.join(path), c).unwrap();
+                index.add_path(Path::new(path)).unwrap();
+            }
+            None => {
+                std::fs::remove_file(workdir.join(path)).unwrap();
+                index.remove_path(Path::new(path)).unwrap();
+            }
+        }
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        let id = repo
+            .commit(Some("HEAD"), &sig, &sig, path, &tree, &parents)
+            .unwrap();
+        Oid::from_git2(id)
+    }
+
+    #[test]
+    fn lists_files_and_reads_old_blobs() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let first = commit_file(&repo, "a.txt", Some("one\n"));
+        commit_file(&repo, "a.txt", Some("two\n"));
+        let deleted = commit_file(&repo, "a.txt", None);
+
+        let files = changed_files(&repo, first).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "a.txt");
+        assert_eq!(files[0].status, FileStatus::Added);
+        assert_eq!(
+            files[0].stat,
+            Some(DiffStat {
+                insertions: 1,
+                deletions: 0
+            })
+        );
+        assert_eq!(
+            changed_files(&repo, deleted).unwrap()[0].status,
+            FileStatus::Deleted
+        );
+
+        assert_eq!(blob_at(&repo, first, "a.txt").unwrap().unwrap(), b"one\n");
+        // Deleted by this commit: the parent's version
+        assert_eq!(blob_at(&repo, deleted, "a.txt").unwrap().unwrap(), b"two\n");
+        assert!(blob_at(&repo, first, "missing").unwrap().is_none());
+
+        let patch = String::from_utf8(patch_text(&repo, deleted).unwrap()).unwrap();
+        assert!(patch.starts_with("diff --git a/a.txt b/a.txt\n"));
+        assert!(patch.contains("\n-two\n"));
+
+        let files = file_patches(&repo, deleted).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].status, FileStatus::Deleted);
+        assert_eq!(files[0].hunks.len(), 1);
+        assert_eq!(files[0].hunks[0].header, "@@ -1 +0,0 @@");
+        assert_eq!(
+            files[0].hunks[0].lines,
+            vec![DiffLine {
+                origin: '-',
+                content: "two".to_string()
+            }]
+        );
+
+        let stat = |oid| diff_stat(&repo, oid).unwrap();
+        assert_eq!(
+            stat(first),
+            DiffStat {
+                insertions: 1,
+                deletions: 0
+            }
+        );
+        assert_eq!(stat(deleted).deletions, 1);
+    }
+
+    #[test]
+    fn weighs_lfs_pointers_at_their_object_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let pointer = "version https://git-lfs.github.com/spec/v1\n\
+                       oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\n\
+                       size 12345678\n";
+        let oid = commit_file(&repo, "video.mp4", Some(pointer));
+        let files = changed_files(&repo, oid).unwrap();
+        assert_eq!(files[0].size, pointer.len() as u64);
+        assert_eq!(files[0].lfs.as_ref().unwrap().size, 12_345_678);
+
+        let weight = commit_weight(&repo, oid).unwrap();
+        assert_eq!(weight.lfs_files, 1);
+        assert_eq!(weight.largest, 12_345_678);
+
+        let plain = commit_file(&repo, "a.txt", Some("one\n"));
+        assert_eq!(
+            commit_weight(&repo, plain).unwrap(),
+            CommitWeight {
+                lfs_files: 0,
+                largest: 4
+            }
+        );
+
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        commit_file(&repo, "src/b.txt", Some("a bit longer\n"));
+        assert_eq!(
+            largest_files(&repo, 2).unwrap(),
+            vec![
+                ("video.mp4".to_string(), 12_345_678),
+                ("src/b.txt".to_string(), 13)
+            ]
+        );
+    }
+}
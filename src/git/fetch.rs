@@ -0,0 +1,121 @@
+use crate::error::Result;
+use git2::{Cred, CredentialType, FetchOptions, RemoteCallbacks, Repository};
+
+// So a rejected key isn't offered forever
+const MAX_AUTH_ATTEMPTS: usize = 3;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FetchProgress {
+    pub remote: String,
+    pub received: usize,
+    // 0 until the remote has said
+    pub total: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FetchOutcome {
+    pub fetched: Vec<String>,
+    pub failed: Vec<(String, String)>,
+}
+
+// One remote failing doesn't stop the rest
+pub fn fetch_all(
+    repo: &Repository,
+    progress: &mut dyn FnMut(FetchProgress),
+) -> Result<FetchOutcome> {
+    let mut outcome = FetchOutcome::default();
+    for name in repo.remotes()?.iter().flatten() {
+        match fetch_remote(repo, name, progress) {
+            Ok(()) => outcome.fetched.push(name.to_string()),
+            Err(e) => {
+                tracing::warn!(remote = name, "fetch failed: {e}");
+                outcome
+                    .failed
+                    .push((name.to_string(), e.message().to_string()));
+            }
+        }
+    }
+    Ok(outcome)
+}
+
+fn fetch_remote(
+    repo: &Repository,
+    name: &str,
+    progress: &mut dyn FnMut(FetchProgress),
+) -> std::result::Result<(), git2::Error> {
+    let mut remote = repo.find_remote(name)?;
+    let config = repo.config()?;
+    let mut attempts = 0;
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(|url, username, allowed| {
+        attempts += 1;
+        if attempts > MAX_AUTH_ATTEMPTS {
+            return Err(git2::Error::from_str("authentication failed"));
+        }
+        if allowed.contains(CredentialType::SSH_KEY) {
+            if let Some(user) = username {
+                return Cred::ssh_key_from_agent(user);
+            }
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            return Cred::credential_helper(&config, url, username);
+        }
+        Cred::default()
+    });
+    let mut reported = None;
+    callbacks.transfer_progress(|stats| {
+        let (received, total) = (stats.received_objects(), stats.total_objects());
+        let percent = received * 100 / total.max(1);
+        if reported != Some(percent) {
+            reported = Some(percent);
+            progress(FetchProgress {
+                remote: name.to_string(),
+                received,
+                total,
+            });
+        }
+        true
+    });
+    let mut options = FetchOptions::new();
+    options.remote_callbacks(callbacks);
+    remote.fetch(&[] as &[&str], Some(&mut options), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(repo: &Repository, msg: &str) -> git2::Oid {
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, msg, &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn fetches_every_remote_and_reports_the_ones_that_fail() {
+        let upstream_dir = tempfile::tempdir().unwrap();
+        let upstream = Repository::init(upstream_dir.path()).unwrap();
+        let tip = commit(&upstream, "upstream work");
+        let branch = upstream.head().unwrap().shorthand().unwrap().to_string();
+
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        repo.remote("origin", upstream_dir.path().to_str().unwrap())
+            .unwrap();
+        let gone = dir.path().join("nowhere");
+        repo.remote("mirror", gone.to_str().unwrap()).unwrap();
+
+        let outcome = fetch_all(&repo, &mut |_| {}).unwrap();
+        assert_eq!(outcome.fetched, ["origin"]);
+        assert_eq!(outcome.failed.len(), 1);
+        assert_eq!(outcome.failed[0].0, "mirror");
+        let fetched = repo
+            .find_reference(&format!("refs/remotes/origin/{branch}"))
+            .unwrap();
+        assert_eq!(fetched.target(), Some(tip));
+    }
+}
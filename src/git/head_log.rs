@@ -0,0 +1,114 @@
+use crate::error::Result;
+use crate::git::types::Oid;
+use chrono::{DateTime, TimeZone, Utc};
+use git2::Repository;
+
+// A watched move this close to a reflog entry for the same commit is that entry
+const SAME_MOVE_SECS: i64 = 120;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeadMove {
+    pub oid: Oid,
+    pub time: DateTime<Utc>,
+    pub message: String,
+}
+
+pub fn head_moves(repo: &Repository, since: DateTime<Utc>) -> Result<Vec<HeadMove>> {
+    let reflog = repo.reflog("HEAD")?;
+    let mut out = Vec::new();
+    for entry in reflog.iter() {
+        let when = entry.committer().when();
+        let Some(time) = Utc.timestamp_opt(when.seconds(), 0).single() else {
+            continue;
+        };
+        // Newest first, so the rest are older still
+        if time < since {
+            break;
+        }
+        out.push(HeadMove {
+            oid: Oid::from_git2(entry.id_new()),
+            time,
+            message: entry.message().unwrap_or_default().to_string(),
+        });
+    }
+    Ok(out)
+}
+
+// Watched moves the reflog lacks were made without one
+pub fn merge_watched(
+    mut moves: Vec<HeadMove>,
+    watched: &[HeadMove],
+    since: DateTime<Utc>,
+) -> Vec<HeadMove> {
+    let unlogged: Vec<HeadMove> = watched
+        .iter()
+        .filter(|w| w.time >= since)
+        .filter(|w| {
+            !moves
+                .iter()
+                .any(|m| m.oid == w.oid && (w.time - m.time).num_seconds().abs() <= SAME_MOVE_SECS)
+        })
+        .cloned()
+        .collect();
+    moves.extend(unlogged);
+    // Stable, so the reflog's order holds within a second
+    moves.sort_by_key(|m| std::cmp::Reverse(m.time));
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn commit_file(repo: &Repository, path: &str, content: &str) -> git2::Oid {
+        std::fs::write(repo.workdir().unwrap().join(path), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, content, &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn reads_todays_reflog_and_keeps_unlogged_watched_moves() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let first = commit_file(&repo, "a.txt", "one\n");
+        let second = commit_file(&repo, "a.txt", "two\n");
+        repo.set_head_detached(first).unwrap();
+
+        let hour_ago = Utc::now() - chrono::Duration::hours(1);
+        let moves = head_moves(&repo, hour_ago).unwrap();
+        let oids: Vec<_> = moves.iter().map(|m| m.oid).collect();
+        assert_eq!(
+            oids,
+            vec![
+                Oid::from_git2(first),
+                Oid::from_git2(second),
+                Oid::from_git2(first)
+            ]
+        );
+        assert!(moves[1].message.starts_with("commit"));
+        assert!(head_moves(&repo, Utc::now() + chrono::Duration::hours(1))
+            .unwrap()
+            .is_empty());
+
+        let seen = |oid: git2::Oid, secs_ago: i64| HeadMove {
+            oid: Oid::from_git2(oid),
+            time: Utc::now() - chrono::Duration::seconds(secs_ago),
+            message: String::new(),
+        };
+        let watched = [seen(first, 0), seen(second, 1800), seen(second, 7200 + 60)];
+        let merged = merge_watched(moves, &watched, hour_ago);
+        // The first is the reflog's latest entry seen again, the last is before `since`
+        assert_eq!(merged.len(), 4);
+        assert_eq!(merged[0].oid, Oid::from_git2(first));
+        assert_eq!(merged[3].oid, Oid::from_git2(second));
+        assert!(merged[3].message.is_empty());
+    }
+}
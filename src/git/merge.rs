@@ -0,0 +1,209 @@
+use crate::error::Result;
+use crate::git::types::Oid;
+use git2::{Index, Repository};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MergeOutcome {
+    UpToDate,
+    FastForward,
+    Clean,
+    Conflicts(Vec<String>),
+}
+
+// Worked out in memory, without touching the index or working tree
+#[derive(Clone, Debug)]
+pub struct ConflictPreview {
+    pub head: String,
+    pub merge: MergeOutcome,
+    pub cherry_pick: MergeOutcome,
+}
+
+pub fn preview_onto_head(repo: &Repository, oid: Oid) -> Result<ConflictPreview> {
+    let head_ref = repo.head()?;
+    let head = head_ref.shorthand().unwrap_or("HEAD").to_string();
+    let ours = head_ref.peel_to_commit()?;
+    let theirs = repo.find_commit(oid.to_git2())?;
+
+    let base = repo.merge_base(ours.id(), theirs.id()).ok();
+    let merge = if base == Some(theirs.id()) {
+        MergeOutcome::UpToDate
+    } else if base == Some(ours.id()) {
+        MergeOutcome::FastForward
+    } else {
+        outcome(&repo.merge_commits(&ours, &theirs, None)?)?
+    };
+
+    let cherry_pick = if base == Some(theirs.id()) {
+        MergeOutcome::UpToDate
+    } else {
+        // Merge commits are picked against their first parent, as `-m 1`
+        let mainline = if theirs.parent_count() > 1 { 1 } else { 0 };
+        outcome(&repo.cherrypick_commit(&theirs, &ours, mainline, None)?)?
+    };
+
+    Ok(ConflictPreview {
+        head,
+        merge,
+        cherry_pick,
+    })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CherryPickOutcome {
+    Picked(Oid),
+    UpToDate,
+    // Paths that conflicted; nothing was changed
+    Conflicts(Vec<String>),
+}
+
+// Worked out in memory first, so a conflict leaves HEAD, index and worktree alone
+pub fn cherry_pick(repo: &Repository, oid: Oid) -> Result<CherryPickOutcome> {
+    let head_ref = repo.head()?;
+    let ours = head_ref.peel_to_commit()?;
+    let theirs = repo.find_commit(oid.to_git2())?;
+    if repo.merge_base(ours.id(), theirs.id()).ok() == Some(theirs.id()) {
+        return Ok(CherryPickOutcome::UpToDate);
+    }
+
+    let mainline = if theirs.parent_count() > 1 { 1 } else { 0 };
+    let mut index = repo.cherrypick_commit(&theirs, &ours, mainline, None)?;
+    let conflicts = conflicted_paths(&index)?;
+    if !conflicts.is_empty() {
+        return Ok(CherryPickOutcome::Conflicts(conflicts));
+    }
+    let tree = repo.find_tree(index.write_tree_to(repo)?)?;
+    repo.checkout_tree(
+        tree.as_object(),
+        Some(git2::build::CheckoutBuilder::new().safe()),
+    )?;
+
+    let author = theirs.author();
+    // Like git, which needs user.name; without one the author commits it
+    let committer = repo.signature().unwrap_or_else(|_| author.to_owned());
+    let message = theirs.message_raw().unwrap_or_default();
+    let picked = repo.commit(Some("HEAD"), &author, &committer, message, &tree, &[&ours])?;
+    Ok(CherryPickOutcome::Picked(Oid::from_git2(picked)))
+}
+
+fn outcome(index: &Index) -> Result<MergeOutcome> {
+    let conflicts = conflicted_paths(index)?;
+    Ok(if conflicts.is_empty() {
+        MergeOutcome::Clean
+    } else {
+        MergeOutcome::Conflicts(conflicts)
+    })
+}
+
+pub fn conflicted_paths(index: &Index) -> Result<Vec<String>> {
+    if !index.has_conflicts() {
+        return Ok(Vec::new());
+    }
+    let mut paths = Vec::new();
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        let entry = conflict.our.or(conflict.their).or(conflict.ancestor);
+        if let Some(entry) = entry {
+            paths.push(String::from_utf8_lossy(&entry.path).to_string());
+        }
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn commit_file(repo: &Repository, path: &str, content: &str) -> Oid {
+        std::fs::write(repo.workdir().unwrap().join(path), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        let oid = repo
+            .commit(Some("HEAD"), &sig, &sig, "commit", &tree, &parents)
+            .unwrap();
+        Oid::from_git2(oid)
+    }
+
+    fn checkout(repo: &Repository, oid: Oid) {
+        let commit = repo.find_commit(oid.to_git2()).unwrap();
+        repo.checkout_tree(
+            commit.as_object(),
+            Some(git2::build::CheckoutBuilder::new().force()),
+        )
+        .unwrap();
+        repo.set_head_detached(commit.id()).unwrap();
+    }
+
+    #[test]
+    fn previews_conflicts_without_touching_the_worktree() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit_file(&repo, "a.txt", "base\n");
+        let base = commit_file(&repo, "b.txt", "base\n");
+        let theirs = commit_file(&repo, "a.txt", "theirs\n");
+        let other = commit_file(&repo, "b.txt", "other\n");
+
+        // Behind HEAD: nothing to do
+        let preview = preview_onto_head(&repo, theirs).unwrap();
+        assert_eq!(preview.merge, MergeOutcome::UpToDate);
+
+        checkout(&repo, base);
+        let preview = preview_onto_head(&repo, other).unwrap();
+        assert_eq!(preview.head, "HEAD");
+        assert_eq!(preview.merge, MergeOutcome::FastForward);
+        assert_eq!(preview.cherry_pick, MergeOutcome::Clean);
+
+        commit_file(&repo, "a.txt", "ours\n");
+        let preview = preview_onto_head(&repo, other).unwrap();
+        assert_eq!(
+            preview.merge,
+            MergeOutcome::Conflicts(vec!["a.txt".to_string()])
+        );
+        // Only b.txt changes in `other` itself
+        assert_eq!(preview.cherry_pick, MergeOutcome::Clean);
+        assert!(!repo.index().unwrap().has_conflicts());
+        let content = std::fs::read_to_string(dir.path().join("a.txt")).unwrap();
+        assert_eq!(content, "ours\n");
+    }
+
+    #[test]
+    fn cherry_picks_onto_head_unless_it_would_conflict() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit_file(&repo, "a.txt", "base\n");
+        let base = commit_file(&repo, "b.txt", "base\n");
+        let clean = commit_file(&repo, "b.txt", "picked\n");
+        let clashing = commit_file(&repo, "a.txt", "theirs\n");
+        assert_eq!(
+            cherry_pick(&repo, clean).unwrap(),
+            CherryPickOutcome::UpToDate
+        );
+
+        checkout(&repo, base);
+        let ours = commit_file(&repo, "a.txt", "ours\n");
+        assert_eq!(
+            cherry_pick(&repo, clashing).unwrap(),
+            CherryPickOutcome::Conflicts(vec!["a.txt".to_string()])
+        );
+        let head = || Oid::from_git2(repo.head().unwrap().target().unwrap());
+        assert_eq!(head(), ours);
+        assert!(!repo.index().unwrap().has_conflicts());
+
+        let CherryPickOutcome::Picked(picked) = cherry_pick(&repo, clean).unwrap() else {
+            panic!("expected a clean pick");
+        };
+        assert_eq!(head(), picked);
+        let commit = repo.find_commit(picked.to_git2()).unwrap();
+        assert_eq!(commit.parent_id(0).unwrap(), ours.to_git2());
+        assert_eq!(commit.message(), Some("commit"));
+        let content = std::fs::read_to_string(dir.path().join("b.txt")).unwrap();
+        assert_eq!(content, "picked\n");
+        let content = std::fs::read_to_string(dir.path().join("a.txt")).unwrap();
+        assert_eq!(content, "ours\n");
+    }
+}
@@ -1,2 +1,15 @@
+pub mod blame;
+pub mod diff;
+pub mod fetch;
+pub mod head_log;
+pub mod merge;
+pub mod patch;
+pub mod replace;
 pub mod repo;
+pub mod shallow;
+pub mod signature;
+pub mod stash;
+pub mod submodule;
 pub mod types;
+pub mod undo;
+pub mod worker;
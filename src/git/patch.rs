@@ -0,0 +1,103 @@
+use crate::error::Result;
+use crate::git::types::Oid;
+use git2::{Email, EmailCreateOptions, Repository};
+use std::path::{Path, PathBuf};
+
+const MAX_SLUG_LEN: usize = 52;
+
+pub fn patch_file_name(number: usize, subject: &str) -> String {
+    let mut slug = String::new();
+    for ch in subject.chars() {
+        if ch.is_ascii_alphanumeric() || ch == '.' || ch == '_' {
+            slug.push(ch);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        if slug.len() >= MAX_SLUG_LEN {
+            break;
+        }
+    }
+    let slug = slug.trim_end_matches(['-', '.']);
+    format!("{number:04}-{slug}.patch")
+}
+
+pub fn export_patches(repo: &Repository, oids: &[Oid], dir: &Path) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir)?;
+    let total = oids.len();
+    let mut written = Vec::with_capacity(total);
+    for (i, oid) in oids.iter().enumerate() {
+        let commit = repo.find_commit(oid.to_git2())?;
+        let tree = commit.tree()?;
+        let parent_tree = match commit.parents().next() {
+            Some(parent) => Some(parent.tree()?),
+            None => None,
+        };
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+        let summary = commit.summary().unwrap_or_default().to_string();
+        let body = commit.body().unwrap_or_default().to_string();
+        let email = Email::from_diff(
+            &diff,
+            i + 1,
+            total,
+            &commit.id(),
+            summary.as_str(),
+            body.as_str(),
+            &commit.author(),
+            &mut EmailCreateOptions::new(),
+        )?;
+        let path = dir.join(patch_file_name(i + 1, &summary));
+        std::fs::write(&path, email.as_slice())?;
+        written.push(path);
+    }
+    tracing::info!(count = written.len(), dir = %dir.display(), "patches exported");
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_names_follow_format_patch() {
+        assert_eq!(
+            patch_file_name(1, "Fix: handle [weird] input!"),
+            "0001-Fix-handle-weird-input.patch"
+        );
+        assert_eq!(
+            patch_file_name(12, "v1.2 release"),
+            "0012-v1.2-release.patch"
+        );
+        assert_eq!(patch_file_name(3, "***"), "0003-.patch");
+        let long = patch_file_name(1, &"word ".repeat(30));
+        assert!(long.len() <= "0001-".len() + MAX_SLUG_LEN + ".patch".len());
+    }
+
+    #[test]
+    fn exports_numbered_patches() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path().join("repo")).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let mut oids = Vec::new();
+        for (i, msg) in ["first commit", "second commit"].iter().enumerate() {
+            let workdir = repo.workdir().unwrap();
+            std::fs::write(workdir.join("f.txt"), format!("{i}\n")).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("f.txt")).unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<_> = parent.iter().collect();
+            let id = repo
+                .commit(Some("HEAD"), &sig, &sig, msg, &tree, &parents)
+                .unwrap();
+            oids.push(Oid::from_git2(id));
+        }
+
+        let out = dir.path().join("out");
+        let files = export_patches(&repo, &oids, &out).unwrap();
+        assert_eq!(files.len(), 2);
+        assert!(files[1].ends_with("0002-second-commit.patch"));
+        let text = std::fs::read_to_string(&files[1]).unwrap();
+        assert!(text.contains("Subject: [PATCH 2/2] second commit"));
+        assert!(text.contains("+1"));
+    }
+}
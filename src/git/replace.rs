@@ -0,0 +1,97 @@
+use git2::Repository;
+use std::collections::HashMap;
+
+// Empty when core.useReplaceRefs is off or GIT_NO_REPLACE_OBJECTS is set, as in git
+pub fn replacements(repo: &Repository) -> HashMap<git2::Oid, git2::Oid> {
+    let disabled = std::env::var_os("GIT_NO_REPLACE_OBJECTS").is_some()
+        || repo
+            .config()
+            .and_then(|c| c.get_bool("core.useReplaceRefs"))
+            .is_ok_and(|enabled| !enabled);
+    if disabled {
+        return HashMap::new();
+    }
+    let Ok(refs) = repo.references_glob("refs/replace/*") else {
+        return HashMap::new();
+    };
+    refs.flatten()
+        .filter_map(|r| {
+            let original = git2::Oid::from_str(r.name()?.strip_prefix("refs/replace/")?).ok()?;
+            let replacement = r.target()?;
+            // Only commits change the topology; replaced blobs and trees don't
+            repo.find_commit(original).ok()?;
+            repo.find_commit(replacement).ok()?;
+            Some((original, replacement))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::git::repo::read_repo;
+    use crate::git::types::Oid;
+    use git2::Repository;
+
+    fn commit(repo: &Repository, msg: &str, parents: &[git2::Oid]) -> git2::Oid {
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let parents: Vec<_> = parents
+            .iter()
+            .map(|p| repo.find_commit(*p).unwrap())
+            .collect();
+        let parents: Vec<_> = parents.iter().collect();
+        repo.commit(None, &sig, &sig, msg, &tree, &parents).unwrap()
+    }
+
+    #[test]
+    fn replaced_commits_take_their_replacements_parents() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        // Old history, and a new root that was cut from it
+        let old_root = commit(&repo, "old root", &[]);
+        let old_tip = commit(&repo, "old tip", &[old_root]);
+        let new_root = commit(&repo, "new root", &[]);
+        let new_tip = commit(&repo, "new tip", &[new_root]);
+        repo.branch("main", &repo.find_commit(new_tip).unwrap(), false)
+            .unwrap();
+        let oids = |repo: &Repository| -> Vec<Oid> {
+            read_repo(repo, 0)
+                .unwrap()
+                .commits
+                .iter()
+                .map(|c| c.oid)
+                .collect()
+        };
+        assert_eq!(oids(&repo).len(), 2);
+
+        // git replace --graft <new root> <old tip>
+        let grafted = commit(&repo, "new root", &[old_tip]);
+        let name = format!("refs/replace/{new_root}");
+        repo.reference(&name, grafted, false, "graft").unwrap();
+        let data = read_repo(&repo, 0).unwrap();
+        let root = data
+            .commits
+            .iter()
+            .find(|c| c.oid == Oid::from_git2(new_root))
+            .unwrap();
+        assert_eq!(root.parents, vec![Oid::from_git2(old_tip)]);
+        assert_eq!(data.commits.len(), 4);
+        assert!(!data
+            .commits
+            .iter()
+            .any(|c| c.oid == Oid::from_git2(grafted)));
+
+        // git replace --graft <new tip>: cuts new root off
+        let cut = commit(&repo, "new tip", &[]);
+        let name = format!("refs/replace/{new_tip}");
+        repo.reference(&name, cut, false, "graft").unwrap();
+        assert_eq!(oids(&repo), vec![Oid::from_git2(new_tip)]);
+
+        repo.config()
+            .unwrap()
+            .set_bool("core.useReplaceRefs", false)
+            .unwrap();
+        assert_eq!(oids(&repo).len(), 2);
+    }
+}
@@ -1,24 +1,59 @@
 use crate::error::{ArachneError, Result};
+use crate::git::replace;
+use crate::git::shallow;
+use crate::git::signature::has_signature_block;
+use crate::git::stash::{self, StashEntry};
+use crate::git::submodule::{self, SubmoduleInfo};
 use crate::git::types::*;
 use chrono::TimeZone;
 use git2::{BranchType, Repository, Sort};
-use std::collections::HashSet;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
 
 pub fn open_repo(path: &Path) -> Result<Repository> {
+    tracing::debug!(path = %path.display(), "opening repo");
     Repository::discover(path)
         .map_err(|e| ArachneError::NotARepo(format!("{}: {e}", path.display())))
 }
 
+pub const PROGRESS_CHUNK: usize = 10_000;
+
+pub type Progress<'a> = &'a mut dyn FnMut(usize);
+
 pub fn read_repo(repo: &Repository, max_commits: usize) -> Result<RepoData> {
+    read_repo_with_progress(repo, max_commits, &mut |_| {})
+}
+
+#[tracing::instrument(skip(repo, progress), fields(path = %repo.path().display()))]
+pub fn read_repo_with_progress(
+    repo: &Repository,
+    max_commits: usize,
+    progress: Progress,
+) -> Result<RepoData> {
     let mut data = RepoData::default();
 
     data.branches = list_branches(repo)?;
     data.tags = list_tags(repo)?;
     data.head = resolve_head(repo);
     data.branch_tips = data.branches.iter().map(|b| b.tip).collect();
-    data.commits = topo_walk(repo, max_commits)?;
+    data.shallow = shallow::graft_points(repo);
+    data.stashes = read_stashes(repo);
+    data.submodules = read_submodules(repo);
+    (data.commits, data.replaced) = topo_walk(repo, max_commits, progress)?;
+    if !data.replaced.is_empty() {
+        let tips = data.known_tips();
+        retain_reachable(&mut data.commits, &tips);
+    }
 
+    tracing::debug!(
+        commits = data.commits.len(),
+        branches = data.branches.len(),
+        tags = data.tags.len(),
+        "repo read"
+    );
     Ok(data)
 }
 
@@ -42,11 +77,16 @@ fn list_branches(repo: &Repository) -> Result<Vec<BranchInfo>> {
             .map(Oid::from_git2)
             .unwrap_or(Oid::zero());
         let is_head = head_oid.as_ref() == Some(&tip) && branch.is_head();
+        let upstream = branch
+            .get()
+            .name()
+            .and_then(|refname| read_upstream(repo, refname));
         out.push(BranchInfo {
             name,
             tip,
             is_head,
             source: CommitSource::Local,
+            upstream,
         });
     }
 
@@ -67,27 +107,89 @@ fn list_branches(repo: &Repository) -> Result<Vec<BranchInfo>> {
             tip,
             is_head: false,
             source: CommitSource::Remote(remote_name),
+            upstream: None,
         });
     }
 
     Ok(out)
 }
 
+// Fails for the checked-out branch
+pub fn delete_local_branch(repo: &Repository, name: &str) -> Result<Oid> {
+    let mut branch = repo.find_branch(name, BranchType::Local)?;
+    let tip = branch
+        .get()
+        .target()
+        .map(Oid::from_git2)
+        .unwrap_or(Oid::zero());
+    branch.delete()?;
+    Ok(tip)
+}
+
+// The ref's own target, so an annotated tag keeps its tag object
+pub fn delete_tag(repo: &Repository, name: &str) -> Result<Oid> {
+    let mut reference = repo.find_reference(&format!("refs/tags/{name}"))?;
+    let target = reference
+        .target()
+        .map(Oid::from_git2)
+        .unwrap_or(Oid::zero());
+    reference.delete()?;
+    Ok(target)
+}
+
+// Like git branch, fails rather than move an existing branch
+pub fn create_branch(repo: &Repository, name: &str, tip: Oid) -> Result<()> {
+    let commit = repo.find_commit(tip.to_git2())?;
+    repo.branch(name, &commit, false)?;
+    Ok(())
+}
+
+// Like git checkout, fails rather than overwrite uncommitted changes
+pub fn checkout_ref(repo: &Repository, refname: &str) -> Result<()> {
+    let commit = repo.find_reference(refname)?.peel_to_commit()?;
+    repo.checkout_tree(
+        commit.as_object(),
+        Some(git2::build::CheckoutBuilder::new().safe()),
+    )?;
+    if refname.starts_with("refs/heads/") {
+        repo.set_head(refname)?;
+    } else {
+        repo.set_head_detached(commit.id())?;
+    }
+    Ok(())
+}
+
+// Resolved from config alone, so a pruned tracking ref still has one; it's then gone
+fn read_upstream(repo: &Repository, refname: &str) -> Option<Upstream> {
+    let buf = repo.branch_upstream_name(refname).ok()?;
+    let full = buf.as_str()?;
+    let name = full
+        .strip_prefix("refs/remotes/")
+        .or_else(|| full.strip_prefix("refs/heads/"))
+        .unwrap_or(full)
+        .to_string();
+    let gone = repo.find_reference(full).is_err();
+    Some(Upstream { name, gone })
+}
+
 fn list_tags(repo: &Repository) -> Result<Vec<TagInfo>> {
     let mut out = Vec::new();
     let mut callback_err: Option<git2::Error> = None;
     repo.tag_foreach(|oid, name_bytes| {
         let raw = String::from_utf8_lossy(name_bytes);
         let name = raw.strip_prefix("refs/tags/").unwrap_or(&raw).to_string();
-        let target_oid = match repo.find_tag(oid) {
+        let (target_oid, signed) = match repo.find_tag(oid) {
             Ok(tag) => match tag.target() {
-                Ok(obj) => obj.id(),
+                Ok(obj) => (
+                    obj.id(),
+                    tag.message_bytes().is_some_and(has_signature_block),
+                ),
                 Err(e) => {
                     callback_err = Some(e);
                     return false;
                 }
             },
-            Err(e) if e.code() == git2::ErrorCode::NotFound => oid,
+            Err(e) if e.code() == git2::ErrorCode::NotFound => (oid, false),
             Err(e) => {
                 callback_err = Some(e);
                 return false;
@@ -105,17 +207,136 @@ fn list_tags(repo: &Repository) -> Result<Vec<TagInfo>> {
             name,
             target: Oid::from_git2(target_oid),
             time,
+            signed,
         });
         true
     })?;
     if let Some(e) = callback_err {
         return Err(e.into());
     }
-    out.sort_by(|a, b| b.time.cmp(&a.time));
+    out.sort_by_key(|t| std::cmp::Reverse(t.time));
     Ok(out)
 }
 
-fn topo_walk(repo: &Repository, max_commits: usize) -> Result<Vec<CommitInfo>> {
+pub fn refresh_repo(
+    repo: &Repository,
+    known_tips: Option<&HashSet<Oid>>,
+    max_commits: usize,
+    progress: Progress,
+) -> Result<RepoUpdate> {
+    if let Some(known) = known_tips {
+        if let Some(delta) = read_repo_since(repo, known, max_commits, progress)? {
+            return Ok(RepoUpdate::Delta(delta));
+        }
+    }
+    read_repo_with_progress(repo, max_commits, progress).map(RepoUpdate::Full)
+}
+
+// None when a known tip is gone or max_commits is hit; that needs a full read
+#[tracing::instrument(skip_all, fields(path = %repo.path().display(), known = known_tips.len()))]
+pub fn read_repo_since(
+    repo: &Repository,
+    known_tips: &HashSet<Oid>,
+    max_commits: usize,
+    progress: Progress,
+) -> Result<Option<RepoDelta>> {
+    let branches = list_branches(repo)?;
+    let tags = list_tags(repo)?;
+    let branch_tips: HashSet<Oid> = branches.iter().map(|b| b.tip).collect();
+
+    if !replace::replacements(repo).is_empty() {
+        // Replacements can rewire commits that are already loaded
+        return Ok(None);
+    }
+    let no_replacements = HashMap::new();
+    let mut revwalk = new_revwalk(repo, &no_replacements)?;
+    for tip in known_tips {
+        // Tag targets may not be commits; those can't be hidden and don't need to be
+        let _ = revwalk.hide(tip.to_git2());
+    }
+    let new_commits = collect_commits(repo, revwalk, max_commits, &no_replacements, progress)?;
+    if max_commits > 0 && new_commits.len() >= max_commits {
+        return Ok(None);
+    }
+
+    let current: HashSet<Oid> = branch_tips
+        .iter()
+        .copied()
+        .chain(tags.iter().map(|t| t.target))
+        .collect();
+    let new_parents: HashSet<Oid> = new_commits
+        .iter()
+        .flat_map(|c| c.parents.iter().copied())
+        .collect();
+    if known_tips
+        .iter()
+        .any(|t| !current.contains(t) && !new_parents.contains(t))
+    {
+        tracing::debug!("known tip vanished, falling back to full read");
+        return Ok(None);
+    }
+
+    tracing::debug!(new = new_commits.len(), "incremental read");
+    Ok(Some(RepoDelta {
+        new_commits,
+        branches,
+        tags,
+        head: resolve_head(repo),
+        branch_tips,
+        shallow: shallow::graft_points(repo),
+        stashes: read_stashes(repo),
+        submodules: read_submodules(repo),
+    }))
+}
+
+// A stash that can't be read doesn't fail the graph
+fn read_stashes(repo: &Repository) -> Vec<StashEntry> {
+    stash::list_stashes(repo).unwrap_or_else(|e| {
+        tracing::warn!("stash list unreadable: {e}");
+        Vec::new()
+    })
+}
+
+// Like stashes, unreadable ones are left out
+fn read_submodules(repo: &Repository) -> Vec<SubmoduleInfo> {
+    submodule::list_submodules(repo).unwrap_or_else(|e| {
+        tracing::warn!("submodules unreadable: {e}");
+        Vec::new()
+    })
+}
+
+fn topo_walk(
+    repo: &Repository,
+    max_commits: usize,
+    progress: Progress,
+) -> Result<(Vec<Arc<CommitInfo>>, HashSet<Oid>)> {
+    let replaced = replace::replacements(repo);
+    let revwalk = new_revwalk(repo, &replaced)?;
+    let commits = collect_commits(repo, revwalk, max_commits, &replaced, progress)?;
+    Ok((commits, replaced.into_keys().map(Oid::from_git2).collect()))
+}
+
+// Still reached through their original children, but no longer ancestors of a tip
+fn retain_reachable(commits: &mut Vec<Arc<CommitInfo>>, tips: &HashSet<Oid>) {
+    let by_oid: HashMap<Oid, &Arc<CommitInfo>> = commits.iter().map(|c| (c.oid, c)).collect();
+    let mut reachable = HashSet::new();
+    let mut stack: Vec<Oid> = tips.iter().copied().collect();
+    while let Some(oid) = stack.pop() {
+        let Some(commit) = by_oid.get(&oid) else {
+            continue;
+        };
+        if reachable.insert(oid) {
+            stack.extend(commit.parents.iter().copied());
+        }
+    }
+    commits.retain(|c| reachable.contains(&c.oid));
+}
+
+// Replacement parents are seeded, since the walk wouldn't reach them through the originals
+fn new_revwalk<'r>(
+    repo: &'r Repository,
+    replaced: &HashMap<git2::Oid, git2::Oid>,
+) -> Result<git2::Revwalk<'r>> {
     let mut revwalk = repo.revwalk()?;
     revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
 
@@ -141,32 +362,62 @@ fn topo_walk(repo: &Repository, max_commits: usize) -> Result<Vec<CommitInfo>> {
         true
     })?;
 
+    for replacement in replaced.values() {
+        for parent in repo.find_commit(*replacement)?.parent_ids() {
+            if pushed.insert(parent) {
+                revwalk.push(parent)?;
+            }
+        }
+    }
+
+    Ok(revwalk)
+}
+
+fn collect_commits(
+    repo: &Repository,
+    revwalk: git2::Revwalk<'_>,
+    max_commits: usize,
+    replaced: &HashMap<git2::Oid, git2::Oid>,
+    progress: Progress,
+) -> Result<Vec<Arc<CommitInfo>>> {
     let mut commits = Vec::new();
     for oid_result in revwalk {
         if max_commits > 0 && commits.len() >= max_commits {
             break;
         }
+        if !commits.is_empty() && commits.len() % PROGRESS_CHUNK == 0 {
+            progress(commits.len());
+        }
         let oid = oid_result?;
-        let commit = repo.find_commit(oid)?;
+        // Read through the replacement, keeping the original id, as git log does
+        let commit = repo.find_commit(replaced.get(&oid).copied().unwrap_or(oid))?;
         let time_secs = commit.time().seconds();
         let time = chrono::Utc
             .timestamp_opt(time_secs, 0)
             .single()
             .unwrap_or_default();
 
-        commits.push(CommitInfo {
+        commits.push(Arc::new(CommitInfo {
             oid: Oid::from_git2(oid),
             parents: commit.parent_ids().map(Oid::from_git2).collect(),
             message: commit.summary().unwrap_or("").to_string(),
             author: commit.author().name().unwrap_or("").to_string(),
+            email: commit.author().email().unwrap_or("").to_string(),
             time,
             source: CommitSource::Local,
-        });
+        }));
     }
 
     Ok(commits)
 }
 
+// Canonicalized so different spellings of a repo compare equal
+pub fn enclosing_root(path: &Path) -> Option<PathBuf> {
+    let repo = Repository::discover(path).ok()?;
+    let root = repo.workdir().unwrap_or_else(|| repo.path());
+    root.canonicalize().ok()
+}
+
 pub fn detect_repo_name(repo: &Repository) -> String {
     repo.find_remote("origin")
         .ok()
@@ -199,3 +450,133 @@ fn parse_github_owner_repo(url: &str) -> Option<String> {
     }
     url.rsplit('/').next().map(String::from)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(repo: &Repository, msg: &str) -> git2::Oid {
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, msg, &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn read_since_walks_only_new_commits() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let first = commit(&repo, "first");
+        commit(&repo, "second");
+        let known = read_repo(&repo, 0).unwrap().known_tips();
+
+        let third = commit(&repo, "third");
+        let delta = read_repo_since(&repo, &known, 0, &mut |_| {})
+            .unwrap()
+            .unwrap();
+        assert_eq!(delta.new_commits.len(), 1);
+        assert_eq!(delta.new_commits[0].oid, Oid::from_git2(third));
+
+        // Moving the branch backwards drops history: needs a full read
+        let known = read_repo(&repo, 0).unwrap().known_tips();
+        let target = repo.find_object(first, None).unwrap();
+        repo.reset(&target, git2::ResetType::Soft, None).unwrap();
+        assert!(read_repo_since(&repo, &known, 0, &mut |_| {})
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn checks_out_branches_and_detaches_at_tags() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let first = commit(&repo, "first");
+        let base = repo.head().unwrap().name().unwrap().to_string();
+        repo.branch("fix", &repo.find_commit(first).unwrap(), false)
+            .unwrap();
+        commit(&repo, "second");
+        repo.tag_lightweight("v1", repo.find_object(first, None).as_ref().unwrap(), false)
+            .unwrap();
+
+        checkout_ref(&repo, "refs/heads/fix").unwrap();
+        assert_eq!(repo.head().unwrap().name(), Some("refs/heads/fix"));
+
+        checkout_ref(&repo, "refs/tags/v1").unwrap();
+        assert!(repo.head_detached().unwrap());
+        assert_eq!(repo.head().unwrap().target(), Some(first));
+
+        checkout_ref(&repo, &base).unwrap();
+        assert_eq!(repo.head().unwrap().name(), Some(base.as_str()));
+    }
+
+    #[test]
+    fn creates_branches_but_never_moves_an_existing_one() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let first = Oid::from_git2(commit(&repo, "first"));
+        let second = Oid::from_git2(commit(&repo, "second"));
+
+        create_branch(&repo, "topic", first).unwrap();
+        let tip = |repo: &Repository| {
+            let branch = repo.find_branch("topic", BranchType::Local).unwrap();
+            branch.get().target().map(Oid::from_git2)
+        };
+        assert_eq!(tip(&repo), Some(first));
+
+        assert!(create_branch(&repo, "topic", second).is_err());
+        assert_eq!(tip(&repo), Some(first));
+        assert!(create_branch(&repo, "bad..name", second).is_err());
+    }
+
+    #[test]
+    fn reads_upstream_and_notices_it_gone() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let tip = commit(&repo, "first");
+        repo.remote("origin", "https://example.com/app.git")
+            .unwrap();
+        repo.reference("refs/remotes/origin/main", tip, true, "test")
+            .unwrap();
+        let head = repo.head().unwrap().name().unwrap().to_string();
+        let short = head.trim_start_matches("refs/heads/").to_string();
+        let mut config = repo.config().unwrap();
+        config
+            .set_str(&format!("branch.{short}.remote"), "origin")
+            .unwrap();
+        config
+            .set_str(&format!("branch.{short}.merge"), "refs/heads/main")
+            .unwrap();
+
+        let upstream_of = |repo: &Repository| {
+            let branches = list_branches(repo).unwrap();
+            let local = branches.iter().find(|b| b.name == short).unwrap();
+            local.upstream.clone().unwrap()
+        };
+        let upstream = upstream_of(&repo);
+        assert_eq!(upstream.name, "origin/main");
+        assert!(!upstream.gone);
+
+        repo.find_reference("refs/remotes/origin/main")
+            .unwrap()
+            .delete()
+            .unwrap();
+        assert!(upstream_of(&repo).gone);
+    }
+
+    #[test]
+    fn enclosing_root_walks_up_from_subdirectories() {
+        let dir = tempfile::tempdir().unwrap();
+        Repository::init(dir.path()).unwrap();
+        let nested = dir.path().join("src/ui");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let root = dir.path().canonicalize().unwrap();
+        assert_eq!(enclosing_root(&nested), Some(root.clone()));
+        assert_eq!(enclosing_root(&root.join("src/../src")), Some(root));
+        let outside = tempfile::tempdir().unwrap();
+        assert_eq!(enclosing_root(outside.path()), None);
+    }
+}
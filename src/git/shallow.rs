@@ -0,0 +1,76 @@
+use crate::git::types::Oid;
+use git2::Repository;
+use std::collections::HashSet;
+use std::process::Command;
+
+// Their parents were never fetched, so git reads them as root commits
+pub fn graft_points(repo: &Repository) -> HashSet<Oid> {
+    if !repo.is_shallow() {
+        return HashSet::new();
+    }
+    std::fs::read_to_string(repo.path().join("shallow"))
+        .map(|text| parse_shallow(&text))
+        .unwrap_or_default()
+}
+
+fn parse_shallow(text: &str) -> HashSet<Oid> {
+    text.lines()
+        .filter_map(|line| git2::Oid::from_str(line.trim()).ok())
+        .map(Oid::from_git2)
+        .collect()
+}
+
+pub fn deepen(repo: &Repository, by: u32) -> Result<(), String> {
+    let output = Command::new("git")
+        .arg("--git-dir")
+        .arg(repo.path())
+        .args(["fetch", "--quiet"])
+        .arg(format!("--deepen={by}"))
+        .output()
+        .map_err(|e| format!("git: {e}"))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(stderr
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("git fetch failed")
+        .trim()
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::repo::read_repo;
+
+    fn commit(repo: &Repository, msg: &str) -> git2::Oid {
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, msg, &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn shallow_file_lists_graft_points() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit(&repo, "first");
+        let second = commit(&repo, "second");
+        commit(&repo, "third");
+        assert!(graft_points(&repo).is_empty());
+
+        std::fs::write(repo.path().join("shallow"), format!("{second}\n")).unwrap();
+        let repo = Repository::open(dir.path()).unwrap();
+        let data = read_repo(&repo, 0).unwrap();
+        assert_eq!(data.shallow, [Oid::from_git2(second)].into_iter().collect());
+        // The commit behind the graft point is cut off
+        assert_eq!(data.commits.len(), 2);
+        assert!(data.commits[1].parents.is_empty());
+    }
+}
@@ -0,0 +1,149 @@
+use git2::Repository;
+use std::process::Command;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TagSignature {
+    Pending,
+    Good { signer: String },
+    Bad { signer: Option<String> },
+    Unverifiable { reason: String },
+}
+
+const SIGNATURE_MARKERS: [&[u8]; 3] = [
+    b"-----BEGIN PGP SIGNATURE-----",
+    b"-----BEGIN SSH SIGNATURE-----",
+    b"-----BEGIN SIGNED MESSAGE-----",
+];
+
+pub fn has_signature_block(message: &[u8]) -> bool {
+    SIGNATURE_MARKERS
+        .iter()
+        .any(|marker| message.windows(marker.len()).any(|w| w == *marker))
+}
+
+// Shells out so the repo's gpg.format, gpg.program and allowed signers apply
+pub fn verify_tag(repo: &Repository, name: &str) -> TagSignature {
+    let output = Command::new("git")
+        .arg("--git-dir")
+        .arg(repo.path())
+        .args(["verify-tag", "--raw", "--"])
+        .arg(name)
+        .output();
+    match output {
+        Ok(out) => {
+            let stderr = String::from_utf8_lossy(&out.stderr);
+            parse_verify_output(out.status.success(), &stderr)
+        }
+        Err(e) => TagSignature::Unverifiable {
+            reason: format!("git: {e}"),
+        },
+    }
+}
+
+pub fn parse_verify_output(success: bool, stderr: &str) -> TagSignature {
+    for line in stderr.lines() {
+        let Some(status) = line.strip_prefix("[GNUPG:] ") else {
+            continue;
+        };
+        let mut words = status.splitn(3, ' ');
+        let keyword = words.next().unwrap_or_default();
+        let uid = words.nth(1).map(str::to_string);
+        match keyword {
+            "GOODSIG" if success => {
+                return TagSignature::Good {
+                    signer: uid.unwrap_or_else(|| "unknown signer".to_string()),
+                }
+            }
+            "BADSIG" => return TagSignature::Bad { signer: uid },
+            "EXPKEYSIG" | "REVKEYSIG" => {
+                let state = if keyword == "EXPKEYSIG" {
+                    "expired"
+                } else {
+                    "revoked"
+                };
+                let reason = match uid {
+                    Some(uid) => format!("{state} key of {uid}"),
+                    None => format!("{state} key"),
+                };
+                return TagSignature::Unverifiable { reason };
+            }
+            "NO_PUBKEY" => {
+                return TagSignature::Unverifiable {
+                    reason: "no public key".to_string(),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // ssh-keygen: Good "git" signature for alice@example.com with ED25519 key SHA256:...
+    if success {
+        let signer = stderr
+            .lines()
+            .find_map(|l| l.split_once(" signature for ").map(|(_, rest)| rest))
+            .map(|rest| rest.split(" with ").next().unwrap_or(rest).to_string())
+            .unwrap_or_else(|| "unknown signer".to_string());
+        return TagSignature::Good { signer };
+    }
+    if stderr.contains("incorrect signature") {
+        return TagSignature::Bad { signer: None };
+    }
+    let reason = stderr
+        .lines()
+        .map(|l| l.trim().trim_start_matches("error: "))
+        .find(|l| !l.is_empty())
+        .unwrap_or("verification failed")
+        .to_string();
+    TagSignature::Unverifiable { reason }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_gpg_and_ssh_verification_output() {
+        let good = "[GNUPG:] NEWSIG\n[GNUPG:] GOODSIG 1234ABCD Alice <alice@example.com>\n";
+        assert_eq!(
+            parse_verify_output(true, good),
+            TagSignature::Good {
+                signer: "Alice <alice@example.com>".to_string()
+            }
+        );
+        let bad = "[GNUPG:] BADSIG 1234ABCD Alice <alice@example.com>\n";
+        assert_eq!(
+            parse_verify_output(false, bad),
+            TagSignature::Bad {
+                signer: Some("Alice <alice@example.com>".to_string())
+            }
+        );
+        let missing =
+            "[GNUPG:] ERRSIG 1234ABCD 1 10 00 1700000000 9\n[GNUPG:] NO_PUBKEY 1234ABCD\n";
+        assert_eq!(
+            parse_verify_output(false, missing),
+            TagSignature::Unverifiable {
+                reason: "no public key".to_string()
+            }
+        );
+
+        let ssh = "Good \"git\" signature for alice@example.com with ED25519 key SHA256:abc\n";
+        assert_eq!(
+            parse_verify_output(true, ssh),
+            TagSignature::Good {
+                signer: "alice@example.com".to_string()
+            }
+        );
+        let unconfigured = "error: gpg.ssh.allowedSignersFile needs to be configured\n";
+        assert_eq!(
+            parse_verify_output(false, unconfigured),
+            TagSignature::Unverifiable {
+                reason: "gpg.ssh.allowedSignersFile needs to be configured".to_string()
+            }
+        );
+
+        assert!(has_signature_block(
+            b"v1.0\n-----BEGIN PGP SIGNATURE-----\n\nabc\n-----END PGP SIGNATURE-----\n"
+        ));
+        assert!(!has_signature_block(b"v1.0 release\n"));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::error::Result;
+use crate::git::merge::conflicted_paths;
+use crate::git::types::Oid;
+use git2::Repository;
+
+#[derive(Clone, Debug)]
+pub struct StashEntry {
+    pub index: usize,
+    pub message: String,
+    pub oid: Oid,
+    pub base: Oid,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StashOp {
+    Apply,
+    Pop,
+    Drop,
+}
+
+impl StashOp {
+    pub fn verb(self) -> &'static str {
+        match self {
+            StashOp::Apply => "apply",
+            StashOp::Pop => "pop",
+            StashOp::Drop => "drop",
+        }
+    }
+}
+
+// Stash calls need &mut Repository, but workers only lend a shared one
+fn reopen(repo: &Repository) -> Result<Repository> {
+    Ok(Repository::open(repo.path())?)
+}
+
+pub fn list_stashes(repo: &Repository) -> Result<Vec<StashEntry>> {
+    let mut repo = reopen(repo)?;
+    let mut out = Vec::new();
+    repo.stash_foreach(|index, message, oid| {
+        out.push(StashEntry {
+            index,
+            message: message.to_string(),
+            oid: Oid::from_git2(*oid),
+            base: Oid::zero(),
+        });
+        true
+    })?;
+    for entry in &mut out {
+        let stash = repo.find_commit(entry.oid.to_git2())?;
+        entry.base = Oid::from_git2(stash.parent_id(0)?);
+    }
+    Ok(out)
+}
+
+// A conflicted pop keeps the stash, like git stash pop
+pub fn run_stash_op(repo: &Repository, op: StashOp, index: usize) -> Result<Vec<String>> {
+    let mut repo = reopen(repo)?;
+    if op == StashOp::Drop {
+        repo.stash_drop(index)?;
+        return Ok(Vec::new());
+    }
+    repo.stash_apply(index, None)?;
+    let conflicts = conflicted_paths(&repo.index()?)?;
+    if op == StashOp::Pop && conflicts.is_empty() {
+        repo.stash_drop(index)?;
+    }
+    Ok(conflicts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn commit_file(repo: &Repository, path: &str, content: &str) {
+        let workdir = repo.workdir().unwrap();
+        std::fs::write(workdir.join(path), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, "commit", &tree, &parents)
+            .unwrap();
+    }
+
+    fn stash(repo: &Repository, path: &str, content: &str, message: &str) {
+        std::fs::write(repo.workdir().unwrap().join(path), content).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        reopen(repo)
+            .unwrap()
+            .stash_save(&sig, message, None)
+            .unwrap();
+    }
+
+    #[test]
+    fn pop_applies_and_drops_while_conflicts_keep_the_stash() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit_file(&repo, "a.txt", "base\n");
+        stash(&repo, "a.txt", "stashed\n", "wip one");
+        stash(&repo, "a.txt", "other\n", "wip two");
+
+        let stashes = list_stashes(&repo).unwrap();
+        let messages: Vec<_> = stashes.iter().map(|s| s.message.as_str()).collect();
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].ends_with("wip two"));
+        let head = Oid::from_git2(repo.head().unwrap().target().unwrap());
+        assert!(stashes.iter().all(|s| s.base == head));
+
+        assert!(run_stash_op(&repo, StashOp::Pop, 0).unwrap().is_empty());
+        assert_eq!(list_stashes(&repo).unwrap().len(), 1);
+        let content = std::fs::read_to_string(dir.path().join("a.txt")).unwrap();
+        assert_eq!(content, "other\n");
+
+        // Commit over the popped change, then pop a stash touching the same line
+        commit_file(&repo, "a.txt", "committed\n");
+        let conflicts = run_stash_op(&repo, StashOp::Pop, 0).unwrap();
+        assert_eq!(conflicts, vec!["a.txt".to_string()]);
+        assert_eq!(list_stashes(&repo).unwrap().len(), 1);
+
+        run_stash_op(&repo, StashOp::Drop, 0).unwrap();
+        assert!(list_stashes(&repo).unwrap().is_empty());
+    }
+}
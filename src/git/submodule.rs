@@ -0,0 +1,81 @@
+use crate::error::Result;
+use crate::git::types::Oid;
+use git2::Repository;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubmoduleInfo {
+    pub name: String,
+    // Relative to the superproject's working directory
+    pub path: String,
+    // None when not yet added
+    pub pinned: Option<Oid>,
+    pub workdir: Option<PathBuf>,
+}
+
+pub fn list_submodules(repo: &Repository) -> Result<Vec<SubmoduleInfo>> {
+    let mut out: Vec<SubmoduleInfo> = repo
+        .submodules()?
+        .iter()
+        .map(|sm| {
+            let path = sm.path().to_string_lossy().into_owned();
+            let workdir = repo
+                .workdir()
+                .map(|w| w.join(sm.path()))
+                .filter(|dir| Repository::open(dir).is_ok());
+            SubmoduleInfo {
+                name: sm.name().unwrap_or(&path).to_string(),
+                pinned: sm.head_id().map(Oid::from_git2),
+                path,
+                workdir,
+            }
+        })
+        .collect();
+    out.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn commit_all(repo: &Repository, message: &str) -> git2::Oid {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn lists_submodules_with_the_commit_they_are_pinned_to() {
+        let lib_dir = tempfile::tempdir().unwrap();
+        let lib = Repository::init(lib_dir.path()).unwrap();
+        std::fs::write(lib_dir.path().join("lib.rs"), "").unwrap();
+        let pinned = commit_all(&lib, "lib");
+
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        assert!(list_submodules(&repo).unwrap().is_empty());
+
+        let url = lib_dir.path().to_str().unwrap();
+        let mut sm = repo.submodule(url, Path::new("vendor/lib"), true).unwrap();
+        sm.clone(None).unwrap();
+        sm.add_finalize().unwrap();
+        commit_all(&repo, "add lib");
+
+        let subs = list_submodules(&repo).unwrap();
+        assert_eq!(subs.len(), 1);
+        assert_eq!(subs[0].name, "vendor/lib");
+        assert_eq!(subs[0].path, "vendor/lib");
+        assert_eq!(subs[0].pinned, Some(Oid::from_git2(pinned)));
+        assert_eq!(subs[0].workdir, Some(dir.path().join("vendor/lib")));
+    }
+}
@@ -1,7 +1,10 @@
+use crate::git::stash::StashEntry;
+use crate::git::submodule::SubmoduleInfo;
 use chrono::{DateTime, Utc};
 use std::collections::HashSet;
 use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Oid([u8; 20]);
@@ -17,9 +20,14 @@ impl Oid {
         Self([0u8; 20])
     }
 
+    #[cfg_attr(not(feature = "github"), allow(dead_code))]
     pub fn from_bytes(bytes: [u8; 20]) -> Self {
         Self(bytes)
     }
+
+    pub fn to_git2(self) -> git2::Oid {
+        git2::Oid::from_bytes(&self.0).unwrap_or_else(|_| git2::Oid::zero())
+    }
 }
 
 impl Hash for Oid {
@@ -40,10 +48,11 @@ impl fmt::Display for Oid {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum CommitSource {
     Local,
     Remote(String),
+    #[cfg_attr(not(feature = "github"), allow(dead_code))]
     Fork(String),
 }
 
@@ -53,16 +62,25 @@ pub struct CommitInfo {
     pub parents: Vec<Oid>,
     pub message: String,
     pub author: String,
+    // Empty when unknown, e.g. for some commits from the GitHub API
+    pub email: String,
     pub time: DateTime<Utc>,
     pub source: CommitSource,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct BranchInfo {
     pub name: String,
     pub tip: Oid,
     pub is_head: bool,
     pub source: CommitSource,
+    pub upstream: Option<Upstream>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Upstream {
+    pub name: String,
+    pub gone: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -70,15 +88,69 @@ pub struct TagInfo {
     pub name: String,
     pub target: Oid,
     pub time: Option<DateTime<Utc>>,
+    pub signed: bool,
 }
 
+// Commits are behind Arc so clones never copy messages or names
 #[derive(Clone, Debug, Default)]
 pub struct RepoData {
-    pub commits: Vec<CommitInfo>,
+    pub commits: Vec<Arc<CommitInfo>>,
+    pub branches: Vec<BranchInfo>,
+    pub tags: Vec<TagInfo>,
+    pub head: Option<Oid>,
+    pub branch_tips: HashSet<Oid>,
+    pub shallow: HashSet<Oid>,
+    pub replaced: HashSet<Oid>,
+    // Their commits aren't part of the graph
+    pub stashes: Vec<StashEntry>,
+    pub submodules: Vec<SubmoduleInfo>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RepoDelta {
+    pub new_commits: Vec<Arc<CommitInfo>>,
     pub branches: Vec<BranchInfo>,
     pub tags: Vec<TagInfo>,
     pub head: Option<Oid>,
     pub branch_tips: HashSet<Oid>,
+    pub shallow: HashSet<Oid>,
+    pub stashes: Vec<StashEntry>,
+    pub submodules: Vec<SubmoduleInfo>,
+}
+
+#[derive(Debug)]
+pub enum RepoUpdate {
+    Full(RepoData),
+    Delta(RepoDelta),
+}
+
+impl RepoData {
+    pub fn known_tips(&self) -> HashSet<Oid> {
+        self.branch_tips
+            .iter()
+            .copied()
+            .chain(self.tags.iter().map(|t| t.target))
+            .collect()
+    }
+
+    // New commits descend from the old ones, so prepending keeps topo order
+    pub fn apply_delta(&mut self, delta: RepoDelta, max_commits: usize) -> bool {
+        let mut commits = delta.new_commits;
+        commits.append(&mut self.commits);
+        let trimmed = max_commits > 0 && commits.len() > max_commits;
+        if trimmed {
+            commits.truncate(max_commits);
+        }
+        self.commits = commits;
+        self.branches = delta.branches;
+        self.tags = delta.tags;
+        self.head = delta.head;
+        self.branch_tips = delta.branch_tips;
+        self.shallow = delta.shallow;
+        self.stashes = delta.stashes;
+        self.submodules = delta.submodules;
+        trimmed
+    }
 }
 
 #[cfg(test)]
@@ -126,4 +198,26 @@ mod tests {
         assert!(a < b);
         assert_eq!(a, a);
     }
+
+    #[test]
+    fn apply_delta_prepends_and_trims() {
+        use crate::test_utils::*;
+        let mut data = make_repo_data(
+            vec![make_commit(2, vec![1], 10), make_commit(1, vec![], 20)],
+            vec![],
+        );
+        let delta = RepoDelta {
+            new_commits: vec![Arc::new(make_commit(3, vec![2], 0))],
+            branch_tips: [make_oid(3)].into_iter().collect(),
+            ..Default::default()
+        };
+        assert!(data.apply_delta(delta.clone(), 2));
+        let oids: Vec<_> = data.commits.iter().map(|c| c.oid).collect();
+        assert_eq!(oids, vec![make_oid(3), make_oid(2)]);
+        assert!(data.branch_tips.contains(&make_oid(3)));
+
+        let mut untrimmed = make_repo_data(vec![make_commit(2, vec![], 10)], vec![]);
+        assert!(!untrimmed.apply_delta(delta, 0));
+        assert_eq!(untrimmed.commits.len(), 2);
+    }
 }
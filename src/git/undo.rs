@@ -0,0 +1,180 @@
+use crate::error::Result;
+use crate::git::repo;
+use crate::git::types::Oid;
+use git2::Repository;
+
+// The commits stay in the object database until gc prunes them
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeletedRef {
+    Branch {
+        name: String,
+        tip: Oid,
+        // branch.<name>.remote and .merge, which deleting the branch drops
+        upstream: Option<(String, String)>,
+    },
+    Tag {
+        name: String,
+        target: Oid,
+    },
+    Stash {
+        message: String,
+        oid: Oid,
+    },
+}
+
+impl DeletedRef {
+    pub fn label(&self) -> String {
+        match self {
+            DeletedRef::Branch { name, .. } => format!("branch {name}"),
+            DeletedRef::Tag { name, .. } => format!("tag {name}"),
+            DeletedRef::Stash { message, .. } => format!("stash \"{message}\""),
+        }
+    }
+
+    pub fn oid(&self) -> Oid {
+        match self {
+            DeletedRef::Branch { tip, .. } => *tip,
+            DeletedRef::Tag { target, .. } => *target,
+            DeletedRef::Stash { oid, .. } => *oid,
+        }
+    }
+}
+
+pub fn delete_branch(repo: &Repository, name: &str) -> Result<DeletedRef> {
+    let config = repo.config()?.snapshot()?;
+    let setting = |key: &str| config.get_string(&format!("branch.{name}.{key}")).ok();
+    let upstream = setting("remote").zip(setting("merge"));
+    let tip = repo::delete_local_branch(repo, name)?;
+    Ok(DeletedRef::Branch {
+        name: name.to_string(),
+        tip,
+        upstream,
+    })
+}
+
+pub fn delete_tag(repo: &Repository, name: &str) -> Result<DeletedRef> {
+    let target = repo::delete_tag(repo, name)?;
+    Ok(DeletedRef::Tag {
+        name: name.to_string(),
+        target,
+    })
+}
+
+// A branch or tag recreated in the meantime is left alone; a stash goes back on top
+pub fn restore(repo: &Repository, deleted: &DeletedRef) -> Result<()> {
+    match deleted {
+        DeletedRef::Branch {
+            name,
+            tip,
+            upstream,
+        } => {
+            let commit = repo.find_commit(tip.to_git2())?;
+            repo.branch(name, &commit, false)?;
+            if let Some((remote, merge)) = upstream {
+                let mut config = repo.config()?;
+                config.set_str(&format!("branch.{name}.remote"), remote)?;
+                config.set_str(&format!("branch.{name}.merge"), merge)?;
+            }
+        }
+        DeletedRef::Tag { name, target } => {
+            repo.reference(
+                &format!("refs/tags/{name}"),
+                target.to_git2(),
+                false,
+                "arachne: restore tag",
+            )?;
+        }
+        DeletedRef::Stash { message, oid } => {
+            repo.find_commit(oid.to_git2())?;
+            // The stash list is the reflog of refs/stash, which dropping the
+            // last entry removes
+            repo.reference_ensure_log("refs/stash")?;
+            repo.reference("refs/stash", oid.to_git2(), true, message)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::stash::{list_stashes, run_stash_op, StashOp};
+    use std::path::Path;
+
+    fn commit_file(repo: &Repository, path: &str, content: &str) -> git2::Oid {
+        std::fs::write(repo.workdir().unwrap().join(path), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, "commit", &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn restores_deleted_tag_with_its_tag_object() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let base = commit_file(&repo, "a.txt", "base\n");
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let object = repo.find_object(base, None).unwrap();
+        let annotated = repo.tag("v1.0", &object, &sig, "release", false).unwrap();
+
+        let tag = delete_tag(&repo, "v1.0").unwrap();
+        assert!(repo.find_reference("refs/tags/v1.0").is_err());
+        restore(&repo, &tag).unwrap();
+        let restored = repo.find_reference("refs/tags/v1.0").unwrap();
+        assert_eq!(restored.target(), Some(annotated));
+        assert_eq!(restored.peel_to_commit().unwrap().id(), base);
+        assert!(restore(&repo, &tag).is_err());
+    }
+
+    #[test]
+    fn restores_deleted_branch_and_dropped_stash() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let base = commit_file(&repo, "a.txt", "base\n");
+        repo.branch("topic", &repo.find_commit(base).unwrap(), false)
+            .unwrap();
+
+        let mut config = repo.config().unwrap();
+        config.set_str("branch.topic.remote", "origin").unwrap();
+        config
+            .set_str("branch.topic.merge", "refs/heads/topic")
+            .unwrap();
+
+        let branch = delete_branch(&repo, "topic").unwrap();
+        let upstream = |repo: &Repository| {
+            let config = repo.config().unwrap().snapshot().unwrap();
+            config.get_string("branch.topic.merge").ok()
+        };
+        assert_eq!(upstream(&repo), None);
+        restore(&repo, &branch).unwrap();
+        let restored = repo.find_branch("topic", git2::BranchType::Local).unwrap();
+        assert_eq!(restored.get().target(), Some(base));
+        assert_eq!(upstream(&repo).as_deref(), Some("refs/heads/topic"));
+        // Taken again since
+        assert!(restore(&repo, &branch).is_err());
+
+        std::fs::write(dir.path().join("a.txt"), "wip\n").unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let mut handle = Repository::open(repo.path()).unwrap();
+        handle.stash_save(&sig, "wip", None).unwrap();
+        let entry = list_stashes(&repo).unwrap().remove(0);
+        run_stash_op(&repo, StashOp::Drop, 0).unwrap();
+        assert!(list_stashes(&repo).unwrap().is_empty());
+
+        let stash = DeletedRef::Stash {
+            message: entry.message.clone(),
+            oid: entry.oid,
+        };
+        restore(&repo, &stash).unwrap();
+        let stashes = list_stashes(&repo).unwrap();
+        assert_eq!(stashes.len(), 1);
+        assert_eq!(stashes[0].oid, entry.oid);
+        assert_eq!(stashes[0].message, entry.message);
+    }
+}
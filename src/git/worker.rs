@@ -0,0 +1,130 @@
+use crate::error::{ArachneError, Result};
+use crate::git::repo::open_repo;
+use git2::Repository;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+type Job = Box<dyn FnOnce(&Repository) + Send>;
+
+enum Message {
+    Job(Job),
+    Reopen,
+}
+
+// git2::Repository is !Send, so it lives on its own thread
+pub struct RepoWorker {
+    jobs: mpsc::Sender<Message>,
+    workdir: Option<PathBuf>,
+}
+
+impl RepoWorker {
+    pub fn open(path: &Path) -> Result<Self> {
+        let (jobs, job_rx) = mpsc::channel::<Message>();
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let path = path.to_path_buf();
+        let thread_name = format!(
+            "repo-{}",
+            path.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default()
+        );
+
+        std::thread::Builder::new()
+            .name(thread_name)
+            .spawn(move || {
+                let mut repo = match open_repo(&path) {
+                    Ok(repo) => {
+                        let _ = ready_tx.send(Ok(repo.workdir().map(Path::to_path_buf)));
+                        repo
+                    }
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(e));
+                        return;
+                    }
+                };
+                while let Ok(message) = job_rx.recv() {
+                    match message {
+                        // One bad job mustn't take the repo down for the session
+                        Message::Job(job) => {
+                            if panic::catch_unwind(AssertUnwindSafe(|| job(&repo))).is_err() {
+                                tracing::error!("repo job panicked");
+                            }
+                        }
+                        Message::Reopen => match open_repo(&path) {
+                            Ok(fresh) => repo = fresh,
+                            Err(e) => tracing::warn!("reopening repo failed: {e}"),
+                        },
+                    }
+                }
+            })?;
+
+        let workdir = ready_rx.recv().map_err(|_| ArachneError::WorkerGone)??;
+        Ok(Self { jobs, workdir })
+    }
+
+    pub fn workdir(&self) -> Option<&Path> {
+        self.workdir.as_deref()
+    }
+
+    pub fn submit(&self, job: impl FnOnce(&Repository) + Send + 'static) -> Result<()> {
+        self.jobs
+            .send(Message::Job(Box::new(job)))
+            .map_err(|_| ArachneError::WorkerGone)
+    }
+
+    // git2 reads some state, like a shallow clone's graft points, only on open
+    pub fn reopen(&self) -> Result<()> {
+        self.jobs
+            .send(Message::Reopen)
+            .map_err(|_| ArachneError::WorkerGone)
+    }
+
+    // Blocks, so only for before the UI is running
+    pub fn call<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&Repository) -> T + Send + 'static,
+    ) -> Result<T> {
+        let (tx, rx) = mpsc::sync_channel(1);
+        self.submit(move |repo| {
+            let _ = tx.send(f(repo));
+        })?;
+        rx.recv().map_err(|_| ArachneError::WorkerGone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn call_runs_on_worker_thread() {
+        let dir = tempfile::tempdir().unwrap();
+        Repository::init(dir.path()).unwrap();
+        let worker = RepoWorker::open(dir.path()).unwrap();
+        let name = worker
+            .call(|_| std::thread::current().name().map(String::from))
+            .unwrap();
+        assert!(name.unwrap().starts_with("repo-"));
+        assert!(worker.workdir().is_some());
+    }
+
+    #[test]
+    fn worker_survives_a_panicking_job() {
+        let dir = tempfile::tempdir().unwrap();
+        Repository::init(dir.path()).unwrap();
+        let worker = RepoWorker::open(dir.path()).unwrap();
+        let failed = worker.call(|_| -> bool { panic!("bad job") });
+        assert!(matches!(failed, Err(ArachneError::WorkerGone)));
+        assert!(worker.call(|repo| repo.is_empty().unwrap()).unwrap());
+    }
+
+    #[test]
+    fn open_reports_missing_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            RepoWorker::open(dir.path()),
+            Err(ArachneError::NotARepo(_))
+        ));
+    }
+}
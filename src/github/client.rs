@@ -1,27 +1,51 @@
+use crate::data_source::{MergeMethod, PullRequestInfo, PullRequestStatus, ReviewState};
 use crate::error::{ArachneError, Result};
 use crate::git::types::{BranchInfo, CommitInfo, CommitSource, Oid};
+use crate::github::queue::{
+    is_network_error, reset_from_headers, Failed, Priority, QueueError, RequestQueue,
+};
 use crate::github::types::ForkInfo;
 use chrono::{DateTime, Utc};
+use octocrab::models::{pulls, repos};
 use octocrab::Octocrab;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
 
 const MAX_FORKS: usize = 50;
 const MAX_BRANCHES_PER_FORK: usize = 100;
+// Each PR costs a reviews request on top of the listing
+const MAX_PULL_REQUESTS: u8 = 30;
 
-fn api_err(e: octocrab::Error) -> ArachneError {
-    let msg = match &e {
-        octocrab::Error::GitHub { source, .. } => {
+fn api_err(e: impl Into<QueueError>) -> ArachneError {
+    let msg = match e.into() {
+        QueueError::RateLimited(until) => return ArachneError::RateLimited(until),
+        QueueError::Api(octocrab::Error::GitHub { source, .. }) => {
             format!("{} ({})", source.message, source.status_code)
         }
-        _ => "GitHub API request failed".to_string(),
+        QueueError::Api(ref e) if is_network_error(e) => "GitHub unreachable".to_string(),
+        QueueError::Api(_) => "GitHub API request failed".to_string(),
+        QueueError::Malformed(e) => format!("unexpected GitHub response: {e}"),
     };
+    tracing::warn!("github api error: {msg}");
     ArachneError::GitHub(msg)
 }
 
+#[derive(Clone, Copy)]
+enum Method<'a> {
+    Get,
+    Put(&'a serde_json::Value),
+    Delete,
+}
+
 #[derive(Clone)]
 pub struct GitHubClient {
     octo: Octocrab,
     owner: String,
     repo: String,
+    requests: Arc<AtomicU32>,
+    priority: Priority,
 }
 
 impl GitHubClient {
@@ -35,29 +59,96 @@ impl GitHubClient {
             octo,
             owner: owner.to_string(),
             repo: repo.to_string(),
+            requests: Arc::new(AtomicU32::new(0)),
+            priority: Priority::Poller,
         })
     }
 
+    pub fn for_user(&self) -> Self {
+        Self {
+            priority: Priority::User,
+            ..self.clone()
+        }
+    }
+
+    pub fn request_count(&self) -> u32 {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    // Outside the queue so a pause can't hold it up; /rate_limit is free
+    pub async fn is_reachable(&self) -> bool {
+        let reached = self.octo._get("/rate_limit").await.is_ok();
+        RequestQueue::shared().set_unreachable(!reached);
+        reached
+    }
+
+    // Raw rather than through octocrab's builders, which drop a failed
+    // response's headers and with them when a rate limit lifts
+    async fn send(
+        &self,
+        method: Method<'_>,
+        route: &str,
+    ) -> std::result::Result<(String, bool), QueueError> {
+        RequestQueue::shared()
+            .run(self.priority, || async move {
+                self.requests.fetch_add(1, Ordering::Relaxed);
+                let response = match method {
+                    Method::Get => self.octo._get(route).await,
+                    Method::Put(body) => self.octo._put(route, Some(body)).await,
+                    Method::Delete => self.octo._delete(route, None::<&()>).await,
+                }?;
+                let header = |name: &str| {
+                    response
+                        .headers()
+                        .get(name)?
+                        .to_str()
+                        .ok()
+                        .map(str::to_string)
+                };
+                let retry_at = reset_from_headers(header, Utc::now());
+                let more = header("link").is_some_and(|link| link.contains("rel=\"next\""));
+                let response = octocrab::map_github_error(response)
+                    .await
+                    .map_err(|error| Failed { error, retry_at })?;
+                Ok((self.octo.body_to_string(response).await?, more))
+            })
+            .await
+    }
+
+    async fn get<T: DeserializeOwned>(&self, route: &str) -> std::result::Result<T, QueueError> {
+        let (body, _) = self.send(Method::Get, route).await?;
+        serde_json::from_str(&body).map_err(QueueError::Malformed)
+    }
+
+    // A page of a listing, and whether there's another after it
+    async fn get_page<T: DeserializeOwned>(
+        &self,
+        route: &str,
+    ) -> std::result::Result<(Vec<T>, bool), QueueError> {
+        let (body, more) = self.send(Method::Get, route).await?;
+        let items = serde_json::from_str(&body).map_err(QueueError::Malformed)?;
+        Ok((items, more))
+    }
+
+    #[tracing::instrument(skip(self), fields(owner = %self.owner, repo = %self.repo))]
     pub async fn fetch_forks(&self) -> Result<Vec<ForkInfo>> {
         let mut forks = Vec::new();
         let mut page = 1u32;
 
         loop {
-            let result = self
-                .octo
-                .repos(&self.owner, &self.repo)
-                .list_forks()
-                .per_page(100)
-                .page(page)
-                .send()
+            let (items, more) = self
+                .get_page::<octocrab::models::Repository>(&format!(
+                    "/repos/{}/{}/forks?per_page=100&page={page}",
+                    self.owner, self.repo
+                ))
                 .await
                 .map_err(api_err)?;
 
-            if result.items.is_empty() {
+            if items.is_empty() {
                 break;
             }
 
-            for fork in &result.items {
+            for fork in &items {
                 let owner = fork
                     .owner
                     .as_ref()
@@ -70,48 +161,49 @@ impl GitHubClient {
                 }
             }
 
-            if forks.len() >= MAX_FORKS || result.next.is_none() {
+            if forks.len() >= MAX_FORKS || !more {
                 break;
             }
             page += 1;
         }
 
+        tracing::debug!(forks = forks.len(), "fetched forks");
         Ok(forks)
     }
 
+    #[tracing::instrument(skip(self, fork), fields(owner = %fork.owner, repo = %fork.repo))]
     pub async fn fetch_fork_branches(&self, fork: &ForkInfo) -> Result<Vec<BranchInfo>> {
         let mut branches = Vec::new();
         let mut page = 1u32;
 
         loop {
-            let result = self
-                .octo
-                .repos(&fork.owner, &fork.repo)
-                .list_branches()
-                .per_page(100)
-                .page(page)
-                .send()
+            let (items, more) = self
+                .get_page::<repos::Branch>(&format!(
+                    "/repos/{}/{}/branches?per_page=100&page={page}",
+                    fork.owner, fork.repo
+                ))
                 .await
                 .map_err(api_err)?;
 
-            if result.items.is_empty() {
+            if items.is_empty() {
                 break;
             }
 
-            for branch in &result.items {
+            for branch in &items {
                 let sha_bytes = sha_str_to_bytes(&branch.commit.sha)?;
                 branches.push(BranchInfo {
                     name: branch.name.clone(),
                     tip: Oid::from_bytes(sha_bytes),
                     is_head: false,
                     source: CommitSource::Fork(fork.owner.clone()),
+                    upstream: None,
                 });
                 if branches.len() >= MAX_BRANCHES_PER_FORK {
                     break;
                 }
             }
 
-            if branches.len() >= MAX_BRANCHES_PER_FORK || result.next.is_none() {
+            if branches.len() >= MAX_BRANCHES_PER_FORK || !more {
                 break;
             }
             page += 1;
@@ -120,6 +212,7 @@ impl GitHubClient {
         Ok(branches)
     }
 
+    #[tracing::instrument(skip(self))]
     pub async fn fetch_commits(
         &self,
         owner: &str,
@@ -131,22 +224,19 @@ impl GitHubClient {
         let mut page = 1u32;
 
         while commits.len() < max {
-            let result = self
-                .octo
-                .repos(owner, repo)
-                .list_commits()
-                .sha(sha)
-                .per_page(100)
-                .page(page)
-                .send()
+            let (items, more) = self
+                .get_page::<repos::RepoCommit>(&format!(
+                    "/repos/{owner}/{repo}/commits?sha={}&per_page=100&page={page}",
+                    encode(sha)
+                ))
                 .await
                 .map_err(api_err)?;
 
-            if result.items.is_empty() {
+            if items.is_empty() {
                 break;
             }
 
-            for c in &result.items {
+            for c in &items {
                 let oid = Oid::from_bytes(sha_str_to_bytes(&c.sha)?);
                 let parents: Vec<Oid> = c
                     .parents
@@ -164,6 +254,13 @@ impl GitHubClient {
                     .map(|a| a.name.clone())
                     .unwrap_or_default();
 
+                let email = c
+                    .commit
+                    .author
+                    .as_ref()
+                    .map(|a| a.email.clone())
+                    .unwrap_or_default();
+
                 let time: DateTime<Utc> = c
                     .commit
                     .author
@@ -177,12 +274,13 @@ impl GitHubClient {
                     parents,
                     message,
                     author,
+                    email,
                     time,
                     source: CommitSource::Fork(owner.to_string()),
                 });
             }
 
-            if result.next.is_none() || commits.len() >= max {
+            if !more || commits.len() >= max {
                 break;
             }
             page += 1;
@@ -191,16 +289,174 @@ impl GitHubClient {
         Ok(commits)
     }
 
+    #[tracing::instrument(skip(self), fields(owner = %self.owner, repo = %self.repo))]
+    pub async fn find_open_pr(&self, branch: &str) -> Result<Option<PullRequestInfo>> {
+        let (items, _) = self
+            .get_page::<pulls::PullRequest>(&format!(
+                "/repos/{}/{}/pulls?state=open&head={}&per_page=1",
+                self.owner,
+                self.repo,
+                encode(&format!("{}:{branch}", self.owner))
+            ))
+            .await
+            .map_err(api_err)?;
+        Ok(items.into_iter().next().map(|pr| PullRequestInfo {
+            number: pr.number,
+            title: pr.title.unwrap_or_default(),
+        }))
+    }
+
+    #[tracing::instrument(skip(self), fields(owner = %self.owner, repo = %self.repo))]
+    pub async fn fetch_pull_requests(&self) -> Result<Vec<PullRequestStatus>> {
+        use octocrab::models::pulls::ReviewState as Verdict;
+        let (items, _) = self
+            .get_page::<pulls::PullRequest>(&format!(
+                "/repos/{}/{}/pulls?state=open&per_page={MAX_PULL_REQUESTS}",
+                self.owner, self.repo
+            ))
+            .await
+            .map_err(api_err)?;
+
+        let mut out = Vec::with_capacity(items.len());
+        for pr in items {
+            let reviews = match self
+                .get_page::<pulls::Review>(&format!(
+                    "/repos/{}/{}/pulls/{}/reviews?per_page=100",
+                    self.owner, self.repo, pr.number
+                ))
+                .await
+            {
+                Ok((reviews, _)) => reviews,
+                Err(e) => {
+                    tracing::warn!(number = pr.number, "skipping reviews: {}", api_err(e));
+                    Vec::new()
+                }
+            };
+            let review = ReviewState::from_reviews(reviews.iter().filter_map(|r| {
+                let verdict = match r.state? {
+                    Verdict::Approved => Some(ReviewState::Approved),
+                    Verdict::ChangesRequested => Some(ReviewState::ChangesRequested),
+                    Verdict::Dismissed => None,
+                    _ => return None,
+                };
+                Some((r.user.as_ref()?.login.as_str(), verdict))
+            }));
+            out.push(PullRequestStatus {
+                number: pr.number,
+                branch: pr.head.ref_field.clone(),
+                draft: pr.draft.unwrap_or(false),
+                review,
+                head: sha_str_to_bytes(&pr.head.sha).ok().map(Oid::from_bytes),
+            });
+        }
+        Ok(out)
+    }
+
+    #[tracing::instrument(skip(self), fields(owner = %self.owner, repo = %self.repo))]
+    pub async fn fetch_protected_branches(&self) -> Result<Vec<String>> {
+        let (items, _) = self
+            .get_page::<repos::Branch>(&format!(
+                "/repos/{}/{}/branches?protected=true&per_page=100",
+                self.owner, self.repo
+            ))
+            .await
+            .map_err(api_err)?;
+        Ok(items.into_iter().map(|b| b.name).collect())
+    }
+
+    // Commits GitHub can't tie to an account are left out
+    #[tracing::instrument(skip(self), fields(owner = %self.owner, repo = %self.repo))]
+    pub async fn fetch_author_logins(&self) -> Result<HashMap<String, String>> {
+        let (items, _) = self
+            .get_page::<repos::RepoCommit>(&format!(
+                "/repos/{}/{}/commits?per_page=100",
+                self.owner, self.repo
+            ))
+            .await
+            .map_err(api_err)?;
+        Ok(items
+            .into_iter()
+            .filter_map(|c| Some((c.commit.author?.user.email, c.author?.login)))
+            .collect())
+    }
+
+    #[tracing::instrument(skip(self), fields(owner = %self.owner, repo = %self.repo))]
+    pub async fn fetch_codeowners(&self) -> Result<Option<String>> {
+        for path in [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"] {
+            let result = self
+                .get::<repos::Content>(&format!(
+                    "/repos/{}/{}/contents/{path}",
+                    self.owner, self.repo
+                ))
+                .await;
+            match result {
+                Ok(content) => return Ok(content.decoded_content()),
+                Err(QueueError::Api(octocrab::Error::GitHub { ref source, .. }))
+                    if source.status_code.as_u16() == 404 => {}
+                Err(e) => return Err(api_err(e)),
+            }
+        }
+        Ok(None)
+    }
+
+    #[tracing::instrument(skip(self), fields(owner = %self.owner, repo = %self.repo))]
+    pub async fn merge_pr(&self, number: u64, method: MergeMethod) -> Result<()> {
+        let method = match method {
+            MergeMethod::Merge => "merge",
+            MergeMethod::Squash => "squash",
+            MergeMethod::Rebase => "rebase",
+        };
+        let body = serde_json::json!({ "merge_method": method });
+        let route = format!("/repos/{}/{}/pulls/{number}/merge", self.owner, self.repo);
+        let (body, _) = self
+            .send(Method::Put(&body), &route)
+            .await
+            .map_err(api_err)?;
+        let merge: pulls::Merge = serde_json::from_str(&body)
+            .map_err(QueueError::Malformed)
+            .map_err(api_err)?;
+        if merge.merged {
+            Ok(())
+        } else {
+            Err(ArachneError::GitHub(
+                merge.message.unwrap_or_else(|| "not merged".to_string()),
+            ))
+        }
+    }
+
+    #[tracing::instrument(skip(self), fields(owner = %self.owner, repo = %self.repo))]
+    pub async fn delete_branch(&self, branch: &str) -> Result<()> {
+        let route = format!(
+            "/repos/{}/{}/git/refs/heads/{branch}",
+            self.owner, self.repo
+        );
+        self.send(Method::Delete, &route)
+            .await
+            .map(|_| ())
+            .map_err(api_err)
+    }
+
     pub async fn rate_limit(&self) -> Option<u32> {
-        self.octo
-            .ratelimit()
-            .get()
+        self.get::<octocrab::models::RateLimit>("/rate_limit")
             .await
             .ok()
             .map(|r| r.rate.remaining as u32)
     }
 }
 
+// Percent-encodes a query parameter value
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
 fn sha_str_to_bytes(sha: &str) -> Result<[u8; 20]> {
     if sha.len() != 40 {
         return Err(ArachneError::GitHub(format!(
@@ -1,4 +1,5 @@
 pub mod client;
 pub mod network;
+pub mod queue;
 pub mod remote_loader;
 pub mod types;
@@ -1,17 +1,23 @@
-use crate::git::types::{BranchInfo, CommitInfo};
+use crate::data_source::ForkNetwork;
+use crate::git::types::CommitSource;
 use crate::github::client::GitHubClient;
 
 pub async fn fetch_network_detached(
     client: &GitHubClient,
-) -> std::result::Result<(Vec<BranchInfo>, Vec<CommitInfo>, Option<u32>), String> {
+) -> std::result::Result<ForkNetwork, String> {
     let forks = client.fetch_forks().await.map_err(|e| e.to_string())?;
     let mut all_branches = Vec::new();
     let mut all_commits = Vec::new();
+    let mut unlisted = Vec::new();
 
     for fork in &forks {
         let branches = match client.fetch_fork_branches(fork).await {
             Ok(b) => b,
-            Err(_) => continue,
+            Err(e) => {
+                tracing::warn!(owner = %fork.owner, "skipping fork branches: {e}");
+                unlisted.push(CommitSource::Fork(fork.owner.clone()));
+                continue;
+            }
         };
 
         for branch in &branches {
@@ -20,7 +26,10 @@ pub async fn fetch_network_detached(
                 .await
             {
                 Ok(commits) => all_commits.extend(commits),
-                Err(_) => continue,
+                Err(e) => {
+                    tracing::warn!(owner = %fork.owner, branch = %branch.name, "skipping commits: {e}");
+                    continue;
+                }
             }
         }
 
@@ -28,5 +37,16 @@ pub async fn fetch_network_detached(
     }
 
     let rate = client.rate_limit().await;
-    Ok((all_branches, all_commits, rate))
+    tracing::debug!(
+        branches = all_branches.len(),
+        commits = all_commits.len(),
+        rate_limit = ?rate,
+        "network fetch done"
+    );
+    Ok(ForkNetwork {
+        branches: all_branches,
+        commits: all_commits,
+        unlisted,
+        rate_limit: rate,
+    })
 }
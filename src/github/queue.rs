@@ -0,0 +1,352 @@
+use chrono::{DateTime, TimeZone, Utc};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use tokio::sync::oneshot;
+
+// In flight at once across all repositories, which share the rate limits
+const MAX_IN_FLIGHT: usize = 4;
+const MAX_RETRIES: u32 = 3;
+// A rate limit lifting sooner than this is waited out; a later one fails the request
+const MAX_RETRY_WAIT_SECS: i64 = 60;
+const SECONDARY_WAIT_SECS: i64 = 60;
+
+// A free slot goes to the oldest `User` request before any `Poller` one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    User,
+    Poller,
+}
+
+#[derive(Default)]
+struct Slots {
+    in_flight: usize,
+    user: VecDeque<oneshot::Sender<()>>,
+    poller: VecDeque<oneshot::Sender<()>>,
+}
+
+#[derive(Debug)]
+pub enum QueueError {
+    Api(octocrab::Error),
+    RateLimited(DateTime<Utc>),
+    Malformed(serde_json::Error),
+}
+
+// A failed request, with when its response said to try again
+pub struct Failed {
+    pub error: octocrab::Error,
+    pub retry_at: Option<DateTime<Utc>>,
+}
+
+impl From<octocrab::Error> for Failed {
+    fn from(error: octocrab::Error) -> Self {
+        Failed {
+            error,
+            retry_at: None,
+        }
+    }
+}
+
+impl From<octocrab::Error> for QueueError {
+    fn from(e: octocrab::Error) -> Self {
+        QueueError::Api(e)
+    }
+}
+
+pub struct RequestQueue {
+    limit: usize,
+    slots: Mutex<Slots>,
+    paused_until: Mutex<Option<DateTime<Utc>>>,
+    unreachable: AtomicBool,
+}
+
+// Hands its slot to the next waiter when dropped
+pub struct Permit<'a> {
+    queue: &'a RequestQueue,
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        self.queue.release();
+    }
+}
+
+// Dropped between being handed a slot and taking it, it passes the slot on
+struct InLine<'a> {
+    queue: &'a RequestQueue,
+    rx: oneshot::Receiver<()>,
+}
+
+impl Drop for InLine<'_> {
+    fn drop(&mut self) {
+        if self.rx.try_recv().is_ok() {
+            self.queue.release();
+        }
+    }
+}
+
+impl RequestQueue {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit: limit.max(1),
+            slots: Mutex::new(Slots::default()),
+            paused_until: Mutex::new(None),
+            unreachable: AtomicBool::new(false),
+        }
+    }
+
+    pub fn shared() -> &'static RequestQueue {
+        static QUEUE: OnceLock<RequestQueue> = OnceLock::new();
+        QUEUE.get_or_init(|| RequestQueue::new(MAX_IN_FLIGHT))
+    }
+
+    pub async fn acquire(&self, priority: Priority) -> Permit<'_> {
+        let waiting = {
+            let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+            if slots.in_flight < self.limit {
+                slots.in_flight += 1;
+                None
+            } else {
+                let (tx, rx) = oneshot::channel();
+                match priority {
+                    Priority::User => slots.user.push_back(tx),
+                    Priority::Poller => slots.poller.push_back(tx),
+                }
+                Some(rx)
+            }
+        };
+        if let Some(rx) = waiting {
+            let mut line = InLine { queue: self, rx };
+            // The sender is only dropped once the slot is handed over
+            let _ = (&mut line.rx).await;
+        }
+        Permit { queue: self }
+    }
+
+    fn release(&self) {
+        let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+        while let Some(next) = slots.user.pop_front().or_else(|| slots.poller.pop_front()) {
+            // A waiter that gave up has dropped its receiver
+            if next.send(()).is_ok() {
+                return;
+            }
+        }
+        slots.in_flight -= 1;
+    }
+
+    pub fn paused_until(&self) -> Option<DateTime<Utc>> {
+        let until = *self.paused_until.lock().unwrap_or_else(|e| e.into_inner());
+        until.filter(|&until| until > Utc::now())
+    }
+
+    pub fn unreachable(&self) -> bool {
+        self.unreachable.load(Ordering::Relaxed)
+    }
+
+    pub fn set_unreachable(&self, unreachable: bool) {
+        self.unreachable.store(unreachable, Ordering::Relaxed);
+    }
+
+    fn pause(&self, until: DateTime<Utc>) {
+        let mut paused = self.paused_until.lock().unwrap_or_else(|e| e.into_inner());
+        *paused = Some(until.max(paused.unwrap_or(until)));
+    }
+
+    pub async fn run<T, F, Fut>(
+        &self,
+        priority: Priority,
+        mut request: F,
+    ) -> std::result::Result<T, QueueError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = std::result::Result<T, Failed>>,
+    {
+        let mut attempt = 0;
+        loop {
+            if let Some(until) = self.paused_until() {
+                return Err(QueueError::RateLimited(until));
+            }
+            let result = {
+                let _permit = self.acquire(priority).await;
+                request().await
+            };
+            match result {
+                Err(ref failed) if is_rate_limited(&failed.error) => {
+                    self.set_unreachable(false);
+                    // Otherwise a secondary limit: the core one has requests left
+                    let until = failed.retry_at.unwrap_or_else(|| {
+                        Utc::now() + chrono::Duration::seconds(SECONDARY_WAIT_SECS)
+                    });
+                    self.pause(until);
+                    let wait = until - Utc::now();
+                    if attempt == MAX_RETRIES || wait.num_seconds() > MAX_RETRY_WAIT_SECS {
+                        tracing::warn!(?priority, %until, "rate limited");
+                        return Err(QueueError::RateLimited(until));
+                    }
+                    tracing::warn!(?priority, attempt, "rate limited, retrying at {until}");
+                    tokio::time::sleep(wait.to_std().unwrap_or_default()).await;
+                    attempt += 1;
+                }
+                result => {
+                    let result = result.map_err(|failed| failed.error);
+                    match &result {
+                        Err(e) if is_network_error(e) => self.set_unreachable(true),
+                        Ok(_) | Err(octocrab::Error::GitHub { .. }) => self.set_unreachable(false),
+                        Err(_) => {}
+                    }
+                    return result.map_err(QueueError::Api);
+                }
+            }
+        }
+    }
+}
+
+// x-ratelimit-reset only counts once x-ratelimit-remaining is down to 0
+pub fn reset_from_headers(
+    header: impl Fn(&str) -> Option<String>,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    if let Some(secs) = header("retry-after").and_then(|s| s.trim().parse::<i64>().ok()) {
+        return Some(now + chrono::Duration::seconds(secs));
+    }
+    let remaining = header("x-ratelimit-remaining")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    if remaining > 0 {
+        return None;
+    }
+    let reset = header("x-ratelimit-reset")?.trim().parse::<i64>().ok()?;
+    Utc.timestamp_opt(reset, 0).single()
+}
+
+pub fn is_network_error(e: &octocrab::Error) -> bool {
+    matches!(
+        e,
+        octocrab::Error::Hyper { .. } | octocrab::Error::Service { .. }
+    )
+}
+
+fn is_rate_limited(e: &octocrab::Error) -> bool {
+    match e {
+        octocrab::Error::GitHub { source, .. } => {
+            rate_limit_response(source.status_code.as_u16(), &source.message)
+        }
+        _ => false,
+    }
+}
+
+// Other 403s, e.g. a token without access, won't go away by waiting
+fn rate_limit_response(status: u16, message: &str) -> bool {
+    status == 429 || (status == 403 && message.to_lowercase().contains("rate limit"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn free_slots_go_to_user_requests_first() {
+        let queue = Arc::new(RequestQueue::new(1));
+        let held = queue.acquire(Priority::Poller).await;
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let mut waiters = Vec::new();
+        for (name, priority) in [
+            ("poll", Priority::Poller),
+            ("user", Priority::User),
+            ("poll2", Priority::Poller),
+        ] {
+            let (queue, order) = (Arc::clone(&queue), Arc::clone(&order));
+            waiters.push(tokio::spawn(async move {
+                let _permit = queue.acquire(priority).await;
+                order.lock().unwrap().push(name);
+            }));
+            // Queued in this order before the slot frees
+            tokio::task::yield_now().await;
+        }
+        drop(held);
+        for waiter in waiters {
+            waiter.await.unwrap();
+        }
+        assert_eq!(*order.lock().unwrap(), ["user", "poll", "poll2"]);
+        assert_eq!(queue.slots.lock().unwrap().in_flight, 0);
+    }
+
+    #[tokio::test]
+    async fn paused_queue_turns_requests_away_until_the_reset() {
+        let queue = RequestQueue::new(1);
+        let until = Utc::now() + chrono::Duration::minutes(5);
+        queue.pause(until);
+        // An earlier reset learned later doesn't shorten the pause
+        queue.pause(Utc::now() + chrono::Duration::seconds(10));
+        assert_eq!(queue.paused_until(), Some(until));
+        let mut sent = 0;
+        let result = queue
+            .run(Priority::User, || {
+                sent += 1;
+                async { Ok::<_, Failed>(()) }
+            })
+            .await;
+        assert!(matches!(result, Err(QueueError::RateLimited(t)) if t == until));
+        assert_eq!(sent, 0);
+
+        *queue.paused_until.lock().unwrap() = Some(Utc::now());
+        assert_eq!(queue.paused_until(), None);
+    }
+
+    #[test]
+    fn reads_the_reset_from_a_failed_response() {
+        let now = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let headers = |pairs: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                pairs
+                    .iter()
+                    .find(|(k, _)| *k == name)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+        assert_eq!(
+            reset_from_headers(headers(&[("retry-after", "30")]), now),
+            Some(now + chrono::Duration::seconds(30))
+        );
+        assert_eq!(
+            reset_from_headers(
+                headers(&[
+                    ("x-ratelimit-remaining", "0"),
+                    ("x-ratelimit-reset", "1700000900")
+                ]),
+                now
+            ),
+            Some(now + chrono::Duration::seconds(900))
+        );
+        // Requests left and no retry-after: a secondary limit, which the
+        // caller waits out
+        assert_eq!(
+            reset_from_headers(
+                headers(&[
+                    ("x-ratelimit-remaining", "4000"),
+                    ("x-ratelimit-reset", "1700000900")
+                ]),
+                now
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn only_rate_limits_are_retried() {
+        assert!(rate_limit_response(
+            403,
+            "You have exceeded a secondary rate limit"
+        ));
+        assert!(rate_limit_response(403, "API rate limit exceeded for user"));
+        assert!(rate_limit_response(429, "Too Many Requests"));
+        assert!(!rate_limit_response(
+            403,
+            "Resource not accessible by integration"
+        ));
+        assert!(!rate_limit_response(404, "Not Found"));
+    }
+}
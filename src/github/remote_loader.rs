@@ -1,7 +1,9 @@
 use crate::git::types::{Oid, RepoData};
 use crate::github::client::GitHubClient;
 use std::collections::HashSet;
+use std::sync::Arc;
 
+#[tracing::instrument(skip(client))]
 pub async fn load_remote_repo_data(
     client: &GitHubClient,
     max_commits: usize,
@@ -35,7 +37,7 @@ pub async fn load_remote_repo_data(
                 Ok(commits) => {
                     for c in commits {
                         if seen_oids.insert(c.oid) {
-                            all_commits.push(c);
+                            all_commits.push(Arc::new(c));
                         }
                     }
                 }
@@ -58,5 +60,9 @@ pub async fn load_remote_repo_data(
         tags: Vec::new(),
         head,
         branch_tips,
+        shallow: HashSet::new(),
+        replaced: HashSet::new(),
+        stashes: Vec::new(),
+        submodules: Vec::new(),
     })
 }
@@ -1,9 +1,10 @@
 use crate::git::types::{CommitInfo, Oid, RepoData};
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 #[derive(Clone, Debug)]
 pub struct DagNode {
-    pub commit: CommitInfo,
+    pub commit: Arc<CommitInfo>,
     pub children: Vec<Oid>,
 }
 
@@ -14,6 +15,7 @@ pub struct Dag {
 }
 
 impl Dag {
+    #[tracing::instrument(skip_all, fields(commits = data.commits.len()))]
     pub fn from_repo_data(data: &RepoData) -> Self {
         let mut nodes = HashMap::new();
 
@@ -42,10 +44,15 @@ impl Dag {
         Self { nodes, topo_order }
     }
 
+    #[tracing::instrument(skip_all, fields(remote = remote_commits.len()))]
     pub fn merge_remote(&mut self, remote_commits: Vec<CommitInfo>) {
+        self.extend(remote_commits.into_iter().map(Arc::new).collect());
+    }
+
+    pub fn extend(&mut self, commits: Vec<Arc<CommitInfo>>) {
         let mut newly_inserted = Vec::new();
 
-        for commit in remote_commits {
+        for commit in commits {
             if self.nodes.contains_key(&commit.oid) {
                 continue;
             }
@@ -59,36 +66,107 @@ impl Dag {
             );
             newly_inserted.push(oid);
         }
+        if newly_inserted.is_empty() {
+            return;
+        }
 
-        let all_oids: HashSet<_> = self.nodes.keys().copied().collect();
         for oid in &newly_inserted {
             let parents: Vec<_> = self.nodes[oid].commit.parents.clone();
             for parent_oid in &parents {
-                if all_oids.contains(parent_oid) {
-                    if let Some(parent_node) = self.nodes.get_mut(parent_oid) {
-                        if !parent_node.children.contains(oid) {
-                            parent_node.children.push(*oid);
-                        }
+                if let Some(parent_node) = self.nodes.get_mut(parent_oid) {
+                    if !parent_node.children.contains(oid) {
+                        parent_node.children.push(*oid);
                     }
                 }
             }
         }
 
-        self.topo_order = kahns_topo_sort(&self.nodes);
+        // Existing commits whose missing parent just arrived (history beyond
+        // max_commits): wire them up, and fall back to a full sort since the
+        // new commit has to move below them.
+        let new_set: HashSet<Oid> = newly_inserted.iter().copied().collect();
+        let mut adopted = Vec::new();
+        for oid in &self.topo_order {
+            for parent_oid in &self.nodes[oid].commit.parents {
+                if new_set.contains(parent_oid) {
+                    adopted.push((*parent_oid, *oid));
+                }
+            }
+        }
+
+        tracing::debug!(
+            inserted = newly_inserted.len(),
+            adopted = adopted.len(),
+            "merged commits"
+        );
+        if adopted.is_empty() {
+            let new_order = kahns_sort_subset(&self.nodes, newly_inserted.into_iter(), |oid| {
+                new_set.contains(oid)
+            });
+            self.topo_order = splice_topo_order(&self.nodes, &self.topo_order, new_order);
+        } else {
+            for (parent_oid, child_oid) in adopted {
+                if let Some(parent_node) = self.nodes.get_mut(&parent_oid) {
+                    if !parent_node.children.contains(&child_oid) {
+                        parent_node.children.push(child_oid);
+                    }
+                }
+            }
+            self.topo_order = kahns_topo_sort(&self.nodes);
+        }
+    }
+
+    pub fn reachable_from(&self, tips: impl IntoIterator<Item = Oid>) -> HashSet<Oid> {
+        let mut seen = HashSet::new();
+        let mut stack: Vec<Oid> = tips
+            .into_iter()
+            .filter(|t| self.nodes.contains_key(t))
+            .collect();
+        while let Some(oid) = stack.pop() {
+            if !seen.insert(oid) {
+                continue;
+            }
+            if let Some(node) = self.nodes.get(&oid) {
+                stack.extend(
+                    node.commit
+                        .parents
+                        .iter()
+                        .filter(|p| self.nodes.contains_key(p) && !seen.contains(p)),
+                );
+            }
+        }
+        seen
+    }
+
+    pub fn unreachable_from(&self, tips: impl IntoIterator<Item = Oid>) -> HashSet<Oid> {
+        let reached = self.reachable_from(tips);
+        self.nodes
+            .keys()
+            .filter(|oid| !reached.contains(oid))
+            .copied()
+            .collect()
     }
 }
 
 fn kahns_topo_sort(nodes: &HashMap<Oid, DagNode>) -> Vec<Oid> {
+    kahns_sort_subset(nodes, nodes.keys().copied(), |oid| nodes.contains_key(oid))
+}
+
+fn kahns_sort_subset(
+    nodes: &HashMap<Oid, DagNode>,
+    members: impl Iterator<Item = Oid>,
+    is_member: impl Fn(&Oid) -> bool,
+) -> Vec<Oid> {
     // In-degree: count how many children point to each node (children → parent edges)
     // We want newest-first, so "edges" go from child→parent.
     // In-degree = number of children (nodes that have this as parent).
     let mut in_degree: HashMap<Oid, usize> = HashMap::new();
-    for oid in nodes.keys() {
-        in_degree.entry(*oid).or_insert(0);
+    for oid in members {
+        in_degree.entry(oid).or_insert(0);
     }
-    for node in nodes.values() {
-        for parent_oid in &node.commit.parents {
-            if nodes.contains_key(parent_oid) {
+    for oid in in_degree.keys().copied().collect::<Vec<_>>() {
+        for parent_oid in &nodes[&oid].commit.parents {
+            if is_member(parent_oid) {
                 *in_degree.entry(*parent_oid).or_insert(0) += 1;
             }
         }
@@ -106,16 +184,15 @@ fn kahns_topo_sort(nodes: &HashMap<Oid, DagNode>) -> Vec<Oid> {
         }
     }
 
-    let mut result = Vec::with_capacity(nodes.len());
+    let mut result = Vec::with_capacity(in_degree.len());
     while let Some((_, oid)) = heap.pop() {
         result.push(oid);
-        let parents: Vec<_> = nodes[&oid].commit.parents.clone();
-        for parent_oid in parents {
-            if let Some(deg) = in_degree.get_mut(&parent_oid) {
+        for parent_oid in &nodes[&oid].commit.parents {
+            if let Some(deg) = in_degree.get_mut(parent_oid) {
                 *deg -= 1;
                 if *deg == 0 {
-                    if let Some(pnode) = nodes.get(&parent_oid) {
-                        heap.push((pnode.commit.time, parent_oid));
+                    if let Some(pnode) = nodes.get(parent_oid) {
+                        heap.push((pnode.commit.time, *parent_oid));
                     }
                 }
             }
@@ -125,6 +202,47 @@ fn kahns_topo_sort(nodes: &HashMap<Oid, DagNode>) -> Vec<Oid> {
     result
 }
 
+// An existing commit waits while a pending new one still has it as parent
+fn splice_topo_order(
+    nodes: &HashMap<Oid, DagNode>,
+    existing: &[Oid],
+    new_order: Vec<Oid>,
+) -> Vec<Oid> {
+    let mut blocked: HashMap<Oid, usize> = HashMap::new();
+    for oid in &new_order {
+        for parent_oid in &nodes[oid].commit.parents {
+            *blocked.entry(*parent_oid).or_insert(0) += 1;
+        }
+    }
+
+    let mut out = Vec::with_capacity(existing.len() + new_order.len());
+    let (mut i, mut j) = (0, 0);
+    while i < existing.len() || j < new_order.len() {
+        let take_new = match (existing.get(i), new_order.get(j)) {
+            (Some(e), Some(n)) => {
+                blocked.get(e).is_some_and(|&c| c > 0)
+                    || nodes[n].commit.time >= nodes[e].commit.time
+            }
+            (None, Some(_)) => true,
+            _ => false,
+        };
+        if take_new {
+            let oid = new_order[j];
+            j += 1;
+            for parent_oid in &nodes[&oid].commit.parents {
+                if let Some(c) = blocked.get_mut(parent_oid) {
+                    *c = c.saturating_sub(1);
+                }
+            }
+            out.push(oid);
+        } else {
+            out.push(existing[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,6 +257,7 @@ mod tests {
                 tip,
                 is_head: true,
                 source: crate::git::types::CommitSource::Local,
+                upstream: None,
             }],
         )
     }
@@ -223,12 +342,14 @@ mod tests {
                     tip: make_oid(1),
                     is_head: true,
                     source: crate::git::types::CommitSource::Local,
+                    upstream: None,
                 },
                 crate::git::types::BranchInfo {
                     name: "b".to_string(),
                     tip: make_oid(2),
                     is_head: false,
                     source: crate::git::types::CommitSource::Local,
+                    upstream: None,
                 },
             ],
         );
@@ -237,4 +358,76 @@ mod tests {
         assert_eq!(dag.topo_order[0], make_oid(1));
         assert_eq!(dag.topo_order[1], make_oid(2));
     }
+
+    fn assert_topo_valid(dag: &Dag) {
+        let pos: HashMap<_, _> = dag
+            .topo_order
+            .iter()
+            .enumerate()
+            .map(|(i, oid)| (*oid, i))
+            .collect();
+        assert_eq!(pos.len(), dag.nodes.len());
+        for (oid, node) in &dag.nodes {
+            for parent in &node.commit.parents {
+                if let Some(&p) = pos.get(parent) {
+                    assert!(pos[oid] < p, "child must precede parent");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn extend_splices_without_full_sort() {
+        let commits = vec![
+            make_commit(1, vec![2], 10),
+            make_commit(2, vec![3], 20),
+            make_commit(3, vec![], 30),
+        ];
+        let mut dag = Dag::from_repo_data(&simple_repo_data(commits));
+
+        // An old fork tip off 3 and a newer one off 1
+        dag.merge_remote(vec![
+            make_commit(4, vec![3], 25),
+            make_commit(5, vec![1], 5),
+        ]);
+
+        assert_topo_valid(&dag);
+        assert_eq!(dag.topo_order[0], make_oid(5));
+        let pos4 = dag.topo_order.iter().position(|o| *o == make_oid(4));
+        assert_eq!(pos4, Some(3));
+    }
+
+    #[test]
+    fn extend_adopts_missing_parent() {
+        // 2's parent 3 was cut off by max_commits and arrives later
+        let commits = vec![make_commit(1, vec![2], 10), make_commit(2, vec![3], 20)];
+        let mut dag = Dag::from_repo_data(&simple_repo_data(commits));
+
+        dag.merge_remote(vec![make_commit(3, vec![], 30)]);
+
+        assert_topo_valid(&dag);
+        assert_eq!(dag.nodes[&make_oid(3)].children, vec![make_oid(2)]);
+        assert_eq!(dag.topo_order.last(), Some(&make_oid(3)));
+    }
+
+    #[test]
+    fn reachable_from_follows_parents_only() {
+        // 1 and 4 are tips; 4 forks off 2
+        let commits = vec![
+            make_commit(1, vec![2], 10),
+            make_commit(4, vec![2], 15),
+            make_commit(2, vec![3], 20),
+            make_commit(3, vec![], 30),
+        ];
+        let dag = Dag::from_repo_data(&simple_repo_data(commits));
+
+        let reached = dag.reachable_from([make_oid(4), make_oid(9)]);
+        let expected: HashSet<Oid> = [4, 2, 3].into_iter().map(make_oid).collect();
+        assert_eq!(reached, expected);
+        assert!(dag.reachable_from([make_oid(9)]).is_empty());
+        assert_eq!(
+            dag.unreachable_from([make_oid(4)]),
+            HashSet::from([make_oid(1)])
+        );
+    }
 }
@@ -0,0 +1,197 @@
+use crate::git::types::{CommitSource, Oid, RepoData};
+use crate::graph::dag::Dag;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+
+// First-parent steps down to the commit's merge base with the trunk
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrunkDistance {
+    pub trunk: String,
+    pub ahead: usize,
+}
+
+impl TrunkDistance {
+    pub fn label(&self) -> String {
+        if self.ahead == 0 {
+            self.trunk.clone()
+        } else {
+            format!("{}+{}", self.trunk, self.ahead)
+        }
+    }
+}
+
+pub fn trunk_tips(data: &RepoData, trunk_branches: &[String]) -> Vec<(String, Oid)> {
+    let local = |name: &str| {
+        data.branches
+            .iter()
+            .find(|b| b.source == CommitSource::Local && b.name == name)
+    };
+    let configured: Vec<_> = trunk_branches
+        .iter()
+        .filter_map(|name| Some((name.clone(), local(name)?.tip)))
+        .collect();
+    if !configured.is_empty() {
+        return configured;
+    }
+    data.branches
+        .iter()
+        .find(|b| b.is_head && b.source == CommitSource::Local)
+        .map(|b| vec![(b.name.clone(), b.tip)])
+        .unwrap_or_default()
+}
+
+// Ties go to the earlier trunk; chains leaving the loaded history are left out
+pub fn trunk_distances(dag: &Dag, trunks: &[(String, Oid)]) -> HashMap<Oid, TrunkDistance> {
+    let mut nearest: HashMap<Oid, TrunkDistance> = HashMap::new();
+    for (name, tip) in trunks {
+        let on_trunk = dag.reachable_from([*tip]);
+        for (oid, ahead) in first_parent_distances(dag, &on_trunk) {
+            if nearest.get(&oid).is_none_or(|d| ahead < d.ahead) {
+                nearest.insert(
+                    oid,
+                    TrunkDistance {
+                        trunk: name.clone(),
+                        ahead,
+                    },
+                );
+            }
+        }
+    }
+    nearest
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TipStat {
+    pub time: DateTime<Utc>,
+    pub ahead: usize,
+}
+
+// Stops at the trunk, so a branch costs only its own commits
+pub fn ahead_of_trunk(dag: &Dag, tip: Oid, on_trunk: &HashSet<Oid>) -> usize {
+    let mut seen = HashSet::new();
+    let mut stack = vec![tip];
+    while let Some(oid) = stack.pop() {
+        if on_trunk.contains(&oid) || !seen.insert(oid) {
+            continue;
+        }
+        if let Some(node) = dag.nodes.get(&oid) {
+            stack.extend(
+                node.commit
+                    .parents
+                    .iter()
+                    .filter(|p| dag.nodes.contains_key(p)),
+            );
+        }
+    }
+    seen.len()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RebasePlan {
+    pub replayed: Vec<Oid>,
+    // As git rebase does without --rebase-merges
+    pub merges_dropped: usize,
+    // None when the histories don't meet in the loaded commits
+    pub base: Option<Oid>,
+}
+
+impl RebasePlan {
+    pub fn is_noop(&self, onto: Oid) -> bool {
+        self.replayed.is_empty() || self.base == Some(onto)
+    }
+}
+
+pub fn rebase_plan(dag: &Dag, tip: Oid, onto: Oid) -> RebasePlan {
+    let on_onto = dag.reachable_from([onto]);
+    let on_branch = dag.reachable_from([tip]);
+    let mut plan = RebasePlan {
+        replayed: Vec::new(),
+        merges_dropped: 0,
+        base: dag
+            .topo_order
+            .iter()
+            .find(|oid| on_onto.contains(oid) && on_branch.contains(oid))
+            .copied(),
+    };
+    for oid in dag.topo_order.iter().rev() {
+        if !on_branch.contains(oid) || on_onto.contains(oid) {
+            continue;
+        }
+        if dag.nodes[oid].commit.parents.len() > 1 {
+            plan.merges_dropped += 1;
+        } else {
+            plan.replayed.push(*oid);
+        }
+    }
+    plan
+}
+
+fn first_parent_distances(dag: &Dag, on_trunk: &HashSet<Oid>) -> HashMap<Oid, usize> {
+    let mut out: HashMap<Oid, usize> = HashMap::with_capacity(dag.topo_order.len());
+    // Oldest first, so a first parent is always settled before its child
+    for oid in dag.topo_order.iter().rev() {
+        if on_trunk.contains(oid) {
+            out.insert(*oid, 0);
+            continue;
+        }
+        let parent = dag.nodes[oid].commit.parents.first();
+        if let Some(&ahead) = parent.and_then(|p| out.get(p)) {
+            out.insert(*oid, ahead + 1);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::sample_repo;
+
+    #[test]
+    fn distances_count_first_parent_steps_off_trunk() {
+        let data = sample_repo();
+        let dag = Dag::from_repo_data(&data);
+        let oid = crate::test_utils::make_oid;
+
+        // No configured trunk exists, so HEAD's branch stands in
+        let trunks = trunk_tips(&data, &["production".to_string()]);
+        assert_eq!(trunks, vec![("main".to_string(), oid(1))]);
+        let distances = trunk_distances(&dag, &trunks);
+        assert_eq!(distances[&oid(5)].label(), "main");
+        assert_eq!(distances[&oid(7)].label(), "main+1");
+
+        let trunks = trunk_tips(&data, &["fix/empty-config".to_string(), "main".to_string()]);
+        let distances = trunk_distances(&dag, &trunks);
+        assert_eq!(distances[&oid(7)].label(), "fix/empty-config");
+        // On both; the first trunk wins the tie
+        assert_eq!(distances[&oid(6)].label(), "fix/empty-config");
+        assert_eq!(distances[&oid(2)].label(), "main");
+
+        let on_main = dag.reachable_from([oid(1)]);
+        assert_eq!(ahead_of_trunk(&dag, oid(5), &on_main), 0);
+        assert_eq!(ahead_of_trunk(&dag, oid(7), &on_main), 1);
+        let on_fix = dag.reachable_from([oid(7)]);
+        // Merge, bump, the two login commits and the release
+        assert_eq!(ahead_of_trunk(&dag, oid(1), &on_fix), 5);
+    }
+
+    #[test]
+    fn rebase_plan_replays_what_onto_lacks_oldest_first() {
+        let data = sample_repo();
+        let dag = Dag::from_repo_data(&data);
+        let oid = crate::test_utils::make_oid;
+
+        let plan = rebase_plan(&dag, oid(5), oid(7));
+        assert_eq!(plan.replayed, vec![oid(3), oid(4), oid(5)]);
+        assert_eq!(plan.base, Some(oid(6)));
+        assert!(!plan.is_noop(oid(7)));
+
+        // main's merge is dropped, its other commits replayed
+        let plan = rebase_plan(&dag, oid(1), oid(7));
+        assert_eq!(plan.merges_dropped, 1);
+        assert_eq!(plan.replayed.len(), 4);
+
+        assert!(rebase_plan(&dag, oid(5), oid(1)).is_noop(oid(1)), "merged");
+        assert!(rebase_plan(&dag, oid(1), oid(2)).is_noop(oid(2)), "on top");
+    }
+}
@@ -1,96 +1,301 @@
-use crate::git::types::{Oid, RepoData};
-use std::collections::{HashMap, HashSet, VecDeque};
+use crate::git::types::{BranchInfo, CommitInfo, Oid, RepoData};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
-pub fn filter_by_author(data: &mut RepoData, author_query: &str) {
+pub fn filter_by_author(data: &RepoData, author_query: &str) -> RepoData {
     let query = author_query.to_lowercase();
-    let commit_map: HashMap<_, _> = data.commits.iter().map(|c| (c.oid, c)).collect();
+    filter_commits(data, |c| author_matches(c, &query))
+}
+
+// A query starting with @ matches the start of the email domain; subdomains count
+pub fn author_matches(commit: &CommitInfo, query: &str) -> bool {
+    match query.strip_prefix('@') {
+        Some(domain) => commit.email.rsplit_once('@').is_some_and(|(_, host)| {
+            let host = host.to_lowercase();
+            host.starts_with(domain) || host.ends_with(&format!(".{domain}"))
+        }),
+        None => commit.author.to_lowercase().contains(query),
+    }
+}
+
+// Case-insensitive; * matches any run of characters
+pub fn author_excluded(author: &str, patterns: &[String]) -> bool {
+    let author = author.to_lowercase();
+    patterns
+        .iter()
+        .any(|p| wildcard_match(&p.to_lowercase(), &author))
+}
+
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = text.strip_prefix(parts.next().unwrap_or("")) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No `*`: the whole text must match
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
 
-    let matching: HashSet<Oid> = data
-        .commits
+// Only commits whose parents change are copied
+#[tracing::instrument(skip_all, fields(commits = data.commits.len()))]
+pub fn filter_commits(data: &RepoData, keep: impl Fn(&CommitInfo) -> bool) -> RepoData {
+    let commits = &data.commits;
+    let index_of: HashMap<Oid, usize> = commits
         .iter()
-        .filter(|c| c.author.to_lowercase().contains(&query))
-        .map(|c| c.oid)
+        .enumerate()
+        .map(|(i, c)| (c.oid, i))
         .collect();
+    let matching: Vec<bool> = commits.iter().map(|c| keep(c)).collect();
 
-    let matching_branches: Vec<_> = data
-        .branches
+    // Nearest matching ancestors per commit. Commits are in topo order
+    // (children first), so walking backwards sees parents before children.
+    let mut skip: Vec<Vec<Oid>> = vec![Vec::new(); commits.len()];
+    for (i, commit) in commits.iter().enumerate().rev() {
+        if matching[i] {
+            skip[i] = vec![commit.oid];
+            continue;
+        }
+        skip[i] = nearest_matches(&commit.parents, &index_of, &skip);
+    }
+
+    let filtered: Vec<Arc<CommitInfo>> = commits
         .iter()
-        .filter(|branch| {
-            let mut visited = HashSet::new();
-            let mut queue = VecDeque::new();
-            queue.push_back(branch.tip);
-            while let Some(oid) = queue.pop_front() {
-                if !visited.insert(oid) {
-                    continue;
-                }
-                if matching.contains(&oid) {
-                    return true;
-                }
-                if let Some(commit) = commit_map.get(&oid) {
-                    queue.extend(commit.parents.iter().copied());
-                }
+        .enumerate()
+        .filter(|&(i, _)| matching[i])
+        .map(|(_, commit)| {
+            let parents = nearest_matches(&commit.parents, &index_of, &skip);
+            if parents == commit.parents {
+                Arc::clone(commit)
+            } else {
+                Arc::new(CommitInfo {
+                    parents,
+                    ..CommitInfo::clone(commit)
+                })
             }
-            false
         })
+        .collect();
+
+    // A branch survives if a matching commit is reachable from its tip; it
+    // then points at the nearest one.
+    let branches: Vec<BranchInfo> = data
+        .branches
+        .iter()
+        .filter_map(|branch| {
+            let &i = index_of.get(&branch.tip)?;
+            let tip = *skip[i].first()?;
+            Some(BranchInfo {
+                tip,
+                ..branch.clone()
+            })
+        })
+        .collect();
+
+    RepoData {
+        commits: filtered,
+        branch_tips: branches.iter().map(|b| b.tip).collect(),
+        branches,
+        tags: data.tags.clone(),
+        head: data.head,
+        shallow: data.shallow.clone(),
+        replaced: data.replaced.clone(),
+        stashes: data.stashes.clone(),
+        submodules: data.submodules.clone(),
+    }
+}
+
+pub fn reachable_from(data: &RepoData, tip: Oid) -> RepoData {
+    let by_oid: HashMap<Oid, &Arc<CommitInfo>> = data.commits.iter().map(|c| (c.oid, c)).collect();
+    let mut reachable = HashSet::new();
+    let mut stack = vec![tip];
+    while let Some(oid) = stack.pop() {
+        let Some(commit) = by_oid.get(&oid) else {
+            continue;
+        };
+        if reachable.insert(oid) {
+            stack.extend(commit.parents.iter().copied());
+        }
+    }
+
+    let branches: Vec<BranchInfo> = data
+        .branches
+        .iter()
+        .filter(|b| reachable.contains(&b.tip))
         .cloned()
         .collect();
+    RepoData {
+        commits: data
+            .commits
+            .iter()
+            .filter(|c| reachable.contains(&c.oid))
+            .cloned()
+            .collect(),
+        branch_tips: branches.iter().map(|b| b.tip).collect(),
+        branches,
+        tags: data
+            .tags
+            .iter()
+            .filter(|t| reachable.contains(&t.target))
+            .cloned()
+            .collect(),
+        head: data.head.filter(|h| reachable.contains(h)),
+        shallow: data
+            .shallow
+            .iter()
+            .filter(|oid| reachable.contains(oid))
+            .copied()
+            .collect(),
+        replaced: data
+            .replaced
+            .iter()
+            .filter(|oid| reachable.contains(oid))
+            .copied()
+            .collect(),
+        stashes: data
+            .stashes
+            .iter()
+            .filter(|s| reachable.contains(&s.base))
+            .cloned()
+            .collect(),
+        submodules: data.submodules.clone(),
+    }
+}
 
-    let mut skip_cache: HashMap<Oid, Vec<Oid>> = HashMap::new();
-    for commit in data.commits.iter().rev() {
-        if matching.contains(&commit.oid) {
-            skip_cache.insert(commit.oid, vec![commit.oid]);
-        } else {
-            let mut ancestors = Vec::new();
-            let mut seen = HashSet::new();
-            for &parent in &commit.parents {
-                if let Some(parent_ancestors) = skip_cache.get(&parent) {
-                    for &a in parent_ancestors {
-                        if seen.insert(a) {
-                            ancestors.push(a);
-                        }
-                    }
+fn nearest_matches(parents: &[Oid], index_of: &HashMap<Oid, usize>, skip: &[Vec<Oid>]) -> Vec<Oid> {
+    let mut out = Vec::new();
+    let mut seen = HashSet::new();
+    for parent in parents {
+        if let Some(&p) = index_of.get(parent) {
+            for &a in &skip[p] {
+                if seen.insert(a) {
+                    out.push(a);
                 }
             }
-            skip_cache.insert(commit.oid, ancestors);
         }
     }
+    out
+}
 
-    for commit in &mut data.commits {
-        if !matching.contains(&commit.oid) {
-            continue;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::types::CommitSource;
+    use crate::test_utils::*;
+
+    fn authored(val: u8, parents: Vec<u8>, author: &str) -> CommitInfo {
+        CommitInfo {
+            author: author.to_string(),
+            ..make_commit(val, parents, 100 - val as i64)
         }
-        let mut new_parents = Vec::new();
-        let mut seen = HashSet::new();
-        for &parent in &commit.parents {
-            if matching.contains(&parent) {
-                if seen.insert(parent) {
-                    new_parents.push(parent);
-                }
-            } else if let Some(ancestors) = skip_cache.get(&parent) {
-                for &a in ancestors {
-                    if seen.insert(a) {
-                        new_parents.push(a);
-                    }
-                }
-            }
+    }
+
+    fn branch(name: &str, tip: u8) -> BranchInfo {
+        BranchInfo {
+            name: name.to_string(),
+            tip: make_oid(tip),
+            is_head: false,
+            source: CommitSource::Local,
+            upstream: None,
         }
-        commit.parents = new_parents;
     }
 
-    data.commits.retain(|c| matching.contains(&c.oid));
+    #[test]
+    fn rewires_parents_and_retargets_branches() {
+        let data = make_repo_data(
+            vec![
+                authored(3, vec![2], "Alice"),
+                authored(4, vec![2], "Bob"),
+                authored(2, vec![1], "Bob"),
+                authored(1, vec![], "Alice"),
+            ],
+            vec![branch("main", 3), branch("bob", 4)],
+        );
 
-    data.branches = matching_branches
-        .into_iter()
-        .filter_map(|mut branch| {
-            if matching.contains(&branch.tip) {
-                Some(branch)
-            } else {
-                let ancestors = skip_cache.get(&branch.tip)?;
-                branch.tip = *ancestors.first()?;
-                Some(branch)
-            }
-        })
-        .collect();
+        let filtered = filter_by_author(&data, "alice");
+
+        let oids: Vec<_> = filtered.commits.iter().map(|c| c.oid).collect();
+        assert_eq!(oids, vec![make_oid(3), make_oid(1)]);
+        assert_eq!(filtered.commits[0].parents, vec![make_oid(1)]);
+        // Unchanged commits are shared, not copied
+        assert!(Arc::ptr_eq(&filtered.commits[1], &data.commits[3]));
 
-    data.branch_tips = data.branches.iter().map(|b| b.tip).collect();
+        let bob = filtered.branches.iter().find(|b| b.name == "bob").unwrap();
+        assert_eq!(bob.tip, make_oid(1));
+    }
+
+    #[test]
+    fn author_patterns_match_wildcards() {
+        let patterns = vec!["*[bot]".to_string(), "Dependabot*".to_string()];
+        assert!(author_excluded("github-actions[bot]", &patterns));
+        assert!(author_excluded("dependabot-preview", &patterns));
+        assert!(!author_excluded("Alice", &patterns));
+        assert!(!author_excluded("[bot] fan", &patterns));
+
+        let exact = vec!["renovate".to_string(), "a*b*c".to_string()];
+        assert!(author_excluded("Renovate", &exact));
+        assert!(!author_excluded("renovate-bot", &exact));
+        assert!(author_excluded("abxbc", &exact));
+        assert!(!author_excluded("acb", &exact));
+    }
+
+    #[test]
+    fn at_queries_match_email_domains() {
+        let by = |email: &str| CommitInfo {
+            email: email.to_string(),
+            ..authored(1, vec![], "Alice")
+        };
+        assert!(author_matches(&by("alice@mycompany.com"), "@mycompany.com"));
+        assert!(author_matches(
+            &by("alice@eu.MyCompany.com"),
+            "@mycompany.com"
+        ));
+        assert!(author_matches(&by("alice@mycompany.com"), "@myco"));
+        assert!(!author_matches(
+            &by("alice@notmycompany.com"),
+            "@mycompany.com"
+        ));
+        assert!(!author_matches(&by(""), "@mycompany.com"));
+        // Plain queries still go by name
+        assert!(author_matches(&by("x@y.z"), "ali"));
+        assert!(!author_matches(&by("ali@y.z"), "y.z"));
+    }
+
+    #[test]
+    fn reachable_keeps_only_tip_history() {
+        let data = make_repo_data(
+            vec![
+                make_commit(4, vec![2], 4),
+                make_commit(3, vec![2], 3),
+                make_commit(2, vec![1], 2),
+                make_commit(1, vec![], 1),
+            ],
+            vec![branch("main", 3), branch("feature", 4)],
+        );
+
+        let main = reachable_from(&data, make_oid(3));
+        let oids: Vec<_> = main.commits.iter().map(|c| c.oid).collect();
+        assert_eq!(oids, vec![make_oid(3), make_oid(2), make_oid(1)]);
+        assert_eq!(main.branches.len(), 1);
+        assert_eq!(main.branches[0].name, "main");
+        assert!(!main.branch_tips.contains(&make_oid(4)));
+    }
+
+    #[test]
+    fn drops_branches_without_matches() {
+        let data = make_repo_data(
+            vec![authored(2, vec![1], "Bob"), authored(1, vec![], "Bob")],
+            vec![branch("main", 2)],
+        );
+        let filtered = filter_by_author(&data, "alice");
+        assert!(filtered.commits.is_empty());
+        assert!(filtered.branches.is_empty());
+    }
 }
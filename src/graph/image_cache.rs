@@ -47,6 +47,8 @@ pub struct ImageCache {
     png_cache: HashMap<CacheKey, Vec<u8>>,
     max_lanes: usize,
     dirty: bool,
+    hits: u64,
+    misses: u64,
 }
 
 impl ImageCache {
@@ -55,9 +57,15 @@ impl ImageCache {
             png_cache: HashMap::new(),
             max_lanes: 0,
             dirty: false,
+            hits: 0,
+            misses: 0,
         }
     }
 
+    pub fn stats(&self) -> (u64, u64) {
+        (self.hits, self.misses)
+    }
+
     pub fn take_dirty(&mut self) -> bool {
         let was = self.dirty;
         self.dirty = false;
@@ -79,7 +87,10 @@ impl ImageCache {
     ) -> Option<&[u8]> {
         let key = CacheKey::from_layout(layout, trunk_count);
 
-        if !self.png_cache.contains_key(&key) {
+        if self.png_cache.contains_key(&key) {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
             if self.png_cache.len() >= MAX_CACHE_ENTRIES {
                 self.png_cache.clear();
             }
@@ -19,6 +19,7 @@ impl GraphRow {
     }
 }
 
+#[tracing::instrument(skip_all, fields(commits = dag.topo_order.len()))]
 pub fn compute_layout(dag: &Dag, repo_data: &RepoData, trunk_branches: &[String]) -> LayoutResult {
     let branch_map = build_branch_map(&repo_data.branches);
     let tag_map = build_tag_map(&repo_data.tags);
@@ -187,6 +188,7 @@ pub fn compute_layout(dag: &Dag, repo_data: &RepoData, trunk_branches: &[String]
             oid: *oid,
             message: node.commit.message.clone(),
             author: node.commit.author.clone(),
+            email: node.commit.email.clone(),
             time: node.commit.time,
             source: node.commit.source.clone(),
             branch_names,
@@ -204,12 +206,14 @@ pub fn compute_layout(dag: &Dag, repo_data: &RepoData, trunk_branches: &[String]
         .map(|r| num_lanes_for_layout(&r.layout))
         .max()
         .unwrap_or(1);
+    tracing::debug!(rows = rows.len(), max_lanes, "layout computed");
 
     LayoutResult {
         rows,
         branch_index_to_name,
         trunk_count,
         max_lanes,
+        lane_overflow: state.overflowed,
     }
 }
 
@@ -325,7 +329,7 @@ pub fn rasterize_to_cells(layout: &RowLayout) -> Vec<Cell> {
     cells
 }
 
-fn duration_bucket(time: &chrono::DateTime<chrono::Utc>) -> (i64, &'static str) {
+pub fn duration_bucket(time: &chrono::DateTime<chrono::Utc>) -> (i64, &'static str) {
     let dur = chrono::Utc::now().signed_duration_since(*time);
     if dur.num_seconds() < 60 {
         (dur.num_seconds(), "s")
@@ -366,6 +370,7 @@ mod tests {
                 tip: commits[0].oid,
                 is_head: true,
                 source: CommitSource::Local,
+                upstream: None,
             }],
         );
         let dag = Dag::from_repo_data(&data);
@@ -444,12 +449,14 @@ mod tests {
                     tip: commits[0].oid,
                     is_head: true,
                     source: CommitSource::Local,
+                    upstream: None,
                 },
                 BranchInfo {
                     name: "feat".to_string(),
                     tip: commits[1].oid,
                     is_head: false,
                     source: CommitSource::Local,
+                    upstream: None,
                 },
             ],
         );
@@ -468,6 +475,7 @@ mod tests {
                 tip: make_oid(1),
                 is_head: true,
                 source: CommitSource::Local,
+                upstream: None,
             }],
         );
         let dag = Dag::from_repo_data(&data);
@@ -495,12 +503,14 @@ mod tests {
                     tip: make_oid(1),
                     is_head: true,
                     source: CommitSource::Local,
+                    upstream: None,
                 },
                 BranchInfo {
                     name: "feat/x".to_string(),
                     tip: make_oid(2),
                     is_head: false,
                     source: CommitSource::Local,
+                    upstream: None,
                 },
             ],
         );
@@ -561,12 +571,14 @@ mod tests {
                     tip: make_oid(1),
                     is_head: true,
                     source: CommitSource::Local,
+                    upstream: None,
                 },
                 BranchInfo {
                     name: "feat/x".to_string(),
                     tip: make_oid(2),
                     is_head: false,
                     source: CommitSource::Local,
+                    upstream: None,
                 },
             ],
         );
@@ -599,12 +611,14 @@ mod tests {
                     tip: make_oid(1),
                     is_head: true,
                     source: CommitSource::Local,
+                    upstream: None,
                 },
                 BranchInfo {
                     name: "feat/x".to_string(),
                     tip: make_oid(2),
                     is_head: false,
                     source: CommitSource::Local,
+                    upstream: None,
                 },
             ],
         );
@@ -703,6 +717,7 @@ mod tests {
                 tip: make_oid(1),
                 is_head: true,
                 source: CommitSource::Local,
+                upstream: None,
             }],
         );
         let dag = Dag::from_repo_data(&data);
@@ -729,12 +744,14 @@ mod tests {
                     tip: make_oid(1),
                     is_head: true,
                     source: CommitSource::Local,
+                    upstream: None,
                 },
                 BranchInfo {
                     name: "feat/x".to_string(),
                     tip: make_oid(2),
                     is_head: false,
                     source: CommitSource::Local,
+                    upstream: None,
                 },
             ],
         );
@@ -755,3 +772,176 @@ mod tests {
         }
     }
 }
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use crate::git::types::*;
+    use crate::test_utils::*;
+    use proptest::prelude::*;
+    use proptest::sample::Index;
+
+    // parents[i] are older commits (< i); tips[0] is main
+    fn build_repo(parents: &[Vec<usize>], tips: &[usize]) -> RepoData {
+        let base = chrono::Utc::now() - chrono::Duration::days(1);
+        let mut commits: Vec<CommitInfo> = parents
+            .iter()
+            .enumerate()
+            .map(|(i, ps)| CommitInfo {
+                oid: index_oid(i),
+                parents: ps.iter().map(|&p| index_oid(p)).collect(),
+                message: format!("commit {i}"),
+                author: "test".to_string(),
+                email: "test@example.com".to_string(),
+                time: base + chrono::Duration::minutes(i as i64),
+                source: CommitSource::Local,
+            })
+            .collect();
+        let branches = tips
+            .iter()
+            .enumerate()
+            .map(|(b, &t)| BranchInfo {
+                name: if b == 0 {
+                    "main".to_string()
+                } else {
+                    format!("b{b}")
+                },
+                tip: index_oid(t),
+                is_head: b == 0,
+                source: CommitSource::Local,
+                upstream: None,
+            })
+            .collect();
+        commits.reverse();
+        make_repo_data(commits, branches)
+    }
+
+    fn history() -> impl Strategy<Value = (RepoData, Vec<String>)> {
+        (
+            prop::collection::vec(prop::collection::vec(any::<Index>(), 0..4), 1..80),
+            prop::collection::vec(any::<Index>(), 0..8),
+            0usize..3,
+        )
+            .prop_map(|(picks, tip_picks, trunks)| {
+                let parents: Vec<Vec<usize>> = picks
+                    .iter()
+                    .enumerate()
+                    .map(|(i, ps)| {
+                        let mut out: Vec<usize> = Vec::new();
+                        for p in ps.iter().filter(|_| i > 0).map(|p| p.index(i)) {
+                            if !out.contains(&p) {
+                                out.push(p);
+                            }
+                        }
+                        out
+                    })
+                    .collect();
+                let n = parents.len();
+                let mut tips = vec![n - 1];
+                for t in tip_picks.iter().map(|t| t.index(n)) {
+                    if !tips.contains(&t) {
+                        tips.push(t);
+                    }
+                }
+                let data = build_repo(&parents, &tips);
+                let trunk_names = data
+                    .branches
+                    .iter()
+                    .take(trunks)
+                    .map(|b| b.name.clone())
+                    .collect();
+                (data, trunk_names)
+            })
+    }
+
+    fn occupied(row: &GraphRow) -> Vec<usize> {
+        let mut lanes: Vec<usize> = row
+            .layout
+            .passthrough_lanes
+            .iter()
+            .map(|p| p.lane)
+            .collect();
+        lanes.push(row.layout.commit_lane);
+        lanes
+    }
+
+    fn check_invariants(data: &RepoData, trunks: &[String]) -> Result<(), TestCaseError> {
+        let dag = Dag::from_repo_data(data);
+        let result = compute_layout(&dag, data, trunks);
+        prop_assert_eq!(result.rows.len(), data.commits.len());
+        prop_assert!(result.max_lanes <= MAX_LANES);
+
+        for (r, row) in result.rows.iter().enumerate() {
+            let commit_cells = row
+                .cells
+                .iter()
+                .filter(|c| c.symbol == CellSymbol::Commit)
+                .count();
+            prop_assert_eq!(
+                commit_cells,
+                1,
+                "row {} has {} commit cells",
+                r,
+                commit_cells
+            );
+            prop_assert_eq!(
+                &row.cells[row.layout.commit_lane].symbol,
+                &CellSymbol::Commit
+            );
+
+            for edge in &row.layout.edges {
+                prop_assert_eq!(edge.from_lane, row.layout.commit_lane);
+                if let Some(next) = result.rows.get(r + 1) {
+                    prop_assert!(
+                        occupied(next).contains(&edge.to_lane),
+                        "row {} edge to lane {} is empty in the next row",
+                        r,
+                        edge.to_lane
+                    );
+                }
+            }
+
+            let is_trunk = is_trunk_branch(row.meta.branch_index, result.trunk_count);
+            if row.layout.commit_lane < result.trunk_count {
+                prop_assert!(is_trunk, "non-trunk commit in reserved lane, row {}", r);
+            }
+            // Two trunks may share a tip, so any reserved lane will do
+            if row.meta.branch_names.iter().any(|n| trunks.contains(n)) {
+                prop_assert!(
+                    row.layout.commit_lane < result.trunk_count,
+                    "trunk tip in row {} outside the reserved lanes",
+                    r
+                );
+            }
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn layout_invariants_hold((data, trunks) in history()) {
+            check_invariants(&data, &trunks)?;
+        }
+    }
+
+    fn wide_fan(width: usize) -> RepoData {
+        let mut parents: Vec<Vec<usize>> = vec![vec![]];
+        parents.extend((0..width).map(|_| vec![0]));
+        parents.extend((0..width).map(|b| vec![1 + b]));
+        parents.push((0..width).map(|b| 1 + width + b).collect());
+        let top = parents.len() - 1;
+        build_repo(&parents, &[top])
+    }
+
+    #[test]
+    fn no_panic_past_max_lanes() {
+        for width in [MAX_LANES - 1, MAX_LANES, MAX_LANES + 1, MAX_LANES * 2] {
+            let data = wide_fan(width);
+            let dag = Dag::from_repo_data(&data);
+            let result = compute_layout(&dag, &data, &["main".to_string()]);
+            assert_eq!(result.rows.len(), data.commits.len());
+            assert!(result.max_lanes <= MAX_LANES, "width {width}");
+            assert_eq!(result.lane_overflow, width > MAX_LANES, "width {width}");
+        }
+    }
+}
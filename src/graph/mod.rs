@@ -1,5 +1,6 @@
 pub mod branch_assign;
 pub mod dag;
+pub mod drift;
 pub mod filter;
 pub mod image_cache;
 pub mod layout;
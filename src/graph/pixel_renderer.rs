@@ -1,10 +1,15 @@
-use crate::graph::types::{EdgeKind, RowLayout};
+#[cfg(feature = "kitty")]
+use crate::graph::types::EdgeKind;
+use crate::graph::types::RowLayout;
 use crate::ui::theme::ThemePalette;
+#[cfg(feature = "kitty")]
 use ratatui::style::Color;
+#[cfg(feature = "kitty")]
 use tiny_skia::{FillRule, LineCap, LineJoin, Paint, PathBuilder, Pixmap, Stroke, Transform};
 
 pub const COLS_PER_LANE: u16 = 2;
 
+#[cfg_attr(not(feature = "kitty"), allow(dead_code))]
 pub struct RenderParams {
     pub cell_height: u16,
     pub line_width: f32,
@@ -24,6 +29,7 @@ impl RenderParams {
     }
 }
 
+#[cfg(feature = "kitty")]
 fn color_to_skia(c: Color) -> tiny_skia::Color {
     match c {
         Color::Rgb(r, g, b) => tiny_skia::Color::from_rgba8(r, g, b, 255),
@@ -43,10 +49,12 @@ fn color_to_skia(c: Color) -> tiny_skia::Color {
     }
 }
 
+#[cfg(feature = "kitty")]
 fn lane_center_x(lane: usize, lane_width: f32) -> f32 {
     lane as f32 * lane_width + lane_width / 2.0
 }
 
+#[cfg(feature = "kitty")]
 pub fn render_row_image(
     layout: &RowLayout,
     params: &RenderParams,
@@ -158,7 +166,19 @@ pub fn render_row_image(
     pixmap.encode_png().ok()
 }
 
-#[cfg(test)]
+// Unreachable: graphics detection reports Unsupported without kitty
+#[cfg(not(feature = "kitty"))]
+pub fn render_row_image(
+    _layout: &RowLayout,
+    _params: &RenderParams,
+    _palette: &ThemePalette,
+    _trunk_count: usize,
+    _total_lanes: usize,
+) -> Option<Vec<u8>> {
+    None
+}
+
+#[cfg(all(test, feature = "kitty"))]
 mod tests {
     use super::*;
     use crate::graph::types::{num_lanes_for_layout, Edge, EdgeKind, LaneOccupant, RowLayout};
@@ -75,6 +75,7 @@ pub struct RowMeta {
     pub oid: Oid,
     pub message: String,
     pub author: String,
+    pub email: String,
     pub time: DateTime<Utc>,
     pub source: CommitSource,
     pub branch_names: Vec<String>,
@@ -98,6 +99,8 @@ pub struct LayoutResult {
     pub branch_index_to_name: HashMap<usize, String>,
     pub trunk_count: usize,
     pub max_lanes: usize,
+    // The extras past MAX_LANES share the last lane
+    pub lane_overflow: bool,
 }
 
 pub fn num_lanes_for_layout(layout: &RowLayout) -> usize {
@@ -117,6 +120,7 @@ pub const MAX_LANES: usize = 64;
 pub struct LayoutState {
     pub columns: Vec<Option<Oid>>,
     pub reserved_count: usize,
+    pub overflowed: bool,
 }
 
 impl LayoutState {
@@ -126,6 +130,7 @@ impl LayoutState {
         Self {
             columns,
             reserved_count: reserved,
+            overflowed: false,
         }
     }
 
@@ -146,7 +151,10 @@ impl LayoutState {
             self.columns[idx] = Some(oid);
             idx
         } else if self.columns.len() >= MAX_LANES {
-            eprintln!("warning: MAX_LANES exceeded; reusing last column");
+            if !self.overflowed {
+                tracing::warn!("MAX_LANES exceeded; reusing last column");
+                self.overflowed = true;
+            }
             let last = self.columns.len() - 1;
             self.columns[last] = Some(oid);
             last
@@ -0,0 +1,263 @@
+use crate::config::{config_dir, state_dir, Config, RepoEntry};
+use std::path::{Path, PathBuf};
+
+// Each one is a pane
+const MAX_IMPORTED_REPOS: usize = 4;
+
+// Only what has an arachne counterpart is read; arachne's keys are fixed
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Imported {
+    pub repos: Vec<PathBuf>,
+    pub theme: Option<&'static str>,
+    pub diff_pager: Option<String>,
+    pub skipped_keybindings: bool,
+}
+
+impl Imported {
+    pub fn is_empty(&self) -> bool {
+        self.repos.is_empty() && self.theme.is_none() && self.diff_pager.is_none()
+    }
+
+    fn or(mut self, other: Imported) -> Self {
+        if self.repos.is_empty() {
+            self.repos = other.repos;
+        }
+        self.theme = self.theme.or(other.theme);
+        self.diff_pager = self.diff_pager.or(other.diff_pager);
+        self.skipped_keybindings |= other.skipped_keybindings;
+        self
+    }
+
+    // Keeps anything already set
+    pub fn apply(&self, config: &mut Config) {
+        if config.repos.is_empty() {
+            config.repos = self
+                .repos
+                .iter()
+                .map(|path| RepoEntry {
+                    path: path.clone(),
+                    name: None,
+                })
+                .collect();
+        }
+        if config.theme.is_none() {
+            config.theme = self.theme.map(str::to_string);
+        }
+        if config.diff_pager.is_none() {
+            config.diff_pager = self.diff_pager.clone();
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        match self.repos.len() {
+            0 => {}
+            1 => parts.push("1 repo".to_string()),
+            n => parts.push(format!("{n} repos")),
+        }
+        if self.theme.is_some() {
+            parts.push("theme".to_string());
+        }
+        if self.diff_pager.is_some() {
+            parts.push("pager".to_string());
+        }
+        if self.skipped_keybindings {
+            parts.push("keybindings skipped".to_string());
+        }
+        parts.join(", ")
+    }
+}
+
+pub fn import_all() -> (Vec<&'static str>, Imported) {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let read = |path: &Path| std::fs::read_to_string(path).ok();
+    let found = [
+        ("lazygit", lazygit(&read)),
+        (
+            "gitui",
+            read(&config_dir().join("gitui/theme.ron")).map(|text| {
+                let mut imported = parse_gitui_theme(&text);
+                imported.skipped_keybindings = ["key_bindings.ron", "key_config.ron"]
+                    .iter()
+                    .any(|f| config_dir().join("gitui").join(f).exists());
+                imported
+            }),
+        ),
+        (
+            "tig",
+            [
+                home.as_ref().map(|h| h.join(".tigrc")),
+                Some(config_dir().join("tig/config")),
+            ]
+            .into_iter()
+            .flatten()
+            .find_map(|path| read(&path))
+            .map(|text| parse_tigrc(&text)),
+        ),
+    ];
+    let mut tools = Vec::new();
+    let mut out = Imported::default();
+    for (tool, imported) in found {
+        let Some(mut imported) = imported else {
+            continue;
+        };
+        imported.repos.retain(|path| path.join(".git").exists());
+        imported.repos.truncate(MAX_IMPORTED_REPOS);
+        if !imported.is_empty() {
+            tools.push(tool);
+        }
+        out = out.or(imported);
+    }
+    (tools, out)
+}
+
+fn lazygit(read: &dyn Fn(&Path) -> Option<String>) -> Option<Imported> {
+    let config = read(&config_dir().join("lazygit/config.yml")).map(|t| parse_lazygit_config(&t));
+    // Newer lazygit keeps state.yml in the state dir, older beside config.yml
+    let repos = [state_dir(), config_dir()]
+        .iter()
+        .find_map(|dir| read(&dir.join("lazygit/state.yml")))
+        .map(|t| parse_lazygit_state(&t));
+    if config.is_none() && repos.is_none() {
+        return None;
+    }
+    let mut imported = config.unwrap_or_default();
+    imported.repos = repos.unwrap_or_default();
+    Some(imported)
+}
+
+fn parse_lazygit_state(text: &str) -> Vec<PathBuf> {
+    text.lines()
+        .skip_while(|line| line.trim_end() != "recentrepos:")
+        .skip(1)
+        .map_while(|line| line.trim().strip_prefix("- "))
+        .map(|path| PathBuf::from(unquote(path)))
+        .collect()
+}
+
+// A plain line scan: these keys are unique in the file
+fn parse_lazygit_config(text: &str) -> Imported {
+    let mut imported = Imported::default();
+    let mut lines = text.lines().map(str::trim);
+    while let Some(line) = lines.next() {
+        // `pagers:` in newer versions is a list of `- pager: ...`
+        let line = line.strip_prefix("- ").unwrap_or(line);
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key {
+            "activeBorderColor" => {
+                // Either `[green, bold]` or a list on the following lines
+                let first = if value.is_empty() {
+                    lines.next().and_then(|l| l.strip_prefix("- "))
+                } else {
+                    value.trim_start_matches('[').split(',').next()
+                };
+                imported.theme = first.and_then(|c| theme_for_color(unquote(c)));
+            }
+            "pager" if !value.is_empty() => {
+                imported.diff_pager = Some(unquote(value).to_string());
+            }
+            "keybinding" => imported.skipped_keybindings = true,
+            _ => {}
+        }
+    }
+    imported
+}
+
+fn parse_gitui_theme(text: &str) -> Imported {
+    let theme = text
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("selection_bg:"))
+        .map(|value| value.trim().trim_end_matches(','))
+        .map(|value| value.trim_start_matches("Some(").trim_end_matches(')'))
+        .and_then(|color| theme_for_color(unquote(color)));
+    Imported {
+        theme,
+        ..Default::default()
+    }
+}
+
+fn parse_tigrc(text: &str) -> Imported {
+    let mut imported = Imported::default();
+    for line in text.lines().map(str::trim) {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["color", "cursor", _, bg, ..] => imported.theme = theme_for_color(bg),
+            ["bind", ..] => imported.skipped_keybindings = true,
+            _ => {}
+        }
+    }
+    imported
+}
+
+fn theme_for_color(color: &str) -> Option<&'static str> {
+    let color = color.to_ascii_lowercase();
+    let base = color
+        .trim_start_matches("light")
+        .trim_start_matches("bright")
+        .trim_start_matches("dark")
+        .trim_start_matches(['-', '_']);
+    match base {
+        "magenta" | "purple" => Some("Arachne Purple"),
+        "blue" | "black" => Some("Midnight"),
+        "green" => Some("Emerald"),
+        "yellow" | "red" => Some("Amber"),
+        "cyan" | "white" => Some("Frost"),
+        _ => None,
+    }
+}
+
+fn unquote(s: &str) -> &str {
+    s.trim().trim_matches(|c| c == '"' || c == '\'')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_what_maps_from_each_tool() {
+        let state =
+            "lastupdatecheck: 0\nrecentrepos:\n- /src/app\n- '/src/lib'\nstartuppopupversion: 5\n";
+        assert_eq!(
+            parse_lazygit_state(state),
+            vec![PathBuf::from("/src/app"), PathBuf::from("/src/lib")]
+        );
+
+        let config = "gui:\n  theme:\n    activeBorderColor:\n      - green\n      - bold\n\
+                      git:\n  paging:\n    colorArg: always\n    pager: \"delta --dark --paging=never\"\n\
+                      keybinding:\n  universal:\n    quit: 'q'\n";
+        let lazygit = parse_lazygit_config(config);
+        assert_eq!(lazygit.theme, Some("Emerald"));
+        assert_eq!(
+            lazygit.diff_pager.as_deref(),
+            Some("delta --dark --paging=never")
+        );
+        assert!(lazygit.skipped_keybindings);
+        let inline = parse_lazygit_config("activeBorderColor: [magenta, bold]");
+        assert_eq!(inline.theme, Some("Arachne Purple"));
+
+        let gitui = parse_gitui_theme(
+            "(\n    selected_tab: Some(\"Reset\"),\n    selection_bg: Some(\"LightBlue\"),\n)",
+        );
+        assert_eq!(gitui.theme, Some("Midnight"));
+
+        let tig = parse_tigrc("# colors\ncolor cursor black yellow bold\nbind main R !git rebase");
+        assert_eq!(tig.theme, Some("Amber"));
+        assert!(tig.skipped_keybindings);
+
+        // The first tool with a value wins; set config keys are kept
+        let merged = lazygit.or(tig);
+        assert_eq!(merged.theme, Some("Emerald"));
+        let mut config = Config {
+            diff_pager: Some("less".to_string()),
+            ..Config::default()
+        };
+        merged.apply(&mut config);
+        assert_eq!(config.theme.as_deref(), Some("Emerald"));
+        assert_eq!(config.diff_pager.as_deref(), Some("less"));
+        assert_eq!(merged.summary(), "theme, pager, keybindings skipped");
+    }
+}
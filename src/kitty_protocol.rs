@@ -1,7 +1,10 @@
+#[cfg(feature = "kitty")]
 use base64::{engine::general_purpose::STANDARD, Engine};
 
+#[cfg(feature = "kitty")]
 const CHUNK_SIZE: usize = 4096;
 
+#[cfg(feature = "kitty")]
 pub fn encode_kitty_image(image_id: u32, png_bytes: &[u8], cols: u16, rows: u16) -> String {
     let b64 = STANDARD.encode(png_bytes);
     let chunks: Vec<&str> = b64
@@ -30,6 +33,11 @@ pub fn encode_kitty_image(image_id: u32, png_bytes: &[u8], cols: u16, rows: u16)
     out
 }
 
+#[cfg(not(feature = "kitty"))]
+pub fn encode_kitty_image(_image_id: u32, _png_bytes: &[u8], _cols: u16, _rows: u16) -> String {
+    String::new()
+}
+
 pub fn delete_all_kitty_images() -> String {
     "\x1b_Ga=d,d=a,q=2;\x1b\\".to_string()
 }
@@ -39,6 +47,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[cfg(feature = "kitty")]
     fn encode_produces_valid_apc_sequence() {
         let png = vec![0x89, 0x50, 0x4E, 0x47]; // PNG magic header bytes
         let encoded = encode_kitty_image(1, &png, 4, 1);
@@ -48,6 +57,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "kitty")]
     fn encode_chunks_large_payload() {
         let png = vec![0xAA; 8000]; // large enough to require 2+ chunks
         let encoded = encode_kitty_image(2, &png, 8, 1);
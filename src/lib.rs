@@ -0,0 +1,33 @@
+pub mod aggregate;
+pub mod annotations;
+pub mod app;
+pub mod authors;
+pub mod codeowners;
+pub mod compare;
+pub mod config;
+pub mod conventional;
+pub mod crash;
+pub mod data_source;
+pub mod dates;
+pub mod error;
+pub mod event;
+pub mod external;
+pub mod git;
+#[cfg(feature = "github")]
+pub mod github;
+pub mod graph;
+pub mod import;
+pub mod kitty_protocol;
+pub mod logging;
+pub mod project;
+pub mod remote_cache;
+pub mod screen;
+pub mod script;
+pub mod session;
+pub mod stats;
+pub mod terminal_graphics;
+#[cfg(any(test, feature = "bench"))]
+#[doc(hidden)]
+pub mod test_utils;
+pub mod ui;
+pub mod watcher;
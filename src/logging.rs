@@ -0,0 +1,46 @@
+use crate::config::state_dir;
+use std::path::PathBuf;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{fmt::format::FmtSpan, EnvFilter};
+
+const LOG_ENV: &str = "ARACHNE_LOG";
+const DEFAULT_DIRECTIVE: &str = "arachne=debug";
+const MAX_LOG_FILES: usize = 7;
+
+pub fn log_dir() -> PathBuf {
+    state_dir().join("arachne").join("logs")
+}
+
+// The TUI owns the terminal, so logs only ever go to a file
+pub fn init(debug: bool) -> Option<WorkerGuard> {
+    let directive = std::env::var(LOG_ENV).ok().filter(|v| !v.trim().is_empty());
+    if !debug && directive.is_none() {
+        return None;
+    }
+    let filter = directive
+        .and_then(|d| EnvFilter::try_new(d).ok())
+        .unwrap_or_else(|| EnvFilter::new(DEFAULT_DIRECTIVE));
+
+    let dir = log_dir();
+    std::fs::create_dir_all(&dir).ok()?;
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("arachne")
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(&dir)
+        .ok()?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(writer)
+        .with_ansi(false)
+        .with_span_events(FmtSpan::CLOSE)
+        .try_init()
+        .ok()?;
+
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), "logging started");
+    Some(guard)
+}
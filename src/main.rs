@@ -1,58 +1,165 @@
-mod app;
-mod config;
-mod data_source;
-mod error;
-mod event;
-mod git;
-mod github;
-mod graph;
-mod kitty_protocol;
-mod project;
-mod screen;
-mod session;
-mod terminal_graphics;
-#[cfg(test)]
-mod test_utils;
-mod ui;
-mod watcher;
-
-use app::App;
-use clap::Parser;
-use config::Config;
+use arachne::{
+    app::{expand_tilde, App, Offline},
+    authors::AuthorStyle,
+    config::Config,
+    crash, data_source,
+    event::{AppEvent, GitHubData},
+    external,
+    git::repo,
+    logging,
+    project::Project,
+    screen::{ConfigScreenState, Screen},
+    script, session, stats, terminal_graphics,
+    watcher::{self, fs::FsWatcherHandle},
+};
+use clap::{Parser, Subcommand, ValueEnum};
 use crossterm::{
-    event::{Event, EventStream, KeyEventKind},
+    event::{DisableMouseCapture, EnableMouseCapture, Event, EventStream, KeyEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use event::{AppEvent, GitHubData};
 use futures::StreamExt;
-use screen::{ConfigScreenState, Screen};
 use std::collections::HashSet;
+use std::io::Write;
 use std::path::PathBuf;
+use std::time::Duration;
 use tokio::sync::mpsc;
-use tokio::task::JoinHandle;
-use watcher::fs::FsWatcherHandle;
+use tokio_util::sync::CancellationToken;
+
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(2);
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+// Per-frame work is deferred here instead of running per event
+#[derive(Default)]
+struct Batch {
+    events: usize,
+    fs_changed: HashSet<usize>,
+    restart_watchers: bool,
+    resized: bool,
+}
 
 #[derive(Parser)]
 #[command(name = "arachne", about = "TUI git network graph viewer")]
 struct Cli {
     #[arg(long, short, help = "Path to git repository")]
     repo: Option<PathBuf>,
+    #[arg(long, help = "Write a debug log (see also ARACHNE_LOG)")]
+    debug: bool,
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Open this workspace instead of the configured one"
+    )]
+    workspace: Option<String>,
+    #[arg(
+        long,
+        help = "Start offline: no GitHub calls, the remote view from cache"
+    )]
+    offline: bool,
+    #[arg(long, help = "Don't watch repos or poll GitHub; refresh only on r")]
+    manual_refresh: bool,
+    #[arg(
+        long,
+        value_name = "SCRIPT",
+        help = "Commands to run once loaded, e.g. \"filter author:alice; select sha:abc123; view remote\""
+    )]
+    cmd: Option<String>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[command(about = "Print commit counts per author, branch and fork for the configured repos")]
+    Stats {
+        #[arg(
+            long,
+            help = "Only count commits newer than this: 90d, 12w, 1y or 2024-01-31"
+        )]
+        since: Option<String>,
+        #[arg(long, value_enum, default_value_t = StatsFormat::Csv)]
+        format: StatsFormat,
+    },
 }
 
-// git2::Repository is !Send — current_thread avoids the need to shuffle it across threads
-#[tokio::main(flavor = "current_thread")]
+#[derive(Clone, Copy, ValueEnum)]
+enum StatsFormat {
+    Csv,
+    Json,
+}
+
+fn adopt_cwd_repo(config: &mut Config) {
+    let Some(cwd_root) = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| repo::enclosing_root(&cwd))
+    else {
+        return;
+    };
+    let configured = if Config::config_file_exists() {
+        config.resolved_repos()
+    } else {
+        Vec::new()
+    };
+    let covered = configured
+        .iter()
+        .any(|entry| repo::enclosing_root(&expand_tilde(&entry.path)).as_ref() == Some(&cwd_root));
+    if !covered {
+        tracing::info!(path = %cwd_root.display(), "opening repo from working directory");
+        config.adhoc_repo = Some(cwd_root);
+    }
+}
+
+#[tokio::main]
 async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
-    let config = Config::load(cli.repo);
+    let startup_script = match cli.cmd.as_deref().map(script::parse).transpose() {
+        Ok(commands) => commands.unwrap_or_default(),
+        Err(e) => {
+            eprintln!("Error: --cmd {e}");
+            std::process::exit(2);
+        }
+    };
+    let log_guard = logging::init(cli.debug);
+    let explicit_repo = cli.repo.is_some();
+    let mut config = Config::load(cli.repo);
+    if cli.workspace.is_some() {
+        config.workspace = cli.workspace;
+    }
+    if !explicit_repo {
+        adopt_cwd_repo(&mut config);
+    }
+
+    if let Some(Command::Stats { since, format }) = cli.command {
+        // Fork counts come from GitHub
+        if cli.offline {
+            config.show_forks = false;
+        }
+        return print_stats(&config, since.as_deref(), format).await;
+    }
 
-    let graphics_cap = terminal_graphics::detect_graphics_cap();
+    let graphics_cap = if config.reduced_motion {
+        terminal_graphics::GraphicsCapability::Unsupported
+    } else {
+        terminal_graphics::detect_graphics_cap()
+    };
     let poll_interval = config.poll_interval_secs;
-    let is_first_launch = !Config::config_file_exists();
+    let is_first_launch = !Config::config_file_exists() && config.adhoc_repo.is_none();
     let mut app = App::new(config, graphics_cap);
+    if cli.offline {
+        app.offline = Some(Offline::Manual);
+    }
+    if cli.manual_refresh {
+        app.manual_refresh = true;
+    }
+
+    // Created before load_repos so repo reads report back through the event loop
+    let (tx, mut rx) = mpsc::unbounded_channel::<AppEvent>();
+    app.event_tx = Some(tx.clone());
 
     if is_first_launch {
-        app.screen = Screen::Config(Box::new(ConfigScreenState::first_launch(&app.config)));
+        let mut state = ConfigScreenState::first_launch(&app.config);
+        state.import_from_other_tools();
+        app.screen = Screen::Config(Box::new(state));
     } else if let Err(e) = app.load_repos() {
         eprintln!("Error: {e}");
         std::process::exit(1);
@@ -61,58 +168,62 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     // Load session state
     if !is_first_launch {
         session::restore(&mut app);
+        app.script = startup_script.into();
+        app.run_script();
     }
 
     // Install panic hook before entering raw mode so terminal is restored on panic
-    let default_hook = std::panic::take_hook();
-    std::panic::set_hook(Box::new(move |info| {
-        let _ = disable_raw_mode();
-        let _ = execute!(std::io::stdout(), LeaveAlternateScreen);
-        default_hook(info);
-    }));
+    crash::set_config_summary(&app.config);
+    crash::install_panic_hook();
 
     enable_raw_mode()?;
     let mut stdout = std::io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
+    if app.config.mouse {
+        execute!(stdout, EnableMouseCapture)?;
+    }
+    tracing::info!(projects = app.projects.len(), "entering event loop");
     let backend = ratatui::backend::CrosstermBackend::new(stdout);
     let mut terminal = ratatui::Terminal::new(backend)?;
 
-    let (tx, mut rx) = mpsc::unbounded_channel::<AppEvent>();
-    app.event_tx = Some(tx.clone());
-
     let mut watchers: Vec<FsWatcherHandle> = Vec::new();
-    let mut poller_handles: Vec<JoinHandle<()>> = Vec::new();
+    // Child of app.shutdown, replaced whenever watchers/pollers are restarted
+    let mut watch_token = app.shutdown.child_token();
 
     if !is_first_launch {
-        start_watchers_and_pollers(&app, &tx, poll_interval, &mut watchers, &mut poller_handles);
+        start_watchers_and_pollers(&app, &tx, poll_interval, &mut watchers, &watch_token);
     }
 
-    let input_tx = tx.clone();
-    let input_handle = tokio::spawn(async move {
-        let mut reader = EventStream::new();
-        while let Some(Ok(event)) = reader.next().await {
-            let app_event = match event {
-                Event::Key(key) if key.kind == KeyEventKind::Press => Some(AppEvent::Key(key)),
-                Event::Resize(_, _) => Some(AppEvent::Resize),
-                _ => None,
-            };
-            if let Some(e) = app_event {
-                if input_tx.send(e).is_err() {
-                    break;
-                }
-            }
-        }
-    });
+    // Child of app.shutdown, replaced when input is paused for an external program
+    let mut input_token = app.shutdown.child_token();
+    let mut input_task = spawn_input_reader(&app, tx.clone(), input_token.clone());
 
-    loop {
+    let mut channel_closed = false;
+    while !channel_closed {
+        app.sync_detail();
+        app.sync_tag_signatures();
+        app.sync_commit_weights();
+        app.sync_diff_stats();
+        app.sync_trunk_distances();
+        app.sync_lane_overflow();
+        app.expire_flash();
         app.flush_kitty_if_needed(terminal.backend_mut())?;
+        let frame_started = std::time::Instant::now();
         terminal.draw(|f| app.render(f))?;
+        app.perf.record_frame(frame_started.elapsed());
 
-        let first = if app.has_active_notification() {
+        let first = if app.has_active_notification() || app.is_flashing() {
             match tokio::time::timeout(std::time::Duration::from_secs(1), rx.recv()).await {
                 Ok(Some(e)) => Some(e),
                 Ok(None) => break,
-                Err(_) => None, // timeout — redraw to dismiss stale toast
+                Err(_) => None, // timeout — redraw to dismiss stale toast or flash
+            }
+        } else if let Some(park_at) = app.next_park_at() {
+            let park_at = tokio::time::Instant::from_std(park_at);
+            match tokio::time::timeout_at(park_at, rx.recv()).await {
+                Ok(Some(e)) => Some(e),
+                Ok(None) => break,
+                Err(_) => None, // timeout — an off-screen project is due to park
             }
         } else {
             match rx.recv().await {
@@ -121,36 +232,74 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
             }
         };
 
-        let mut fs_changed: HashSet<usize> = HashSet::new();
-        let mut config_saved = false;
+        let mut batch = Batch::default();
         if let Some(e) = first {
-            process_event(&mut app, e, &mut fs_changed, &tx, &mut config_saved);
+            process_event(&mut app, e, &mut batch, &tx);
+        }
+        // Keep absorbing events until the next frame is due, so a held key
+        // or a paste costs one draw per tick rather than one per event.
+        let next_frame = tokio::time::Instant::from_std(frame_started + FRAME_INTERVAL);
+        while !app.should_quit {
+            match rx.try_recv() {
+                Ok(pending) => process_event(&mut app, pending, &mut batch, &tx),
+                Err(mpsc::error::TryRecvError::Disconnected) => {
+                    channel_closed = true;
+                    break;
+                }
+                Err(mpsc::error::TryRecvError::Empty) => {
+                    match tokio::time::timeout_at(next_frame, rx.recv()).await {
+                        Ok(Some(pending)) => process_event(&mut app, pending, &mut batch, &tx),
+                        Ok(None) => {
+                            channel_closed = true;
+                            break;
+                        }
+                        Err(_) => break,
+                    }
+                }
+            }
         }
-        while let Ok(pending) = rx.try_recv() {
-            process_event(&mut app, pending, &mut fs_changed, &tx, &mut config_saved);
+        app.perf.record_backlog(batch.events);
+        tracing::trace!(
+            batch = batch.events,
+            fs_changed = batch.fs_changed.len(),
+            "event batch"
+        );
+        if batch.resized {
+            app.handle_event(AppEvent::Resize);
         }
-        for idx in fs_changed {
+        for idx in batch.fs_changed {
             app.rebuild_graph(idx);
         }
-        if config_saved {
-            // Re-start watchers/pollers after config save (first-launch or profile switch)
-            for w in &watchers {
-                w.debounce_task.abort();
-            }
-            for h in &poller_handles {
-                h.abort();
-            }
+        batch.restart_watchers |= app.park_idle_projects(std::time::Instant::now());
+        if batch.restart_watchers {
+            crash::set_config_summary(&app.config);
+            // Re-start watchers/pollers after config save (first-launch or
+            // profile switch), for a newly opened workspace or around parked
+            // projects
+            watch_token.cancel();
             watchers.clear();
-            poller_handles.clear();
+            watch_token = app.shutdown.child_token();
             start_watchers_and_pollers(
                 &app,
                 &tx,
                 app.config.poll_interval_secs,
                 &mut watchers,
-                &mut poller_handles,
+                &watch_token,
             );
         }
 
+        if let Some(cmd) = app.pending_command.take() {
+            // Stop reading stdin so the program gets the keystrokes
+            input_token.cancel();
+            let _ = input_task.await;
+            app.cleanup_kitty(terminal.backend_mut())?;
+            let result = external::run_suspended(terminal.backend_mut(), &cmd, app.config.mouse);
+            terminal.clear()?;
+            app.finish_external(&cmd, result);
+            input_token = app.shutdown.child_token();
+            input_task = spawn_input_reader(&app, tx.clone(), input_token.clone());
+        }
+
         if app.should_quit {
             break;
         }
@@ -159,47 +308,145 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     // Save session before exit
     session::save(&app);
 
-    input_handle.abort();
-    for w in &watchers {
-        w.debounce_task.abort();
-    }
-    for handle in poller_handles {
-        handle.abort();
-    }
+    drop(watchers);
+    app.drain_tasks(SHUTDOWN_GRACE).await;
 
     app.cleanup_kitty(terminal.backend_mut())?;
 
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(
+        terminal.backend_mut(),
+        DisableMouseCapture,
+        LeaveAlternateScreen
+    )?;
     terminal.show_cursor()?;
 
+    if log_guard.is_some() {
+        tracing::info!("shutdown complete");
+        drop(log_guard);
+        eprintln!("debug log: {}", logging::log_dir().display());
+    }
+
     Ok(())
 }
 
+async fn print_stats(
+    config: &Config,
+    since: Option<&str>,
+    format: StatsFormat,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let since =
+        match since {
+            Some(spec) => Some(stats::parse_since(spec, chrono::Utc::now()).ok_or_else(|| {
+                format!("invalid --since {spec:?}: expected e.g. 90d or 2024-01-31")
+            })?),
+            None => None,
+        };
+
+    let mut rows = Vec::new();
+    for entry in config.resolved_repos() {
+        let git_repo = repo::open_repo(&expand_tilde(&entry.path))?;
+        let name = entry
+            .name
+            .clone()
+            .unwrap_or_else(|| repo::detect_repo_name(&git_repo));
+        let data = repo::read_repo(&git_repo, 0)?;
+
+        let fork_commits = match data_source::init_github_client(config, &name) {
+            Some(client) if config.show_forks => match data_source::fetch_network(&client).await {
+                Ok(network) => network.commits,
+                Err(e) => {
+                    eprintln!("warning: {name}: fork network unavailable: {e}");
+                    Vec::new()
+                }
+            },
+            _ => Vec::new(),
+        };
+
+        let proj = Project::snapshot(name.clone(), data, &config.trunk_branches);
+        rows.extend(stats::summarize(
+            &name,
+            &proj.repo_data,
+            &proj.rows,
+            &proj.branch_index_to_name,
+            &fork_commits,
+            since,
+        ));
+    }
+
+    let mut out = std::io::stdout().lock();
+    match format {
+        StatsFormat::Csv => stats::write_csv(&rows, &mut out)?,
+        StatsFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &rows)?;
+            writeln!(out)?;
+        }
+    }
+    Ok(())
+}
+
+fn spawn_input_reader(
+    app: &App,
+    tx: mpsc::UnboundedSender<AppEvent>,
+    cancel: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    app.tasks.spawn(async move {
+        let mut reader = EventStream::new();
+        loop {
+            let event = tokio::select! {
+                _ = cancel.cancelled() => break,
+                event = reader.next() => event,
+            };
+            let Some(Ok(event)) = event else {
+                break;
+            };
+            let app_event = match event {
+                Event::Key(key) if key.kind == KeyEventKind::Press => Some(AppEvent::Key(key)),
+                Event::Resize(_, _) => Some(AppEvent::Resize),
+                Event::Mouse(mouse) => Some(AppEvent::Mouse(mouse)),
+                _ => None,
+            };
+            if let Some(e) = app_event {
+                if tx.send(e).is_err() {
+                    break;
+                }
+            }
+        }
+    })
+}
+
 fn start_watchers_and_pollers(
     app: &App,
     tx: &mpsc::UnboundedSender<AppEvent>,
     poll_interval: u64,
     watchers: &mut Vec<FsWatcherHandle>,
-    poller_handles: &mut Vec<JoinHandle<()>>,
+    cancel: &CancellationToken,
 ) {
+    if app.manual_refresh {
+        return;
+    }
     for (idx, proj) in app.projects.iter().enumerate() {
+        if !app.in_workspace(idx) || proj.parked {
+            continue;
+        }
         if let Some(ref local) = proj.local_source {
-            if let Some(workdir) = local.repo.workdir() {
+            if let Some(workdir) = local.worker.workdir() {
                 let repo_path = workdir.to_path_buf();
-                match watcher::fs::start_fs_watcher(&repo_path, idx, tx.clone()) {
+                match watcher::fs::start_fs_watcher(&repo_path, idx, tx.clone(), cancel.clone()) {
                     Ok(w) => watchers.push(w),
-                    Err(e) => eprintln!("warn: fs watcher failed for {}: {e}", repo_path.display()),
+                    Err(e) => {
+                        tracing::warn!(path = %repo_path.display(), "fs watcher failed: {e}")
+                    }
                 }
             }
         }
 
         if proj.github_client().is_some() {
             let poll_tx = tx.clone();
-            let handle = tokio::spawn(async move {
-                watcher::poll::start_github_poller(poll_tx, idx, poll_interval).await;
+            let cancel = cancel.clone();
+            app.tasks.spawn(async move {
+                watcher::poll::start_github_poller(poll_tx, idx, poll_interval, cancel).await;
             });
-            poller_handles.push(handle);
         }
     }
 }
@@ -207,15 +454,40 @@ fn start_watchers_and_pollers(
 fn process_event(
     app: &mut App,
     event: AppEvent,
-    fs_changed: &mut HashSet<usize>,
+    batch: &mut Batch,
     tx: &mpsc::UnboundedSender<AppEvent>,
-    config_saved: &mut bool,
 ) {
+    tracing::trace!(kind = event.kind(), "event");
+    crash::record_event(event.kind());
+    batch.events += 1;
     match event {
         AppEvent::FsChanged(idx) => {
-            fs_changed.insert(idx);
+            batch.fs_changed.insert(idx);
+        }
+        AppEvent::Resize => {
+            batch.resized = true;
         }
         AppEvent::GitHubUpdate(idx) => {
+            if let Some(offline) = app.offline {
+                app.offline_refresh.insert(idx);
+                // Ticks check whether an unreachable GitHub is back
+                let client = app.projects.get(idx).and_then(|p| p.github_client());
+                if let (Offline::Unreachable, false, Some(client)) =
+                    (offline, app.probing, client.cloned())
+                {
+                    app.probing = true;
+                    let tx = tx.clone();
+                    app.spawn_task(async move {
+                        let reachable = client.is_reachable().await;
+                        let _ = tx.send(AppEvent::GitHubProbed { reachable });
+                    });
+                }
+                return;
+            }
+            // Polls resume once the rate limit resets
+            if data_source::github_paused_until().is_some() {
+                return;
+            }
             if let Some(proj) = app.projects.get(idx) {
                 if !proj.github_polling_enabled() {
                     return;
@@ -223,15 +495,54 @@ fn process_event(
                 if let Some(client) = proj.github_client() {
                     let tx = tx.clone();
                     let client = client.clone();
-                    tokio::spawn(async move {
-                        let result = github::network::fetch_network_detached(&client).await;
+                    let need_codeowners = proj.codeowners.is_none();
+                    let need_logins =
+                        app.authors.style == AuthorStyle::Username && proj.author_logins.is_none();
+                    tracing::debug!(project = idx, "github poll");
+                    app.spawn_task(async move {
+                        let result = data_source::fetch_network(&client).await;
                         let event = match result {
-                            Ok((branches, commits, rate_limit)) => AppEvent::GitHubResult {
+                            Ok(network) => AppEvent::GitHubResult {
                                 project_idx: idx,
                                 result: Ok(GitHubData {
-                                    rate_limit,
-                                    branches,
-                                    commits,
+                                    rate_limit: network.rate_limit,
+                                    branches: network.branches,
+                                    commits: network.commits,
+                                    unlisted_forks: network.unlisted,
+                                    pull_requests: data_source::fetch_pull_requests(&client)
+                                        .await
+                                        .inspect_err(|e| {
+                                            tracing::warn!("pull request listing failed: {e}")
+                                        })
+                                        .ok(),
+                                    protected_branches: data_source::fetch_protected_branches(
+                                        &client,
+                                    )
+                                    .await
+                                    .inspect_err(|e| {
+                                        tracing::warn!("protected branch listing failed: {e}")
+                                    })
+                                    .ok(),
+                                    codeowners: if need_codeowners {
+                                        data_source::fetch_codeowners(&client)
+                                            .await
+                                            .inspect_err(|e| {
+                                                tracing::warn!("CODEOWNERS fetch failed: {e}")
+                                            })
+                                            .ok()
+                                    } else {
+                                        None
+                                    },
+                                    author_logins: if need_logins {
+                                        data_source::fetch_author_logins(&client)
+                                            .await
+                                            .inspect_err(|e| {
+                                                tracing::warn!("author login fetch failed: {e}")
+                                            })
+                                            .ok()
+                                    } else {
+                                        None
+                                    },
                                 }),
                             },
                             Err(e) => AppEvent::GitHubResult {
@@ -244,8 +555,8 @@ fn process_event(
                 }
             }
         }
-        AppEvent::ConfigSaved => {
-            *config_saved = true;
+        AppEvent::ConfigSaved | AppEvent::WatchersChanged => {
+            batch.restart_watchers = true;
         }
         _ => app.handle_event(event),
     }
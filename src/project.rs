@@ -1,38 +1,298 @@
-use crate::data_source::{LocalSource, RemoteSource, ViewMode};
-use crate::git::types::RepoData;
-use crate::github::client::GitHubClient;
+use crate::annotations::Annotations;
+use crate::codeowners::CodeOwners;
+use crate::data_source::{GitHubClient, LocalSource, PullRequestStatus, RemoteSource, ViewMode};
+use crate::git::diff::{CommitWeight, DiffStat};
+use crate::git::fetch::FetchProgress;
+use crate::git::head_log::HeadMove;
+use crate::git::signature::TagSignature;
+use crate::git::types::{BranchInfo, CommitInfo, CommitSource, Oid, RepoData};
 use crate::graph::{
     dag::Dag,
+    drift::{self, TipStat, TrunkDistance},
     image_cache::ImageCache,
     layout,
     types::{GraphRow, LayoutResult},
 };
-use std::collections::HashMap;
+use crate::stats::{self, GraphStats};
+use crate::ui::line_cache::LineCache;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 const MAX_GITHUB_FAILURES: u8 = 3;
+pub const LOADING_LABEL: &str = "loading\u{2026}";
 
 pub struct Project {
     pub name: String,
+    pub web_url: Option<String>,
     pub local_source: Option<LocalSource>,
     pub remote_source: Option<RemoteSource>,
     pub active_mode: ViewMode,
+    // Lags active_mode after a toggle until the other view's data arrives
+    pub rows_mode: ViewMode,
     pub repo_data: RepoData,
     pub dag: Dag,
     pub rows: Vec<GraphRow>,
     pub branch_index_to_name: HashMap<usize, String>,
     pub trunk_count: usize,
     pub max_lanes: usize,
+    pub lane_overflow: bool,
+    pub lane_overflow_reported: bool,
     pub current_branch: String,
+    pub graph_selected: usize,
+    pub graph_scroll_y: usize,
+    pub branch_selected: usize,
     pub scroll_x: usize,
+    pub lane_scroll: usize,
+    pub x_followed: Option<Oid>,
     pub last_sync: String,
     pub rate_limit: Option<u32>,
     pub time_sorted_indices: Vec<usize>,
     pub cached_repo_data: Option<RepoData>,
     pub github_failures: u8,
     pub image_cache: ImageCache,
+    pub line_cache: LineCache,
+    pub layout_time: Duration,
+    pub loading: bool,
+    pub load_progress: Option<usize>,
+    pub fetch_progress: Option<FetchProgress>,
+    pub annotations: Annotations,
+    // Kept across refreshes since verifying shells out
+    pub tag_signatures: HashMap<(String, Oid), TagSignature>,
+    pub commit_weights: HashMap<Oid, CommitWeight>,
+    pub diff_stats: HashMap<Oid, DiffStat>,
+    pub watched_head_moves: Vec<HeadMove>,
+    pub stats: GraphStats,
+    pub pull_requests: Vec<PullRequestStatus>,
+    // Tip each watched branch was last reported at
+    pub watched_branches: HashMap<(CommitSource, String), Oid>,
+    pub protected_branches: HashSet<String>,
+    pub codeowners: Option<CodeOwners>,
+    pub author_logins: Option<HashMap<String, String>>,
+    // Tips when the project was last looked at; empty until a baseline exists
+    pub seen_tips: HashSet<Oid>,
+    pub unseen: HashSet<Oid>,
+    pub remote_only: HashSet<Oid>,
+    // Dimmed as what git gc would collect once the tags go
+    pub detached: HashSet<Oid>,
+    pub unreleased: HashSet<Oid>,
+    // Set while the remote view is the disk cache's, fetched at this time
+    pub stale_remote: Option<DateTime<Utc>>,
+    pub trunk_distances: Option<HashMap<Oid, TrunkDistance>>,
+    pub tip_stats: HashMap<Oid, TipStat>,
+    on_trunk: Option<HashSet<Oid>>,
+    pub shown_at: Instant,
+    pub parked: bool,
 }
 
+static PENDING: TagSignature = TagSignature::Pending;
+
 impl Project {
+    pub fn loading(
+        name: String,
+        local_source: LocalSource,
+        remote_source: Option<RemoteSource>,
+    ) -> Self {
+        Self {
+            name,
+            web_url: None,
+            local_source: Some(local_source),
+            remote_source,
+            active_mode: ViewMode::Local,
+            rows_mode: ViewMode::Local,
+            repo_data: RepoData::default(),
+            dag: Dag::default(),
+            rows: Vec::new(),
+            branch_index_to_name: HashMap::new(),
+            trunk_count: 0,
+            max_lanes: 0,
+            lane_overflow: false,
+            lane_overflow_reported: false,
+            current_branch: LOADING_LABEL.to_string(),
+            graph_selected: 0,
+            graph_scroll_y: 0,
+            branch_selected: 0,
+            scroll_x: 0,
+            lane_scroll: 0,
+            x_followed: None,
+            last_sync: "never".to_string(),
+            rate_limit: None,
+            time_sorted_indices: Vec::new(),
+            cached_repo_data: None,
+            github_failures: 0,
+            image_cache: ImageCache::new(),
+            line_cache: LineCache::new(),
+            layout_time: Duration::ZERO,
+            loading: true,
+            load_progress: None,
+            fetch_progress: None,
+            annotations: Annotations::default(),
+            tag_signatures: HashMap::new(),
+            commit_weights: HashMap::new(),
+            diff_stats: HashMap::new(),
+            watched_head_moves: Vec::new(),
+            stats: GraphStats::default(),
+            pull_requests: Vec::new(),
+            watched_branches: HashMap::new(),
+            protected_branches: HashSet::new(),
+            codeowners: None,
+            author_logins: None,
+            seen_tips: HashSet::new(),
+            unseen: HashSet::new(),
+            remote_only: HashSet::new(),
+            detached: HashSet::new(),
+            unreleased: HashSet::new(),
+            stale_remote: None,
+            trunk_distances: None,
+            tip_stats: HashMap::new(),
+            on_trunk: None,
+            shown_at: Instant::now(),
+            parked: false,
+        }
+    }
+
+    pub fn snapshot(name: String, repo_data: RepoData, trunk_branches: &[String]) -> Self {
+        let mut proj = Self {
+            name: name.clone(),
+            web_url: None,
+            local_source: None,
+            remote_source: None,
+            active_mode: ViewMode::Local,
+            rows_mode: ViewMode::Local,
+            repo_data,
+            dag: Dag::default(),
+            rows: Vec::new(),
+            branch_index_to_name: HashMap::new(),
+            trunk_count: 0,
+            max_lanes: 0,
+            lane_overflow: false,
+            lane_overflow_reported: false,
+            current_branch: name,
+            graph_selected: 0,
+            graph_scroll_y: 0,
+            branch_selected: 0,
+            scroll_x: 0,
+            lane_scroll: 0,
+            x_followed: None,
+            last_sync: "never".to_string(),
+            rate_limit: None,
+            time_sorted_indices: Vec::new(),
+            cached_repo_data: None,
+            github_failures: 0,
+            image_cache: ImageCache::new(),
+            line_cache: LineCache::new(),
+            layout_time: Duration::ZERO,
+            loading: false,
+            load_progress: None,
+            fetch_progress: None,
+            annotations: Annotations::default(),
+            tag_signatures: HashMap::new(),
+            commit_weights: HashMap::new(),
+            diff_stats: HashMap::new(),
+            watched_head_moves: Vec::new(),
+            stats: GraphStats::default(),
+            pull_requests: Vec::new(),
+            watched_branches: HashMap::new(),
+            protected_branches: HashSet::new(),
+            codeowners: None,
+            author_logins: None,
+            seen_tips: HashSet::new(),
+            unseen: HashSet::new(),
+            remote_only: HashSet::new(),
+            detached: HashSet::new(),
+            unreleased: HashSet::new(),
+            stale_remote: None,
+            trunk_distances: None,
+            tip_stats: HashMap::new(),
+            on_trunk: None,
+            shown_at: Instant::now(),
+            parked: false,
+        };
+        proj.rebuild_layout(trunk_branches);
+        proj
+    }
+
+    pub fn current_tips(&self) -> HashSet<Oid> {
+        let mut tips = self.repo_data.known_tips();
+        tips.extend(self.repo_data.branches.iter().map(|b| b.tip));
+        tips
+    }
+
+    pub fn mark_seen(&mut self) {
+        if self.active_mode == ViewMode::Local && !self.rows.is_empty() {
+            self.seen_tips = self.current_tips();
+        }
+        self.unseen.clear();
+    }
+
+    // Nothing is flagged when none of the seen tips are loaded, e.g. after a rewrite
+    pub fn refresh_unseen(&mut self) {
+        self.unseen.clear();
+        if self.active_mode != ViewMode::Local || self.rows.is_empty() {
+            return;
+        }
+        if self.seen_tips.is_empty() {
+            self.seen_tips = self.current_tips();
+            return;
+        }
+        let seen = self.dag.reachable_from(self.seen_tips.iter().copied());
+        if seen.is_empty() {
+            return;
+        }
+        self.unseen = self
+            .rows
+            .iter()
+            .map(|r| r.meta.oid)
+            .filter(|oid| !seen.contains(oid))
+            .collect();
+    }
+
+    // The checked-out branch isn't offered
+    pub fn prunable_branches(&self) -> Vec<String> {
+        self.repo_data
+            .branches
+            .iter()
+            .filter(|b| matches!(b.source, CommitSource::Local) && !b.is_head)
+            .filter(|b| b.upstream.as_ref().is_some_and(|u| u.gone))
+            .map(|b| b.name.clone())
+            .collect()
+    }
+
+    pub fn tag_signature(&self, name: &str) -> Option<&TagSignature> {
+        let tag = self
+            .repo_data
+            .tags
+            .iter()
+            .find(|t| t.name == name && t.signed)?;
+        Some(
+            self.tag_signatures
+                .get(&(tag.name.clone(), tag.target))
+                .unwrap_or(&PENDING),
+        )
+    }
+
+    pub fn find_closest_by_time_binary(&self, time: &DateTime<Utc>) -> Option<usize> {
+        let time_of = |i: usize| self.rows[i].meta.time;
+        // Sorted newest first: everything before `pos` is newer than `time`
+        let pos = self
+            .time_sorted_indices
+            .partition_point(|&i| time_of(i) > *time);
+        let after = self.time_sorted_indices.get(pos).copied();
+        let before = pos
+            .checked_sub(1)
+            .and_then(|p| self.time_sorted_indices.get(p).copied());
+        match (before, after) {
+            (Some(b), Some(a)) => {
+                if time_of(b) - *time < *time - time_of(a) {
+                    Some(b)
+                } else {
+                    Some(a)
+                }
+            }
+            (b, a) => b.or(a),
+        }
+    }
+
     pub fn github_client(&self) -> Option<&GitHubClient> {
         self.remote_source.as_ref().map(|s| &s.client)
     }
@@ -42,9 +302,97 @@ impl Project {
     }
 
     pub fn rebuild_layout(&mut self, trunk_branches: &[String]) {
+        let started = Instant::now();
         self.dag = Dag::from_repo_data(&self.repo_data);
+        self.relayout(trunk_branches);
+        self.layout_time = started.elapsed();
+    }
+
+    // Returns false, leaving the layout alone, when nothing changed
+    pub fn merge_github(
+        &mut self,
+        branches: Vec<BranchInfo>,
+        commits: Vec<CommitInfo>,
+        unlisted: &[CommitSource],
+        trunk_branches: &[String],
+    ) -> bool {
+        let before = self.repo_data.branches.len();
+        // A fork missing from the poll was deleted or has no branches left
+        self.repo_data.branches.retain(|old| {
+            !matches!(old.source, CommitSource::Fork(_))
+                || unlisted.contains(&old.source)
+                || branches
+                    .iter()
+                    .any(|b| b.source == old.source && b.name == old.name)
+        });
+        let mut changed = self.repo_data.branches.len() != before;
+
+        for branch in branches {
+            let existing = self
+                .repo_data
+                .branches
+                .iter_mut()
+                .find(|b| b.source == branch.source && b.name == branch.name);
+            match existing {
+                Some(old) if *old == branch => {}
+                Some(old) => {
+                    *old = branch;
+                    changed = true;
+                }
+                None => {
+                    self.repo_data.branches.push(branch);
+                    changed = true;
+                }
+            }
+        }
+        changed |= commits.iter().any(|c| !self.dag.nodes.contains_key(&c.oid));
+        if !changed {
+            return false;
+        }
+        self.dag.merge_remote(commits);
+        self.relayout(trunk_branches);
+        self.cached_repo_data = None;
+        true
+    }
+
+    pub fn ensure_trunk_distances(&mut self, trunk_branches: &[String]) {
+        if self.trunk_distances.is_some() {
+            return;
+        }
+        let trunks = drift::trunk_tips(&self.repo_data, trunk_branches);
+        self.trunk_distances = Some(drift::trunk_distances(&self.dag, &trunks));
+        // Lines built before were missing the labels
+        self.line_cache.clear();
+    }
+
+    pub fn ensure_tip_stats(&mut self, tips: &[Oid], trunk_branches: &[String]) {
+        let missing: Vec<Oid> = tips
+            .iter()
+            .copied()
+            .filter(|t| !self.tip_stats.contains_key(t) && self.dag.nodes.contains_key(t))
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+        let on_trunk = self.on_trunk.get_or_insert_with(|| {
+            let trunks = drift::trunk_tips(&self.repo_data, trunk_branches);
+            self.dag
+                .reachable_from(trunks.into_iter().map(|(_, tip)| tip))
+        });
+        for tip in missing {
+            let stat = TipStat {
+                time: self.dag.nodes[&tip].commit.time,
+                ahead: drift::ahead_of_trunk(&self.dag, tip, on_trunk),
+            };
+            self.tip_stats.insert(tip, stat);
+        }
+    }
+
+    pub fn relayout(&mut self, trunk_branches: &[String]) {
+        let started = Instant::now();
         let result = layout::compute_layout(&self.dag, &self.repo_data, trunk_branches);
         self.apply_layout_result(result);
+        self.layout_time = started.elapsed();
     }
 
     pub fn apply_layout_result(&mut self, result: LayoutResult) {
@@ -52,8 +400,22 @@ impl Project {
         self.branch_index_to_name = result.branch_index_to_name;
         self.trunk_count = result.trunk_count;
         self.max_lanes = result.max_lanes;
+        self.lane_overflow = result.lane_overflow;
+        if !self.lane_overflow {
+            self.lane_overflow_reported = false;
+        }
         self.time_sorted_indices = build_time_sorted_indices(&self.rows);
+        self.detached = self
+            .dag
+            .unreachable_from(self.repo_data.branches.iter().map(|b| b.tip));
+        self.unreleased = stats::unreleased(&self.dag, &self.repo_data);
+        self.stats = stats::graph_stats(&self.repo_data, self.max_lanes, Utc::now());
+        self.refresh_unseen();
+        self.trunk_distances = None;
+        self.tip_stats.clear();
+        self.on_trunk = None;
         self.image_cache.clear(self.max_lanes);
+        self.line_cache.clear();
     }
 }
 
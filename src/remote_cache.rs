@@ -0,0 +1,209 @@
+use crate::config::{repo_file_name, state_dir, write_atomic};
+use crate::git::types::{BranchInfo, CommitInfo, CommitSource, Oid, RepoData};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub struct CachedRemote {
+    pub data: RepoData,
+    pub fetched_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    fetched_at: DateTime<Utc>,
+    commits: Vec<CachedCommit>,
+    branches: Vec<CachedBranch>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedCommit {
+    oid: String,
+    parents: Vec<String>,
+    message: String,
+    author: String,
+    email: String,
+    time: DateTime<Utc>,
+    source: Source,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedBranch {
+    name: String,
+    tip: String,
+    is_head: bool,
+    source: Source,
+}
+
+#[derive(Serialize, Deserialize)]
+enum Source {
+    Local,
+    Remote(String),
+    Fork(String),
+}
+
+impl From<&CommitSource> for Source {
+    fn from(source: &CommitSource) -> Self {
+        match source {
+            CommitSource::Local => Source::Local,
+            CommitSource::Remote(name) => Source::Remote(name.clone()),
+            CommitSource::Fork(name) => Source::Fork(name.clone()),
+        }
+    }
+}
+
+impl From<Source> for CommitSource {
+    fn from(source: Source) -> Self {
+        match source {
+            Source::Local => CommitSource::Local,
+            Source::Remote(name) => CommitSource::Remote(name),
+            Source::Fork(name) => CommitSource::Fork(name),
+        }
+    }
+}
+
+fn parse_oid(sha: &str) -> Option<Oid> {
+    git2::Oid::from_str(sha).ok().map(Oid::from_git2)
+}
+
+pub fn cache_path(repo_name: &str) -> PathBuf {
+    state_dir()
+        .join("arachne")
+        .join("remote")
+        .join(repo_file_name(repo_name, "json"))
+}
+
+pub fn save(path: &Path, data: &RepoData, fetched_at: DateTime<Utc>) -> std::io::Result<()> {
+    let file = CacheFile {
+        fetched_at,
+        commits: data
+            .commits
+            .iter()
+            .map(|c| CachedCommit {
+                oid: c.oid.to_string(),
+                parents: c.parents.iter().map(Oid::to_string).collect(),
+                message: c.message.clone(),
+                author: c.author.clone(),
+                email: c.email.clone(),
+                time: c.time,
+                source: (&c.source).into(),
+            })
+            .collect(),
+        branches: data
+            .branches
+            .iter()
+            .map(|b| CachedBranch {
+                name: b.name.clone(),
+                tip: b.tip.to_string(),
+                is_head: b.is_head,
+                source: (&b.source).into(),
+            })
+            .collect(),
+    };
+    let content = serde_json::to_vec(&file).map_err(std::io::Error::other)?;
+    write_atomic(path, &content)
+}
+
+pub fn load(path: &Path) -> Option<CachedRemote> {
+    let content = std::fs::read(path).ok()?;
+    let file: CacheFile = match serde_json::from_slice(&content) {
+        Ok(file) => file,
+        Err(e) => {
+            tracing::warn!(path = %path.display(), "remote cache unreadable: {e}");
+            return None;
+        }
+    };
+    let commits = file
+        .commits
+        .into_iter()
+        .filter_map(|c| {
+            Some(Arc::new(CommitInfo {
+                oid: parse_oid(&c.oid)?,
+                parents: c.parents.iter().filter_map(|p| parse_oid(p)).collect(),
+                message: c.message,
+                author: c.author,
+                email: c.email,
+                time: c.time,
+                source: c.source.into(),
+            }))
+        })
+        .collect();
+    let branches: Vec<BranchInfo> = file
+        .branches
+        .into_iter()
+        .filter_map(|b| {
+            Some(BranchInfo {
+                name: b.name,
+                tip: parse_oid(&b.tip)?,
+                is_head: b.is_head,
+                source: b.source.into(),
+                upstream: None,
+            })
+        })
+        .collect();
+    // As `load_remote_repo_data` builds them
+    let branch_tips: HashSet<Oid> = branches.iter().map(|b| b.tip).collect();
+    let head = branches.first().map(|b| b.tip);
+    Some(CachedRemote {
+        data: RepoData {
+            commits,
+            branches,
+            tags: Vec::new(),
+            head,
+            branch_tips,
+            shallow: HashSet::new(),
+            replaced: HashSet::new(),
+            stashes: Vec::new(),
+            submodules: Vec::new(),
+        },
+        fetched_at: file.fetched_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn remote_view_round_trips_through_the_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("remote").join("org_app.json");
+        assert!(load(&path).is_none());
+
+        let data = make_repo_data(
+            vec![make_commit(2, vec![1], 20), make_commit(1, vec![], 10)],
+            vec![BranchInfo {
+                name: "main".to_string(),
+                tip: make_oid(2),
+                is_head: false,
+                source: CommitSource::Fork("alice".to_string()),
+                upstream: None,
+            }],
+        );
+        let fetched_at = Utc::now();
+        save(&path, &data, fetched_at).unwrap();
+
+        let cached = load(&path).unwrap();
+        assert_eq!(cached.fetched_at, fetched_at);
+        assert_eq!(cached.data.branches, data.branches);
+        assert_eq!(cached.data.head, Some(make_oid(2)));
+        let commits: Vec<_> = cached
+            .data
+            .commits
+            .iter()
+            .map(|c| (c.oid, c.parents.clone(), c.time))
+            .collect();
+        let expected: Vec<_> = data
+            .commits
+            .iter()
+            .map(|c| (c.oid, c.parents.clone(), c.time))
+            .collect();
+        assert_eq!(commits, expected);
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(load(&path).is_none());
+    }
+}
@@ -46,6 +46,12 @@ impl ConfigSection {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepoField {
+    Path,
+    Name,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FieldMode {
     Navigate,
@@ -56,9 +62,11 @@ pub struct ConfigScreenState {
     pub active_section: ConfigSection,
     pub cursor: usize,
     pub field_mode: FieldMode,
+    pub repo_field: RepoField,
     pub draft: Config,
     pub dirty: bool,
     pub first_launch: bool,
+    pub import_note: Option<String>,
 }
 
 impl ConfigScreenState {
@@ -67,9 +75,11 @@ impl ConfigScreenState {
             active_section: ConfigSection::Repos,
             cursor: 0,
             field_mode: FieldMode::Navigate,
+            repo_field: RepoField::Path,
             draft: config.clone(),
             dirty: false,
             first_launch: false,
+            import_note: None,
         }
     }
 
@@ -79,6 +89,20 @@ impl ConfigScreenState {
         state
     }
 
+    pub fn import_from_other_tools(&mut self) {
+        let (tools, imported) = crate::import::import_all();
+        if tools.is_empty() {
+            return;
+        }
+        imported.apply(&mut self.draft);
+        self.dirty = true;
+        self.import_note = Some(format!(
+            "imported from {}: {}",
+            tools.join(", "),
+            imported.summary()
+        ));
+    }
+
     pub fn handle_key(&mut self, key: KeyEvent) -> ConfigAction {
         if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
             return ConfigAction::Quit;
@@ -122,6 +146,7 @@ impl ConfigScreenState {
                 if self.active_section == ConfigSection::Theme {
                     return ConfigAction::SelectTheme;
                 }
+                self.repo_field = RepoField::Path;
                 self.start_edit();
                 ConfigAction::None
             }
@@ -144,6 +169,15 @@ impl ConfigScreenState {
                 self.confirm_edit();
                 ConfigAction::None
             }
+            KeyCode::Tab | KeyCode::BackTab if self.active_section == ConfigSection::Repos => {
+                self.confirm_edit();
+                self.repo_field = match self.repo_field {
+                    RepoField::Path => RepoField::Name,
+                    RepoField::Name => RepoField::Path,
+                };
+                self.start_edit();
+                ConfigAction::None
+            }
             KeyCode::Backspace => {
                 if let FieldMode::Editing(ref mut text) = self.field_mode {
                     text.pop();
@@ -186,9 +220,11 @@ impl ConfigScreenState {
         match self.active_section {
             ConfigSection::Repos => {
                 let repos = self.draft.resolved_repos();
-                repos
-                    .get(self.cursor)
-                    .map(|r| r.path.to_string_lossy().to_string())
+                let entry = repos.get(self.cursor)?;
+                Some(match self.repo_field {
+                    RepoField::Path => entry.path.to_string_lossy().to_string(),
+                    RepoField::Name => entry.name.clone().unwrap_or_default(),
+                })
             }
             ConfigSection::Trunk => self.draft.trunk_branches.get(self.cursor).cloned(),
             ConfigSection::Theme | ConfigSection::Profiles => None,
@@ -198,8 +234,18 @@ impl ConfigScreenState {
     fn set_current_field_value(&mut self, value: &str) {
         match self.active_section {
             ConfigSection::Repos => {
+                // Single-repo mode becomes a one-entry list, so it can be named
+                if self.draft.repos.is_empty() && self.draft.adhoc_repo.is_none() {
+                    self.draft.repos = self.draft.resolved_repos();
+                }
                 if let Some(entry) = self.draft.repos.get_mut(self.cursor) {
-                    entry.path = std::path::PathBuf::from(value);
+                    match self.repo_field {
+                        RepoField::Path => entry.path = std::path::PathBuf::from(value),
+                        RepoField::Name => {
+                            let name = value.trim();
+                            entry.name = (!name.is_empty()).then(|| name.to_string());
+                        }
+                    }
                 }
             }
             ConfigSection::Trunk => {
@@ -238,6 +284,7 @@ impl ConfigScreenState {
                 });
                 self.cursor = self.draft.repos.len().saturating_sub(1);
                 self.dirty = true;
+                self.repo_field = RepoField::Path;
                 self.start_edit_public();
             }
             ConfigSection::Trunk => {
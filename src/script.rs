@@ -0,0 +1,92 @@
+use crate::data_source::ViewMode;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScriptCommand {
+    FilterAuthor(String),
+    FilterBranch(String),
+    SelectSha(String),
+    SelectBranch(String),
+    View(ViewMode),
+}
+
+// Empty commands are skipped; the first bad one fails the whole script
+pub fn parse(script: &str) -> Result<Vec<ScriptCommand>, String> {
+    script
+        .split(';')
+        .map(str::trim)
+        .filter(|cmd| !cmd.is_empty())
+        .map(parse_command)
+        .collect()
+}
+
+fn parse_command(cmd: &str) -> Result<ScriptCommand, String> {
+    let (verb, arg) = cmd.split_once(char::is_whitespace).unwrap_or((cmd, ""));
+    let arg = arg.trim();
+    let keyed = || {
+        arg.split_once(':')
+            .map(|(key, value)| (key, value.trim()))
+            .filter(|(_, value)| !value.is_empty())
+            .ok_or_else(|| format!("{cmd:?}: expected {verb} <key>:<value>"))
+    };
+    match verb {
+        "filter" => match keyed()? {
+            ("author", value) => Ok(ScriptCommand::FilterAuthor(value.to_string())),
+            ("branch", value) => Ok(ScriptCommand::FilterBranch(value.to_string())),
+            (key, _) => Err(format!("{cmd:?}: filter by author: or branch:, not {key}:")),
+        },
+        "select" => match keyed()? {
+            ("sha", value) if value.chars().all(|c| c.is_ascii_hexdigit()) => {
+                Ok(ScriptCommand::SelectSha(value.to_ascii_lowercase()))
+            }
+            ("sha", value) => Err(format!("{cmd:?}: {value} is not a commit id")),
+            ("branch", value) => Ok(ScriptCommand::SelectBranch(value.to_string())),
+            (key, _) => Err(format!("{cmd:?}: select by sha: or branch:, not {key}:")),
+        },
+        "view" => match arg {
+            "local" => Ok(ScriptCommand::View(ViewMode::Local)),
+            "remote" => Ok(ScriptCommand::View(ViewMode::Remote)),
+            _ => Err(format!("{cmd:?}: expected view local or view remote")),
+        },
+        _ => Err(format!(
+            "{cmd:?}: unknown command {verb:?} (filter, select, view)"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands_and_rejects_bad_ones() {
+        assert_eq!(
+            parse("filter author:alice; select sha:ABC123;; view remote ;").unwrap(),
+            vec![
+                ScriptCommand::FilterAuthor("alice".to_string()),
+                ScriptCommand::SelectSha("abc123".to_string()),
+                ScriptCommand::View(ViewMode::Remote),
+            ]
+        );
+        assert_eq!(
+            parse("filter branch: feature/x;select branch:main").unwrap(),
+            vec![
+                ScriptCommand::FilterBranch("feature/x".to_string()),
+                ScriptCommand::SelectBranch("main".to_string()),
+            ]
+        );
+        assert!(parse("").unwrap().is_empty());
+
+        for bad in [
+            "filter alice",
+            "filter author:",
+            "filter date:2024",
+            "select sha:xyz",
+            "view forks",
+            "zoom in",
+        ] {
+            assert!(parse(bad).is_err(), "{bad} should not parse");
+        }
+        let err = parse("view local; jump 3").unwrap_err();
+        assert!(err.contains("\"jump 3\""), "{err}");
+    }
+}
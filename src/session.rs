@@ -1,6 +1,9 @@
 use crate::app::{App, Panel};
-use crate::config::config_dir;
+use crate::config::{config_dir, write_atomic};
+use crate::git::types::Oid;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct SessionState {
@@ -14,9 +17,13 @@ pub struct SessionState {
 pub struct ProjectSession {
     pub name: String,
     pub graph_selected: usize,
+    #[serde(default)]
+    pub graph_scroll_y: usize,
     pub branch_selected: usize,
     pub scroll_x: usize,
     pub collapsed_sections: Vec<String>,
+    #[serde(default)]
+    pub seen_tips: Vec<String>,
 }
 
 fn session_path() -> std::path::PathBuf {
@@ -24,9 +31,21 @@ fn session_path() -> std::path::PathBuf {
 }
 
 pub fn save(app: &App) {
+    if let Err(e) = save_to(app, &session_path()) {
+        tracing::warn!("session save failed: {e}");
+    }
+}
+
+pub fn save_to(app: &App, path: &Path) -> std::io::Result<()> {
     let mut projects = Vec::new();
     for (idx, proj) in app.projects.iter().enumerate() {
         let is_active = idx == app.active_project;
+        let on_screen = is_active || app.split.as_ref().is_some_and(|s| s.project == idx);
+        let seen_tips = if on_screen && !proj.rows.is_empty() {
+            proj.current_tips()
+        } else {
+            proj.seen_tips.clone()
+        };
         let collapsed: Vec<String> = app
             .collapsed_sections
             .iter()
@@ -34,10 +53,12 @@ pub fn save(app: &App) {
             .collect();
         projects.push(ProjectSession {
             name: proj.name.clone(),
-            graph_selected: if is_active { app.graph_selected } else { 0 },
-            branch_selected: if is_active { app.branch_selected } else { 0 },
+            graph_selected: proj.graph_selected,
+            graph_scroll_y: proj.graph_scroll_y,
+            branch_selected: proj.branch_selected,
             scroll_x: proj.scroll_x,
             collapsed_sections: if is_active { collapsed } else { Vec::new() },
+            seen_tips: seen_tips.iter().map(|oid| oid.to_string()).collect(),
         });
     }
 
@@ -54,13 +75,8 @@ pub fn save(app: &App) {
         show_detail: app.show_detail,
     };
 
-    if let Ok(content) = toml::to_string_pretty(&state) {
-        let path = session_path();
-        if let Some(parent) = path.parent() {
-            let _ = std::fs::create_dir_all(parent);
-        }
-        let _ = std::fs::write(path, content);
-    }
+    let content = toml::to_string_pretty(&state).map_err(std::io::Error::other)?;
+    write_atomic(path, content.as_bytes())
 }
 
 pub fn restore(app: &mut App) {
@@ -75,40 +91,58 @@ pub fn restore(app: &mut App) {
     };
 
     // Match active project by name
-    if let Some(idx) = app
+    let restored = app
         .projects
         .iter()
         .position(|p| state.projects.get(state.active_project).map(|s| &s.name) == Some(&p.name))
-    {
-        app.active_project = idx;
-    } else {
-        app.active_project = state
-            .active_project
-            .min(app.projects.len().saturating_sub(1));
+        .unwrap_or_else(|| {
+            state
+                .active_project
+                .min(app.projects.len().saturating_sub(1))
+        });
+    // Left in another workspace, it stays on the open one's first project
+    let same_project = app.in_workspace(restored);
+    if same_project {
+        app.active_project = restored;
     }
 
     // Restore per-project state
     for saved in &state.projects {
         if let Some(proj) = app.projects.iter_mut().find(|p| p.name == saved.name) {
             proj.scroll_x = saved.scroll_x.min(10_000);
+            // A still-loading project is clamped once its rows arrive
+            proj.graph_selected = if proj.loading {
+                saved.graph_selected
+            } else if !proj.rows.is_empty() {
+                saved.graph_selected.min(proj.rows.len() - 1)
+            } else {
+                0
+            };
+            proj.graph_scroll_y = saved.graph_scroll_y.min(proj.graph_selected);
+            proj.branch_selected = saved.branch_selected;
+            let seen: HashSet<Oid> = saved
+                .seen_tips
+                .iter()
+                .filter_map(|sha| git2::Oid::from_str(sha).ok())
+                .map(Oid::from_git2)
+                .collect();
+            if !seen.is_empty() {
+                proj.seen_tips = seen;
+                proj.refresh_unseen();
+            }
         }
     }
 
     // Restore view state
-    if let Some(saved) = state.projects.get(state.active_project) {
-        let row_count = app
-            .projects
-            .get(app.active_project)
-            .map(|p| p.rows.len())
-            .unwrap_or(0);
-        app.graph_selected = if row_count > 0 {
-            saved.graph_selected.min(row_count - 1)
-        } else {
-            0
-        };
-        app.branch_selected = saved
-            .branch_selected
+    if let Some(saved) = state
+        .projects
+        .get(state.active_project)
+        .filter(|_| same_project)
+    {
+        let entry = app
+            .branch_selected()
             .min(app.cached_entries.len().saturating_sub(1));
+        app.set_branch_selected(entry);
 
         // Restore collapsed sections
         for s in &saved.collapsed_sections {
@@ -130,7 +164,13 @@ fn section_key_to_string(key: &crate::ui::branch_panel::SectionKey) -> Option<St
     use crate::ui::branch_panel::SectionKey;
     match key {
         SectionKey::Local(i) => Some(format!("local:{i}")),
+        SectionKey::PullRequests(i) => Some(format!("pulls:{i}")),
+        SectionKey::Remote(i, remote) => Some(format!("remote:{i}:{remote}")),
         SectionKey::Fork(i, owner) => Some(format!("fork:{i}:{owner}")),
+        SectionKey::InactiveForks(i) => Some(format!("inactive_forks:{i}")),
+        SectionKey::Prunable(i) => Some(format!("prunable:{i}")),
+        SectionKey::Stashes(i) => Some(format!("stashes:{i}")),
+        SectionKey::Submodules(i) => Some(format!("submodules:{i}")),
         SectionKey::Tags(i) => Some(format!("tags:{i}")),
         SectionKey::Authors(i) => Some(format!("authors:{i}")),
     }
@@ -141,10 +181,19 @@ fn string_to_section_key(s: &str) -> Option<crate::ui::branch_panel::SectionKey>
     let parts: Vec<&str> = s.splitn(3, ':').collect();
     match parts.as_slice() {
         ["local", idx] => idx.parse().ok().map(SectionKey::Local),
+        ["pulls", idx] => idx.parse().ok().map(SectionKey::PullRequests),
+        ["remote", idx, remote] => idx
+            .parse()
+            .ok()
+            .map(|i| SectionKey::Remote(i, remote.to_string())),
         ["fork", idx, owner] => idx
             .parse()
             .ok()
             .map(|i| SectionKey::Fork(i, owner.to_string())),
+        ["inactive_forks", idx] => idx.parse().ok().map(SectionKey::InactiveForks),
+        ["prunable", idx] => idx.parse().ok().map(SectionKey::Prunable),
+        ["stashes", idx] => idx.parse().ok().map(SectionKey::Stashes),
+        ["submodules", idx] => idx.parse().ok().map(SectionKey::Submodules),
         ["tags", idx] => idx.parse().ok().map(SectionKey::Tags),
         ["authors", idx] => idx.parse().ok().map(SectionKey::Authors),
         _ => None,
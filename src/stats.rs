@@ -0,0 +1,552 @@
+use crate::git::types::{CommitInfo, CommitSource, Oid, RepoData};
+use crate::graph::dag::Dag;
+use crate::graph::drift::TipStat;
+use crate::graph::types::GraphRow;
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Write;
+use std::sync::Arc;
+
+pub fn daily_counts<Tz: TimeZone>(data: &RepoData, tz: &Tz) -> HashMap<NaiveDate, usize> {
+    let mut counts = HashMap::new();
+    for commit in &data.commits {
+        let day = commit.time.with_timezone(tz).date_naive();
+        *counts.entry(day).or_default() += 1;
+    }
+    counts
+}
+
+// 0 means no commits, 4 the busiest day
+pub fn intensity(count: usize, max: usize) -> u8 {
+    if count == 0 || max == 0 {
+        return 0;
+    }
+    (count * 4).div_ceil(max).clamp(1, 4) as u8
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GraphStats {
+    pub branches: usize,
+    // Fork owners with a commit in the last 30 days
+    pub active_forks: usize,
+    pub commits_this_week: usize,
+    pub max_lanes: usize,
+}
+
+pub fn graph_stats(data: &RepoData, max_lanes: usize, now: DateTime<Utc>) -> GraphStats {
+    let week_ago = now - Duration::days(7);
+    let month_ago = now - Duration::days(30);
+    let mut active_forks = HashSet::new();
+    let mut commits_this_week = 0;
+    for commit in &data.commits {
+        if commit.time >= week_ago {
+            commits_this_week += 1;
+        }
+        if let CommitSource::Fork(ref owner) = commit.source {
+            if commit.time >= month_ago {
+                active_forks.insert(owner.as_str());
+            }
+        }
+    }
+    GraphStats {
+        branches: data.branches.len(),
+        active_forks: active_forks.len(),
+        commits_this_week,
+        max_lanes,
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Release {
+    pub name: String,
+    pub target: Oid,
+    pub time: DateTime<Utc>,
+    pub commits: usize,
+    pub contributors: usize,
+}
+
+// Each with what's new since the earlier tags, as git log <earlier tags>..tag
+pub fn release_timeline(data: &RepoData) -> Vec<Release> {
+    let by_oid: HashMap<Oid, &Arc<CommitInfo>> = data.commits.iter().map(|c| (c.oid, c)).collect();
+    let mut tags: Vec<_> = data
+        .tags
+        .iter()
+        .filter_map(|t| {
+            let commit = by_oid.get(&t.target)?;
+            Some((t, t.time.unwrap_or(commit.time)))
+        })
+        .collect();
+    tags.sort_by(|(a, at), (b, bt)| at.cmp(bt).then_with(|| a.name.cmp(&b.name)));
+
+    let mut released: HashSet<Oid> = HashSet::new();
+    let mut out = Vec::with_capacity(tags.len());
+    for (tag, time) in tags {
+        let mut commits = 0;
+        let mut authors = HashSet::new();
+        let mut stack = vec![tag.target];
+        while let Some(oid) = stack.pop() {
+            let Some(commit) = by_oid.get(&oid) else {
+                continue;
+            };
+            if !released.insert(oid) {
+                continue;
+            }
+            commits += 1;
+            authors.insert(commit.author.as_str());
+            stack.extend(commit.parents.iter().copied());
+        }
+        out.push(Release {
+            name: tag.name.clone(),
+            target: tag.target,
+            time,
+            commits,
+            contributors: authors.len(),
+        });
+    }
+    out
+}
+
+// Empty while no loaded commit is tagged
+pub fn unreleased(dag: &Dag, data: &RepoData) -> HashSet<Oid> {
+    let latest = data
+        .tags
+        .iter()
+        .filter_map(|t| {
+            let commit = &dag.nodes.get(&t.target)?.commit;
+            Some((t.time.unwrap_or(commit.time), &t.name, t.target))
+        })
+        .max();
+    let (Some((_, _, released)), Some(head)) = (latest, data.head) else {
+        return HashSet::new();
+    };
+    let shipped = dag.reachable_from([released]);
+    let mut out = dag.reachable_from([head]);
+    out.retain(|oid| !shipped.contains(oid));
+    out
+}
+
+// Quiet up to STALE_DAYS, stale after
+pub const ACTIVE_DAYS: i64 = 30;
+pub const STALE_DAYS: i64 = 90;
+
+#[derive(Clone, Debug)]
+pub struct Health {
+    pub default_branch: Option<String>,
+    pub active_branches: usize,
+    pub quiet_branches: usize,
+    pub stale_branches: usize,
+    pub unmerged_branches: usize,
+    pub active_forks: usize,
+    pub last_release: Option<Release>,
+}
+
+// A tip outside the loaded history counts as stale
+pub fn health(
+    data: &RepoData,
+    trunks: &[(String, Oid)],
+    tips: &HashMap<Oid, TipStat>,
+    now: DateTime<Utc>,
+) -> Health {
+    let is_trunk = |name: &str| trunks.iter().any(|(trunk, _)| trunk == name);
+    let mut health = Health {
+        default_branch: trunks.first().map(|(name, _)| name.clone()),
+        active_branches: 0,
+        quiet_branches: 0,
+        stale_branches: 0,
+        unmerged_branches: 0,
+        active_forks: graph_stats(data, 0, now).active_forks,
+        last_release: release_timeline(data).pop(),
+    };
+    for branch in &data.branches {
+        let short = match branch.source {
+            CommitSource::Local => branch.name.as_str(),
+            CommitSource::Remote(ref remote) => branch
+                .name
+                .strip_prefix(remote.as_str())
+                .and_then(|n| n.strip_prefix('/'))
+                .unwrap_or(&branch.name),
+            CommitSource::Fork(_) => continue,
+        };
+        if short == "HEAD" || is_trunk(short) {
+            continue;
+        }
+        let Some(stat) = tips.get(&branch.tip) else {
+            health.stale_branches += 1;
+            continue;
+        };
+        let age = now - stat.time;
+        if age < Duration::days(ACTIVE_DAYS) {
+            health.active_branches += 1;
+        } else if age < Duration::days(STALE_DAYS) {
+            health.quiet_branches += 1;
+        } else {
+            health.stale_branches += 1;
+        }
+        health.unmerged_branches += usize::from(stat.ahead > 0);
+    }
+    health
+}
+
+// None for a count that isn't positive or reaches past the representable dates
+pub fn parse_since(spec: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let spec = spec.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(spec, "%Y-%m-%d") {
+        return Some(date.and_hms_opt(0, 0, 0)?.and_utc());
+    }
+    let unit = spec.chars().last()?;
+    let n: i64 = spec[..spec.len() - unit.len_utf8()].parse().ok()?;
+    if n <= 0 {
+        return None;
+    }
+    let days = match unit {
+        'd' => n,
+        'w' => n.checked_mul(7)?,
+        'y' => n.checked_mul(365)?,
+        _ => return None,
+    };
+    now.checked_sub_signed(Duration::try_days(days)?)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct StatRow {
+    pub repo: String,
+    pub kind: &'static str,
+    pub name: String,
+    pub commits: usize,
+    pub merges: usize,
+}
+
+// Branches get the commits drawn on their lane, so shared history counts once
+pub fn summarize(
+    repo: &str,
+    data: &RepoData,
+    rows: &[GraphRow],
+    branch_names: &HashMap<usize, String>,
+    fork_commits: &[CommitInfo],
+    since: Option<DateTime<Utc>>,
+) -> Vec<StatRow> {
+    let in_window = |time: &DateTime<Utc>| since.is_none_or(|s| *time >= s);
+    let mut out = Vec::new();
+    let mut push = |kind: &'static str, counts: BTreeMap<&str, (usize, usize)>| {
+        let mut rows: Vec<_> = counts
+            .into_iter()
+            .map(|(name, (commits, merges))| StatRow {
+                repo: repo.to_string(),
+                kind,
+                name: name.to_string(),
+                commits,
+                merges,
+            })
+            .collect();
+        // Stable on the name order from the BTreeMap
+        rows.sort_by_key(|r| std::cmp::Reverse(r.commits));
+        out.extend(rows);
+    };
+
+    let mut authors: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    for c in data.commits.iter().filter(|c| in_window(&c.time)) {
+        let entry = authors.entry(c.author.as_str()).or_default();
+        entry.0 += 1;
+        entry.1 += usize::from(c.parents.len() > 1);
+    }
+    push("author", authors);
+
+    let mut branches: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    for row in rows.iter().filter(|r| in_window(&r.meta.time)) {
+        let Some(name) = row.meta.branch_index.and_then(|i| branch_names.get(&i)) else {
+            continue;
+        };
+        let entry = branches.entry(name.as_str()).or_default();
+        entry.0 += 1;
+        entry.1 += usize::from(row.meta.is_merge);
+    }
+    push("branch", branches);
+
+    let mut forks: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    for c in fork_commits.iter().filter(|c| in_window(&c.time)) {
+        if let CommitSource::Fork(ref owner) = c.source {
+            let entry = forks.entry(owner.as_str()).or_default();
+            entry.0 += 1;
+            entry.1 += usize::from(c.parents.len() > 1);
+        }
+    }
+    push("fork", forks);
+    out
+}
+
+pub fn write_csv(rows: &[StatRow], out: &mut impl Write) -> std::io::Result<()> {
+    fn field(s: &str) -> String {
+        if s.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", s.replace('"', "\"\""))
+        } else {
+            s.to_string()
+        }
+    }
+    writeln!(out, "repo,kind,name,commits,merges")?;
+    for row in rows {
+        writeln!(
+            out,
+            "{},{},{},{},{}",
+            field(&row.repo),
+            row.kind,
+            field(&row.name),
+            row.commits,
+            row.merges
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::types::{BranchInfo, TagInfo};
+    use crate::test_utils::*;
+
+    #[test]
+    fn counts_commits_per_day() {
+        let mut data = make_repo_data(
+            vec![
+                make_commit(3, vec![2], 0),
+                make_commit(2, vec![1], 0),
+                make_commit(1, vec![], 0),
+            ],
+            vec![],
+        );
+        let day = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        let times = [day, day + Duration::hours(1), day - Duration::days(2)];
+        for (commit, time) in data.commits.iter_mut().zip(times) {
+            Arc::make_mut(commit).time = time;
+        }
+
+        let counts = daily_counts(&data, &Utc);
+        assert_eq!(counts[&day.date_naive()], 2);
+        assert_eq!(counts[&(day - Duration::days(2)).date_naive()], 1);
+        assert_eq!(counts.len(), 2);
+    }
+
+    #[test]
+    fn releases_count_commits_since_previous_tag() {
+        let mut data = make_repo_data(
+            vec![
+                make_commit(4, vec![3], 1),
+                make_commit(3, vec![2], 2),
+                make_commit(2, vec![1], 3),
+                make_commit(1, vec![], 4),
+            ],
+            vec![],
+        );
+        Arc::make_mut(&mut data.commits[1]).author = "other".to_string();
+        data.tags = [("v1", 2), ("v2", 4), ("gone", 9)]
+            .iter()
+            .map(|&(name, target)| TagInfo {
+                name: name.to_string(),
+                target: make_oid(target),
+                time: None,
+                signed: false,
+            })
+            .collect();
+
+        let releases = release_timeline(&data);
+        let summary: Vec<_> = releases
+            .iter()
+            .map(|r| (r.name.as_str(), r.commits, r.contributors))
+            .collect();
+        assert_eq!(summary, vec![("v1", 2, 1), ("v2", 2, 2)]);
+    }
+
+    #[test]
+    fn unreleased_is_what_head_has_past_the_newest_tag() {
+        let mut data = make_repo_data(
+            vec![
+                make_commit(5, vec![4], 1),
+                make_commit(4, vec![3], 2),
+                make_commit(3, vec![2], 3),
+                make_commit(2, vec![1], 4),
+                make_commit(1, vec![], 5),
+            ],
+            vec![],
+        );
+        data.head = Some(make_oid(5));
+        let dag = Dag::from_repo_data(&data);
+        assert!(unreleased(&dag, &data).is_empty(), "no tags, no release");
+
+        // v2 is the newer tag though it sits on the older commit
+        let now = Utc::now();
+        data.tags = [("v1", 3, 10), ("v2", 2, 1)]
+            .iter()
+            .map(|&(name, target, days_ago)| TagInfo {
+                name: name.to_string(),
+                target: make_oid(target),
+                time: Some(now - Duration::days(days_ago)),
+                signed: false,
+            })
+            .collect();
+        let expected: HashSet<Oid> = [5, 4, 3].into_iter().map(make_oid).collect();
+        assert_eq!(unreleased(&dag, &data), expected);
+    }
+
+    #[test]
+    fn since_accepts_spans_and_dates() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 30, 8, 0, 0).unwrap();
+        assert_eq!(parse_since("90d", now), Some(now - Duration::days(90)));
+        assert_eq!(parse_since("2w", now), Some(now - Duration::days(14)));
+        assert_eq!(
+            parse_since("2024-01-31", now),
+            Some(Utc.with_ymd_and_hms(2024, 1, 31, 0, 0, 0).unwrap())
+        );
+        assert_eq!(parse_since("3x", now), None);
+        assert_eq!(parse_since("d", now), None);
+        assert_eq!(parse_since("-5d", now), None);
+        assert_eq!(parse_since("0w", now), None);
+        assert_eq!(parse_since("999999999d", now), None);
+        assert_eq!(parse_since("9223372036854775807y", now), None);
+    }
+
+    #[test]
+    fn summary_counts_authors_merges_and_forks() {
+        let mut data = make_repo_data(
+            vec![
+                make_commit(3, vec![2, 1], 10),
+                make_commit(2, vec![1], 20),
+                make_commit(1, vec![], 10_000),
+            ],
+            vec![],
+        );
+        Arc::make_mut(&mut data.commits[1]).author = "Doe, Jane".to_string();
+        let fork = CommitInfo {
+            source: CommitSource::Fork("alice".to_string()),
+            ..make_commit(9, vec![3], 5)
+        };
+        let since = Some(Utc::now() - Duration::seconds(100));
+
+        let rows = summarize("org/app", &data, &[], &HashMap::new(), &[fork], since);
+        let summary: Vec<_> = rows
+            .iter()
+            .map(|r| (r.kind, r.name.as_str(), r.commits, r.merges))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("author", "Doe, Jane", 1, 0),
+                ("author", "test", 1, 1),
+                ("fork", "alice", 1, 0),
+            ]
+        );
+
+        let mut csv = Vec::new();
+        write_csv(&rows, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.starts_with("repo,kind,name,commits,merges\n"));
+        assert!(csv.contains("org/app,author,\"Doe, Jane\",1,0\n"));
+    }
+
+    #[test]
+    fn graph_stats_count_recent_commits_and_forks() {
+        let day = 24 * 3600;
+        let mut data = make_repo_data(
+            vec![
+                make_commit(4, vec![3], 60),
+                make_commit(3, vec![2], 3 * day),
+                make_commit(2, vec![1], 10 * day),
+                make_commit(1, vec![], 60 * day),
+            ],
+            vec![BranchInfo {
+                name: "main".to_string(),
+                tip: make_oid(4),
+                is_head: true,
+                source: CommitSource::Local,
+                upstream: None,
+            }],
+        );
+        for (i, owner) in [(1, "bob"), (2, "bob"), (3, "alice")] {
+            Arc::make_mut(&mut data.commits[i]).source = CommitSource::Fork(owner.to_string());
+        }
+
+        let stats = graph_stats(&data, 3, Utc::now());
+        assert_eq!(
+            stats,
+            GraphStats {
+                branches: 1,
+                active_forks: 1,
+                commits_this_week: 2,
+                max_lanes: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn health_buckets_branches_by_tip_age() {
+        let day = 24 * 3600;
+        let branch = |name: &str, tip: u8, source: CommitSource| BranchInfo {
+            name: name.to_string(),
+            tip: make_oid(tip),
+            is_head: false,
+            source,
+            upstream: None,
+        };
+        let origin = || CommitSource::Remote("origin".to_string());
+        let mut data = make_repo_data(
+            vec![
+                make_commit(4, vec![1], day),
+                make_commit(3, vec![1], 40 * day),
+                make_commit(2, vec![1], 200 * day),
+                make_commit(1, vec![], 300 * day),
+            ],
+            vec![
+                branch("main", 1, CommitSource::Local),
+                branch("origin/main", 1, origin()),
+                branch("origin/HEAD", 1, origin()),
+                branch("fresh", 4, CommitSource::Local),
+                branch("origin/fresh", 4, origin()),
+                branch("quiet", 3, CommitSource::Local),
+                branch("old", 2, CommitSource::Local),
+                branch("unloaded", 9, CommitSource::Local),
+            ],
+        );
+        data.tags = vec![TagInfo {
+            name: "v1".to_string(),
+            target: make_oid(1),
+            time: None,
+            signed: false,
+        }];
+        let now = Utc::now();
+        let tips: HashMap<Oid, TipStat> = data
+            .commits
+            .iter()
+            .map(|c| {
+                let ahead = usize::from(c.oid != make_oid(1));
+                (
+                    c.oid,
+                    TipStat {
+                        time: c.time,
+                        ahead,
+                    },
+                )
+            })
+            .collect();
+
+        let health = health(&data, &[("main".to_string(), make_oid(1))], &tips, now);
+        assert_eq!(health.default_branch.as_deref(), Some("main"));
+        assert_eq!(
+            (
+                health.active_branches,
+                health.quiet_branches,
+                health.stale_branches
+            ),
+            (2, 1, 2)
+        );
+        assert_eq!(health.unmerged_branches, 4);
+        assert_eq!(health.last_release.map(|r| r.name), Some("v1".to_string()));
+    }
+
+    #[test]
+    fn intensity_scales_to_four_levels() {
+        assert_eq!(intensity(0, 10), 0);
+        assert_eq!(intensity(1, 10), 1);
+        assert_eq!(intensity(5, 10), 2);
+        assert_eq!(intensity(10, 10), 4);
+        assert_eq!(intensity(1, 1), 4);
+    }
+}
@@ -7,6 +7,9 @@ pub enum GraphicsCapability {
 }
 
 pub fn detect_graphics_cap() -> GraphicsCapability {
+    if !cfg!(feature = "kitty") {
+        return GraphicsCapability::Unsupported;
+    }
     if is_multiplexer() {
         return GraphicsCapability::Unsupported;
     }
@@ -1,5 +1,3 @@
-#![cfg(test)]
-
 use crate::git::types::*;
 
 pub fn make_oid(val: u8) -> Oid {
@@ -14,6 +12,7 @@ pub fn make_commit(val: u8, parents: Vec<u8>, secs_ago: i64) -> CommitInfo {
         parents: parents.into_iter().map(make_oid).collect(),
         message: format!("commit {val}"),
         author: "test".to_string(),
+        email: "test@example.com".to_string(),
         time: chrono::Utc::now() - chrono::Duration::seconds(secs_ago),
         source: CommitSource::Local,
     }
@@ -23,10 +22,237 @@ pub fn make_repo_data(commits: Vec<CommitInfo>, branches: Vec<BranchInfo>) -> Re
     let branch_tips = branches.iter().map(|b| b.tip).collect();
     let head = branches.iter().find(|b| b.is_head).map(|b| b.tip);
     RepoData {
-        commits,
+        commits: commits.into_iter().map(std::sync::Arc::new).collect(),
         branches,
         tags: vec![],
         head,
         branch_tips,
+        shallow: Default::default(),
+        replaced: Default::default(),
+        stashes: vec![],
+        submodules: vec![],
+    }
+}
+
+pub fn index_oid(i: usize) -> Oid {
+    let mut bytes = [0u8; 20];
+    bytes[..8].copy_from_slice(&(i as u64 + 1).to_be_bytes());
+    Oid::from_bytes(bytes)
+}
+
+#[derive(Clone, Debug)]
+pub struct SyntheticSpec {
+    pub commits: usize,
+    pub width: usize,
+    // Per mille
+    pub fork_rate: u32,
+    // Per mille
+    pub merge_rate: u32,
+    // 0 disables octopus merges
+    pub octopus_arity: usize,
+    pub authors: usize,
+    pub seed: u64,
+}
+
+impl SyntheticSpec {
+    pub fn linear(commits: usize) -> Self {
+        Self {
+            commits,
+            width: 4,
+            fork_rate: 20,
+            merge_rate: 20,
+            octopus_arity: 0,
+            authors: 8,
+            seed: 1,
+        }
+    }
+
+    pub fn fork_fan(commits: usize) -> Self {
+        Self {
+            commits,
+            width: 48,
+            fork_rate: 150,
+            merge_rate: 10,
+            octopus_arity: 0,
+            authors: 64,
+            seed: 2,
+        }
+    }
+
+    pub fn octopus(commits: usize, arity: usize) -> Self {
+        Self {
+            commits,
+            width: 16,
+            fork_rate: 100,
+            merge_rate: 80,
+            octopus_arity: arity,
+            authors: 16,
+            seed: 3,
+        }
+    }
+}
+
+// Commit ages sit mid-way between day boundaries so relative times render the same all day
+pub fn sample_repo() -> RepoData {
+    let day = |d: i64| chrono::Utc::now() - chrono::Duration::hours(d * 24 + 12);
+    let commit = |val: u8, parents: Vec<u8>, days: i64, author: &str, message: &str| CommitInfo {
+        oid: make_oid(val),
+        parents: parents.into_iter().map(make_oid).collect(),
+        message: message.to_string(),
+        author: author.to_string(),
+        email: match author {
+            "carol" => "carol@contractor.dev".to_string(),
+            _ => format!("{author}@example.com"),
+        },
+        time: day(days),
+        source: CommitSource::Local,
+    };
+    let commits = vec![
+        commit(1, vec![2, 5], 1, "alice", "Merge branch 'feature/login'"),
+        commit(2, vec![3], 2, "alice", "Bump version to 1.1"),
+        commit(5, vec![4], 3, "bob", "Remember the last user name"),
+        commit(4, vec![3], 4, "bob", "Add login form"),
+        commit(7, vec![6], 5, "carol", "Guard against empty config"),
+        commit(3, vec![6], 6, "alice", "Release 1.0"),
+        commit(6, vec![], 9, "alice", "Initial commit"),
+    ];
+    let branch = |name: &str, tip: u8, is_head: bool| BranchInfo {
+        name: name.to_string(),
+        tip: make_oid(tip),
+        is_head,
+        source: CommitSource::Local,
+        upstream: None,
+    };
+    let mut data = make_repo_data(
+        commits,
+        vec![
+            branch("main", 1, true),
+            branch("feature/login", 5, false),
+            branch("fix/empty-config", 7, false),
+        ],
+    );
+    data.tags.push(TagInfo {
+        name: "v1.0".to_string(),
+        target: make_oid(3),
+        time: Some(day(6)),
+        signed: false,
+    });
+    data
+}
+
+// Newest first, as a revwalk returns them; main is HEAD
+pub fn synthetic_repo(spec: &SyntheticSpec) -> RepoData {
+    let mut rng = spec.seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    let mut next = move |bound: usize| -> usize {
+        // xorshift64
+        rng ^= rng << 13;
+        rng ^= rng >> 7;
+        rng ^= rng << 17;
+        (rng % bound.max(1) as u64) as usize
+    };
+    let base = chrono::Utc::now() - chrono::Duration::minutes(spec.commits as i64);
+
+    // (branch name, tip index)
+    let mut heads: Vec<(String, usize)> = Vec::new();
+    let mut closed: Vec<(String, usize)> = Vec::new();
+    let mut commits: Vec<CommitInfo> = Vec::with_capacity(spec.commits);
+    let mut branch_seq = 0usize;
+
+    for i in 0..spec.commits {
+        let mut parents = Vec::new();
+        let lane = if heads.is_empty() {
+            heads.push(("main".to_string(), i));
+            0
+        } else if heads.len() < spec.width.max(1) && next(1000) < spec.fork_rate as usize {
+            let from = heads[next(heads.len())].1;
+            branch_seq += 1;
+            heads.push((format!("feature/{branch_seq}"), from));
+            parents.push(index_oid(from));
+            heads.len() - 1
+        } else {
+            let lane = next(heads.len());
+            parents.push(index_oid(heads[lane].1));
+            lane
+        };
+
+        if heads.len() > 1 && next(1000) < spec.merge_rate as usize {
+            let extra = 1 + spec.octopus_arity.min(heads.len() - 2);
+            for _ in 0..extra {
+                let other = next(heads.len());
+                if other == lane || other == 0 || parents.contains(&index_oid(heads[other].1)) {
+                    continue;
+                }
+                parents.push(index_oid(heads[other].1));
+            }
+        }
+
+        commits.push(CommitInfo {
+            oid: index_oid(i),
+            parents,
+            message: format!("commit {i}"),
+            author: format!("author{}", next(spec.authors)),
+            email: String::new(),
+            time: base + chrono::Duration::minutes(i as i64),
+            source: CommitSource::Local,
+        });
+        heads[lane].1 = i;
+
+        // Branches merged into another lane are finished
+        let merged: Vec<Oid> = commits[i].parents.iter().skip(1).copied().collect();
+        let mut idx = 1;
+        while idx < heads.len() {
+            if idx != lane && merged.contains(&index_oid(heads[idx].1)) {
+                closed.push(heads.remove(idx));
+            } else {
+                idx += 1;
+            }
+        }
+    }
+
+    let branches = heads
+        .into_iter()
+        .chain(closed.into_iter().rev().take(spec.width))
+        .enumerate()
+        .map(|(n, (name, tip))| BranchInfo {
+            name,
+            tip: index_oid(tip),
+            is_head: n == 0,
+            source: CommitSource::Local,
+            upstream: None,
+        })
+        .collect();
+    commits.reverse();
+    make_repo_data(commits, branches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn synthetic_repo_is_closed_under_parents() {
+        for spec in [
+            SyntheticSpec::linear(500),
+            SyntheticSpec::fork_fan(500),
+            SyntheticSpec::octopus(500, 4),
+        ] {
+            let data = synthetic_repo(&spec);
+            assert_eq!(data.commits.len(), 500);
+            let mut seen = HashSet::new();
+            // Newest first: every parent appears later in the list
+            for c in data.commits.iter().rev() {
+                assert!(c.parents.iter().all(|p| seen.contains(p)));
+                seen.insert(c.oid);
+            }
+            assert!(data.branches.iter().all(|b| seen.contains(&b.tip)));
+            assert_eq!(data.head, Some(data.branches[0].tip));
+        }
+    }
+
+    #[test]
+    fn octopus_spec_produces_octopus_merges() {
+        let data = synthetic_repo(&SyntheticSpec::octopus(2000, 4));
+        assert!(data.commits.iter().any(|c| c.parents.len() > 2));
     }
 }
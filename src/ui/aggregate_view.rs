@@ -0,0 +1,97 @@
+use crate::aggregate::Aggregate;
+use crate::graph::layout::format_time_ago;
+use crate::ui::theme::ThemePalette;
+use crate::ui::{fit, TIME_AGO_W};
+use ratatui::{
+    buffer::Buffer as Buf,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+const SHA_W: usize = 8;
+const REPO_W: usize = 14;
+const AUTHOR_W: usize = 14;
+const GAP: &str = "  ";
+
+pub struct AggregateView<'a> {
+    pub aggregate: &'a Aggregate,
+    pub palette: &'a ThemePalette,
+}
+
+pub fn project_color(palette: &ThemePalette, idx: usize) -> Color {
+    palette.branch_color_by_identity(idx, 0)
+}
+
+impl AggregateView<'_> {
+    pub fn render_into(self, area: Rect, buf: &mut Buf) {
+        if area.height == 0 {
+            return;
+        }
+        let p = self.palette;
+        let agg = self.aggregate;
+        buf.set_style(area, Style::default().bg(p.content_bg));
+
+        let gutter_w = 2 * agg.columns.len() + 1;
+        let fixed = gutter_w + REPO_W + SHA_W + AUTHOR_W + TIME_AGO_W + 4 * GAP.len();
+        let message_w = (area.width as usize).saturating_sub(fixed);
+        for (i, row) in agg
+            .rows
+            .iter()
+            .enumerate()
+            .skip(agg.scroll_y)
+            .take(area.height as usize)
+        {
+            let selected = i == agg.selected;
+            let bg = if selected {
+                p.selected_bg
+            } else {
+                p.content_bg
+            };
+            let text = Style::default().fg(p.content_fg).bg(bg);
+            let dim = text.fg(p.dim_text);
+            let mut spans = vec![Span::styled(
+                if selected { ">" } else { " " },
+                text.fg(p.selected_accent),
+            )];
+            for &idx in &agg.columns {
+                let symbol = if idx == row.project_idx {
+                    "\u{25cf} "
+                } else if agg.spans_row(idx, i) {
+                    "\u{2502} "
+                } else {
+                    "  "
+                };
+                spans.push(Span::styled(symbol, text.fg(project_color(p, idx))));
+            }
+            let repo = agg.projects.get(row.project_idx).map_or("", String::as_str);
+            let sha = row.commit.oid.to_string();
+            let summary = row.commit.message.lines().next().unwrap_or_default();
+            spans.extend([
+                Span::styled(
+                    fit(repo, REPO_W),
+                    text.fg(project_color(p, row.project_idx))
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(GAP, text),
+                Span::styled(fit(&sha[..SHA_W.min(sha.len())], SHA_W), dim),
+                Span::styled(GAP, text),
+                Span::styled(fit(summary, message_w), text),
+                Span::styled(GAP, text),
+                Span::styled(fit(&row.commit.author, AUTHOR_W), dim),
+                Span::styled(GAP, text),
+                Span::styled(fit(&format_time_ago(&row.commit.time), TIME_AGO_W), dim),
+            ]);
+            let y = area.y + (i - agg.scroll_y) as u16;
+            buf.set_style(
+                Rect {
+                    y,
+                    height: 1,
+                    ..area
+                },
+                Style::default().bg(bg),
+            );
+            buf.set_line(area.x, y, &Line::from(spans), area.width);
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use crate::git::blame::BlameLine;
+use crate::git::types::Oid;
+use crate::graph::layout::format_time_ago;
+use crate::ui::theme::ThemePalette;
+use crate::ui::{truncate_with_ellipsis, TIME_AGO_W};
+use ratatui::{
+    buffer::Buffer as Buf,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Widget},
+};
+
+const AUTHOR_W: usize = 14;
+const TAB_W: usize = 4;
+
+pub struct BlameState {
+    pub path: String,
+    pub commit: Oid,
+    pub lines: Vec<BlameLine>,
+    pub selected: usize,
+    pub scroll: usize,
+    // Set at render
+    pub page: usize,
+    pub loading: bool,
+}
+
+impl BlameState {
+    pub fn new(path: String, commit: Oid) -> Self {
+        Self {
+            path,
+            commit,
+            lines: Vec::new(),
+            selected: 0,
+            scroll: 0,
+            page: 0,
+            loading: true,
+        }
+    }
+
+    pub fn move_by(&mut self, delta: isize) {
+        let last = self.lines.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    pub fn update_scroll(&mut self, rows: usize) {
+        self.page = rows;
+        if rows == 0 {
+            return;
+        }
+        if self.selected >= self.scroll + rows {
+            self.scroll = self.selected + 1 - rows;
+        }
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        }
+    }
+}
+
+pub fn popup_rect(area: Rect) -> Rect {
+    super::centered_rect(90, 90, area)
+}
+
+pub fn visible_lines(area: Rect) -> usize {
+    // Borders and the footer
+    popup_rect(area).height.saturating_sub(3) as usize
+}
+
+// Runs of lines from one commit name it once
+pub struct BlameView<'a> {
+    pub state: &'a BlameState,
+    pub palette: &'a ThemePalette,
+}
+
+impl<'a> Widget for BlameView<'a> {
+    fn render(self, area: Rect, buf: &mut Buf) {
+        let p = self.palette;
+        let state = self.state;
+        let popup = popup_rect(area);
+        Clear.render(popup, buf);
+
+        let sha = state.commit.to_string();
+        let block = Block::default()
+            .title(format!(" Blame: {} @ {} ", state.path, &sha[..7]))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(p.accent));
+        let inner = block.inner(popup);
+        block.render(popup, buf);
+        if inner.height < 2 {
+            return;
+        }
+
+        let dim = Style::default().fg(p.dim_text);
+        buf.set_string(
+            inner.x + 1,
+            inner.bottom() - 1,
+            "Enter jump to commit  Esc close",
+            dim,
+        );
+        if state.lines.is_empty() {
+            let msg = if state.loading {
+                "blaming\u{2026}"
+            } else {
+                "empty file"
+            };
+            buf.set_string(inner.x + 1, inner.y, msg, dim);
+            return;
+        }
+
+        let number_w = state.lines.len().to_string().len();
+        let rows = (inner.height - 1) as usize;
+        for (i, line) in state.lines.iter().enumerate().skip(state.scroll).take(rows) {
+            let y = inner.y + (i - state.scroll) as u16;
+            let is_selected = i == state.selected;
+            let base = if is_selected {
+                Style::default().bg(p.selected_bg)
+            } else {
+                Style::default()
+            };
+            // The top row of the screen always says where it's from
+            let first_of_run = i == state.scroll || state.lines[i - 1].oid != line.oid;
+            let blame = if first_of_run || is_selected {
+                let sha = line.oid.to_string();
+                vec![
+                    Span::styled(
+                        format!(" {} ", &sha[..7]),
+                        base.fg(p.accent).add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        format!(
+                            "{:<AUTHOR_W$} ",
+                            truncate_with_ellipsis(&line.author, AUTHOR_W)
+                        ),
+                        base,
+                    ),
+                    Span::styled(
+                        format!("{:>TIME_AGO_W$} ", format_time_ago(&line.time)),
+                        base.fg(p.dim_text),
+                    ),
+                ]
+            } else {
+                vec![Span::styled(
+                    " ".repeat(9 + AUTHOR_W + 1 + TIME_AGO_W + 1),
+                    base,
+                )]
+            };
+            let mut spans = blame;
+            spans.push(Span::styled(
+                format!("{:>number_w$} \u{2502} ", i + 1),
+                base.fg(p.dim_text),
+            ));
+            spans.push(Span::styled(
+                line.content.replace('\t', &" ".repeat(TAB_W)),
+                base,
+            ));
+            if is_selected {
+                for x in inner.x..inner.right() {
+                    buf[(x, y)].set_style(base);
+                }
+            }
+            buf.set_line(inner.x, y, &Line::from(spans), inner.width);
+        }
+    }
+}
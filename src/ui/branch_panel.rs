@@ -1,20 +1,34 @@
-use crate::git::types::{CommitSource, Oid};
+use crate::data_source::ReviewState;
+use crate::git::signature::TagSignature;
+use crate::git::types::{CommitSource, Oid, Upstream};
+use crate::graph::drift::TipStat;
+use crate::graph::filter::author_excluded;
+use crate::graph::layout::format_time_short;
 use crate::project::Project;
 use crate::ui::theme::{self, ThemePalette};
+use crate::ui::truncate_with_ellipsis;
+use chrono::{DateTime, Utc};
 use ratatui::{
     buffer::Buffer as Buf,
     layout::Rect,
-    style::{Modifier, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::Widget,
 };
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use unicode_width::UnicodeWidthStr;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum SectionKey {
     Local(usize),
+    PullRequests(usize),
+    Remote(usize, String),
     Fork(usize, String),
+    InactiveForks(usize),
+    Prunable(usize),
+    Stashes(usize),
+    Submodules(usize),
     Tags(usize),
     Authors(usize),
 }
@@ -22,12 +36,46 @@ pub enum SectionKey {
 #[derive(Debug, Clone)]
 pub enum EntryKind {
     RepoHeader,
-    SectionHeader { key: SectionKey, count: usize },
+    SectionHeader {
+        key: SectionKey,
+        count: usize,
+    },
     Spacer,
-    LocalBranch { is_head: bool, tip: Oid },
-    ForkBranch { tip: Oid },
-    Tag { target: Oid },
-    Author { name: String },
+    LocalBranch {
+        is_head: bool,
+        tip: Oid,
+        upstream: Option<Upstream>,
+        protected: bool,
+    },
+    RemoteBranch {
+        tip: Oid,
+    },
+    ForkBranch {
+        tip: Oid,
+    },
+    PullRequest {
+        number: u64,
+        review: ReviewState,
+        draft: bool,
+        head: Option<Oid>,
+    },
+    Tag {
+        target: Oid,
+        signature: Option<TagSignature>,
+    },
+    Author {
+        name: String,
+    },
+    Stash {
+        index: usize,
+        base: Oid,
+        message: String,
+    },
+    Submodule {
+        name: String,
+        pinned: Option<Oid>,
+        workdir: Option<PathBuf>,
+    },
 }
 
 pub struct DisplayEntry {
@@ -39,8 +87,24 @@ impl DisplayEntry {
     pub fn tip_oid(&self) -> Option<Oid> {
         match &self.kind {
             EntryKind::LocalBranch { tip, .. } => Some(*tip),
-            EntryKind::ForkBranch { tip } => Some(*tip),
-            EntryKind::Tag { target } => Some(*target),
+            EntryKind::RemoteBranch { tip } | EntryKind::ForkBranch { tip } => Some(*tip),
+            EntryKind::Tag { target, .. } => Some(*target),
+            EntryKind::PullRequest { head, .. } => *head,
+            EntryKind::Stash { base, .. } => Some(*base),
+            _ => None,
+        }
+    }
+
+    pub fn branch_name(&self) -> Option<&str> {
+        match self.kind {
+            EntryKind::LocalBranch { .. }
+            | EntryKind::RemoteBranch { .. }
+            | EntryKind::ForkBranch { .. } => Some(
+                self.label
+                    .trim_start()
+                    .trim_start_matches('\u{25b8}')
+                    .trim_start(),
+            ),
             _ => None,
         }
     }
@@ -64,14 +128,91 @@ impl DisplayEntry {
     }
 }
 
+pub const TIP_STAT_W: usize = 8;
+
 pub struct BranchPanel<'a> {
     pub entries: &'a [DisplayEntry],
     pub selected: usize,
     pub scroll: usize,
     pub focused: bool,
+    pub linked: Option<Oid>,
+    pub tip_stats: &'a HashMap<Oid, TipStat>,
+    pub footer: bool,
+    pub github: bool,
     pub palette: &'a ThemePalette,
 }
 
+// Most used first
+pub fn quick_actions(kind: &EntryKind, github: bool) -> Vec<(&'static str, &'static str)> {
+    match kind {
+        EntryKind::SectionHeader { .. } => vec![("Enter", "fold")],
+        EntryKind::LocalBranch { is_head, .. } => {
+            let mut actions = vec![
+                ("Enter", "jump"),
+                ("B", "refs"),
+                ("M", "merge check"),
+                ("=", "compare"),
+                ("E", "rebase"),
+            ];
+            if github {
+                actions.extend([("P", "merge PR"), ("#", "open PR")]);
+            }
+            actions.extend([("!", "watch"), ("n", "branch")]);
+            if !is_head {
+                actions.push(("D", "delete"));
+            }
+            actions
+        }
+        EntryKind::RemoteBranch { .. } => vec![
+            ("Enter", "jump"),
+            ("B", "refs"),
+            ("M", "merge check"),
+            ("=", "compare"),
+            ("E", "rebase"),
+            ("!", "watch"),
+            ("n", "branch"),
+        ],
+        // Only on GitHub, so nothing local can check it out or merge it
+        EntryKind::ForkBranch { .. } => vec![("Enter", "jump"), ("=", "compare"), ("!", "watch")],
+        EntryKind::PullRequest { .. } => vec![("Enter", "jump"), ("#", "open"), ("n", "branch")],
+        EntryKind::Tag { .. } => vec![
+            ("Enter", "jump"),
+            ("B", "refs"),
+            ("n", "branch"),
+            ("D", "delete"),
+        ],
+        EntryKind::Stash { .. } => vec![("Enter", "jump"), ("z", "apply/pop/drop")],
+        EntryKind::Submodule { .. } => vec![("Enter", "open")],
+        EntryKind::Author { .. } => vec![("Enter", "filter")],
+        EntryKind::RepoHeader | EntryKind::Spacer => Vec::new(),
+    }
+}
+
+fn quick_actions_line(
+    actions: &[(&'static str, &'static str)],
+    width: usize,
+    p: &ThemePalette,
+) -> Line<'static> {
+    let mut spans = Vec::new();
+    let mut used = 0;
+    for (key, what) in actions {
+        let w = UnicodeWidthStr::width(*key) + UnicodeWidthStr::width(*what) + 3;
+        if used + w > width {
+            break;
+        }
+        used += w;
+        spans.push(Span::styled(
+            format!(" {key}"),
+            Style::default().fg(p.accent),
+        ));
+        spans.push(Span::styled(
+            format!(" {what} "),
+            Style::default().fg(p.dim_text),
+        ));
+    }
+    Line::from(spans)
+}
+
 impl<'a> Widget for BranchPanel<'a> {
     fn render(self, area: Rect, buf: &mut Buf) {
         if area.height == 0 {
@@ -81,7 +222,8 @@ impl<'a> Widget for BranchPanel<'a> {
         let p = self.palette;
         let inner_y = area.y;
         let inner_w = area.width as usize;
-        let visible = area.height as usize;
+        let footer = self.footer && area.height > 1;
+        let visible = area.height as usize - usize::from(footer);
 
         let sel_bg = if self.focused {
             p.selected_bg
@@ -100,23 +242,45 @@ impl<'a> Widget for BranchPanel<'a> {
             let abs_idx = self.scroll + i;
             let is_selected = abs_idx == self.selected;
 
-            let line = entry_line(entry, is_selected, inner_w, self.focused, p);
+            let stat = entry
+                .branch_name()
+                .and_then(|_| self.tip_stats.get(&entry.tip_oid()?));
+            let line = entry_line(entry, is_selected, inner_w, self.focused, stat, p);
             buf.set_line(area.x, y, &line, area.width);
 
             if is_selected {
                 for x in area.x..(area.x + area.width) {
                     buf[(x, y)].set_style(Style::default().bg(sel_bg));
                 }
+            } else if self.linked.is_some() && entry.tip_oid() == self.linked {
+                for x in area.x..(area.x + area.width) {
+                    buf[(x, y)].set_style(Style::default().add_modifier(Modifier::BOLD));
+                }
+                buf[(area.x, y)]
+                    .set_symbol("\u{203a}")
+                    .set_style(Style::default().fg(p.accent));
             }
         }
+
+        let selected = self.entries.get(self.selected);
+        if let (true, Some(entry)) = (footer, selected) {
+            let line = quick_actions_line(&quick_actions(&entry.kind, self.github), inner_w, p);
+            buf.set_line(area.x, area.bottom() - 1, &line, area.width);
+        }
     }
 }
 
+pub fn linked_entry(entries: &[DisplayEntry], oid: Oid) -> Option<usize> {
+    entries.iter().position(|e| e.tip_oid() == Some(oid))
+}
+
 pub fn build_entries(
     projects: &[Project],
     filter: &str,
     author_filter: &str,
+    exclude_authors: &[String],
     show_forks: bool,
+    stale_before: Option<DateTime<Utc>>,
     collapsed: &HashSet<SectionKey>,
 ) -> Vec<DisplayEntry> {
     let mut entries = Vec::new();
@@ -133,11 +297,12 @@ pub fn build_entries(
         let branches = &proj.repo_data.branches;
         let tags = &proj.repo_data.tags;
 
-        let local: Vec<_> = branches
+        let prunable = proj.prunable_branches();
+        let (gone, local): (Vec<_>, Vec<_>) = branches
             .iter()
-            .filter(|b| matches!(b.source, CommitSource::Local) && !b.name.contains('/'))
+            .filter(|b| matches!(b.source, CommitSource::Local))
             .filter(|b| filter.is_empty() || b.name.contains(filter))
-            .collect();
+            .partition(|b| prunable.contains(&b.name));
 
         if !local.is_empty() {
             let key = SectionKey::Local(project_idx);
@@ -159,6 +324,193 @@ pub fn build_entries(
                         kind: EntryKind::LocalBranch {
                             is_head: b.is_head,
                             tip: b.tip,
+                            upstream: b.upstream.clone(),
+                            protected: proj.protected_branches.contains(&b.name),
+                        },
+                    });
+                }
+            }
+        }
+
+        if !gone.is_empty() {
+            let key = SectionKey::Prunable(project_idx);
+            let is_collapsed = collapsed.contains(&key);
+            let arrow = if is_collapsed { "\u{25b6}" } else { "\u{25bc}" };
+            entries.push(DisplayEntry {
+                label: format!("  {arrow} Prunable"),
+                kind: EntryKind::SectionHeader {
+                    key,
+                    count: gone.len(),
+                },
+            });
+            if !is_collapsed {
+                for b in gone {
+                    entries.push(DisplayEntry {
+                        label: format!("      {}", b.name),
+                        kind: EntryKind::LocalBranch {
+                            is_head: false,
+                            tip: b.tip,
+                            upstream: b.upstream.clone(),
+                            protected: proj.protected_branches.contains(&b.name),
+                        },
+                    });
+                }
+            }
+        }
+
+        let stashes: Vec<_> = proj
+            .repo_data
+            .stashes
+            .iter()
+            .filter(|s| filter.is_empty() || s.message.contains(filter))
+            .collect();
+        if !stashes.is_empty() {
+            let key = SectionKey::Stashes(project_idx);
+            let is_collapsed = collapsed.contains(&key);
+            let arrow = if is_collapsed { "\u{25b6}" } else { "\u{25bc}" };
+            entries.push(DisplayEntry {
+                label: format!("  {arrow} Stashes"),
+                kind: EntryKind::SectionHeader {
+                    key,
+                    count: stashes.len(),
+                },
+            });
+            if !is_collapsed {
+                for stash in stashes {
+                    let summary = stash.message.lines().next().unwrap_or_default();
+                    entries.push(DisplayEntry {
+                        label: format!("      stash@{{{}}} {summary}", stash.index),
+                        kind: EntryKind::Stash {
+                            index: stash.index,
+                            base: stash.base,
+                            message: stash.message.clone(),
+                        },
+                    });
+                }
+            }
+        }
+
+        let submodules: Vec<_> = proj
+            .repo_data
+            .submodules
+            .iter()
+            .filter(|s| filter.is_empty() || s.path.contains(filter))
+            .collect();
+        if !submodules.is_empty() {
+            let key = SectionKey::Submodules(project_idx);
+            let is_collapsed = collapsed.contains(&key);
+            let arrow = if is_collapsed { "\u{25b6}" } else { "\u{25bc}" };
+            entries.push(DisplayEntry {
+                label: format!("  {arrow} Submodules"),
+                kind: EntryKind::SectionHeader {
+                    key,
+                    count: submodules.len(),
+                },
+            });
+            if !is_collapsed {
+                for sm in submodules {
+                    let sha = sm
+                        .pinned
+                        .map(|oid| oid.to_string()[..7].to_string())
+                        .unwrap_or_else(|| "-------".to_string());
+                    entries.push(DisplayEntry {
+                        label: format!("      {sha} {}", sm.path),
+                        kind: EntryKind::Submodule {
+                            name: sm.name.clone(),
+                            pinned: sm.pinned,
+                            workdir: sm.workdir.clone(),
+                        },
+                    });
+                }
+            }
+        }
+
+        // Remote-tracking branches, one section per remote
+        let remote: Vec<_> = branches
+            .iter()
+            .filter(|b| matches!(b.source, CommitSource::Remote(_)) && !b.name.ends_with("/HEAD"))
+            .filter(|b| filter.is_empty() || b.name.contains(filter))
+            .collect();
+        let mut remote_names: Vec<&str> = Vec::new();
+        for b in &remote {
+            if let CommitSource::Remote(ref name) = b.source {
+                if !remote_names.contains(&name.as_str()) {
+                    remote_names.push(name);
+                }
+            }
+        }
+        for remote_name in remote_names {
+            if !entries.is_empty()
+                && !matches!(
+                    entries.last().map(|e| &e.kind),
+                    Some(EntryKind::Spacer | EntryKind::RepoHeader)
+                )
+            {
+                entries.push(DisplayEntry {
+                    label: String::new(),
+                    kind: EntryKind::Spacer,
+                });
+            }
+            let of_remote: Vec<_> = remote
+                .iter()
+                .filter(|b| matches!(&b.source, CommitSource::Remote(r) if r == remote_name))
+                .collect();
+            let key = SectionKey::Remote(project_idx, remote_name.to_string());
+            let is_collapsed = collapsed.contains(&key);
+            let arrow = if is_collapsed { "\u{25b6}" } else { "\u{25bc}" };
+            entries.push(DisplayEntry {
+                label: format!("  {arrow} Remote: {remote_name}"),
+                kind: EntryKind::SectionHeader {
+                    key,
+                    count: of_remote.len(),
+                },
+            });
+            if !is_collapsed {
+                for b in of_remote {
+                    entries.push(DisplayEntry {
+                        label: format!("    {}", b.name),
+                        kind: EntryKind::RemoteBranch { tip: b.tip },
+                    });
+                }
+            }
+        }
+
+        let pull_requests: Vec<_> = proj
+            .pull_requests
+            .iter()
+            .filter(|pr| filter.is_empty() || pr.branch.contains(filter))
+            .collect();
+        if !pull_requests.is_empty() {
+            if !entries.is_empty()
+                && !matches!(
+                    entries.last().map(|e| &e.kind),
+                    Some(EntryKind::Spacer | EntryKind::RepoHeader)
+                )
+            {
+                entries.push(DisplayEntry {
+                    label: String::new(),
+                    kind: EntryKind::Spacer,
+                });
+            }
+            let key = SectionKey::PullRequests(project_idx);
+            let is_collapsed = collapsed.contains(&key);
+            let arrow = if is_collapsed { "\u{25b6}" } else { "\u{25bc}" };
+            entries.push(DisplayEntry {
+                label: format!("  {arrow} Pull Requests"),
+                kind: EntryKind::SectionHeader {
+                    key,
+                    count: pull_requests.len(),
+                },
+            });
+            if !is_collapsed {
+                for pr in pull_requests {
+                    entries.push(DisplayEntry {
+                        label: format!("    #{} {}", pr.number, pr.branch),
+                        kind: EntryKind::PullRequest {
+                            number: pr.number,
+                            review: pr.review,
+                            draft: pr.draft,
+                            head: pr.head,
                         },
                     });
                 }
@@ -169,12 +521,12 @@ pub fn build_entries(
         {
             let mut freq: HashMap<&str, usize> = HashMap::new();
             for c in &proj.repo_data.commits {
-                if !c.author.is_empty() {
+                if !c.author.is_empty() && !author_excluded(&c.author, exclude_authors) {
                     *freq.entry(c.author.as_str()).or_default() += 1;
                 }
             }
             let mut authors: Vec<_> = freq.into_iter().collect();
-            authors.sort_by(|a, b| b.1.cmp(&a.1));
+            authors.sort_by_key(|a| std::cmp::Reverse(a.1));
             authors.truncate(10);
 
             if !authors.is_empty() {
@@ -222,6 +574,8 @@ pub fn build_entries(
                 .filter(|b| matches!(b.source, CommitSource::Fork(_)))
                 .filter(|b| filter.is_empty() || b.name.contains(filter))
                 .collect();
+            let stale_owners = stale_fork_owners(proj, stale_before);
+            let is_stale = |source: &CommitSource| matches!(source, CommitSource::Fork(o) if stale_owners.contains(o.as_str()));
 
             if !forks.is_empty() {
                 entries.push(DisplayEntry {
@@ -229,7 +583,7 @@ pub fn build_entries(
                     kind: EntryKind::Spacer,
                 });
                 let mut current_fork = String::new();
-                for b in &forks {
+                for b in forks.iter().filter(|b| !is_stale(&b.source)) {
                     if let CommitSource::Fork(ref owner) = b.source {
                         if *owner != current_fork {
                             current_fork = owner.clone();
@@ -262,6 +616,29 @@ pub fn build_entries(
                         }
                     }
                 }
+
+                let inactive: Vec<_> = forks.iter().filter(|b| is_stale(&b.source)).collect();
+                if !inactive.is_empty() {
+                    let key = SectionKey::InactiveForks(project_idx);
+                    let is_collapsed = collapsed.contains(&key);
+                    let arrow = if is_collapsed { "\u{25b6}" } else { "\u{25bc}" };
+                    let owners: HashSet<_> = inactive.iter().map(|b| &b.source).collect();
+                    entries.push(DisplayEntry {
+                        label: format!("  {arrow} Inactive forks"),
+                        kind: EntryKind::SectionHeader {
+                            key,
+                            count: owners.len(),
+                        },
+                    });
+                    if !is_collapsed {
+                        for fb in inactive {
+                            entries.push(DisplayEntry {
+                                label: format!("    {}", fb.name),
+                                kind: EntryKind::ForkBranch { tip: fb.tip },
+                            });
+                        }
+                    }
+                }
             }
         }
 
@@ -295,7 +672,10 @@ pub fn build_entries(
                 for t in filtered_tags.iter().take(10) {
                     entries.push(DisplayEntry {
                         label: format!("    ({})", t.name),
-                        kind: EntryKind::Tag { target: t.target },
+                        kind: EntryKind::Tag {
+                            target: t.target,
+                            signature: proj.tag_signature(&t.name).cloned(),
+                        },
                     });
                 }
             }
@@ -313,9 +693,31 @@ pub fn max_entry_width(entries: &[DisplayEntry]) -> usize {
         .unwrap_or(15)
 }
 
+// A fork with a tip not in the graph yet counts as active
+fn stale_fork_owners(proj: &Project, stale_before: Option<DateTime<Utc>>) -> HashSet<&str> {
+    let Some(cutoff) = stale_before else {
+        return HashSet::new();
+    };
+    let mut active: HashSet<&str> = HashSet::new();
+    let mut owners: HashSet<&str> = HashSet::new();
+    for b in &proj.repo_data.branches {
+        let CommitSource::Fork(ref owner) = b.source else {
+            continue;
+        };
+        owners.insert(owner);
+        let tip_time = proj.dag.nodes.get(&b.tip).map(|n| n.commit.time);
+        if tip_time.is_none_or(|t| t >= cutoff) {
+            active.insert(owner);
+        }
+    }
+    owners.retain(|o| !active.contains(o));
+    owners
+}
+
 pub fn auto_collapse_defaults(projects: &[Project]) -> HashSet<SectionKey> {
     let mut set = HashSet::new();
     for (project_idx, _proj) in projects.iter().enumerate() {
+        set.insert(SectionKey::InactiveForks(project_idx));
         set.insert(SectionKey::Tags(project_idx));
         set.insert(SectionKey::Authors(project_idx));
     }
@@ -397,7 +799,129 @@ fn split_branch_prefix(name: &str) -> Option<(&str, &str)> {
     }
 }
 
+fn tip_stat_label(stat: &TipStat) -> String {
+    let age = format_time_short(&stat.time);
+    match stat.ahead {
+        0 => age,
+        ahead => format!("+{ahead} {age}"),
+    }
+}
+
+fn truncate_line(line: &mut Line<'static>, width: usize) {
+    if line.width() <= width {
+        return;
+    }
+    let mut used = 0;
+    let mut spans = Vec::new();
+    for span in line.spans.drain(..) {
+        let span_w = span.width();
+        if used + span_w < width {
+            used += span_w;
+            spans.push(span);
+            continue;
+        }
+        let cut = truncate_with_ellipsis(&span.content, width - used);
+        spans.push(Span::styled(cut, span.style));
+        break;
+    }
+    line.spans = spans;
+}
+
 fn entry_line(
+    entry: &DisplayEntry,
+    selected: bool,
+    max_width: usize,
+    focused: bool,
+    stat: Option<&TipStat>,
+    p: &ThemePalette,
+) -> Line<'static> {
+    let mut line = label_line(entry, selected, max_width, focused, p);
+    // Shown only when the name leaves room for it
+    if let EntryKind::LocalBranch {
+        upstream: Some(ref upstream),
+        ..
+    } = entry.kind
+    {
+        let (text, color) = if upstream.gone {
+            (format!(" \u{2192} {} gone", upstream.name), p.warn_fg)
+        } else {
+            (format!(" \u{2192} {}", upstream.name), p.dim_text)
+        };
+        let mut style = Style::default().fg(color);
+        if selected {
+            style = style.bg(p.selected_bg);
+        }
+        line.spans.push(Span::styled(text, style));
+    }
+    if let EntryKind::LocalBranch {
+        protected: true, ..
+    } = entry.kind
+    {
+        let mut style = Style::default().fg(p.warn_fg);
+        if selected {
+            style = style.bg(p.selected_bg);
+        }
+        line.spans.push(Span::styled(" \u{229d} protected", style));
+    }
+    if let EntryKind::PullRequest { review, draft, .. } = entry.kind {
+        let (text, color) = match review {
+            ReviewState::Approved => (" \u{2713} approved", p.accent),
+            ReviewState::ChangesRequested => (" \u{2717} changes", p.error_fg),
+            ReviewState::Pending => (" \u{25cb} pending", p.dim_text),
+        };
+        let mut style = Style::default().fg(color);
+        let mut draft_style = Style::default()
+            .fg(p.dim_text)
+            .add_modifier(Modifier::ITALIC);
+        if selected {
+            style = style.bg(p.selected_bg);
+            draft_style = draft_style.bg(p.selected_bg);
+        }
+        line.spans.push(Span::styled(text, style));
+        if draft {
+            line.spans.push(Span::styled(" draft", draft_style));
+        }
+    }
+    if let EntryKind::Tag {
+        signature: Some(ref signature),
+        ..
+    } = entry.kind
+    {
+        let (mark, color) = signature_mark(signature, p);
+        let mut style = Style::default().fg(color);
+        if selected {
+            style = style.bg(p.selected_bg);
+        }
+        line.spans.push(Span::styled(format!(" {mark}"), style));
+    }
+    if let Some(stat) = stat {
+        let label = tip_stat_label(stat);
+        let label_w = UnicodeWidthStr::width(label.as_str());
+        // Right-aligned, cutting into the name's annotations if need be
+        if max_width > label_w + TIP_STAT_W {
+            truncate_line(&mut line, max_width - label_w - 1);
+            let pad = max_width - line.width() - label_w;
+            let mut style = Style::default().fg(p.dim_text);
+            if selected {
+                style = style.bg(p.selected_bg);
+            }
+            line.spans.push(Span::styled(" ".repeat(pad), style));
+            line.spans.push(Span::styled(label, style));
+        }
+    }
+    line
+}
+
+pub fn signature_mark(signature: &TagSignature, p: &ThemePalette) -> (&'static str, Color) {
+    match signature {
+        TagSignature::Pending => ("\u{2026}", p.dim_text),
+        TagSignature::Good { .. } => ("\u{2713}", p.accent),
+        TagSignature::Bad { .. } => ("\u{2717}", p.error_fg),
+        TagSignature::Unverifiable { .. } => ("?", p.warn_fg),
+    }
+}
+
+fn label_line(
     entry: &DisplayEntry,
     selected: bool,
     max_width: usize,
@@ -414,7 +938,9 @@ fn entry_line(
 
     let is_branch = matches!(
         entry.kind,
-        EntryKind::LocalBranch { is_head: false, .. } | EntryKind::ForkBranch { .. }
+        EntryKind::LocalBranch { is_head: false, .. }
+            | EntryKind::RemoteBranch { .. }
+            | EntryKind::ForkBranch { .. }
     );
 
     if is_branch {
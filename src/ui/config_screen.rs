@@ -1,5 +1,5 @@
-use crate::screen::{ConfigScreenState, ConfigSection, FieldMode};
-use crate::ui::theme::{ThemePalette, THEME_NAMES};
+use crate::screen::{ConfigScreenState, ConfigSection, FieldMode, RepoField};
+use crate::ui::theme::{self, ThemePalette, THEME_NAMES};
 use ratatui::{
     buffer::Buffer as Buf,
     layout::{Constraint, Direction, Layout, Rect},
@@ -109,16 +109,18 @@ fn render_repos_section(buf: &mut Buf, area: Rect, state: &ConfigScreenState, p:
 
         let line = if is_editing {
             if let FieldMode::Editing(ref text) = state.field_mode {
-                Line::from(vec![
-                    Span::styled(
-                        format!("{text}\u{258c}"),
-                        Style::default().fg(p.filter_color).bg(p.selected_bg),
-                    ),
-                    Span::styled(
-                        format!("  {name_str}"),
-                        Style::default().fg(p.dim_text).bg(p.selected_bg),
-                    ),
-                ])
+                let editing = Style::default().fg(p.filter_color).bg(p.selected_bg);
+                let other = Style::default().fg(p.dim_text).bg(p.selected_bg);
+                match state.repo_field {
+                    RepoField::Path => Line::from(vec![
+                        Span::styled(format!("{text}\u{258c}"), editing),
+                        Span::styled(format!("  {name_str}"), other),
+                    ]),
+                    RepoField::Name => Line::from(vec![
+                        Span::styled(path_str.to_string(), other),
+                        Span::styled(format!("  {text}\u{258c}"), editing),
+                    ]),
+                }
             } else {
                 Line::raw("")
             }
@@ -145,7 +147,7 @@ fn render_repos_section(buf: &mut Buf, area: Rect, state: &ConfigScreenState, p:
     let hint_y = area.y + repos.len() as u16 + 1;
     if hint_y < area.bottom() {
         let hint = Line::from(Span::styled(
-            "  Enter: edit path  a: add  x: remove",
+            "  Enter: edit (Tab: path/name)  a: add  x: remove",
             Style::default().fg(p.dim_text),
         ));
         buf.set_line(area.x, hint_y, &hint, area.width);
@@ -232,12 +234,144 @@ fn render_theme_section(buf: &mut Buf, area: Rect, state: &ConfigScreenState, p:
 
     let hint_y = area.y + THEME_NAMES.len() as u16 + 1;
     if hint_y < area.bottom() {
-        let hint = Line::from(Span::styled(
-            "  Enter/Space: select theme",
-            Style::default().fg(p.dim_text),
-        ));
+        let hint = Line::from(Span::styled(THEME_HINT, Style::default().fg(p.dim_text)));
         buf.set_line(area.x, hint_y, &hint, area.width);
     }
+
+    // Beside the list when there's room, under the hint otherwise
+    let list_w = THEME_HINT.len() as u16 + 2;
+    let preview = if area.width >= list_w + PREVIEW_MIN_WIDTH {
+        Rect {
+            x: area.x + list_w,
+            width: (area.width - list_w - 1).min(PREVIEW_MAX_WIDTH),
+            ..area
+        }
+    } else {
+        Rect {
+            x: area.x + 2,
+            y: hint_y + 2,
+            width: area.width.saturating_sub(4),
+            height: area.bottom().saturating_sub(hint_y + 2),
+        }
+    };
+    let previewed = THEME_NAMES
+        .get(state.cursor)
+        .copied()
+        .unwrap_or(active_theme);
+    render_theme_preview(buf, preview, previewed);
+}
+
+const THEME_HINT: &str = "  Enter/Space: select theme";
+const PREVIEW_MIN_WIDTH: u16 = 40;
+const PREVIEW_MAX_WIDTH: u16 = 60;
+
+// Lanes (trunk 0 or branch 1, None blank), refs, message, author, age
+type MockRow = (
+    [Option<(&'static str, usize)>; 2],
+    &'static str,
+    &'static str,
+    &'static str,
+    &'static str,
+);
+
+const MOCK_ROWS: [MockRow; 5] = [
+    (
+        [Some(("\u{25ef} ", 0)), Some(("\u{256d}\u{2500}", 1))],
+        "[*main] ",
+        "Merge branch 'feature'",
+        "alice",
+        "1d",
+    ),
+    (
+        [Some(("\u{2503} ", 0)), Some(("\u{25ef} ", 1))],
+        "[feature] ",
+        "Add login form",
+        "bob",
+        "2d",
+    ),
+    (
+        [Some(("\u{25ef} ", 0)), Some(("\u{2502} ", 1))],
+        "(v1.0) ",
+        "Release 1.0",
+        "alice",
+        "3d",
+    ),
+    (
+        [Some(("\u{2503} ", 0)), Some(("\u{25ef} ", 1))],
+        "",
+        "Fix typo",
+        "carol",
+        "4d",
+    ),
+    (
+        [Some(("\u{25ef}\u{2500}", 0)), Some(("\u{2500}\u{256f}", 1))],
+        "",
+        "Initial commit",
+        "alice",
+        "5d",
+    ),
+];
+
+fn render_theme_preview(buf: &mut Buf, area: Rect, name: &str) {
+    if area.height < 3 || area.width < 20 {
+        return;
+    }
+    let p = theme::palette_for_theme(Some(name));
+    let area = Rect {
+        height: area.height.min(MOCK_ROWS.len() as u16 + 2),
+        ..area
+    };
+    let block = Block::default()
+        .title(format!(" {name} "))
+        .title_style(Style::default().fg(p.accent))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(p.active_panel_border))
+        .style(Style::default().bg(p.content_bg).fg(p.content_fg));
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    for (i, (lanes, refs, message, author, age)) in MOCK_ROWS.iter().enumerate() {
+        let y = inner.y + i as u16;
+        if y >= inner.bottom() {
+            break;
+        }
+        let selected = i == 1;
+        let base = if selected {
+            Style::default().bg(p.selected_bg)
+        } else {
+            Style::default().bg(p.content_bg)
+        };
+        let mut spans = vec![if selected {
+            Span::styled("\u{258e}", base.fg(p.selected_accent))
+        } else {
+            Span::styled(" ", base)
+        }];
+        for (glyph, branch) in lanes.iter().flatten() {
+            let color = p.branch_color_by_identity(*branch, 1);
+            spans.push(Span::styled(*glyph, base.fg(color)));
+        }
+        let ref_color = match refs.chars().next() {
+            Some('(') => p.tag_color,
+            _ if refs.contains('*') => p.head_color,
+            _ => p.branch_color_by_identity(1, 1),
+        };
+        spans.push(Span::styled(" ", base));
+        spans.push(Span::styled(
+            *refs,
+            base.fg(ref_color).add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::styled(*message, base));
+        spans.push(Span::styled(format!(" {author}"), base.fg(p.dim_text)));
+
+        for x in inner.x..inner.right() {
+            buf[(x, y)].set_style(base);
+        }
+        buf.set_line(inner.x, y, &Line::from(spans), inner.width);
+        let age_x = inner.right().saturating_sub(age.len() as u16 + 1);
+        if age_x > inner.x {
+            buf.set_string(age_x, y, *age, base.fg(p.dim_text));
+        }
+    }
 }
 
 fn render_profiles_section(buf: &mut Buf, area: Rect, state: &ConfigScreenState, p: &ThemePalette) {
@@ -307,7 +441,7 @@ fn render_profiles_section(buf: &mut Buf, area: Rect, state: &ConfigScreenState,
 fn render_footer(buf: &mut Buf, area: Rect, state: &ConfigScreenState, p: &ThemePalette) {
     let dirty_marker = if state.dirty { " [modified]" } else { "" };
 
-    let line = Line::from(vec![
+    let mut spans = vec![
         Span::styled(
             " Ctrl-S: save",
             Style::default()
@@ -324,6 +458,12 @@ fn render_footer(buf: &mut Buf, area: Rect, state: &ConfigScreenState, p: &Theme
             dirty_marker.to_string(),
             Style::default().fg(p.warn_fg).bg(p.app_bg),
         ),
-    ]);
-    buf.set_line(area.x, area.y, &line, area.width);
+    ];
+    if let Some(ref note) = state.import_note {
+        spans.push(Span::styled(
+            format!("  {note}"),
+            Style::default().fg(p.accent).bg(p.app_bg),
+        ));
+    }
+    buf.set_line(area.x, area.y, &Line::from(spans), area.width);
 }
@@ -0,0 +1,107 @@
+use crate::git::merge::{ConflictPreview, MergeOutcome};
+use crate::ui::theme::ThemePalette;
+use crate::ui::truncate_with_ellipsis;
+use ratatui::{
+    buffer::Buffer as Buf,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Widget},
+};
+
+pub struct ConflictPreviewState {
+    pub target: String,
+    pub preview: Option<ConflictPreview>,
+}
+
+pub struct ConflictPreviewPanel<'a> {
+    pub state: &'a ConflictPreviewState,
+    pub palette: &'a ThemePalette,
+}
+
+impl<'a> Widget for ConflictPreviewPanel<'a> {
+    fn render(self, area: Rect, buf: &mut Buf) {
+        let p = self.palette;
+        let popup = super::centered_rect(50, 50, area);
+        Clear.render(popup, buf);
+
+        let block = Block::default()
+            .title(format!(" Preview: {} ", self.state.target))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(p.accent));
+        let inner = block.inner(popup);
+        block.render(popup, buf);
+        if inner.height < 2 {
+            return;
+        }
+
+        let footer_y = inner.bottom() - 1;
+        buf.set_string(
+            inner.x + 1,
+            footer_y,
+            "dry run \u{00b7} nothing was changed \u{00b7} Esc close",
+            Style::default().fg(p.dim_text),
+        );
+        let Some(ref preview) = self.state.preview else {
+            buf.set_string(
+                inner.x + 1,
+                inner.y,
+                "merging in memory\u{2026}",
+                Style::default().fg(p.dim_text),
+            );
+            return;
+        };
+
+        let width = inner.width.saturating_sub(2) as usize;
+        let mut lines = Vec::new();
+        for (label, outcome) in [
+            (format!("Merge into {}", preview.head), &preview.merge),
+            (
+                format!("Cherry-pick onto {}", preview.head),
+                &preview.cherry_pick,
+            ),
+        ] {
+            let (verdict, color) = match outcome {
+                MergeOutcome::UpToDate => ("already contained".to_string(), p.dim_text),
+                MergeOutcome::FastForward => ("fast-forward".to_string(), p.accent),
+                MergeOutcome::Clean => ("no conflicts".to_string(), p.accent),
+                MergeOutcome::Conflicts(paths) => {
+                    (format!("{} conflicting files", paths.len()), p.error_fg)
+                }
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{label}: "),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(verdict, Style::default().fg(color)),
+            ]));
+            if let MergeOutcome::Conflicts(paths) = outcome {
+                for path in paths {
+                    lines.push(Line::from(Span::styled(
+                        format!(
+                            "  {}",
+                            truncate_with_ellipsis(path, width.saturating_sub(2))
+                        ),
+                        Style::default().fg(p.warn_fg),
+                    )));
+                }
+            }
+            lines.push(Line::default());
+        }
+
+        lines.pop();
+        let rows = (inner.height - 1) as usize;
+        let hidden = lines.len().saturating_sub(rows);
+        if hidden > 0 {
+            lines.truncate(rows - 1);
+            lines.push(Line::from(Span::styled(
+                format!("  +{} more lines", hidden + 1),
+                Style::default().fg(p.dim_text),
+            )));
+        }
+        for (i, line) in lines.iter().enumerate() {
+            buf.set_line(inner.x + 1, inner.y + i as u16, line, width as u16);
+        }
+    }
+}
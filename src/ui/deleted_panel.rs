@@ -0,0 +1,105 @@
+use crate::git::undo::DeletedRef;
+use crate::ui::theme::ThemePalette;
+use crate::ui::truncate_with_ellipsis;
+use ratatui::{
+    buffer::Buffer as Buf,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Widget},
+};
+use std::time::{Duration, Instant};
+
+pub const UNDO_GRACE: Duration = Duration::from_secs(15 * 60);
+
+pub struct RecentlyDeleted {
+    pub project_idx: usize,
+    pub deleted: DeletedRef,
+    pub at: Instant,
+}
+
+impl RecentlyDeleted {
+    pub fn expired(&self) -> bool {
+        self.at.elapsed() >= UNDO_GRACE
+    }
+}
+
+pub struct DeletedRefsState {
+    pub selected: usize,
+}
+
+pub struct DeletedPanel<'a> {
+    pub entries: &'a [RecentlyDeleted],
+    pub state: &'a DeletedRefsState,
+    pub palette: &'a ThemePalette,
+}
+
+impl<'a> Widget for DeletedPanel<'a> {
+    fn render(self, area: Rect, buf: &mut Buf) {
+        let p = self.palette;
+        let popup = super::centered_rect(60, 40, area);
+        Clear.render(popup, buf);
+
+        let block = Block::default()
+            .title(format!(" Recently deleted ({}) ", self.entries.len()))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(p.accent));
+        let inner = block.inner(popup);
+        block.render(popup, buf);
+        if inner.height < 2 {
+            return;
+        }
+
+        let footer_y = inner.bottom() - 1;
+        buf.set_string(
+            inner.x + 1,
+            footer_y,
+            format!(
+                "Enter restore  Esc close \u{00b7} kept {} min",
+                UNDO_GRACE.as_secs() / 60
+            ),
+            Style::default().fg(p.dim_text),
+        );
+        if self.entries.is_empty() {
+            buf.set_string(
+                inner.x + 1,
+                inner.y,
+                "nothing deleted recently",
+                Style::default().fg(p.dim_text),
+            );
+            return;
+        }
+
+        let rows = (inner.height - 1) as usize;
+        let skip = (self.state.selected + 1).saturating_sub(rows);
+        for (i, entry) in self.entries.iter().enumerate().skip(skip).take(rows) {
+            let y = inner.y + (i - skip) as u16;
+            let is_selected = i == self.state.selected;
+            let base = if is_selected {
+                Style::default().bg(p.selected_bg)
+            } else {
+                Style::default()
+            };
+            let oid = entry.deleted.oid().to_string();
+            let age = format!(" {}m ago ", entry.at.elapsed().as_secs() / 60);
+            let label_w = (inner.width as usize).saturating_sub(8 + 1 + age.len() + 1);
+            let line = Line::from(vec![
+                Span::styled(
+                    format!(" {} ", &oid[..7]),
+                    base.fg(p.accent).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    truncate_with_ellipsis(&entry.deleted.label(), label_w),
+                    base,
+                ),
+                Span::styled(age, base.fg(p.dim_text)),
+            ]);
+            if is_selected {
+                for x in inner.x..inner.right() {
+                    buf[(x, y)].set_style(base);
+                }
+            }
+            buf.set_line(inner.x, y, &line, inner.width);
+        }
+    }
+}
@@ -1,22 +1,87 @@
-use super::truncate_with_ellipsis;
+use super::links::{hyperlink, WebLinks};
+use super::markdown::{self, MessageRow};
+use super::{format_size, render_scrollbar, truncate_with_ellipsis};
+use crate::dates::DateFormat;
+use crate::git::diff::{ChangedFile, FilePatch};
+use crate::git::signature::TagSignature;
+use crate::git::types::{Oid, Upstream};
 use crate::graph::layout::format_time_ago;
 use crate::graph::types::RowMeta;
+use crate::ui::branch_panel::signature_mark;
 use crate::ui::theme::ThemePalette;
 use ratatui::{
     buffer::Buffer as Buf,
     layout::Rect,
-    style::Style,
-    text::{Line, Span},
+    style::{Modifier, Style},
+    text::{Line, Span, Text},
     widgets::Widget,
 };
+use unicode_width::UnicodeWidthStr;
 
-#[allow(dead_code)]
 pub struct DetailPanel<'a> {
     pub meta: &'a RowMeta,
+    pub files: Option<&'a [ChangedFile]>,
+    pub file_selected: usize,
+    // The inner None while it loads
+    pub diff: Option<Option<&'a Text<'static>>>,
+    pub diff_scroll: usize,
+    pub message_scroll: usize,
+    pub upstreams: Vec<(&'a str, &'a Upstream)>,
+    pub tag_signatures: Vec<(&'a str, &'a TagSignature)>,
+    pub note: Option<&'a str>,
+    pub owners: Vec<&'a str>,
+    pub large_file_bytes: u64,
+    pub hyperlinks: bool,
+    pub links: Option<WebLinks<'a>>,
     pub focused: bool,
+    pub dates: &'a DateFormat,
     pub palette: &'a ThemePalette,
 }
 
+impl DetailPanel<'_> {
+    fn header_rows(&self) -> usize {
+        3 + usize::from(!self.meta.branch_names.is_empty())
+            + usize::from(!self.meta.tag_names.is_empty())
+            + self.tag_signatures.len()
+            + self.upstreams.len()
+            + usize::from(!self.owners.is_empty())
+            + usize::from(self.note.is_some())
+            + 1
+    }
+
+    // A column narrower when the message needs a scrollbar
+    pub fn message_layout(&self, area: Rect) -> (usize, Vec<MessageRow>) {
+        let remaining = (area.height as usize).saturating_sub(self.header_rows());
+        // Leave room for the file list below a long message
+        let rows = if self.files.is_some_and(|f| !f.is_empty()) {
+            remaining.div_ceil(2)
+        } else {
+            remaining
+        };
+        let inner_w = area.width.saturating_sub(1) as usize;
+        let lines = markdown::message_rows(
+            &self.meta.message,
+            inner_w,
+            self.palette,
+            self.links.as_ref(),
+        );
+        if lines.len() > rows {
+            let narrower = inner_w.saturating_sub(1);
+            (
+                rows,
+                markdown::message_rows(
+                    &self.meta.message,
+                    narrower,
+                    self.palette,
+                    self.links.as_ref(),
+                ),
+            )
+        } else {
+            (rows, lines)
+        }
+    }
+}
+
 impl<'a> Widget for DetailPanel<'a> {
     fn render(self, area: Rect, buf: &mut Buf) {
         if area.height < 1 || area.width < 10 {
@@ -48,6 +113,15 @@ impl<'a> Widget for DetailPanel<'a> {
             ]),
             inner_w as u16,
         );
+        if let Some(links) = self.links {
+            hyperlink(
+                buf,
+                x + 4,
+                y,
+                sha.len() as u16,
+                &links.commit(self.meta.oid),
+            );
+        }
         y += 1;
 
         if (y - inner_y) as usize >= inner_h {
@@ -59,6 +133,14 @@ impl<'a> Widget for DetailPanel<'a> {
             &Line::from(vec![
                 Span::styled("Author ", label_style),
                 Span::raw(self.meta.author.as_str()),
+                Span::styled(
+                    if self.meta.email.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" <{}>", self.meta.email)
+                    },
+                    Style::default().fg(p.dim_text),
+                ),
             ]),
             inner_w as u16,
         );
@@ -74,6 +156,10 @@ impl<'a> Widget for DetailPanel<'a> {
             &Line::from(vec![
                 Span::styled("Time ", label_style),
                 Span::raw(time_ago),
+                Span::styled(
+                    format!("  {}", self.dates.date_time(&self.meta.time)),
+                    Style::default().fg(p.dim_text),
+                ),
             ]),
             inner_w as u16,
         );
@@ -89,6 +175,16 @@ impl<'a> Widget for DetailPanel<'a> {
                 ]),
                 inner_w as u16,
             );
+            let right = x + inner_w as u16;
+            let mut col = x + 5;
+            for name in &self.meta.branch_names {
+                let width = UnicodeWidthStr::width(name.as_str()) as u16;
+                let url = self.links.and_then(|links| links.branch(name));
+                if let Some(url) = url.filter(|_| col < right) {
+                    hyperlink(buf, col, y, width.min(right - col), &url);
+                }
+                col = col.saturating_add(width + 2);
+            }
             y += 1;
         }
 
@@ -108,23 +204,296 @@ impl<'a> Widget for DetailPanel<'a> {
             y += 1;
         }
 
+        for (tag, signature) in &self.tag_signatures {
+            if ((y - inner_y) as usize) >= inner_h {
+                break;
+            }
+            let (mark, color) = signature_mark(signature, p);
+            let text = match signature {
+                TagSignature::Pending => "verifying\u{2026}".to_string(),
+                TagSignature::Good { signer } => signer.clone(),
+                TagSignature::Bad {
+                    signer: Some(signer),
+                } => format!("bad signature by {signer}"),
+                TagSignature::Bad { signer: None } => "bad signature".to_string(),
+                TagSignature::Unverifiable { reason } => reason.clone(),
+            };
+            buf.set_line(
+                x,
+                y,
+                &Line::from(vec![
+                    Span::styled("Signed ", label_style),
+                    Span::styled(format!("{tag} "), Style::default().fg(p.tag_color)),
+                    Span::styled(format!("{mark} {text}"), Style::default().fg(color)),
+                ]),
+                inner_w as u16,
+            );
+            y += 1;
+        }
+
+        for (branch, upstream) in &self.upstreams {
+            if ((y - inner_y) as usize) >= inner_h {
+                break;
+            }
+            let mut spans = vec![
+                Span::styled("Upstream ", label_style),
+                Span::raw(format!("{branch} \u{2192} {}", upstream.name)),
+            ];
+            if upstream.gone {
+                spans.push(Span::styled(" (gone)", Style::default().fg(p.warn_fg)));
+            }
+            buf.set_line(x, y, &Line::from(spans), inner_w as u16);
+            y += 1;
+        }
+
+        if !self.owners.is_empty() && ((y - inner_y) as usize) < inner_h {
+            buf.set_line(
+                x,
+                y,
+                &Line::from(vec![
+                    Span::styled("Owners ", label_style),
+                    Span::raw(self.owners.join(", ")),
+                ]),
+                inner_w as u16,
+            );
+            y += 1;
+        }
+
+        if let Some(note) = self.note.filter(|_| ((y - inner_y) as usize) < inner_h) {
+            buf.set_line(
+                x,
+                y,
+                &Line::from(vec![
+                    Span::styled("Note ", label_style),
+                    Span::styled(note, Style::default().add_modifier(Modifier::ITALIC)),
+                ]),
+                inner_w as u16,
+            );
+            y += 1;
+        }
+
         if ((y - inner_y) as usize) < inner_h {
             y += 1;
         }
         let remaining = inner_h.saturating_sub((y - inner_y) as usize);
-        if remaining > 0 {
-            for (i, line) in self.meta.message.lines().enumerate() {
-                if i >= remaining {
-                    break;
-                }
-                let truncated = truncate_with_ellipsis(line, inner_w);
+        if let Some(diff) = self.diff {
+            let Some(diff) = diff else {
                 buf.set_line(
                     x,
-                    y + i as u16,
-                    &Line::from(Span::raw(truncated)),
+                    y,
+                    &Line::from(Span::styled("Diff \u{2026}", label_style)),
                     inner_w as u16,
                 );
+                return;
+            };
+            let track = Rect {
+                x: area.right() - 1,
+                y,
+                width: 1,
+                height: remaining as u16,
+            };
+            // Clear of the scrollbar when there is one
+            let line_w = if diff.lines.len() > remaining {
+                inner_w - 1
+            } else {
+                inner_w
+            };
+            for line in diff.lines.iter().skip(self.diff_scroll).take(remaining) {
+                buf.set_line(x, y, line, line_w as u16);
+                y += 1;
+            }
+            render_scrollbar(buf, track, diff.lines.len(), self.diff_scroll, p);
+            return;
+        }
+        let (message_rows, lines) = self.message_layout(area);
+        let message_rows = message_rows.min(remaining);
+        let scroll = self
+            .message_scroll
+            .min(lines.len().saturating_sub(message_rows));
+        let track = Rect {
+            x: area.right() - 1,
+            y,
+            width: 1,
+            height: message_rows as u16,
+        };
+        render_scrollbar(buf, track, lines.len(), scroll, p);
+        let mut used = 0;
+        for row in lines.iter().skip(scroll).take(message_rows) {
+            buf.set_line(x, y, &row.line, inner_w as u16);
+            for (col, width, url) in row.links.iter().filter(|_| self.hyperlinks) {
+                hyperlink(buf, x + col, y, *width, url);
+            }
+            y += 1;
+            used += 1;
+        }
+        let remaining = remaining.saturating_sub(used + 1);
+        if remaining < 2 {
+            return;
+        }
+        y += 1;
+
+        let mut header = vec![Span::styled(
+            match self.files {
+                Some(files) => format!("Files ({})", files.len()),
+                None => "Files \u{2026}".to_string(),
+            },
+            label_style,
+        )];
+        if let Some(files) = self.files.filter(|f| !f.is_empty()) {
+            let (insertions, deletions) = files
+                .iter()
+                .filter_map(|f| f.stat)
+                .fold((0, 0), |(i, d), s| (i + s.insertions, d + s.deletions));
+            header.push(Span::raw(" "));
+            header.extend(stat_spans(insertions, deletions, p));
+        }
+        buf.set_line(x, y, &Line::from(header), inner_w as u16);
+        y += 1;
+        let Some(files) = self.files else {
+            return;
+        };
+        let rows = remaining - 1;
+        // Keep the selected file in view
+        let skip = (self.file_selected + 1).saturating_sub(rows);
+        let track = Rect {
+            x: area.right() - 1,
+            y,
+            width: 1,
+            height: rows as u16,
+        };
+        render_scrollbar(buf, track, files.len(), skip, p);
+        let inner_w = if files.len() > rows {
+            inner_w - 1
+        } else {
+            inner_w
+        };
+        for (i, file) in files.iter().enumerate().skip(skip).take(rows) {
+            let style = if self.focused && i == self.file_selected {
+                Style::default().bg(p.selected_bg)
+            } else {
+                Style::default()
+            };
+            let size_note = match file.lfs {
+                Some(ref pointer) => {
+                    Some((format!(" LFS {}", format_size(pointer.size)), p.tag_color))
+                }
+                None if file.size >= self.large_file_bytes => {
+                    Some((format!(" {}", format_size(file.size)), p.warn_fg))
+                }
+                None => None,
+            };
+            let note_w = size_note.as_ref().map_or(0, |(text, _)| text.len());
+            let stat = match file.stat {
+                Some(s) => stat_spans(s.insertions, s.deletions, p),
+                None => vec![Span::styled("bin", Style::default().fg(p.dim_text))],
+            };
+            let stat_w: usize = stat.iter().map(|s| s.width()).sum();
+            let path_w = inner_w.saturating_sub(2 + note_w + 1 + stat_w);
+            let path = truncate_with_ellipsis(&file.path, path_w);
+            let pad = path_w.saturating_sub(UnicodeWidthStr::width(path.as_str()));
+            let mut spans = vec![
+                Span::styled(
+                    format!("{} ", file.status.marker()),
+                    Style::default().fg(p.dim_text),
+                ),
+                Span::styled(path, style),
+            ];
+            if let Some((text, color)) = size_note {
+                spans.push(Span::styled(text, Style::default().fg(color)));
+            }
+            // The counts line up on the right edge
+            spans.push(Span::raw(" ".repeat(pad + 1)));
+            spans.extend(stat);
+            buf.set_line(x, y, &Line::from(spans), inner_w as u16);
+            y += 1;
+        }
+    }
+}
+
+pub struct StashDetail<'a> {
+    pub index: usize,
+    pub base: Oid,
+    pub base_summary: Option<&'a str>,
+    pub message: &'a str,
+    pub palette: &'a ThemePalette,
+}
+
+impl Widget for StashDetail<'_> {
+    fn render(self, area: Rect, buf: &mut Buf) {
+        if area.height < 1 || area.width < 10 {
+            return;
+        }
+        let p = self.palette;
+        let label_style = Style::default().fg(p.accent);
+        let sha = self.base.to_string();
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("Stash ", label_style),
+                Span::raw(format!("stash@{{{}}}", self.index)),
+            ]),
+            Line::from(vec![
+                Span::styled("Base ", label_style),
+                Span::raw(sha[..8].to_string()),
+                Span::styled(
+                    self.base_summary
+                        .map(|s| format!("  {s}"))
+                        .unwrap_or_default(),
+                    Style::default().fg(p.dim_text),
+                ),
+            ]),
+            Line::default(),
+        ];
+        lines.extend(self.message.lines().map(Line::raw));
+        let x = area.x + 1;
+        let width = area.width.saturating_sub(1);
+        for (y, line) in (area.y..area.bottom()).zip(&lines) {
+            buf.set_line(x, y, line, width);
+        }
+    }
+}
+
+fn stat_spans(insertions: usize, deletions: usize, p: &ThemePalette) -> Vec<Span<'static>> {
+    vec![
+        Span::styled(format!("+{insertions}"), Style::default().fg(p.diff_add)),
+        Span::styled(format!(" -{deletions}"), Style::default().fg(p.diff_del)),
+    ]
+}
+
+const TAB_W: usize = 4;
+
+pub fn diff_text(files: &[FilePatch], palette: &ThemePalette) -> Text<'static> {
+    let p = palette;
+    let dim = Style::default().fg(p.dim_text);
+    if files.is_empty() {
+        return Text::from(Line::styled("No changes against the first parent", dim));
+    }
+    let mut lines = Vec::new();
+    for (i, file) in files.iter().enumerate() {
+        if i > 0 {
+            lines.push(Line::raw(""));
+        }
+        lines.push(Line::from(vec![
+            Span::styled(format!("{} ", file.status.marker()), dim),
+            Span::styled(
+                file.path.clone(),
+                Style::default().fg(p.accent).add_modifier(Modifier::BOLD),
+            ),
+        ]));
+        if file.binary {
+            lines.push(Line::styled("  binary file", dim));
+        }
+        for hunk in &file.hunks {
+            lines.push(Line::styled(hunk.header.clone(), dim));
+            for line in &hunk.lines {
+                let style = match line.origin {
+                    '+' => Style::default().fg(p.diff_add),
+                    '-' => Style::default().fg(p.diff_del),
+                    _ => Style::default().fg(p.content_fg),
+                };
+                let content = line.content.replace('\t', &" ".repeat(TAB_W));
+                lines.push(Line::styled(format!("{}{content}", line.origin), style));
             }
         }
     }
+    Text::from(lines)
 }
@@ -1,10 +1,17 @@
-use crate::git::types::CommitSource;
+use crate::annotations::Annotations;
+use crate::authors::AuthorNames;
+use crate::conventional::{self, Conventional};
+use crate::git::diff::{CommitWeight, DiffStat};
+use crate::git::types::{CommitSource, Oid};
+use crate::graph::drift::TrunkDistance;
 use crate::graph::image_cache::ImageCache;
-use crate::graph::layout::format_time_short;
+use crate::graph::layout::{duration_bucket, format_time_short};
 use crate::graph::pixel_renderer::{RenderParams, COLS_PER_LANE};
 use crate::graph::types::{Cell, CellSymbol, GraphRow};
 use crate::kitty_protocol::encode_kitty_image;
 use crate::terminal_graphics::GraphicsCapability;
+use crate::ui::line_cache::{LineCache, LineKey};
+use crate::ui::links::{link_graph_row, WebLinks};
 use crate::ui::theme::ThemePalette;
 use ratatui::{
     buffer::Buffer as Buf,
@@ -16,6 +23,13 @@ use std::collections::HashMap;
 use unicode_width::UnicodeWidthStr;
 
 const TRUNK_VERT_CHARS: &[&str] = &["┃ ", "╏ ", "┇ "];
+const REMOTE_ONLY_NODE: &str = "\u{25c7}";
+const MAX_DRIFT_WIDTH: usize = 16;
+const SPARK_W: usize = 5;
+const MAX_MILESTONE_WIDTH: usize = 24;
+const TRUNCATED_LABEL: &str = " \u{22ef} history truncated";
+const DETACHED_LABEL: &str = " detached";
+const UNRELEASED_BAND: &str = "\u{2506}";
 
 fn cell_glyph(cell: &Cell) -> &'static str {
     match cell.symbol {
@@ -41,15 +55,34 @@ pub struct GraphView<'a> {
     pub rows: &'a [GraphRow],
     pub scroll_y: usize,
     pub scroll_x: usize,
+    // Kitty graphics draw every lane as one image and ignore it
+    pub lane_scroll: usize,
     pub selected: usize,
     pub highlighted_oids: &'a std::collections::HashSet<crate::git::types::Oid>,
+    pub marked_oids: Option<&'a std::collections::HashSet<crate::git::types::Oid>>,
+    pub unseen_oids: Option<&'a std::collections::HashSet<crate::git::types::Oid>>,
+    pub remote_only: Option<&'a std::collections::HashSet<crate::git::types::Oid>>,
+    pub annotations: Option<&'a Annotations>,
+    pub weights: Option<&'a HashMap<Oid, CommitWeight>>,
+    pub large_file_bytes: u64,
+    pub diff_stats: Option<&'a HashMap<Oid, DiffStat>>,
+    pub trunk_distances: Option<&'a HashMap<Oid, TrunkDistance>>,
+    pub shallow: Option<&'a std::collections::HashSet<crate::git::types::Oid>>,
+    pub authors: AuthorNames<'a>,
+    pub detached: Option<&'a std::collections::HashSet<crate::git::types::Oid>>,
+    pub unreleased: Option<&'a std::collections::HashSet<crate::git::types::Oid>>,
+    pub milestones: bool,
     pub is_active: bool,
     pub trunk_count: usize,
     pub max_lanes: usize,
+    pub lane_overflow: bool,
+    pub lane_cursor: Option<usize>,
     pub palette: &'a ThemePalette,
     pub branch_index_to_name: &'a HashMap<usize, String>,
+    pub links: Option<WebLinks<'a>>,
     pub graphics_cap: &'a GraphicsCapability,
     pub image_cache: &'a mut ImageCache,
+    pub line_cache: &'a mut LineCache,
     pub render_params: Option<&'a RenderParams>,
 }
 
@@ -67,6 +100,8 @@ impl<'a> GraphView<'a> {
         }
 
         let avail_w = area.width as usize;
+        let use_kitty = self.graphics_cap.is_kitty() && self.render_params.is_some();
+        let lane_scroll = if use_kitty { 0 } else { self.lane_scroll };
 
         let header_y = area.y;
         render_lane_header(
@@ -75,10 +110,43 @@ impl<'a> GraphView<'a> {
             area.x,
             avail_w,
             self.rows.get(self.scroll_y),
+            lane_scroll,
             self.branch_index_to_name,
             self.trunk_count,
             self.palette,
         );
+        let last_lane = self.max_lanes.saturating_sub(1);
+        if self.lane_overflow && last_lane >= lane_scroll {
+            let lane = (last_lane - lane_scroll).min(u16::MAX as usize / 2) as u16;
+            let x = area
+                .x
+                .saturating_add(1)
+                .saturating_add(lane * COLS_PER_LANE);
+            if x < area.right() {
+                buf[(x, header_y)].set_char('+').set_style(
+                    Style::default()
+                        .fg(self.palette.warn_fg)
+                        .bg(self.palette.lane_header_bg)
+                        .add_modifier(Modifier::BOLD),
+                );
+            }
+        }
+
+        // The cursor reverses the lane's header cells, keeping any label readable
+        if let Some(lane) = self.lane_cursor.and_then(|l| l.checked_sub(lane_scroll)) {
+            let lane = lane.min(u16::MAX as usize / 2) as u16;
+            let x = area
+                .x
+                .saturating_add(1)
+                .saturating_add(lane * COLS_PER_LANE);
+            let end = x.saturating_add(COLS_PER_LANE).min(area.right());
+            let cursor = Style::default()
+                .fg(self.palette.accent)
+                .add_modifier(Modifier::REVERSED | Modifier::BOLD);
+            for x in x..end {
+                buf[(x, header_y)].set_style(cursor);
+            }
+        }
 
         let commit_area_top = area.y + 1;
         let visible = (area.height as usize).saturating_sub(1);
@@ -88,8 +156,6 @@ impl<'a> GraphView<'a> {
             self.palette.unfocused_sel_bg
         };
 
-        let use_kitty = self.graphics_cap.is_kitty() && self.render_params.is_some();
-
         for (i, row) in self
             .rows
             .iter()
@@ -119,25 +185,56 @@ impl<'a> GraphView<'a> {
                     sel_bg,
                 );
             } else {
-                let line = build_row_line(
-                    row,
-                    is_selected,
-                    is_highlighted,
-                    self.scroll_x,
-                    avail_w,
-                    self.trunk_count,
-                    self.max_lanes,
-                    sel_bg,
-                    self.branch_index_to_name,
-                    self.palette,
-                );
-                let line_width: usize = line
-                    .spans
-                    .iter()
-                    .map(|s| UnicodeWidthStr::width(s.content.as_ref()))
-                    .sum();
-
-                buf.set_line(area.x, y, &line, area.width);
+                let key = LineKey {
+                    oid: row.meta.oid,
+                    width: avail_w,
+                    scroll_x: self.scroll_x,
+                    lane_scroll,
+                    selected: is_selected,
+                    highlighted: is_highlighted,
+                    active: self.is_active,
+                    age: duration_bucket(&row.meta.time),
+                    text_only: false,
+                };
+                let (scroll_x, trunk_count, max_lanes) =
+                    (self.scroll_x, self.trunk_count, self.max_lanes);
+                let (branch_names, palette) = (self.branch_index_to_name, self.palette);
+                let drift = self
+                    .trunk_distances
+                    .and_then(|d| d.get(&row.meta.oid))
+                    .map(TrunkDistance::label);
+                let truncated = self.shallow.is_some_and(|s| s.contains(&row.meta.oid));
+                let detached = self.detached.is_some_and(|d| d.contains(&row.meta.oid));
+                let author = self.authors.label(&row.meta.author, &row.meta.email);
+                let milestones = self.milestones;
+                let diffstat = self.diff_stat(row);
+                let &(ref line, line_width) = self.line_cache.get_or_build(key, || {
+                    build_row_line(
+                        row,
+                        is_selected,
+                        is_highlighted,
+                        scroll_x,
+                        lane_scroll,
+                        avail_w,
+                        trunk_count,
+                        max_lanes,
+                        sel_bg,
+                        drift.as_deref(),
+                        truncated,
+                        detached,
+                        &author,
+                        milestones,
+                        diffstat,
+                        branch_names,
+                        palette,
+                    )
+                });
+
+                buf.set_line(area.x, y, line, area.width);
+                if let Some(ref links) = self.links {
+                    let names = link_names(row, self.branch_index_to_name);
+                    link_graph_row(buf, area.x, y, area.width, line, &names, links);
+                }
 
                 if is_selected && line_width < avail_w {
                     let fill_style = Style::default().bg(sel_bg);
@@ -146,9 +243,69 @@ impl<'a> GraphView<'a> {
                     }
                 }
             }
+
+            let is_marked = self.marked_oids.is_some_and(|m| m.contains(&row.meta.oid));
+            let is_unseen = self.unseen_oids.is_some_and(|u| u.contains(&row.meta.oid));
+            let is_remote_only = self.remote_only.is_some_and(|r| r.contains(&row.meta.oid));
+            let is_unreleased = self.unreleased.is_some_and(|u| u.contains(&row.meta.oid));
+            let lane = row.layout.commit_lane.checked_sub(lane_scroll);
+            if let (true, false, Some(lane)) = (is_remote_only, use_kitty, lane) {
+                let x = area.x as usize + 1 + lane * COLS_PER_LANE as usize;
+                if let Some(cell) = u16::try_from(x)
+                    .ok()
+                    .and_then(|x| buf.cell_mut(Position::new(x, y)))
+                {
+                    cell.set_symbol(REMOTE_ONLY_NODE);
+                }
+            }
+            let gutter = if is_marked {
+                Some(("\u{2261}", self.palette.dim_text))
+            } else if is_unseen {
+                Some(("\u{2022}", self.palette.accent))
+            } else if is_remote_only && use_kitty {
+                Some((REMOTE_ONLY_NODE, self.palette.accent))
+            } else if is_unreleased {
+                Some((UNRELEASED_BAND, self.palette.tag_color))
+            } else {
+                None
+            };
+            if let Some((symbol, color)) = gutter.filter(|_| !is_selected) {
+                // Drawn over the cached line so marks never enter the cache key
+                if let Some(cell) = buf.cell_mut(Position::new(area.x, y)) {
+                    cell.set_symbol(symbol);
+                    cell.set_style(Style::default().fg(color));
+                }
+            }
+            let weight = self.weights.and_then(|w| w.get(&row.meta.oid));
+            let marker = weight.and_then(|w| {
+                if w.largest >= self.large_file_bytes {
+                    Some(("\u{25b2}", self.palette.warn_fg))
+                } else if w.lfs_files > 0 {
+                    Some(("\u{25c6}", self.palette.tag_color))
+                } else {
+                    None
+                }
+            });
+            if let Some((symbol, color)) = marker.filter(|_| area.width > 2) {
+                if let Some(cell) = buf.cell_mut(Position::new(area.right() - 2, y)) {
+                    cell.set_symbol(symbol);
+                    cell.set_fg(color);
+                }
+            }
+            if self.annotations.is_some_and(|a| a.contains(&row.meta.oid)) {
+                if let Some(cell) = buf.cell_mut(Position::new(area.right() - 1, y)) {
+                    cell.set_symbol("\u{270e}");
+                    cell.set_fg(self.palette.accent);
+                }
+            }
         }
     }
 
+    fn diff_stat(&self, row: &GraphRow) -> Option<DiffStat> {
+        let stats = self.diff_stats?;
+        Some(stats.get(&row.meta.oid).copied().unwrap_or_default())
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn render_kitty_row(
         &mut self,
@@ -199,26 +356,53 @@ impl<'a> GraphView<'a> {
         }
 
         let text_budget = (x_start + avail_w as u16).saturating_sub(text_start) as usize;
-        let text_spans = build_text_spans(
-            row,
-            is_selected,
-            self.scroll_x,
-            text_budget,
-            self.trunk_count,
-            sel_bg,
-            self.branch_index_to_name,
-            self.palette,
-        );
-
-        let line = Line::from(text_spans);
-        buf.set_line(text_start, y, &line, text_budget as u16);
+        let key = LineKey {
+            oid: row.meta.oid,
+            width: text_budget,
+            scroll_x: self.scroll_x,
+            lane_scroll: 0,
+            selected: is_selected,
+            highlighted: false,
+            active: self.is_active,
+            age: duration_bucket(&row.meta.time),
+            text_only: true,
+        };
+        let (scroll_x, trunk_count) = (self.scroll_x, self.trunk_count);
+        let (branch_names, palette) = (self.branch_index_to_name, self.palette);
+        let drift = self
+            .trunk_distances
+            .and_then(|d| d.get(&row.meta.oid))
+            .map(TrunkDistance::label);
+        let truncated = self.shallow.is_some_and(|s| s.contains(&row.meta.oid));
+        let detached = self.detached.is_some_and(|d| d.contains(&row.meta.oid));
+        let author = self.authors.label(&row.meta.author, &row.meta.email);
+        let milestones = self.milestones;
+        let diffstat = self.diff_stat(row);
+        let &(ref line, line_w) = self.line_cache.get_or_build(key, || {
+            Line::from(build_text_spans(
+                row,
+                is_selected,
+                scroll_x,
+                text_budget,
+                trunk_count,
+                sel_bg,
+                drift.as_deref(),
+                truncated,
+                detached,
+                &author,
+                milestones,
+                diffstat,
+                branch_names,
+                palette,
+            ))
+        });
+        buf.set_line(text_start, y, line, text_budget as u16);
+        if let Some(ref links) = self.links {
+            let names = link_names(row, self.branch_index_to_name);
+            link_graph_row(buf, text_start, y, text_budget as u16, line, &names, links);
+        }
 
         if is_selected {
-            let line_w: usize = line
-                .spans
-                .iter()
-                .map(|s| UnicodeWidthStr::width(s.content.as_ref()))
-                .sum();
             let fill_start = text_start.saturating_add(line_w as u16);
             let fill_style = Style::default().bg(sel_bg);
             for x in fill_start..(x_start + avail_w as u16) {
@@ -230,6 +414,23 @@ impl<'a> GraphView<'a> {
     }
 }
 
+// Tips, and the branch a merge or fork point is labelled with
+fn link_names<'a>(
+    row: &'a GraphRow,
+    branch_index_to_name: &'a HashMap<usize, String>,
+) -> Vec<&'a str> {
+    let lane_branch = row
+        .meta
+        .branch_index
+        .and_then(|i| branch_index_to_name.get(&i));
+    row.meta
+        .branch_names
+        .iter()
+        .chain(lane_branch)
+        .map(String::as_str)
+        .collect()
+}
+
 fn render_selection_indicator(
     buf: &mut Buf,
     x_start: u16,
@@ -315,6 +516,7 @@ fn render_lane_header(
     x_start: u16,
     avail_w: usize,
     first_visible_row: Option<&GraphRow>,
+    lane_scroll: usize,
     branch_index_to_name: &HashMap<usize, String>,
     trunk_count: usize,
     palette: &ThemePalette,
@@ -332,7 +534,7 @@ fn render_lane_header(
     let indicator_offset = 1u16;
 
     let mut labels: Vec<(u16, &str, ratatui::style::Color)> = Vec::new();
-    for (col_idx, slot) in lane_branches.iter().enumerate() {
+    for (col_idx, slot) in lane_branches.iter().skip(lane_scroll).enumerate() {
         if let Some(bi) = slot {
             if let Some(name) = branch_index_to_name.get(bi) {
                 let x_pos = indicator_offset
@@ -380,10 +582,17 @@ fn build_row_line(
     selected: bool,
     highlighted: bool,
     scroll_x: usize,
+    lane_scroll: usize,
     avail_width: usize,
     trunk_count: usize,
     max_lanes: usize,
     sel_bg: ratatui::style::Color,
+    drift: Option<&str>,
+    truncated: bool,
+    detached: bool,
+    author: &str,
+    milestones: bool,
+    diffstat: Option<DiffStat>,
     branch_index_to_name: &HashMap<usize, String>,
     palette: &ThemePalette,
 ) -> Line<'static> {
@@ -404,9 +613,11 @@ fn build_row_line(
         graph_spans.push(Span::raw(" "));
     }
 
-    for cell in &row.cells {
+    for cell in row.cells.iter().skip(lane_scroll) {
         let color = if is_fork {
             palette.fork_dim
+        } else if detached && cell.symbol == CellSymbol::Commit {
+            palette.dim_text
         } else {
             palette.branch_color_by_identity(cell.color_index, trunk_count)
         };
@@ -420,7 +631,7 @@ fn build_row_line(
         graph_spans.push(Span::styled(cell_glyph(cell), style));
     }
     // Pad to max_lanes so text starts at a fixed column
-    let pad_count = max_lanes.saturating_sub(row.cells.len());
+    let pad_count = max_lanes.saturating_sub(row.cells.len().max(lane_scroll));
     if pad_count > 0 {
         let pad_style = if selected {
             Style::default().bg(sel_bg)
@@ -444,6 +655,12 @@ fn build_row_line(
         text_budget,
         trunk_count,
         sel_bg,
+        drift,
+        truncated,
+        detached,
+        author,
+        milestones,
+        diffstat,
         branch_index_to_name,
         palette,
     );
@@ -462,13 +679,47 @@ fn build_text_spans(
     total_budget: usize,
     trunk_count: usize,
     sel_bg: ratatui::style::Color,
+    drift: Option<&str>,
+    truncated: bool,
+    detached: bool,
+    author: &str,
+    milestones: bool,
+    diffstat: Option<DiffStat>,
     branch_index_to_name: &HashMap<usize, String>,
     palette: &ThemePalette,
 ) -> Vec<Span<'static>> {
     let is_fork = matches!(row.meta.source, CommitSource::Fork(_));
-    let time_str = format_time_short(&row.meta.time);
-    let time_col_w = 5;
+    let mut time_str = format_time_short(&row.meta.time);
+    let mut time_col_w = 5;
+    // Shares the right-aligned column with the time
+    if let Some(drift) = drift {
+        let drift = truncate_with_ellipsis(drift, MAX_DRIFT_WIDTH);
+        time_col_w += UnicodeWidthStr::width(drift.as_str()) + 1;
+        time_str = format!("{drift} {time_str}");
+    }
+    let spark_w = if diffstat.is_some() { SPARK_W + 1 } else { 0 };
+    time_col_w += spark_w;
     let mut budget = total_budget.saturating_sub(time_col_w);
+    let truncated_w = UnicodeWidthStr::width(TRUNCATED_LABEL);
+    // Kept clear of the message so the graft point always shows
+    let truncated = truncated && budget > truncated_w;
+    if truncated {
+        budget -= truncated_w;
+    }
+    let detached_w = UnicodeWidthStr::width(DETACHED_LABEL);
+    let detached = detached && budget > detached_w;
+    if detached {
+        budget -= detached_w;
+    }
+    // Reserved ahead of the labels and message, so it never gets dropped
+    let tags = &row.meta.tag_names;
+    let milestone = (milestones && !tags.is_empty() && budget >= 8).then(|| {
+        let label = format!("\u{2691} {}", tags.join(" "));
+        truncate_with_ellipsis(&label, MAX_MILESTONE_WIDTH.min(budget / 2))
+    });
+    if let Some(ref label) = milestone {
+        budget -= UnicodeWidthStr::width(label.as_str()) + 2;
+    }
 
     let (label_spans, used) =
         branch_label_spans(row, budget, trunk_count, branch_index_to_name, palette);
@@ -476,7 +727,7 @@ fn build_text_spans(
 
     let mut text_spans = label_spans;
 
-    let author_str = format!(" {}", row.meta.author);
+    let author_str = format!(" {author}");
     let author_w = UnicodeWidthStr::width(author_str.as_str());
     let msg_budget = if budget > author_w + 5 {
         budget - author_w
@@ -485,20 +736,53 @@ fn build_text_spans(
     };
 
     if msg_budget > 0 {
-        let msg = truncate_with_ellipsis(&row.meta.message, msg_budget);
-        let msg_w = UnicodeWidthStr::width(msg.as_str());
         let msg_style = if selected {
             Style::default().bg(sel_bg)
         } else if is_fork {
             Style::default().fg(palette.fork_dim)
+        } else if detached {
+            Style::default().fg(palette.dim_text)
         } else {
             Style::default()
         };
+        let mut message = row.meta.message.as_str();
+        let mut msg_budget = msg_budget;
+        if let Some(badge) = conventional::parse(message).filter(|_| !is_fork) {
+            let spans = badge_spans(&badge, msg_style, palette);
+            let badge_w: usize = spans.iter().map(Span::width).sum();
+            // Only when the description keeps some room of its own
+            if msg_budget > badge_w + 8 {
+                text_spans.extend(spans);
+                msg_budget -= badge_w;
+                budget = budget.saturating_sub(badge_w);
+                message = badge.description;
+            }
+        }
+        let msg = truncate_with_ellipsis(message, msg_budget);
+        let msg_w = UnicodeWidthStr::width(msg.as_str());
         text_spans.push(Span::styled(msg, msg_style));
         budget = budget.saturating_sub(msg_w);
     }
+    if truncated {
+        let mut style = Style::default()
+            .fg(palette.warn_fg)
+            .add_modifier(Modifier::ITALIC);
+        if selected {
+            style = style.bg(sel_bg);
+        }
+        text_spans.push(Span::styled(TRUNCATED_LABEL, style));
+    }
+    if detached {
+        let mut style = Style::default()
+            .fg(palette.dim_text)
+            .add_modifier(Modifier::ITALIC);
+        if selected {
+            style = style.bg(sel_bg);
+        }
+        text_spans.push(Span::styled(DETACHED_LABEL, style));
+    }
 
-    if budget >= author_w && !row.meta.author.is_empty() {
+    if budget >= author_w && !author.is_empty() {
         let style = if selected {
             Style::default().fg(palette.dim_text).bg(sel_bg)
         } else {
@@ -508,27 +792,37 @@ fn build_text_spans(
         budget = budget.saturating_sub(author_w);
     }
 
-    for name in &row.meta.tag_names {
-        let formatted = format!("({name}) ");
-        let w = UnicodeWidthStr::width(formatted.as_str());
-        if budget < w {
-            break;
+    if let Some(label) = milestone {
+        // A rule across what the message left, out to the flag
+        let mut style = Style::default().fg(palette.tag_color);
+        if selected {
+            style = style.bg(sel_bg);
         }
-        let style = Style::default()
-            .fg(palette.tag_color)
-            .add_modifier(Modifier::BOLD);
-        text_spans.push(Span::styled(formatted, style));
-        budget = budget.saturating_sub(w);
+        let rule = format!(" {} ", "\u{2500}".repeat(budget));
+        text_spans.push(Span::styled(rule, style));
+        text_spans.push(Span::styled(label, style.add_modifier(Modifier::BOLD)));
     }
 
     time_column_spans(
         &mut text_spans,
         &time_str,
-        total_budget,
+        total_budget.saturating_sub(spark_w),
         selected,
         sel_bg,
         palette,
     );
+    if let Some(stat) = diffstat {
+        let used: usize = text_spans.iter().map(Span::width).sum();
+        if used + spark_w <= total_budget {
+            let base = if selected {
+                Style::default().bg(sel_bg)
+            } else {
+                Style::default()
+            };
+            text_spans.push(Span::styled(" ", base));
+            text_spans.extend(sparkline_spans(stat, base, palette));
+        }
+    }
 
     if scroll_x > 0 {
         text_spans = skip_chars_preserving_style(text_spans, scroll_x);
@@ -537,6 +831,28 @@ fn build_text_spans(
     text_spans
 }
 
+fn badge_spans(badge: &Conventional, base: Style, palette: &ThemePalette) -> Vec<Span<'static>> {
+    let mut spans = vec![Span::styled(
+        badge.kind.to_string(),
+        base.fg(palette.commit_type_color(badge.kind))
+            .add_modifier(Modifier::BOLD),
+    )];
+    if let Some(scope) = badge.scope {
+        spans.push(Span::styled(
+            format!("({scope})"),
+            base.fg(palette.dim_text),
+        ));
+    }
+    if badge.breaking {
+        spans.push(Span::styled(
+            "!",
+            base.fg(palette.error_fg).add_modifier(Modifier::BOLD),
+        ));
+    }
+    spans.push(Span::styled(" ", base));
+    spans
+}
+
 fn branch_label_spans(
     row: &GraphRow,
     mut budget: usize,
@@ -614,8 +930,9 @@ fn time_column_spans(
         .map(|s| UnicodeWidthStr::width(s.content.as_ref()))
         .sum();
     let remaining = total_budget.saturating_sub(current_w);
-    if remaining > time_str.len() {
-        let padding = remaining - time_str.len();
+    let time_w = UnicodeWidthStr::width(time_str);
+    if remaining > time_w {
+        let padding = remaining - time_w;
         if padding > 0 {
             let pad_style = if selected {
                 Style::default().bg(sel_bg)
@@ -633,6 +950,25 @@ fn time_column_spans(
     }
 }
 
+// Filled cells grow with the log of the lines changed
+fn sparkline_spans(stat: DiffStat, base: Style, palette: &ThemePalette) -> Vec<Span<'static>> {
+    let total = stat.insertions + stat.deletions;
+    // One line fills a cell, 4 two, 16 three, 64 four and 256 all five
+    let filled = match total {
+        0 => 0,
+        n => (1 + n.ilog2() as usize / 2).min(SPARK_W),
+    };
+    let added = (filled * stat.insertions + total / 2) / total.max(1);
+    vec![
+        Span::styled("\u{25b0}".repeat(added), base.fg(palette.diff_add)),
+        Span::styled("\u{25b0}".repeat(filled - added), base.fg(palette.diff_del)),
+        Span::styled(
+            "\u{25b1}".repeat(SPARK_W - filled),
+            base.fg(palette.dim_text),
+        ),
+    ]
+}
+
 fn skip_chars_preserving_style(spans: Vec<Span<'static>>, skip: usize) -> Vec<Span<'static>> {
     let mut remaining = skip;
     let mut result = Vec::new();
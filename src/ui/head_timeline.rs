@@ -0,0 +1,95 @@
+use crate::dates::DateFormat;
+use crate::git::head_log::HeadMove;
+use crate::ui::theme::ThemePalette;
+use crate::ui::truncate_with_ellipsis;
+use ratatui::{
+    buffer::Buffer as Buf,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Widget},
+};
+
+pub struct HeadTimelineState {
+    pub entries: Vec<HeadMove>,
+    pub selected: usize,
+    pub loading: bool,
+}
+
+pub struct HeadTimelinePanel<'a> {
+    pub state: &'a HeadTimelineState,
+    pub dates: &'a DateFormat,
+    pub palette: &'a ThemePalette,
+}
+
+impl<'a> Widget for HeadTimelinePanel<'a> {
+    fn render(self, area: Rect, buf: &mut Buf) {
+        let p = self.palette;
+        let popup = super::centered_rect(60, 50, area);
+        Clear.render(popup, buf);
+
+        let block = Block::default()
+            .title(format!(" HEAD today ({}) ", self.state.entries.len()))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(p.accent));
+        let inner = block.inner(popup);
+        block.render(popup, buf);
+        if inner.height < 2 {
+            return;
+        }
+
+        let footer_y = inner.bottom() - 1;
+        buf.set_string(
+            inner.x + 1,
+            footer_y,
+            "Enter jump  Esc close",
+            Style::default().fg(p.dim_text),
+        );
+        if self.state.entries.is_empty() {
+            let msg = if self.state.loading {
+                "loading\u{2026}"
+            } else {
+                "HEAD hasn't moved today"
+            };
+            buf.set_string(inner.x + 1, inner.y, msg, Style::default().fg(p.dim_text));
+            return;
+        }
+
+        let rows = (inner.height - 1) as usize;
+        let skip = (self.state.selected + 1).saturating_sub(rows);
+        for (i, entry) in self.state.entries.iter().enumerate().skip(skip).take(rows) {
+            let y = inner.y + (i - skip) as u16;
+            let is_selected = i == self.state.selected;
+            let base = if is_selected {
+                Style::default().bg(p.selected_bg)
+            } else {
+                Style::default()
+            };
+            let time = format!(
+                " {} ",
+                self.dates
+                    .time(entry.time.with_timezone(&chrono::Local).time())
+            );
+            let oid = entry.oid.to_string();
+            let sha = format!("{} ", &oid[..7]);
+            let message = if entry.message.is_empty() {
+                "moved outside the reflog"
+            } else {
+                entry.message.as_str()
+            };
+            let message_w =
+                (inner.width as usize).saturating_sub(time.chars().count() + sha.len() + 1);
+            let line = Line::from(vec![
+                Span::styled(time, base.fg(p.dim_text)),
+                Span::styled(sha, base.fg(p.accent).add_modifier(Modifier::BOLD)),
+                Span::styled(truncate_with_ellipsis(message, message_w), base),
+            ]);
+            if is_selected {
+                for x in inner.x..inner.right() {
+                    buf[(x, y)].set_style(base);
+                }
+            }
+            buf.set_line(inner.x, y, &line, inner.width);
+        }
+    }
+}
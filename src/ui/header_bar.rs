@@ -1,4 +1,5 @@
 use crate::data_source::ViewMode;
+use crate::stats::GraphStats;
 use crate::ui::theme::ThemePalette;
 use ratatui::{
     buffer::Buffer as Buf,
@@ -17,11 +18,15 @@ pub struct PaneInfo<'a> {
 
 pub struct HeaderBar<'a> {
     pub panes: &'a [PaneInfo<'a>],
+    pub focused_pane: usize,
     pub last_sync: &'a str,
     pub author_filter: &'a str,
     pub view_mode: Option<&'a ViewMode>,
+    pub stale: bool,
+    pub stats: Option<&'a GraphStats>,
     pub project_count: usize,
     pub active_project_idx: usize,
+    pub workspace: Option<&'a str>,
     pub palette: &'a ThemePalette,
 }
 
@@ -33,6 +38,16 @@ impl<'a> Widget for HeaderBar<'a> {
             buf[(x, area.y)].set_style(bg);
         }
 
+        let right = format!("synced: {}  ? help ", self.last_sync);
+        let right_w = UnicodeWidthStr::width(right.as_str());
+        let area_w = area.width as usize;
+        let width = |spans: &[Span]| -> usize {
+            spans
+                .iter()
+                .map(|s| UnicodeWidthStr::width(s.content.as_ref()))
+                .sum()
+        };
+
         let mut spans: Vec<Span<'static>> = Vec::new();
 
         spans.push(Span::styled(
@@ -48,7 +63,40 @@ impl<'a> Widget for HeaderBar<'a> {
             Style::default().fg(p.separator).bg(p.header_bg),
         ));
 
-        if let Some(pane) = self.panes.first() {
+        if let Some(name) = self.workspace {
+            spans.push(Span::styled(
+                name.to_string(),
+                Style::default().fg(p.dim_text).bg(p.header_bg),
+            ));
+            spans.push(Span::styled(
+                " \u{2503} ",
+                Style::default().fg(p.separator).bg(p.header_bg),
+            ));
+        }
+
+        if let [left, right] = self.panes {
+            for (i, pane) in [left, right].into_iter().enumerate() {
+                if i > 0 {
+                    spans.push(Span::styled(
+                        " \u{2502} ",
+                        Style::default().fg(p.separator).bg(p.header_bg),
+                    ));
+                }
+                let name_style = if i == self.focused_pane {
+                    Style::default()
+                        .fg(p.accent)
+                        .bg(p.header_bg)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(p.dim_text).bg(p.header_bg)
+                };
+                spans.push(Span::styled(pane.name.to_string(), name_style));
+                spans.push(Span::styled(
+                    format!(" ({}) {} commits", pane.branch, pane.commit_count),
+                    Style::default().fg(p.dim_text).bg(p.header_bg),
+                ));
+            }
+        } else if let Some(pane) = self.panes.first() {
             spans.push(Span::styled(
                 pane.name.to_string(),
                 Style::default().bg(p.header_bg),
@@ -60,6 +108,7 @@ impl<'a> Widget for HeaderBar<'a> {
 
             let mode_label = match self.view_mode {
                 Some(ViewMode::Local) => "[Local]",
+                Some(ViewMode::Remote) if self.stale => "[Remote, stale]",
                 Some(ViewMode::Remote) => "[Remote]",
                 None => "",
             };
@@ -75,9 +124,42 @@ impl<'a> Widget for HeaderBar<'a> {
                 Style::default().fg(p.dim_text).bg(p.header_bg),
             ));
 
-            if self.project_count > 1 {
+            let position = if self.project_count > 1 {
+                format!("  [{}/{}]", self.active_project_idx + 1, self.project_count)
+            } else {
+                String::new()
+            };
+            if let Some(stats) = self.stats {
+                let mut parts = vec![format!("{} branches", stats.branches)];
+                if stats.active_forks > 0 {
+                    parts.push(format!("{} active forks", stats.active_forks));
+                }
+                parts.push(format!("+{} this week", stats.commits_this_week));
+                parts.push(format!("{} lanes", stats.max_lanes));
+                // Dropped from the end until they fit left of the sync time
+                let budget = area_w
+                    .saturating_sub(right_w + 1 + width(&spans))
+                    .saturating_sub(UnicodeWidthStr::width(position.as_str()));
+                let mut text = String::new();
+                while !parts.is_empty() {
+                    text = format!(" \u{00b7} {}", parts.join(" \u{00b7} "));
+                    if UnicodeWidthStr::width(text.as_str()) <= budget {
+                        break;
+                    }
+                    parts.pop();
+                    text.clear();
+                }
+                if !text.is_empty() {
+                    spans.push(Span::styled(
+                        text,
+                        Style::default().fg(p.dim_text).bg(p.header_bg),
+                    ));
+                }
+            }
+
+            if !position.is_empty() {
                 spans.push(Span::styled(
-                    format!("  [{}/{}]", self.active_project_idx + 1, self.project_count),
+                    position,
                     Style::default().fg(p.dim_text).bg(p.header_bg),
                 ));
             }
@@ -86,18 +168,10 @@ impl<'a> Widget for HeaderBar<'a> {
         let left_line = Line::from(spans.clone());
         buf.set_line(area.x, area.y, &left_line, area.width);
 
-        let right = format!("synced: {}  ? help ", self.last_sync);
-        let right_w = UnicodeWidthStr::width(right.as_str());
-        let area_w = area.width as usize;
-
         if !self.author_filter.is_empty() {
             let filter_text = format!("author: {}", self.author_filter);
             let filter_w = UnicodeWidthStr::width(filter_text.as_str());
-            let left_used: usize = left_line
-                .spans
-                .iter()
-                .map(|s| UnicodeWidthStr::width(s.content.as_ref()))
-                .sum();
+            let left_used = width(&left_line.spans);
             let center_x = (area_w.saturating_sub(filter_w) / 2).max(left_used);
             if center_x + filter_w < area_w.saturating_sub(right_w) {
                 let filter_span = Span::styled(
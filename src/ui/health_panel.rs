@@ -0,0 +1,128 @@
+use crate::dates::DateFormat;
+use crate::graph::layout::format_time_ago;
+use crate::stats::{Health, ACTIVE_DAYS, STALE_DAYS};
+use crate::ui::theme::ThemePalette;
+use crate::ui::{format_size, truncate_with_ellipsis};
+use ratatui::{
+    buffer::Buffer as Buf,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Widget},
+};
+
+pub const LARGEST_FILES: usize = 5;
+const LABEL_W: usize = 16;
+
+pub struct HealthState {
+    pub project: String,
+    pub health: Health,
+    pub largest_files: Option<Result<Vec<(String, u64)>, String>>,
+}
+
+pub struct HealthPanel<'a> {
+    pub state: &'a HealthState,
+    pub dates: &'a DateFormat,
+    pub palette: &'a ThemePalette,
+}
+
+impl<'a> Widget for HealthPanel<'a> {
+    fn render(self, area: Rect, buf: &mut Buf) {
+        let p = self.palette;
+        let popup = super::centered_rect(60, 70, area);
+        Clear.render(popup, buf);
+
+        let block = Block::default()
+            .title(format!(" Health: {} ", self.state.project))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(p.accent));
+        let inner = block.inner(popup);
+        block.render(popup, buf);
+        if inner.height < 2 {
+            return;
+        }
+
+        let h = &self.state.health;
+        let dim = Style::default().fg(p.dim_text);
+        let row = |label: &str, value: Vec<Span<'static>>| {
+            let mut spans = vec![Span::styled(format!(" {label:<LABEL_W$}"), dim)];
+            spans.extend(value);
+            Line::from(spans)
+        };
+        let bold = |s: String| Span::styled(s, Style::default().add_modifier(Modifier::BOLD));
+        let mut lines = vec![
+            row(
+                "Default branch",
+                vec![match h.default_branch {
+                    Some(ref name) => Span::styled(name.clone(), Style::default().fg(p.head_color)),
+                    None => Span::styled("none found", dim),
+                }],
+            ),
+            row(
+                "Branches",
+                vec![
+                    bold(h.active_branches.to_string()),
+                    Span::raw(" active  "),
+                    bold(h.quiet_branches.to_string()),
+                    Span::raw(" quiet  "),
+                    bold(h.stale_branches.to_string()),
+                    Span::raw(" stale"),
+                    Span::styled(format!("  (<{ACTIVE_DAYS}d, <{STALE_DAYS}d)"), dim),
+                ],
+            ),
+            row(
+                "Unmerged",
+                vec![
+                    bold(h.unmerged_branches.to_string()),
+                    Span::raw(" ahead of trunk"),
+                ],
+            ),
+            row(
+                "Forks",
+                vec![
+                    bold(h.active_forks.to_string()),
+                    Span::raw(" active in the last 30 days"),
+                ],
+            ),
+            row(
+                "Last release",
+                match h.last_release {
+                    Some(ref release) => vec![
+                        Span::styled(release.name.clone(), Style::default().fg(p.tag_color)),
+                        Span::raw(format!(
+                            "  {}",
+                            self.dates
+                                .date(release.time.with_timezone(&chrono::Local).date_naive())
+                        )),
+                        Span::styled(format!(" ({})", format_time_ago(&release.time)), dim),
+                    ],
+                    None => vec![Span::styled("no tags on loaded commits", dim)],
+                },
+            ),
+            Line::raw(""),
+            row("Largest files", Vec::new()),
+        ];
+        match self.state.largest_files {
+            None => lines.push(Line::styled("   reading\u{2026}", dim)),
+            Some(Err(ref e)) => lines.push(Line::styled(format!("   {e}"), dim)),
+            Some(Ok(ref files)) if files.is_empty() => {
+                lines.push(Line::styled("   none at HEAD", dim))
+            }
+            Some(Ok(ref files)) => {
+                let path_w = (inner.width as usize).saturating_sub(3 + 10);
+                for (path, size) in files {
+                    lines.push(Line::from(vec![
+                        Span::styled(format!("   {:>8}  ", format_size(*size)), dim),
+                        Span::raw(truncate_with_ellipsis(path, path_w)),
+                    ]));
+                }
+            }
+        }
+
+        let rows = (inner.height - 1) as usize;
+        for (i, line) in lines.iter().take(rows).enumerate() {
+            buf.set_line(inner.x, inner.y + i as u16, line, inner.width);
+        }
+        buf.set_string(inner.x + 1, inner.bottom() - 1, "Esc close", dim);
+    }
+}
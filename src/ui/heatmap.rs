@@ -0,0 +1,155 @@
+use crate::dates::DateFormat;
+use crate::stats::intensity;
+use crate::ui::theme::ThemePalette;
+use crate::ui::truncate_with_ellipsis;
+use chrono::{Datelike, Days, NaiveDate};
+use ratatui::{
+    buffer::Buffer as Buf,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Widget},
+};
+use std::collections::HashMap;
+use unicode_width::UnicodeWidthStr;
+
+const POPUP_HEIGHT: u16 = 7 + 4;
+const MAX_WEEKS: usize = 53;
+const DAY_LABEL_W: u16 = 4;
+
+pub struct HeatmapState {
+    pub counts: HashMap<NaiveDate, usize>,
+    pub selected: NaiveDate,
+    pub author: Option<String>,
+}
+
+impl HeatmapState {
+    pub fn move_by(&mut self, days: i64, today: NaiveDate) {
+        let moved = if days >= 0 {
+            self.selected.checked_add_days(Days::new(days as u64))
+        } else {
+            self.selected
+                .checked_sub_days(Days::new(days.unsigned_abs()))
+        };
+        if let Some(day) = moved {
+            self.selected = day.min(today);
+        }
+    }
+}
+
+pub struct HeatmapPanel<'a> {
+    pub state: &'a HeatmapState,
+    pub today: NaiveDate,
+    pub dates: &'a DateFormat,
+    pub palette: &'a ThemePalette,
+}
+
+fn blend(from: Color, to: Color, t: f32) -> Color {
+    match (from, to) {
+        (Color::Rgb(r1, g1, b1), Color::Rgb(r2, g2, b2)) => {
+            let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+            Color::Rgb(mix(r1, r2), mix(g1, g2), mix(b1, b2))
+        }
+        _ => to,
+    }
+}
+
+impl<'a> Widget for HeatmapPanel<'a> {
+    fn render(self, area: Rect, buf: &mut Buf) {
+        let p = self.palette;
+        let width = (DAY_LABEL_W + 2 * MAX_WEEKS as u16 + 2).min(area.width);
+        let height = POPUP_HEIGHT.min(area.height);
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        Clear.render(popup, buf);
+
+        let title = match self.state.author {
+            Some(ref author) => format!(" Activity \u{00b7} {author} "),
+            None => " Activity ".to_string(),
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(p.accent));
+        let inner = block.inner(popup);
+        block.render(popup, buf);
+        if inner.height < 9 || inner.width <= DAY_LABEL_W + 2 {
+            return;
+        }
+
+        let weeks = (((inner.width - DAY_LABEL_W) / 2) as usize).min(MAX_WEEKS);
+        let span = Days::new(7 * (weeks as u64 - 1));
+        // Right edge is this week, unless the selection scrolled further back
+        let mut last = self.dates.week_of(self.today);
+        if self.state.selected < last - span {
+            last = self.dates.week_of(self.state.selected);
+        }
+        let first = last - span;
+
+        let max = self.state.counts.values().copied().max().unwrap_or(0);
+        let label_style = Style::default().fg(p.dim_text);
+        let grid_x = inner.x + DAY_LABEL_W;
+        let grid_y = inner.y + 1;
+
+        for row in [0, 2, 4] {
+            let label = self.dates.weekday(first + Days::new(row as u64));
+            let label = truncate_with_ellipsis(&label, DAY_LABEL_W as usize - 1);
+            buf.set_string(inner.x, grid_y + row, label, label_style);
+        }
+
+        let mut last_month = None;
+        for week in 0..weeks {
+            let start = first + Days::new(7 * week as u64);
+            let x = grid_x + 2 * week as u16;
+            if last_month != Some(start.month()) {
+                last_month = Some(start.month());
+                let label = self.dates.month(start);
+                // Skip a label that would run past the grid
+                if x + UnicodeWidthStr::width(label.as_str()) as u16 <= inner.right() {
+                    buf.set_string(x, inner.y, label, label_style);
+                }
+            }
+            for row in 0..7u16 {
+                let day = start + Days::new(row as u64);
+                if day > self.today {
+                    continue;
+                }
+                let count = self.state.counts.get(&day).copied().unwrap_or(0);
+                let level = intensity(count, max);
+                let (glyph, mut style) = if level == 0 {
+                    ("\u{00b7}", Style::default().fg(p.dim_text))
+                } else {
+                    let shade = blend(p.content_bg, p.accent, 0.25 + 0.75 * level as f32 / 4.0);
+                    ("\u{25a0}", Style::default().fg(shade))
+                };
+                if day == self.state.selected {
+                    style = style.bg(p.selected_bg).add_modifier(Modifier::BOLD);
+                }
+                buf.set_string(x, grid_y + row, glyph, style);
+            }
+        }
+
+        let count = self
+            .state
+            .counts
+            .get(&self.state.selected)
+            .copied()
+            .unwrap_or(0);
+        let footer = Line::from(vec![
+            Span::styled(
+                format!(" {} ", self.dates.day(self.state.selected)),
+                Style::default().fg(p.accent).add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!("{count} commits   ")),
+            Span::styled(
+                "h/l week  j/k day  Enter jump  Esc close",
+                Style::default().fg(p.dim_text),
+            ),
+        ]);
+        buf.set_line(inner.x, grid_y + 7, &footer, inner.width);
+    }
+}
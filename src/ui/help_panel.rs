@@ -1,3 +1,4 @@
+use crate::ui::render_scrollbar;
 use crate::ui::theme::ThemePalette;
 use ratatui::{
     buffer::Buffer as Buf,
@@ -7,24 +8,76 @@ use ratatui::{
     widgets::{Block, Borders, Clear, Widget},
 };
 
-const BINDINGS: &[(&str, &str)] = &[
+pub const BINDINGS: &[(&str, &str)] = &[
     ("j/k  \u{2191}/\u{2193}", "Scroll"),
     ("h/l  \u{2190}/\u{2192}", "Switch panel"),
-    ("H/L", "Scroll text"),
-    ("Tab / S-Tab", "Switch project"),
+    ("</>", "Scroll text"),
+    ("PgUp/PgDn", "Page list/message (Home/End)"),
+    ("[ / ]", "Prev / next branch section"),
+    ("H / T", "Jump to HEAD / branch tip"),
+    ("B", "All refs on this commit"),
+    ("Tab / S-Tab", "Switch project / split focus"),
+    ("s", "Split view (two projects)"),
+    ("g", "Switch workspace"),
+    ("A", "Activity heatmap"),
+    ("R", "Release timeline"),
     ("d", "Toggle detail sidebar"),
+    ("t", "Open in difftool"),
+    ("e", "Open file at commit in $EDITOR"),
+    ("b", "Blame file at commit"),
+    ("p", "Toggle diff (diff_pager)"),
+    ("=", "Compare branch with another ref"),
+    ("v", "Visual select commits"),
+    ("F", "Follow newest commit"),
+    ("o", "Distance from trunk column"),
+    ("G", "Tag milestones on/off"),
+    ("I", "Diffstat sparkline per row"),
+    ("L", "Inspect lanes (h/l move)"),
+    ("V", "Plain list instead of graph"),
+    ("u", "Jump to oldest new commit"),
+    ("U", "Recently deleted refs (undo)"),
+    ("n", "New branch at the commit"),
+    ("D", "Delete the selected branch or tag"),
+    ("X", "Delete branches with gone upstream"),
+    ("x", "Export commits as patches"),
+    ("N", "Edit commit note"),
+    ("S", "Deepen a shallow clone"),
+    ("i", "Fetch all remotes"),
+    ("z", "Stashes (apply/pop/drop)"),
+    ("J", "Where HEAD has been today"),
+    ("O", "Repository health summary"),
+    ("W", "All projects in one stream"),
+    ("M", "Preview merge/cherry-pick conflicts"),
+    ("E", "Preview rebasing the branch"),
+    ("Y", "Cherry-pick the commit onto HEAD"),
+    ("P", "Merge the branch's PR on GitHub"),
+    ("#", "Open the branch's PR in the browser"),
+    ("!", "Watch a branch for new commits"),
+    ("Q<reg>", "Record a macro / stop recording"),
+    ("@<reg>", "Replay a macro (@@ repeats)"),
     ("m", "Toggle Local/Remote"),
+    ("Z", "Offline (no GitHub calls)"),
     ("Enter", "Detail / Toggle"),
     ("/", "Filter branches"),
-    ("a", "Filter author"),
+    ("a", "Filter author (@domain by email)"),
+    ("w", "Filter by this commit's author"),
+    ("C", "Filter by commit type (fix ...)"),
+    ("Backspace", "Clear last filter chip (or click)"),
+    ("1-9", "Clear filter chip N"),
     ("f", "Toggle forks"),
     ("r", "Refresh"),
     ("c", "Config screen"),
     ("?", "This help"),
+    ("F12", "Perf HUD"),
     ("q / Esc", "Quit / Close"),
 ];
 
+pub fn visible_rows(area: Rect) -> usize {
+    super::centered_rect(50, 60, area).height.saturating_sub(2) as usize
+}
+
 pub struct HelpPanel<'a> {
+    pub scroll: usize,
     pub palette: &'a ThemePalette,
 }
 
@@ -41,10 +94,8 @@ impl<'a> Widget for HelpPanel<'a> {
         let inner = block.inner(popup);
         block.render(popup, buf);
 
-        for (i, (key, desc)) in BINDINGS.iter().enumerate() {
-            if i >= inner.height as usize {
-                break;
-            }
+        let rows = inner.height as usize;
+        for (i, (key, desc)) in BINDINGS.iter().skip(self.scroll).take(rows).enumerate() {
             let y = inner.y + i as u16;
             let key_style = Style::default()
                 .fg(p.filter_color)
@@ -56,7 +107,13 @@ impl<'a> Widget for HelpPanel<'a> {
                 Span::styled(format!(" {:<width$}", key, width = key_col_w), key_style),
                 Span::styled(desc.to_string(), desc_style),
             ]);
-            buf.set_line(inner.x, y, &line, inner.width);
+            buf.set_line(inner.x, y, &line, inner.width.saturating_sub(1));
         }
+        let track = Rect {
+            x: inner.right().saturating_sub(1),
+            width: 1,
+            ..inner
+        };
+        render_scrollbar(buf, track, BINDINGS.len(), self.scroll, p);
     }
 }
@@ -1,4 +1,5 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Action {
@@ -6,10 +7,17 @@ pub enum Action {
     ScrollDown,
     ScrollLeft,
     ScrollRight,
+    PageDown,
+    PageUp,
+    JumpTop,
+    JumpBottom,
+    NextSection,
+    PrevSection,
     PanelLeft,
     PanelRight,
     NextProject,
     PrevProject,
+    SwitchWorkspace,
     ToggleViewMode,
     ToggleDetailPanel,
     Select,
@@ -20,8 +28,56 @@ pub enum Action {
     FilterBackspace,
     FilterConfirm,
     FilterCancel,
+    ClearFilter,
+    // Zero-based, in status bar order
+    ClearChip(usize),
     Refresh,
     Help,
+    TogglePerfHud,
+    OpenDifftool,
+    OpenInEditor,
+    ToggleDiff,
+    Compare,
+    Aggregate,
+    ToggleSplit,
+    ToggleHeatmap,
+    ToggleReleases,
+    ShowHealth,
+    VisualSelect,
+    ToggleFollow,
+    ToggleTrunkDistance,
+    ToggleMilestones,
+    ToggleDiffstat,
+    FilterByRowAuthor,
+    TypeFilter,
+    ToggleListView,
+    JumpToUnseen,
+    JumpToHead,
+    JumpToBranchTip,
+    InspectLanes,
+    ShowDeleted,
+    NewBranch,
+    DeleteBranch,
+    PruneBranches,
+    ExportPatches,
+    Annotate,
+    DeepenClone,
+    FetchRemotes,
+    ToggleStashes,
+    HeadTimeline,
+    Blame,
+    RefPopover,
+    ToggleOffline,
+    PreviewMerge,
+    PreviewRebase,
+    CherryPick,
+    MergePullRequest,
+    OpenPullRequest,
+    WatchBranch,
+    StashApply,
+    StashPop,
+    StashDrop,
+    CheckoutRef,
     OpenConfig,
     ClosePopup,
     Quit,
@@ -33,6 +89,7 @@ pub enum FilterMode {
     Off,
     Branch,
     Author,
+    Prompt,
 }
 
 impl FilterMode {
@@ -41,6 +98,23 @@ impl FilterMode {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PopupKeys {
+    Stashes,
+    Refs,
+}
+
+// Checked ahead of map_key, whose meaning for these keys doesn't apply in the popup
+pub fn map_popup_key(key: KeyEvent, popup: PopupKeys) -> Option<Action> {
+    match (popup, key.code) {
+        (PopupKeys::Stashes, KeyCode::Char('a')) => Some(Action::StashApply),
+        (PopupKeys::Stashes, KeyCode::Char('p')) => Some(Action::StashPop),
+        (PopupKeys::Stashes, KeyCode::Char('d')) => Some(Action::StashDrop),
+        (PopupKeys::Refs, KeyCode::Char('c')) => Some(Action::CheckoutRef),
+        _ => None,
+    }
+}
+
 pub fn map_key(key: KeyEvent, filter_mode: FilterMode) -> Action {
     if filter_mode.is_active() {
         return match key.code {
@@ -60,8 +134,17 @@ pub fn map_key(key: KeyEvent, filter_mode: FilterMode) -> Action {
         KeyCode::Char('k') | KeyCode::Up => Action::ScrollUp,
         KeyCode::Char('h') | KeyCode::Left => Action::PanelLeft,
         KeyCode::Char('l') | KeyCode::Right => Action::PanelRight,
-        KeyCode::Char('H') => Action::ScrollLeft,
-        KeyCode::Char('L') => Action::ScrollRight,
+        KeyCode::Char('<') => Action::ScrollLeft,
+        KeyCode::Char('>') => Action::ScrollRight,
+        KeyCode::PageDown => Action::PageDown,
+        KeyCode::PageUp => Action::PageUp,
+        KeyCode::Home => Action::JumpTop,
+        KeyCode::End => Action::JumpBottom,
+        KeyCode::Char(']') => Action::NextSection,
+        KeyCode::Char('[') => Action::PrevSection,
+        KeyCode::Char('H') => Action::JumpToHead,
+        KeyCode::Char('T') => Action::JumpToBranchTip,
+        KeyCode::Char('L') => Action::InspectLanes,
         KeyCode::Tab => Action::NextProject,
         KeyCode::BackTab => Action::PrevProject,
         KeyCode::Char('m') => Action::ToggleViewMode,
@@ -69,11 +152,211 @@ pub fn map_key(key: KeyEvent, filter_mode: FilterMode) -> Action {
         KeyCode::Enter | KeyCode::Char(' ') => Action::Select,
         KeyCode::Char('f') => Action::ToggleForks,
         KeyCode::Char('/') => Action::Filter,
+        KeyCode::Backspace => Action::ClearFilter,
+        KeyCode::Char(c @ '1'..='9') => Action::ClearChip(c as usize - '1' as usize),
         KeyCode::Char('a') => Action::AuthorFilter,
+        KeyCode::Char('w') => Action::FilterByRowAuthor,
+        KeyCode::Char('C') => Action::TypeFilter,
+        KeyCode::Char('G') => Action::ToggleMilestones,
+        KeyCode::Char('I') => Action::ToggleDiffstat,
         KeyCode::Char('r') => Action::Refresh,
         KeyCode::Char('?') => Action::Help,
+        KeyCode::F(12) => Action::TogglePerfHud,
         KeyCode::Char('c') => Action::OpenConfig,
+        KeyCode::Char('t') => Action::OpenDifftool,
+        KeyCode::Char('e') => Action::OpenInEditor,
+        KeyCode::Char('p') => Action::ToggleDiff,
+        KeyCode::Char('=') => Action::Compare,
+        KeyCode::Char('W') => Action::Aggregate,
+        KeyCode::Char('s') => Action::ToggleSplit,
+        KeyCode::Char('g') => Action::SwitchWorkspace,
+        KeyCode::Char('A') => Action::ToggleHeatmap,
+        KeyCode::Char('R') => Action::ToggleReleases,
+        KeyCode::Char('v') => Action::VisualSelect,
+        KeyCode::Char('F') => Action::ToggleFollow,
+        KeyCode::Char('o') => Action::ToggleTrunkDistance,
+        KeyCode::Char('V') => Action::ToggleListView,
+        KeyCode::Char('u') => Action::JumpToUnseen,
+        KeyCode::Char('U') => Action::ShowDeleted,
+        KeyCode::Char('n') => Action::NewBranch,
+        KeyCode::Char('D') => Action::DeleteBranch,
+        KeyCode::Char('X') => Action::PruneBranches,
+        KeyCode::Char('x') => Action::ExportPatches,
+        KeyCode::Char('N') => Action::Annotate,
+        KeyCode::Char('S') => Action::DeepenClone,
+        KeyCode::Char('i') => Action::FetchRemotes,
+        KeyCode::Char('z') => Action::ToggleStashes,
+        KeyCode::Char('J') => Action::HeadTimeline,
+        KeyCode::Char('b') => Action::Blame,
+        KeyCode::Char('B') => Action::RefPopover,
+        KeyCode::Char('Z') => Action::ToggleOffline,
+        KeyCode::Char('O') => Action::ShowHealth,
+        KeyCode::Char('M') => Action::PreviewMerge,
+        KeyCode::Char('E') => Action::PreviewRebase,
+        KeyCode::Char('Y') => Action::CherryPick,
+        KeyCode::Char('P') => Action::MergePullRequest,
+        KeyCode::Char('#') => Action::OpenPullRequest,
+        KeyCode::Char('!') => Action::WatchBranch,
         KeyCode::Esc => Action::ClosePopup,
         _ => Action::None,
     }
 }
+
+#[derive(Debug, PartialEq)]
+pub enum MacroStep {
+    Pass(Action),
+    Consumed,
+    Started(char),
+    Stopped(char, usize),
+    Replay {
+        register: char,
+        actions: Vec<Action>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MacroPending {
+    Record,
+    Replay,
+}
+
+// Q<reg> rather than vim's q<reg>, which quits
+#[derive(Debug, Default)]
+pub struct MacroRecorder {
+    registers: HashMap<char, Vec<Action>>,
+    recording: Option<(char, Vec<Action>)>,
+    pending: Option<MacroPending>,
+    last_replayed: Option<char>,
+}
+
+impl MacroRecorder {
+    pub fn recording(&self) -> Option<char> {
+        self.recording.as_ref().map(|(register, _)| *register)
+    }
+
+    // Keys typed into a filter or prompt are only recorded, never macro keys
+    pub fn feed(&mut self, key: KeyEvent, action: Action, filter_mode: FilterMode) -> MacroStep {
+        if let Some(pending) = self.pending.take() {
+            let KeyCode::Char(c) = key.code else {
+                return MacroStep::Consumed;
+            };
+            return match pending {
+                MacroPending::Record if c.is_ascii_alphanumeric() => {
+                    self.recording = Some((c, Vec::new()));
+                    MacroStep::Started(c)
+                }
+                MacroPending::Replay if c.is_ascii_alphanumeric() || c == '@' => {
+                    let Some(register) = (if c == '@' {
+                        self.last_replayed
+                    } else {
+                        Some(c)
+                    }) else {
+                        return MacroStep::Consumed;
+                    };
+                    self.last_replayed = Some(register);
+                    let actions = self.registers.get(&register).cloned().unwrap_or_default();
+                    if let Some((_, ref mut recorded)) = self.recording {
+                        recorded.extend(actions.iter().cloned());
+                    }
+                    MacroStep::Replay { register, actions }
+                }
+                _ => MacroStep::Consumed,
+            };
+        }
+
+        if !filter_mode.is_active() {
+            match key.code {
+                KeyCode::Char('Q') => {
+                    if let Some((register, actions)) = self.recording.take() {
+                        let len = actions.len();
+                        self.registers.insert(register, actions);
+                        return MacroStep::Stopped(register, len);
+                    }
+                    self.pending = Some(MacroPending::Record);
+                    return MacroStep::Consumed;
+                }
+                KeyCode::Char('@') => {
+                    self.pending = Some(MacroPending::Replay);
+                    return MacroStep::Consumed;
+                }
+                _ => {}
+            }
+        }
+
+        if let Some((_, ref mut recorded)) = self.recording {
+            if action != Action::None {
+                recorded.push(action.clone());
+            }
+        }
+        MacroStep::Pass(action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(recorder: &mut MacroRecorder, c: char, filter_mode: FilterMode) -> MacroStep {
+        let key = KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        recorder.feed(key, map_key(key, filter_mode), filter_mode)
+    }
+
+    #[test]
+    fn records_and_replays_registers() {
+        let mut rec = MacroRecorder::default();
+        assert_eq!(feed(&mut rec, 'Q', FilterMode::Off), MacroStep::Consumed);
+        assert_eq!(
+            feed(&mut rec, 'a', FilterMode::Off),
+            MacroStep::Started('a')
+        );
+        assert_eq!(rec.recording(), Some('a'));
+        assert_eq!(
+            feed(&mut rec, 'j', FilterMode::Off),
+            MacroStep::Pass(Action::ScrollDown)
+        );
+        // Filter text is recorded, and `Q` inside it is just a character
+        assert_eq!(
+            feed(&mut rec, 'Q', FilterMode::Branch),
+            MacroStep::Pass(Action::FilterChar('Q'))
+        );
+        assert_eq!(
+            feed(&mut rec, 'Q', FilterMode::Off),
+            MacroStep::Stopped('a', 2)
+        );
+        assert_eq!(rec.recording(), None);
+
+        feed(&mut rec, '@', FilterMode::Off);
+        let expected = MacroStep::Replay {
+            register: 'a',
+            actions: vec![Action::ScrollDown, Action::FilterChar('Q')],
+        };
+        assert_eq!(feed(&mut rec, 'a', FilterMode::Off), expected);
+        feed(&mut rec, '@', FilterMode::Off);
+        assert_eq!(feed(&mut rec, '@', FilterMode::Off), expected);
+
+        feed(&mut rec, '@', FilterMode::Off);
+        assert_eq!(
+            feed(&mut rec, 'b', FilterMode::Off),
+            MacroStep::Replay {
+                register: 'b',
+                actions: Vec::new()
+            }
+        );
+    }
+
+    #[test]
+    fn popup_keys_shadow_global_ones_only_in_their_popup() {
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        assert_eq!(
+            map_popup_key(key('p'), PopupKeys::Stashes),
+            Some(Action::StashPop)
+        );
+        assert_eq!(map_popup_key(key('c'), PopupKeys::Stashes), None);
+        assert_eq!(
+            map_popup_key(key('c'), PopupKeys::Refs),
+            Some(Action::CheckoutRef)
+        );
+        assert_eq!(map_popup_key(key('a'), PopupKeys::Refs), None);
+        assert_eq!(map_key(key('p'), FilterMode::Off), Action::ToggleDiff);
+    }
+}
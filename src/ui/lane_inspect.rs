@@ -0,0 +1,90 @@
+use crate::graph::layout::format_time_ago;
+use crate::graph::types::RowMeta;
+use crate::ui::theme::ThemePalette;
+use crate::ui::truncate_with_ellipsis;
+use ratatui::{
+    buffer::Buffer as Buf,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Widget},
+};
+
+const MAX_WIDTH: u16 = 44;
+
+pub struct LaneBranch<'a> {
+    pub name: &'a str,
+    pub color: Color,
+    pub tip: Option<&'a RowMeta>,
+    pub commits: usize,
+}
+
+pub struct LaneTooltip<'a> {
+    pub lane: usize,
+    pub anchor_x: u16,
+    pub branch: Option<LaneBranch<'a>>,
+    pub palette: &'a ThemePalette,
+}
+
+impl<'a> Widget for LaneTooltip<'a> {
+    fn render(self, area: Rect, buf: &mut Buf) {
+        let p = self.palette;
+        let text_w = MAX_WIDTH.min(area.width).saturating_sub(2) as usize;
+        let dim = Style::default().fg(p.dim_text);
+        let lines: Vec<Line> = match self.branch {
+            Some(branch) => {
+                let name = Span::styled(
+                    truncate_with_ellipsis(branch.name, text_w),
+                    Style::default()
+                        .fg(branch.color)
+                        .add_modifier(Modifier::BOLD),
+                );
+                let mut lines = vec![Line::from(name)];
+                match branch.tip {
+                    Some(tip) => {
+                        let sha = tip.oid.to_string();
+                        lines.push(Line::from(vec![
+                            Span::styled("tip ", dim),
+                            Span::raw(sha[..8.min(sha.len())].to_string()),
+                            Span::styled(format!(" {}", format_time_ago(&tip.time)), dim),
+                        ]));
+                        let summary = tip.message.lines().next().unwrap_or_default();
+                        lines.push(Line::from(truncate_with_ellipsis(summary, text_w)));
+                    }
+                    None => lines.push(Line::styled("tip not loaded", dim)),
+                }
+                let noun = if branch.commits == 1 {
+                    "commit"
+                } else {
+                    "commits"
+                };
+                lines.push(Line::styled(format!("{} {noun}", branch.commits), dim));
+                lines
+            }
+            None => vec![Line::styled("no branch here", dim)],
+        };
+
+        let content_w = lines.iter().map(Line::width).max().unwrap_or(0) as u16;
+        let width = (content_w + 2).min(MAX_WIDTH).min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height.saturating_sub(1));
+        if width < 4 || height < 3 {
+            return;
+        }
+        let x = self
+            .anchor_x
+            .min(area.right().saturating_sub(width))
+            .max(area.x);
+        let popup = Rect::new(x, area.y + 1, width, height);
+        Clear.render(popup, buf);
+        let block = Block::default()
+            .title(format!(" lane {} ", self.lane + 1))
+            .title_style(Style::default().fg(p.accent))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(p.accent));
+        let inner = block.inner(popup);
+        block.render(popup, buf);
+        for (i, line) in lines.iter().take(inner.height as usize).enumerate() {
+            buf.set_line(inner.x, inner.y + i as u16, line, inner.width);
+        }
+    }
+}
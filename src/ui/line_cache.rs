@@ -0,0 +1,62 @@
+use crate::git::types::Oid;
+use ratatui::text::Line;
+use std::collections::HashMap;
+use unicode_width::UnicodeWidthStr;
+
+const MAX_CACHE_ENTRIES: usize = 4096;
+
+// Data and theme changes clear the cache wholesale
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LineKey {
+    pub oid: Oid,
+    pub width: usize,
+    pub scroll_x: usize,
+    pub lane_scroll: usize,
+    pub selected: bool,
+    pub highlighted: bool,
+    pub active: bool,
+    // duration_bucket of the commit time, so "5m" rolls over to "6m"
+    pub age: (i64, &'static str),
+    pub text_only: bool,
+}
+
+pub struct LineCache {
+    lines: HashMap<LineKey, (Line<'static>, usize)>,
+}
+
+impl LineCache {
+    pub fn new() -> Self {
+        Self {
+            lines: HashMap::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    pub fn get_or_build(
+        &mut self,
+        key: LineKey,
+        build: impl FnOnce() -> Line<'static>,
+    ) -> &(Line<'static>, usize) {
+        if !self.lines.contains_key(&key) && self.lines.len() >= MAX_CACHE_ENTRIES {
+            self.lines.clear();
+        }
+        self.lines.entry(key).or_insert_with(|| {
+            let line = build();
+            let width = line
+                .spans
+                .iter()
+                .map(|s| UnicodeWidthStr::width(s.content.as_ref()))
+                .sum();
+            (line, width)
+        })
+    }
+}
+
+impl Default for LineCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,215 @@
+use crate::git::types::{BranchInfo, CommitSource, Oid};
+use ratatui::{buffer::Buffer, text::Line};
+use std::ops::Range;
+use unicode_width::UnicodeWidthStr;
+
+#[derive(Clone, Copy)]
+pub struct WebLinks<'a> {
+    pub base: &'a str,
+    pub branches: &'a [BranchInfo],
+}
+
+impl WebLinks<'_> {
+    pub fn commit(&self, oid: Oid) -> String {
+        format!("{}/commit/{oid}", self.base)
+    }
+
+    pub fn pull(&self, number: u64) -> String {
+        format!("{}/pull/{number}", self.base)
+    }
+
+    // GitHub sends `/issues/<n>` on to the PR when `n` is one
+    pub fn issue(&self, number: u64) -> String {
+        format!("{}/issues/{number}", self.base)
+    }
+
+    // Other remotes' and forks' branches live in other repositories
+    pub fn branch(&self, name: &str) -> Option<String> {
+        let branch = self.branches.iter().find(|b| b.name == name)?;
+        let path = match &branch.source {
+            CommitSource::Local => name,
+            CommitSource::Remote(remote) if remote == "origin" => name.strip_prefix("origin/")?,
+            _ => return None,
+        };
+        Some(format!("{}/tree/{path}", self.base))
+    }
+}
+
+pub fn github_base(identity: &str) -> Option<String> {
+    identity
+        .contains('/')
+        .then(|| format!("https://github.com/{identity}"))
+}
+
+// `before` is the character ahead, which must not be part of a word
+pub fn issue_ref(before: Option<char>, s: &str) -> Option<(usize, u64)> {
+    if before.is_some_and(|c| c.is_alphanumeric() || "&/#_-".contains(c)) {
+        return None;
+    }
+    let digits = s.strip_prefix('#')?;
+    let len = digits.bytes().take_while(u8::is_ascii_digit).count();
+    // A truncated `#12…` may be some other number
+    let after = digits[len..].chars().next();
+    if len == 0 || after.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '\u{2026}') {
+        return None;
+    }
+    Some((len + 1, digits[..len].parse().ok()?))
+}
+
+pub fn issue_refs(text: &str) -> Vec<(Range<usize>, u64)> {
+    text.match_indices('#')
+        .filter_map(|(at, _)| {
+            let (len, number) = issue_ref(text[..at].chars().next_back(), &text[at..])?;
+            Some((at..at + len, number))
+        })
+        .collect()
+}
+
+pub fn link_graph_row(
+    buf: &mut Buffer,
+    x: u16,
+    y: u16,
+    width: u16,
+    line: &Line,
+    names: &[&str],
+    links: &WebLinks,
+) {
+    let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+    let mut targets = Vec::new();
+    let mut from = 0;
+    while let Some(open) = text[from..].find('[').map(|i| from + i) {
+        let Some(close) = text[open..].find(']').map(|i| open + i) else {
+            break;
+        };
+        let label = text[open + 1..close].trim_start_matches('*');
+        let name = match label.strip_suffix('\u{2026}') {
+            Some(prefix) => names.iter().find(|n| n.starts_with(prefix)),
+            None => names.iter().find(|n| **n == label),
+        };
+        if let Some(url) = name.and_then(|name| links.branch(name)) {
+            targets.push((open + 1..close, url));
+        }
+        from = close + 1;
+    }
+    for (range, number) in issue_refs(&text) {
+        targets.push((range, links.issue(number)));
+    }
+    let right = x.saturating_add(width);
+    for (range, url) in targets {
+        let col = x.saturating_add(UnicodeWidthStr::width(&text[..range.start]) as u16);
+        let link_w = UnicodeWidthStr::width(&text[range]) as u16;
+        if col < right {
+            hyperlink(buf, col, y, link_w.min(right - col), &url);
+        }
+    }
+}
+
+// Goes into an escape sequence, so a control character could end it early
+pub fn safe_url(url: &str) -> bool {
+    (url.starts_with("https://") || url.starts_with("http://"))
+        && !url.chars().any(char::is_control)
+}
+
+// The escape goes in the first cell of each pair, since ratatui's diff
+// skips the cell after it; a lone last cell stays unlinked
+pub fn hyperlink(buf: &mut Buffer, x: u16, y: u16, width: u16, url: &str) {
+    if !safe_url(url) {
+        return;
+    }
+    let end = x.saturating_add(width).min(buf.area.right());
+    if y < buf.area.top() || y >= buf.area.bottom() {
+        return;
+    }
+    let mut col = x.max(buf.area.left());
+    while col + 1 < end {
+        let first = buf[(col, y)].symbol();
+        // A wide character fills the pair on its own
+        let pair = if UnicodeWidthStr::width(first) > 1 {
+            first.to_string()
+        } else {
+            format!("{first}{}", buf[(col + 1, y)].symbol())
+        };
+        buf[(col, y)].set_symbol(&format!("\x1B]8;;{url}\x07{pair}\x1B]8;;\x07"));
+        col += 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+
+    fn branch(name: &str, source: CommitSource) -> BranchInfo {
+        BranchInfo {
+            name: name.to_string(),
+            tip: Oid::zero(),
+            is_head: false,
+            source,
+            upstream: None,
+        }
+    }
+
+    fn linked(buf: &Buffer, x: u16) -> Option<&str> {
+        let symbol = buf[(x, 0)].symbol().strip_prefix("\x1B]8;;")?;
+        symbol.split('\x07').next()
+    }
+
+    #[test]
+    fn links_labels_issue_refs_and_pairs_cells() {
+        let refs = issue_refs("Fix #12, see (#3); not a#4, #5x, &#6 or #7\u{2026}");
+        let numbers: Vec<u64> = refs.iter().map(|(_, n)| *n).collect();
+        assert_eq!(numbers, vec![12, 3]);
+        assert_eq!(refs[0].0, 4..7);
+
+        let branches = [
+            branch("main", CommitSource::Local),
+            branch("origin/feature", CommitSource::Remote("origin".to_string())),
+            branch(
+                "upstream/main",
+                CommitSource::Remote("upstream".to_string()),
+            ),
+        ];
+        let links = WebLinks {
+            base: "https://github.com/o/r",
+            branches: &branches,
+        };
+        assert_eq!(
+            links.branch("origin/feature").as_deref(),
+            Some("https://github.com/o/r/tree/feature")
+        );
+        assert_eq!(links.branch("upstream/main"), None);
+        assert_eq!(github_base("arachne"), None);
+
+        let line = Line::from("[*main] [origin/fea\u{2026}] [upstream/main] fix #12");
+        let mut buf = Buffer::empty(Rect::new(0, 0, 50, 1));
+        buf.set_line(0, 0, &line, 50);
+        let names = ["main", "origin/feature", "upstream/main"];
+        link_graph_row(&mut buf, 0, 0, 50, &line, &names, &links);
+        assert_eq!(linked(&buf, 1), Some("https://github.com/o/r/tree/main"));
+        assert_eq!(linked(&buf, 9), Some("https://github.com/o/r/tree/feature"));
+        assert_eq!(linked(&buf, 23), None);
+        assert_eq!(linked(&buf, 42), Some("https://github.com/o/r/issues/12"));
+
+        // Each linked cell prints its pair, so the cell after is left as is
+        let mut buf = Buffer::empty(Rect::new(0, 0, 6, 1));
+        buf.set_string(0, 0, "abcde", Style::default());
+        hyperlink(&mut buf, 0, 0, 5, "https://e.com");
+        assert_eq!(
+            buf[(0, 0)].symbol(),
+            "\x1B]8;;https://e.com\x07ab\x1B]8;;\x07"
+        );
+        assert_eq!(buf[(1, 0)].symbol(), "b");
+        assert_eq!(
+            buf[(2, 0)].symbol(),
+            "\x1B]8;;https://e.com\x07cd\x1B]8;;\x07"
+        );
+        assert_eq!(buf[(4, 0)].symbol(), "e");
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 6, 1));
+        buf.set_string(0, 0, "abcde", Style::default());
+        hyperlink(&mut buf, 0, 0, 5, "https://e.com\x07\x1b[2J");
+        hyperlink(&mut buf, 0, 0, 5, "javascript:alert(1)");
+        assert_eq!(buf[(0, 0)].symbol(), "a");
+    }
+}
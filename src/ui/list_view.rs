@@ -0,0 +1,139 @@
+use crate::authors::AuthorNames;
+use crate::git::types::Oid;
+use crate::graph::layout::format_time_ago;
+use crate::graph::types::GraphRow;
+use crate::ui::theme::ThemePalette;
+use crate::ui::{fit, TIME_AGO_W};
+use ratatui::{
+    buffer::Buffer as Buf,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use std::collections::HashSet;
+use unicode_width::UnicodeWidthStr;
+
+const SHA_W: usize = 8;
+const AUTHOR_W: usize = 14;
+const GAP: &str = "  ";
+
+// Selection and scrolling are the graph's own, so every action works the same
+pub struct ListView<'a> {
+    pub rows: &'a [GraphRow],
+    pub scroll_y: usize,
+    pub selected: usize,
+    pub highlighted_oids: &'a HashSet<Oid>,
+    pub is_active: bool,
+    pub authors: AuthorNames<'a>,
+    pub palette: &'a ThemePalette,
+}
+
+fn refs(row: &GraphRow) -> String {
+    let branches = row.meta.branch_names.iter().map(String::clone);
+    let tags = row.meta.tag_names.iter().map(|t| format!("tag {t}"));
+    branches.chain(tags).collect::<Vec<_>>().join(", ")
+}
+
+impl ListView<'_> {
+    pub fn render_into(self, area: Rect, buf: &mut Buf) {
+        if area.height == 0 {
+            return;
+        }
+        let p = self.palette;
+        buf.set_style(area, Style::default().bg(p.content_bg));
+
+        let visible = (area.height as usize).saturating_sub(1);
+        let shown: Vec<&GraphRow> = self.rows.iter().skip(self.scroll_y).take(visible).collect();
+        let labels: Vec<String> = shown.iter().map(|row| refs(row)).collect();
+        // Marker, sha, author and time, and the gaps between the five columns
+        let fixed = 2 + SHA_W + AUTHOR_W + TIME_AGO_W + 4 * GAP.len();
+        let width = area.width as usize;
+        let refs_w = labels
+            .iter()
+            .map(|r| UnicodeWidthStr::width(r.as_str()))
+            .max()
+            .unwrap_or(0)
+            .min(width.saturating_sub(fixed) / 3);
+        let message_w = width.saturating_sub(fixed + refs_w);
+
+        let columns = [
+            fit("sha", SHA_W),
+            fit("message", message_w),
+            fit("author", AUTHOR_W),
+            fit("time", TIME_AGO_W),
+            fit("refs", refs_w),
+        ];
+        buf.set_style(
+            Rect { height: 1, ..area },
+            Style::default().bg(p.lane_header_bg),
+        );
+        buf.set_line(
+            area.x,
+            area.y,
+            &Line::styled(
+                format!("  {}", columns.join(GAP)),
+                Style::default().fg(p.dim_text).bg(p.lane_header_bg),
+            ),
+            area.width,
+        );
+
+        let sel_bg = if self.is_active {
+            p.selected_bg
+        } else {
+            p.unfocused_sel_bg
+        };
+        for (i, (row, refs)) in shown.iter().zip(&labels).enumerate() {
+            let index = self.scroll_y + i;
+            let selected = index == self.selected;
+            let highlighted = self.highlighted_oids.contains(&row.meta.oid);
+            // Spelled out as text too, not only as color
+            let marker = match (selected, highlighted) {
+                (true, _) => "> ",
+                (false, true) => "+ ",
+                (false, false) => "  ",
+            };
+            let bg = if selected { sel_bg } else { p.content_bg };
+            let mut text = Style::default().fg(p.content_fg).bg(bg);
+            if highlighted {
+                text = text.add_modifier(Modifier::BOLD);
+            }
+            let dim = text.fg(p.dim_text);
+            let color = |c: Color| text.fg(c);
+            let sha = row.meta.oid.to_string();
+            let summary = row.meta.message.lines().next().unwrap_or_default();
+            let refs_color = if row.meta.is_head {
+                p.head_color
+            } else {
+                p.accent
+            };
+            let spans = vec![
+                Span::styled(marker, color(p.selected_accent)),
+                Span::styled(fit(&sha[..SHA_W.min(sha.len())], SHA_W), dim),
+                Span::styled(GAP, text),
+                Span::styled(fit(summary, message_w), text),
+                Span::styled(GAP, text),
+                Span::styled(
+                    fit(
+                        &self.authors.label(&row.meta.author, &row.meta.email),
+                        AUTHOR_W,
+                    ),
+                    dim,
+                ),
+                Span::styled(GAP, text),
+                Span::styled(fit(&format_time_ago(&row.meta.time), TIME_AGO_W), dim),
+                Span::styled(GAP, text),
+                Span::styled(fit(refs, refs_w), color(refs_color)),
+            ];
+            let y = area.y + 1 + i as u16;
+            buf.set_style(
+                Rect {
+                    y,
+                    height: 1,
+                    ..area
+                },
+                Style::default().bg(bg),
+            );
+            buf.set_line(area.x, y, &Line::from(spans), area.width);
+        }
+    }
+}
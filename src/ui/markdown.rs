@@ -0,0 +1,202 @@
+use super::links::{issue_ref, safe_url, WebLinks};
+use super::wrap_line;
+use crate::ui::theme::ThemePalette;
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+use std::ops::Range;
+use unicode_width::UnicodeWidthStr;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Mark {
+    Bold,
+    Code,
+    Link(String),
+}
+
+pub struct MessageRow {
+    pub line: Line<'static>,
+    pub links: Vec<(u16, u16, String)>,
+}
+
+pub fn message_rows(
+    text: &str,
+    width: usize,
+    palette: &ThemePalette,
+    links: Option<&WebLinks>,
+) -> Vec<MessageRow> {
+    let style = |mark: &Mark| match mark {
+        Mark::Bold => Style::default().add_modifier(Modifier::BOLD),
+        Mark::Code => Style::default()
+            .fg(palette.content_fg)
+            .bg(palette.lane_header_bg),
+        Mark::Link(_) => Style::default()
+            .fg(palette.accent)
+            .add_modifier(Modifier::UNDERLINED),
+    };
+    let mut rows = Vec::new();
+    for line in text.lines() {
+        let (plain, marks) = parse_line(&line.trim_end().replace('\t', "    "), links);
+        for (pad, range) in wrap_line(&plain, width) {
+            let mut spans = vec![Span::raw(" ".repeat(pad))];
+            let mut links = Vec::new();
+            let mut at = range.start;
+            for (span, mark) in &marks {
+                let seg = span.start.max(range.start)..span.end.min(range.end);
+                if seg.is_empty() {
+                    continue;
+                }
+                spans.push(Span::raw(plain[at..seg.start].to_string()));
+                if let Mark::Link(url) = mark {
+                    let col = pad + UnicodeWidthStr::width(&plain[range.start..seg.start]);
+                    let width = UnicodeWidthStr::width(&plain[seg.clone()]);
+                    links.push((col as u16, width as u16, url.clone()));
+                }
+                spans.push(Span::styled(plain[seg.clone()].to_string(), style(mark)));
+                at = seg.end;
+            }
+            spans.push(Span::raw(plain[at..range.end].to_string()));
+            spans.retain(|span| !span.content.is_empty());
+            rows.push(MessageRow {
+                line: Line::from(spans),
+                links,
+            });
+        }
+    }
+    rows
+}
+
+fn parse_line(line: &str, links: Option<&WebLinks>) -> (String, Vec<(Range<usize>, Mark)>) {
+    let mut out = String::new();
+    let mut marks = Vec::new();
+    let body = line.trim_start();
+    out.push_str(&line[..line.len() - body.len()]);
+    let mut rest = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|m| body.strip_prefix(m))
+        .map_or(body, |item| {
+            out.push_str("\u{2022} ");
+            item
+        });
+    let mut push = |out: &mut String, text: &str, mark: Mark| {
+        marks.push((out.len()..out.len() + text.len(), mark));
+        out.push_str(text);
+    };
+    while let Some(c) = rest.chars().next() {
+        if let Some((inner, tail)) = enclosed(rest, "**") {
+            push(&mut out, inner, Mark::Bold);
+            rest = tail;
+        } else if let Some((inner, tail)) = enclosed(rest, "`") {
+            push(&mut out, inner, Mark::Code);
+            rest = tail;
+        } else if let Some((text, url, tail)) = link(rest) {
+            push(&mut out, text, Mark::Link(url.to_string()));
+            rest = tail;
+        } else if let Some(url) = bare_url(rest).filter(|_| {
+            out.chars()
+                .next_back()
+                .is_none_or(|p| p.is_whitespace() || "(<".contains(p))
+        }) {
+            push(&mut out, url, Mark::Link(url.to_string()));
+            rest = &rest[url.len()..];
+        } else if let Some((links, (len, number))) =
+            links.and_then(|l| Some((l, issue_ref(out.chars().next_back(), rest)?)))
+        {
+            push(&mut out, &rest[..len], Mark::Link(links.issue(number)));
+            rest = &rest[len..];
+        } else {
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    (out, marks)
+}
+
+// Empty or space-padded contents don't count
+fn enclosed<'a>(s: &'a str, delim: &str) -> Option<(&'a str, &'a str)> {
+    let after = s.strip_prefix(delim)?;
+    let end = after.find(delim)?;
+    let inner = &after[..end];
+    if inner.is_empty() || inner.starts_with(' ') || inner.ends_with(' ') {
+        return None;
+    }
+    Some((inner, &after[end + delim.len()..]))
+}
+
+fn link(s: &str) -> Option<(&str, &str, &str)> {
+    let after = s.strip_prefix('[')?;
+    let (text, tail) = after.split_once("](")?;
+    let (url, tail) = tail.split_once(')')?;
+    if text.is_empty() || text.contains(']') || url.contains(' ') || !safe_url(url) {
+        return None;
+    }
+    Some((text, url, tail))
+}
+
+fn bare_url(s: &str) -> Option<&str> {
+    if !s.starts_with("https://") && !s.starts_with("http://") {
+        return None;
+    }
+    let end = s
+        .find(|c: char| c.is_whitespace() || c.is_control())
+        .unwrap_or(s.len());
+    let url = s[..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')', '>', '\'', '"']);
+    url.split_once("://")
+        .is_some_and(|(_, host)| !host.is_empty())
+        .then_some(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::theme::palette_for_theme;
+
+    fn text(row: &MessageRow) -> String {
+        row.line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn renders_inline_markup_bullets_and_links() {
+        let palette = palette_for_theme(None);
+        let message = "Use **bold** and `code`\n\n* see [the docs](https://example.com/docs) or https://x.io/a.\n";
+        let rows = message_rows(message, 40, &palette, None);
+        let texts: Vec<String> = rows.iter().map(text).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Use bold and code",
+                "",
+                "\u{2022} see the docs or https://x.io/a.",
+            ]
+        );
+        let bold = &rows[0].line.spans[1];
+        assert_eq!(bold.content, "bold");
+        assert!(bold.style.add_modifier.contains(Modifier::BOLD));
+        assert_eq!(
+            rows[2].links,
+            vec![
+                (6, 8, "https://example.com/docs".to_string()),
+                (18, 14, "https://x.io/a".to_string()),
+            ]
+        );
+
+        // Wrapped under the bullet, the link keeps its column on its row
+        let rows = message_rows("- a [link](https://e.com) here", 8, &palette, None);
+        let texts: Vec<String> = rows.iter().map(text).collect();
+        assert_eq!(texts, vec!["\u{2022} a link", "  here"]);
+        assert_eq!(rows[0].links, vec![(4, 4, "https://e.com".to_string())]);
+
+        // Unclosed or padded markup stays as written
+        let rows = message_rows("2 ** 3 and a `tick", 40, &palette, None);
+        assert_eq!(text(&rows[0]), "2 ** 3 and a `tick");
+
+        // Escapes can't ride a link into the OSC 8 sequence
+        let message = "[x](https://e.com/\x1b]8;;evil\x07) [y](file:///etc) https://e.com\x1b[2J";
+        let rows = message_rows(message, 80, &palette, None);
+        assert!(text(&rows[0]).starts_with("[x](https://e.com/\x1b]8;;evil\x07) [y](file"));
+        // Only the harmless parts before the escapes are linked
+        let urls: Vec<&str> = rows[0].links.iter().map(|l| l.2.as_str()).collect();
+        assert_eq!(urls, ["https://e.com/", "https://e.com"]);
+    }
+}
@@ -1,17 +1,46 @@
+pub mod aggregate_view;
+pub mod blame_view;
 pub mod branch_panel;
 pub mod config_screen;
+pub mod conflict_preview;
+pub mod deleted_panel;
 pub mod detail_panel;
 pub mod graph_view;
+pub mod head_timeline;
 pub mod header_bar;
+pub mod health_panel;
+pub mod heatmap;
 pub mod help_panel;
 pub mod input;
+pub mod lane_inspect;
+pub mod line_cache;
+pub mod links;
+pub mod list_view;
+pub mod markdown;
+pub mod perf_hud;
+pub mod pr_merge;
+pub mod rebase_preview;
+pub mod ref_popover;
+pub mod release_panel;
+pub mod stash_panel;
 pub mod status_bar;
 pub mod theme;
 pub mod toast;
 
-use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::Style,
+    widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget},
+};
+use std::ops::Range;
+use theme::ThemePalette;
+use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
+// Fits a relative time such as "11mo ago"
+pub const TIME_AGO_W: usize = 8;
+
 pub fn truncate_with_ellipsis(s: &str, max: usize) -> String {
     if UnicodeWidthStr::width(s) <= max {
         return s.to_string();
@@ -21,18 +50,126 @@ pub fn truncate_with_ellipsis(s: &str, max: usize) -> String {
     }
     let mut result = String::new();
     let mut w = 0;
-    for ch in s.chars() {
-        let cw = unicode_width::UnicodeWidthChar::width(ch).unwrap_or(0);
-        if w + cw > max - 1 {
+    // Whole graphemes, so accents and emoji sequences aren't cut apart
+    for g in s.graphemes(true) {
+        let gw = UnicodeWidthStr::width(g);
+        if w + gw > max - 1 {
             break;
         }
-        result.push(ch);
-        w += cw;
+        result.push_str(g);
+        w += gw;
     }
     result.push('\u{2026}');
     result
 }
 
+pub fn fit(s: &str, width: usize) -> String {
+    let s = truncate_with_ellipsis(s, width);
+    let pad = width.saturating_sub(UnicodeWidthStr::width(s.as_str()));
+    format!("{s}{}", " ".repeat(pad))
+}
+
+// Continuation rows of an indented or list line line up under its text
+pub fn wrap_line(line: &str, width: usize) -> Vec<(usize, Range<usize>)> {
+    let width = width.max(1);
+    let body_start = line.len() - line.trim_start().len();
+    let indent_w = UnicodeWidthStr::width(&line[..body_start]);
+    let mut hang = indent_w + list_marker_width(&line[body_start..]);
+    if hang * 2 > width {
+        hang = 0;
+    }
+    let mut rows = Vec::new();
+    let (mut start, mut row_w) = if indent_w * 2 > width {
+        (body_start, 0)
+    } else {
+        (0, indent_w)
+    };
+    let (mut pad, mut end) = (0, start);
+    let (mut has_word, mut placed) = (false, false);
+    let mut space_w = 0;
+    for (offset, run) in split_spaces(&line[body_start..]) {
+        if run.starts_with(char::is_whitespace) {
+            space_w = UnicodeWidthStr::width(run);
+            continue;
+        }
+        let at = body_start + offset;
+        let word_w = UnicodeWidthStr::width(run);
+        if has_word && row_w + space_w + word_w <= width {
+            row_w += space_w + word_w;
+            end = at + run.len();
+            continue;
+        }
+        if has_word {
+            rows.push((pad, start..end));
+            (pad, start, row_w, placed) = (hang, at, hang, false);
+        }
+        has_word = true;
+        for (i, g) in run.grapheme_indices(true) {
+            let gw = UnicodeWidthStr::width(g);
+            if row_w + gw > width && placed {
+                rows.push((pad, start..at + i));
+                (pad, start, row_w) = (hang, at + i, hang);
+            }
+            row_w += gw;
+            placed = true;
+            end = at + i + g.len();
+        }
+    }
+    rows.push((pad, start..end));
+    rows
+}
+
+fn list_marker_width(line: &str) -> usize {
+    if ["- ", "* ", "+ ", "\u{2022} "]
+        .iter()
+        .any(|m| line.starts_with(m))
+    {
+        return 2;
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let rest = &line[digits..];
+    if (1..=3).contains(&digits) && (rest.starts_with(". ") || rest.starts_with(") ")) {
+        digits + 2
+    } else {
+        0
+    }
+}
+
+fn split_spaces(s: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut at = 0;
+    std::iter::from_fn(move || {
+        let rest = &s[at..];
+        let first = rest.chars().next()?;
+        let len = rest
+            .find(|c: char| c.is_whitespace() != first.is_whitespace())
+            .unwrap_or(rest.len());
+        at += len;
+        Some((at - len, &rest[..len]))
+    })
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+pub fn format_count(n: usize) -> String {
+    match n {
+        0..1_000 => n.to_string(),
+        1_000..1_000_000 => format!("{}k", n / 1_000),
+        _ => format!("{:.1}M", n as f64 / 1_000_000.0),
+    }
+}
+
 pub fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
     let vert = Layout::vertical([
         Constraint::Percentage((100 - percent_y) / 2),
@@ -48,3 +185,91 @@ pub fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
     ])
     .split(vert[1])[1]
 }
+
+// The track itself is left alone, and nothing is drawn when every row fits
+pub fn render_scrollbar(
+    buf: &mut Buffer,
+    track: Rect,
+    total: usize,
+    offset: usize,
+    palette: &ThemePalette,
+) {
+    let viewport = track.height as usize;
+    if viewport == 0 || track.width == 0 || total <= viewport {
+        return;
+    }
+    let mut state = ScrollbarState::new(total - viewport + 1)
+        .position(offset)
+        .viewport_content_length(viewport);
+    Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None)
+        .track_symbol(None)
+        .thumb_symbol("\u{2503}")
+        .thumb_style(Style::default().fg(palette.accent))
+        .render(track, buf, &mut state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrollbar_thumb_follows_offset() {
+        let palette = theme::palette_for_theme(None);
+        let track = Rect::new(0, 0, 1, 10);
+        let thumb = |total, offset| {
+            let mut buf = Buffer::empty(track);
+            render_scrollbar(&mut buf, track, total, offset, &palette);
+            (0..10)
+                .filter(|&y| buf[(0, y)].symbol() == "\u{2503}")
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(thumb(100, 0), vec![0]);
+        assert_eq!(thumb(100, 90), vec![9]);
+        assert_eq!(thumb(20, 5), (3..8).collect::<Vec<_>>());
+        assert!(thumb(10, 0).is_empty());
+    }
+
+    fn wrap_text(text: &str, width: usize) -> Vec<String> {
+        text.lines()
+            .flat_map(|line| {
+                wrap_line(line, width)
+                    .into_iter()
+                    .map(|(pad, range)| format!("{}{}", " ".repeat(pad), &line[range]))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn wrap_keeps_words_blank_lines_and_list_indents() {
+        let message = "Fix the parser\n\nIt failed on long inputs.\n- first item wraps here\n  1. nested numbered item";
+        assert_eq!(
+            wrap_text(message, 14),
+            vec![
+                "Fix the parser",
+                "",
+                "It failed on",
+                "long inputs.",
+                "- first item",
+                "  wraps here",
+                "  1. nested",
+                "     numbered",
+                "     item",
+            ]
+        );
+        // Too wide for a row: broken between graphemes, never inside one
+        assert_eq!(
+            wrap_text("ab\u{e9}\u{301}cd", 3),
+            vec!["ab\u{e9}\u{301}", "cd"]
+        );
+        assert_eq!(
+            wrap_text("\u{65e5}\u{672c}\u{8a9e}", 4),
+            vec!["\u{65e5}\u{672c}", "\u{8a9e}"]
+        );
+        assert_eq!(
+            truncate_with_ellipsis("e\u{301}e\u{301}e\u{301}", 2),
+            "e\u{301}\u{2026}"
+        );
+    }
+}
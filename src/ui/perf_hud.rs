@@ -0,0 +1,100 @@
+use crate::ui::theme::ThemePalette;
+use ratatui::{
+    buffer::Buffer as Buf,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Widget},
+};
+use std::time::Duration;
+
+const HUD_WIDTH: u16 = 26;
+
+#[derive(Debug, Default, Clone)]
+pub struct PerfStats {
+    pub frame_time: Duration,
+    pub frame_avg: Duration,
+    pub rows_rendered: usize,
+    pub backlog: usize,
+    pub max_backlog: usize,
+}
+
+impl PerfStats {
+    pub fn record_frame(&mut self, elapsed: Duration) {
+        self.frame_time = elapsed;
+        // exponential moving average — smooths out single slow frames
+        self.frame_avg = if self.frame_avg.is_zero() {
+            elapsed
+        } else {
+            (self.frame_avg * 9 + elapsed) / 10
+        };
+    }
+
+    pub fn record_backlog(&mut self, batch: usize) {
+        self.backlog = batch;
+        self.max_backlog = self.max_backlog.max(batch);
+    }
+}
+
+pub struct PerfHud<'a> {
+    pub stats: &'a PerfStats,
+    pub layout_time: Duration,
+    pub image_cache: Option<(u64, u64)>,
+    pub github_requests: Option<u32>,
+    pub palette: &'a ThemePalette,
+}
+
+fn ms(d: Duration) -> String {
+    format!("{:.1}ms", d.as_secs_f64() * 1000.0)
+}
+
+impl<'a> Widget for PerfHud<'a> {
+    fn render(self, area: Rect, buf: &mut Buf) {
+        let p = self.palette;
+        let s = self.stats;
+
+        let mut rows: Vec<(&str, String)> = vec![
+            (
+                "frame",
+                format!("{} ~{}", ms(s.frame_time), ms(s.frame_avg)),
+            ),
+            ("layout", ms(self.layout_time)),
+            ("rows", s.rows_rendered.to_string()),
+            ("backlog", format!("{} (max {})", s.backlog, s.max_backlog)),
+        ];
+        if let Some((hits, misses)) = self.image_cache {
+            let total = hits + misses;
+            let rate = (hits * 100)
+                .checked_div(total)
+                .map(|pct| format!("{pct}% of {total}"))
+                .unwrap_or_else(|| "-".to_string());
+            rows.push(("img hit", rate));
+        }
+        if let Some(n) = self.github_requests {
+            rows.push(("gh reqs", n.to_string()));
+        }
+
+        let box_h = rows.len() as u16 + 2;
+        if area.width < HUD_WIDTH + 2 || area.height < box_h {
+            return;
+        }
+        let hud_area = Rect::new(area.right() - HUD_WIDTH - 1, area.y, HUD_WIDTH, box_h);
+        Clear.render(hud_area, buf);
+
+        let block = Block::default()
+            .title(" perf ")
+            .title_style(Style::default().fg(p.accent).add_modifier(Modifier::BOLD))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(p.separator));
+        let inner = block.inner(hud_area);
+        block.render(hud_area, buf);
+
+        for (i, (label, value)) in rows.into_iter().enumerate() {
+            let line = Line::from(vec![
+                Span::styled(format!("{label:<8}"), Style::default().fg(p.dim_text)),
+                Span::styled(value, Style::default().fg(p.content_fg)),
+            ]);
+            buf.set_line(inner.x, inner.y + i as u16, &line, inner.width);
+        }
+    }
+}
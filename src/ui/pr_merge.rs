@@ -0,0 +1,80 @@
+use crate::data_source::{MergeMethod, PullRequestInfo};
+use crate::ui::theme::ThemePalette;
+use crate::ui::truncate_with_ellipsis;
+use ratatui::{
+    buffer::Buffer as Buf,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, Widget},
+};
+
+pub struct PrMergeState {
+    pub project_idx: usize,
+    pub branch: String,
+    pub pr: PullRequestInfo,
+    pub selected: usize,
+}
+
+impl PrMergeState {
+    pub fn method(&self) -> MergeMethod {
+        MergeMethod::ALL[self.selected.min(MergeMethod::ALL.len() - 1)]
+    }
+}
+
+pub struct PrMergePanel<'a> {
+    pub state: &'a PrMergeState,
+    pub palette: &'a ThemePalette,
+}
+
+const POPUP_HEIGHT: u16 = 9;
+
+impl<'a> Widget for PrMergePanel<'a> {
+    fn render(self, area: Rect, buf: &mut Buf) {
+        let p = self.palette;
+        let width = 50.min(area.width);
+        let height = POPUP_HEIGHT.min(area.height);
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        Clear.render(popup, buf);
+
+        let block = Block::default()
+            .title(format!(" Merge PR #{} ", self.state.pr.number))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(p.accent));
+        let inner = block.inner(popup);
+        block.render(popup, buf);
+        if inner.height < 7 {
+            return;
+        }
+
+        let text_w = inner.width.saturating_sub(2) as usize;
+        buf.set_string(
+            inner.x + 1,
+            inner.y,
+            truncate_with_ellipsis(&self.state.pr.title, text_w),
+            Style::default().add_modifier(Modifier::BOLD),
+        );
+        for (i, method) in MergeMethod::ALL.iter().enumerate() {
+            let style = if i == self.state.selected {
+                Style::default().bg(p.selected_bg).fg(p.accent)
+            } else {
+                Style::default()
+            };
+            let y = inner.y + 2 + i as u16;
+            for x in inner.x..inner.right() {
+                buf[(x, y)].set_style(style);
+            }
+            buf.set_string(inner.x + 1, y, method.label(), style);
+        }
+        buf.set_string(
+            inner.x + 1,
+            inner.y + 6,
+            "j/k pick  Enter merge  Esc cancel",
+            Style::default().fg(p.dim_text),
+        );
+    }
+}
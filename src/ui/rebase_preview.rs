@@ -0,0 +1,128 @@
+use crate::git::types::{CommitInfo, Oid};
+use crate::graph::drift::RebasePlan;
+use crate::ui::theme::ThemePalette;
+use crate::ui::truncate_with_ellipsis;
+use ratatui::{
+    buffer::Buffer as Buf,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Widget},
+};
+use std::sync::Arc;
+
+pub struct RebasePreviewState {
+    pub branch: String,
+    pub onto: String,
+    pub onto_tip: Oid,
+    pub plan: RebasePlan,
+    pub commits: Vec<Arc<CommitInfo>>,
+    pub onto_row: Option<usize>,
+}
+
+pub struct RebasePreviewPanel<'a> {
+    pub state: &'a RebasePreviewState,
+    pub palette: &'a ThemePalette,
+}
+
+impl<'a> Widget for RebasePreviewPanel<'a> {
+    fn render(self, area: Rect, buf: &mut Buf) {
+        let p = self.palette;
+        let state = self.state;
+        let popup = super::centered_rect(60, 60, area);
+        Clear.render(popup, buf);
+
+        let block = Block::default()
+            .title(format!(" Rebase {} onto {} ", state.branch, state.onto))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(p.accent));
+        let inner = block.inner(popup);
+        block.render(popup, buf);
+        if inner.height < 2 {
+            return;
+        }
+
+        buf.set_string(
+            inner.x + 1,
+            inner.bottom() - 1,
+            "dry run \u{00b7} Enter show new base \u{00b7} Esc close",
+            Style::default().fg(p.dim_text),
+        );
+
+        let width = inner.width.saturating_sub(2) as usize;
+        let onto_sha = &state.onto_tip.to_string()[..7];
+        let count = state.plan.replayed.len();
+        let mut lines = Vec::new();
+        if state.plan.is_noop(state.onto_tip) {
+            let verdict = if count == 0 {
+                format!(
+                    "{} already has every commit of {}",
+                    state.onto, state.branch
+                )
+            } else {
+                format!("{} already sits on {}", state.branch, state.onto)
+            };
+            lines.push(Line::from(Span::styled(
+                verdict,
+                Style::default().fg(p.dim_text),
+            )));
+        } else {
+            let noun = if count == 1 { "commit" } else { "commits" };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{count} {noun} replayed"),
+                    Style::default().fg(p.accent).add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(format!(" onto {} @ {onto_sha}", state.onto)),
+            ]));
+            let landing = match state.onto_row {
+                Some(row) => format!("new tip lands above row {}", row + 1),
+                None => "new tip lands past the loaded history".to_string(),
+            };
+            lines.push(Line::from(Span::styled(
+                landing,
+                Style::default().fg(p.dim_text),
+            )));
+        }
+        if state.plan.merges_dropped > 0 {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "{} merge commits dropped (no --rebase-merges)",
+                    state.plan.merges_dropped
+                ),
+                Style::default().fg(p.warn_fg),
+            )));
+        }
+        if state.plan.base.is_none() {
+            lines.push(Line::from(Span::styled(
+                "no shared commit loaded; older history may replay too",
+                Style::default().fg(p.warn_fg),
+            )));
+        }
+        if !state.plan.is_noop(state.onto_tip) {
+            lines.push(Line::default());
+            for commit in &state.commits {
+                let sha = commit.oid.to_string();
+                let message = truncate_with_ellipsis(&commit.message, width.saturating_sub(13));
+                lines.push(Line::from(vec![
+                    Span::styled("pick ", Style::default().fg(p.dim_text)),
+                    Span::styled(format!("{} ", &sha[..7]), Style::default().fg(p.accent)),
+                    Span::raw(message),
+                ]));
+            }
+        }
+
+        let rows = (inner.height - 1) as usize;
+        let hidden = lines.len().saturating_sub(rows);
+        if hidden > 0 {
+            lines.truncate(rows - 1);
+            lines.push(Line::from(Span::styled(
+                format!("  +{} more", hidden + 1),
+                Style::default().fg(p.dim_text),
+            )));
+        }
+        for (i, line) in lines.iter().enumerate() {
+            buf.set_line(inner.x + 1, inner.y + i as u16, line, width as u16);
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::git::types::{CommitSource, Oid};
+use crate::ui::theme::ThemePalette;
+use crate::ui::truncate_with_ellipsis;
+use ratatui::{
+    buffer::Buffer as Buf,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Widget},
+};
+use unicode_width::UnicodeWidthStr;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RefKind {
+    Branch { source: CommitSource, is_head: bool },
+    Tag,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RefItem {
+    pub name: String,
+    pub kind: RefKind,
+}
+
+impl RefItem {
+    // Fork branches only exist on GitHub and have none
+    pub fn refname(&self) -> Option<String> {
+        match &self.kind {
+            RefKind::Branch {
+                source: CommitSource::Local,
+                ..
+            } => Some(format!("refs/heads/{}", self.name)),
+            RefKind::Branch {
+                source: CommitSource::Remote(_),
+                ..
+            } => Some(format!("refs/remotes/{}", self.name)),
+            RefKind::Branch {
+                source: CommitSource::Fork(_),
+                ..
+            } => None,
+            RefKind::Tag => Some(format!("refs/tags/{}", self.name)),
+        }
+    }
+
+    fn kind_label(&self) -> String {
+        match &self.kind {
+            RefKind::Branch {
+                source: CommitSource::Local,
+                ..
+            } => "local".to_string(),
+            RefKind::Branch {
+                source: CommitSource::Remote(remote),
+                ..
+            } => remote.clone(),
+            RefKind::Branch {
+                source: CommitSource::Fork(owner),
+                ..
+            } => format!("fork: {owner}"),
+            RefKind::Tag => "tag".to_string(),
+        }
+    }
+}
+
+pub struct RefPopoverState {
+    pub oid: Oid,
+    // Local branches first, HEAD's leading, then remote and fork ones, then tags
+    pub refs: Vec<RefItem>,
+    pub selected: usize,
+}
+
+impl RefPopoverState {
+    pub fn new(oid: Oid, mut refs: Vec<RefItem>) -> Self {
+        refs.sort_by_key(|r| match &r.kind {
+            RefKind::Branch { is_head: true, .. } => 0,
+            RefKind::Branch {
+                source: CommitSource::Local,
+                ..
+            } => 1,
+            RefKind::Branch {
+                source: CommitSource::Remote(_),
+                ..
+            } => 2,
+            RefKind::Branch {
+                source: CommitSource::Fork(_),
+                ..
+            } => 3,
+            RefKind::Tag => 4,
+        });
+        Self {
+            oid,
+            refs,
+            selected: 0,
+        }
+    }
+}
+
+pub struct RefPopover<'a> {
+    pub state: &'a RefPopoverState,
+    pub palette: &'a ThemePalette,
+}
+
+impl<'a> Widget for RefPopover<'a> {
+    fn render(self, area: Rect, buf: &mut Buf) {
+        let p = self.palette;
+        let state = self.state;
+        let popup = super::centered_rect(50, 50, area);
+        Clear.render(popup, buf);
+
+        let sha = state.oid.to_string();
+        let block = Block::default()
+            .title(format!(" Refs on {} ({}) ", &sha[..7], state.refs.len()))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(p.accent));
+        let inner = block.inner(popup);
+        block.render(popup, buf);
+        if inner.height < 2 {
+            return;
+        }
+
+        buf.set_string(
+            inner.x + 1,
+            inner.bottom() - 1,
+            "Enter jump  c checkout  Esc close",
+            Style::default().fg(p.dim_text),
+        );
+        let rows = (inner.height - 1) as usize;
+        let skip = (state.selected + 1).saturating_sub(rows);
+        for (i, item) in state.refs.iter().enumerate().skip(skip).take(rows) {
+            let y = inner.y + (i - skip) as u16;
+            let is_selected = i == state.selected;
+            let base = if is_selected {
+                Style::default().bg(p.selected_bg)
+            } else {
+                Style::default()
+            };
+            let kind = format!(" {} ", item.kind_label());
+            let head = matches!(item.kind, RefKind::Branch { is_head: true, .. });
+            let marker = if head { " *" } else { "  " };
+            let name_w = (inner.width as usize)
+                .saturating_sub(marker.len() + UnicodeWidthStr::width(kind.as_str()));
+            let name = truncate_with_ellipsis(&item.name, name_w);
+            let pad = name_w.saturating_sub(UnicodeWidthStr::width(name.as_str()));
+            let name_style = match item.kind {
+                RefKind::Tag => base.fg(p.tag_color),
+                RefKind::Branch { .. } => base.add_modifier(Modifier::BOLD),
+            };
+            let line = Line::from(vec![
+                Span::styled(marker, base.fg(p.accent)),
+                Span::styled(format!("{name}{}", " ".repeat(pad)), name_style),
+                Span::styled(kind, base.fg(p.dim_text)),
+            ]);
+            if is_selected {
+                for x in inner.x..inner.right() {
+                    buf[(x, y)].set_style(base);
+                }
+            }
+            buf.set_line(inner.x, y, &line, inner.width);
+        }
+    }
+}
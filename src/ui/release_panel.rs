@@ -0,0 +1,101 @@
+use crate::dates::DateFormat;
+use crate::stats::Release;
+use crate::ui::theme::ThemePalette;
+use crate::ui::truncate_with_ellipsis;
+use ratatui::{
+    buffer::Buffer as Buf,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Widget},
+};
+
+pub struct ReleasesState {
+    pub releases: Vec<Release>,
+    pub selected: usize,
+}
+
+pub struct ReleasePanel<'a> {
+    pub state: &'a ReleasesState,
+    pub dates: &'a DateFormat,
+    pub palette: &'a ThemePalette,
+}
+
+const NAME_W: usize = 24;
+
+impl<'a> Widget for ReleasePanel<'a> {
+    fn render(self, area: Rect, buf: &mut Buf) {
+        let p = self.palette;
+        let popup = super::centered_rect(60, 70, area);
+        Clear.render(popup, buf);
+
+        let block = Block::default()
+            .title(format!(" Releases ({}) ", self.state.releases.len()))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(p.accent));
+        let inner = block.inner(popup);
+        block.render(popup, buf);
+        if inner.height == 0 {
+            return;
+        }
+        if self.state.releases.is_empty() {
+            buf.set_string(
+                inner.x + 1,
+                inner.y,
+                "no tags on loaded commits",
+                Style::default().fg(p.dim_text),
+            );
+            return;
+        }
+
+        // Newest at the top, like the graph
+        let rows = inner.height as usize;
+        let newest_first = self.state.releases.len() - 1 - self.state.selected;
+        let skip = (newest_first + 1).saturating_sub(rows);
+        for (i, release) in self
+            .state
+            .releases
+            .iter()
+            .enumerate()
+            .rev()
+            .skip(skip)
+            .take(rows)
+        {
+            let y = inner.y + (self.state.releases.len() - 1 - i - skip) as u16;
+            let is_selected = i == self.state.selected;
+            let base = if is_selected {
+                Style::default().bg(p.selected_bg)
+            } else {
+                Style::default()
+            };
+            let name = truncate_with_ellipsis(&release.name, NAME_W);
+            let line = Line::from(vec![
+                Span::styled(
+                    format!(" {name:<NAME_W$} "),
+                    base.fg(p.tag_color).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!(
+                        "{}  ",
+                        self.dates
+                            .date(release.time.with_timezone(&chrono::Local).date_naive())
+                    ),
+                    base,
+                ),
+                Span::styled(
+                    format!(
+                        "+{} commits, {} contributors",
+                        release.commits, release.contributors
+                    ),
+                    base.fg(p.dim_text),
+                ),
+            ]);
+            if is_selected {
+                for x in inner.x..inner.right() {
+                    buf[(x, y)].set_style(base);
+                }
+            }
+            buf.set_line(inner.x, y, &line, inner.width);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use crate::git::stash::StashEntry;
+use crate::ui::theme::ThemePalette;
+use crate::ui::truncate_with_ellipsis;
+use ratatui::{
+    buffer::Buffer as Buf,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Widget},
+};
+
+pub struct StashesState {
+    pub entries: Vec<StashEntry>,
+    pub selected: usize,
+    pub loading: bool,
+}
+
+pub struct StashPanel<'a> {
+    pub state: &'a StashesState,
+    pub palette: &'a ThemePalette,
+}
+
+impl<'a> Widget for StashPanel<'a> {
+    fn render(self, area: Rect, buf: &mut Buf) {
+        let p = self.palette;
+        let popup = super::centered_rect(60, 50, area);
+        Clear.render(popup, buf);
+
+        let block = Block::default()
+            .title(format!(" Stashes ({}) ", self.state.entries.len()))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(p.accent));
+        let inner = block.inner(popup);
+        block.render(popup, buf);
+        if inner.height < 2 {
+            return;
+        }
+
+        let footer_y = inner.bottom() - 1;
+        buf.set_string(
+            inner.x + 1,
+            footer_y,
+            "a apply  p pop  d drop  Esc close",
+            Style::default().fg(p.dim_text),
+        );
+        if self.state.entries.is_empty() {
+            let msg = if self.state.loading {
+                "loading\u{2026}"
+            } else {
+                "no stashes"
+            };
+            buf.set_string(inner.x + 1, inner.y, msg, Style::default().fg(p.dim_text));
+            return;
+        }
+
+        let rows = (inner.height - 1) as usize;
+        let skip = (self.state.selected + 1).saturating_sub(rows);
+        for (i, entry) in self.state.entries.iter().enumerate().skip(skip).take(rows) {
+            let y = inner.y + (i - skip) as u16;
+            let is_selected = i == self.state.selected;
+            let base = if is_selected {
+                Style::default().bg(p.selected_bg)
+            } else {
+                Style::default()
+            };
+            let label = format!(" stash@{{{}}} ", entry.index);
+            let message_w = (inner.width as usize).saturating_sub(label.len() + 1);
+            let line = Line::from(vec![
+                Span::styled(label, base.fg(p.accent).add_modifier(Modifier::BOLD)),
+                Span::styled(truncate_with_ellipsis(&entry.message, message_w), base),
+            ]);
+            if is_selected {
+                for x in inner.x..inner.right() {
+                    buf[(x, y)].set_style(base);
+                }
+            }
+            buf.set_line(inner.x, y, &line, inner.width);
+        }
+    }
+}
@@ -7,62 +7,148 @@ use ratatui::{
     text::{Line, Span},
     widgets::Widget,
 };
+use std::ops::Range;
 use unicode_width::UnicodeWidthStr;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterChip {
+    Branch,
+    Author,
+    Type,
+}
+
 pub struct StatusBar<'a> {
     pub branch_name: &'a str,
     pub last_sync: &'a str,
     pub filter_mode: FilterMode,
     pub filter_text: &'a str,
     pub author_filter_text: &'a str,
-    pub loading_message: Option<&'a str>,
+    pub type_filter: &'a [String],
+    pub prompt: Option<(&'a str, &'a str)>,
+    pub loading_message: Option<String>,
+    pub recording: Option<char>,
+    pub github_paused_until: Option<String>,
+    pub offline: Option<&'a str>,
+    pub following: bool,
+    pub manual_refresh: bool,
     pub commit_count: usize,
+    pub selected_row: usize,
     pub branch_count: usize,
     pub palette: &'a ThemePalette,
 }
 
-impl<'a> Widget for StatusBar<'a> {
-    fn render(self, area: Rect, buf: &mut Buf) {
-        let p = self.palette;
-        let bg = Style::default().bg(p.status_bg);
-        for x in area.x..area.right() {
-            buf[(x, area.y)].set_style(bg);
+impl StatusBar<'_> {
+    pub fn chips(&self) -> Vec<(FilterChip, String)> {
+        let mut chips = Vec::new();
+        if !self.filter_text.is_empty() {
+            chips.push((FilterChip::Branch, format!("/{}", self.filter_text)));
+        }
+        if !self.author_filter_text.is_empty() {
+            chips.push((
+                FilterChip::Author,
+                format!("author: {}", self.author_filter_text),
+            ));
         }
+        if !self.type_filter.is_empty() {
+            chips.push((
+                FilterChip::Type,
+                format!("type: {}", self.type_filter.join(",")),
+            ));
+        }
+        chips
+    }
 
+    pub fn chip_at(&self, area: Rect, x: u16) -> Option<FilterChip> {
         if self.filter_mode.is_active() {
-            let (prefix, text) = match self.filter_mode {
-                FilterMode::Branch => (" /", self.filter_text),
-                FilterMode::Author => (" a/", self.author_filter_text),
-                FilterMode::Off => return,
-            };
-            let line = Line::from(vec![
-                Span::styled(
-                    prefix,
-                    Style::default()
-                        .fg(p.filter_color)
-                        .bg(p.status_bg)
-                        .add_modifier(Modifier::BOLD),
-                ),
-                Span::styled(text.to_string(), Style::default().bg(p.status_bg)),
-                Span::styled(
-                    "\u{258c}",
-                    Style::default().fg(p.filter_color).bg(p.status_bg),
-                ),
-            ]);
-            buf.set_line(area.x, area.y, &line, area.width);
-            return;
+            return None;
         }
+        let col = x.checked_sub(area.x)? as usize;
+        let (_, hits) = self.left_spans();
+        hits.into_iter()
+            .find(|(_, cols)| cols.contains(&col))
+            .map(|(chip, _)| chip)
+    }
 
-        let mut left_spans = Vec::new();
-
-        left_spans.push(Span::styled(" ", Style::default().bg(p.status_bg)));
+    fn left_spans(&self) -> (Vec<Span<'static>>, Vec<(FilterChip, Range<usize>)>) {
+        let p = self.palette;
+        let mut left_spans = vec![Span::styled(" ", Style::default().bg(p.status_bg))];
+        let mut hits = Vec::new();
+        let width = |spans: &[Span]| -> usize {
+            spans
+                .iter()
+                .map(|s| UnicodeWidthStr::width(s.content.as_ref()))
+                .sum()
+        };
 
         left_spans.push(Span::styled(
             format!("{} ", self.branch_name),
             Style::default().bg(p.status_bg),
         ));
 
-        if let Some(msg) = self.loading_message {
+        for (n, (chip, text)) in self.chips().into_iter().enumerate() {
+            left_spans.push(Span::styled(" ", Style::default().bg(p.status_bg)));
+            let start = width(&left_spans);
+            left_spans.push(Span::styled(
+                format!(" {} {text} \u{2715} ", n + 1),
+                Style::default().fg(p.status_bg).bg(p.filter_color),
+            ));
+            hits.push((chip, start..width(&left_spans)));
+        }
+        if !hits.is_empty() {
+            left_spans.push(Span::styled(" ", Style::default().bg(p.status_bg)));
+        }
+
+        if let Some(register) = self.recording {
+            left_spans.push(Span::styled(
+                "\u{2502}",
+                Style::default().fg(p.separator).bg(p.status_bg),
+            ));
+            left_spans.push(Span::styled(
+                format!(" recording @{register} "),
+                Style::default()
+                    .fg(p.error_fg)
+                    .bg(p.status_bg)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
+
+        if let Some(ref until) = self.github_paused_until {
+            left_spans.push(Span::styled(
+                "\u{2502}",
+                Style::default().fg(p.separator).bg(p.status_bg),
+            ));
+            left_spans.push(Span::styled(
+                format!(" GitHub rate limited until {until} "),
+                Style::default().fg(p.warn_fg).bg(p.status_bg),
+            ));
+        }
+
+        if let Some(why) = self.offline {
+            left_spans.push(Span::styled(
+                "\u{2502}",
+                Style::default().fg(p.separator).bg(p.status_bg),
+            ));
+            left_spans.push(Span::styled(
+                format!(" {why} "),
+                Style::default()
+                    .fg(p.warn_fg)
+                    .bg(p.status_bg)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
+
+        if self.following {
+            left_spans.push(Span::styled(
+                "\u{2502}",
+                Style::default().fg(p.separator).bg(p.status_bg),
+            ));
+            left_spans.push(Span::styled(
+                " following ",
+                Style::default().fg(p.accent).bg(p.status_bg),
+            ));
+        }
+
+        if let Some(msg) = &self.loading_message {
             left_spans.push(Span::styled(
                 "\u{2502}",
                 Style::default().fg(p.separator).bg(p.status_bg),
@@ -72,27 +158,59 @@ impl<'a> Widget for StatusBar<'a> {
                 Style::default().fg(p.accent).bg(p.status_bg),
             ));
         } else {
-            if !self.author_filter_text.is_empty() {
-                left_spans.push(Span::styled(
-                    "\u{2502}",
-                    Style::default().fg(p.separator).bg(p.status_bg),
-                ));
-                left_spans.push(Span::styled(
-                    format!(" author: {} ", self.author_filter_text),
-                    Style::default().fg(p.filter_color).bg(p.status_bg),
-                ));
-            }
-
             left_spans.push(Span::styled(
                 "\u{2502}",
                 Style::default().fg(p.separator).bg(p.status_bg),
             ));
+            let manual = if self.manual_refresh {
+                " (r to refresh)"
+            } else {
+                ""
+            };
             left_spans.push(Span::styled(
-                format!(" synced: {} ", self.last_sync),
+                format!(" synced: {}{manual} ", self.last_sync),
                 Style::default().bg(p.status_bg),
             ));
         }
 
+        (left_spans, hits)
+    }
+}
+
+impl<'a> Widget for StatusBar<'a> {
+    fn render(self, area: Rect, buf: &mut Buf) {
+        let p = self.palette;
+        let bg = Style::default().bg(p.status_bg);
+        for x in area.x..area.right() {
+            buf[(x, area.y)].set_style(bg);
+        }
+
+        if self.filter_mode.is_active() {
+            let (prefix, text) = match (self.filter_mode, self.prompt) {
+                (FilterMode::Branch, _) => (" /".to_string(), self.filter_text),
+                (FilterMode::Author, _) => (" a/".to_string(), self.author_filter_text),
+                (FilterMode::Prompt, Some((label, text))) => (format!(" {label}: "), text),
+                (FilterMode::Prompt, None) | (FilterMode::Off, _) => return,
+            };
+            let line = Line::from(vec![
+                Span::styled(
+                    prefix,
+                    Style::default()
+                        .fg(p.filter_color)
+                        .bg(p.status_bg)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(text.to_string(), Style::default().bg(p.status_bg)),
+                Span::styled(
+                    "\u{258c}",
+                    Style::default().fg(p.filter_color).bg(p.status_bg),
+                ),
+            ]);
+            buf.set_line(area.x, area.y, &line, area.width);
+            return;
+        }
+
+        let (left_spans, _) = self.left_spans();
         let left_line = Line::from(left_spans);
         buf.set_line(area.x, area.y, &left_line, area.width);
 
@@ -110,10 +228,16 @@ impl<'a> Widget for StatusBar<'a> {
         let hints_w = UnicodeWidthStr::width(hints);
         let area_w = area.width as usize;
 
-        let stats = format!(
-            "{} commits  {} branches",
-            self.commit_count, self.branch_count
-        );
+        let stats = if self.commit_count == 0 {
+            format!("0 commits  {} branches", self.branch_count)
+        } else {
+            format!(
+                "row {}/{}  {} branches",
+                self.selected_row + 1,
+                self.commit_count,
+                self.branch_count
+            )
+        };
         let stats_w = UnicodeWidthStr::width(stats.as_str());
 
         if area_w > hints_w + stats_w {
@@ -33,20 +33,38 @@ pub struct ThemePalette {
     pub fork_dim: Color,
     pub error_fg: Color,
     pub warn_fg: Color,
+    pub diff_add: Color,
+    pub diff_del: Color,
 
     pub branch_colors: &'static [Color],
     pub trunk_colors: &'static [Color],
+    // Others use accent
+    pub commit_type_colors: &'static [(&'static str, Color)],
 }
 
 impl ThemePalette {
+    // Wrapped trunks reuse the palette a shade darker, so neighbours stay apart
     pub fn branch_color_by_identity(&self, branch_index: usize, trunk_count: usize) -> Color {
-        if branch_index < trunk_count && branch_index < self.trunk_colors.len() {
-            self.trunk_colors[branch_index]
+        if branch_index < trunk_count && !self.trunk_colors.is_empty() {
+            let len = self.trunk_colors.len();
+            let wraps = (branch_index / len).min(3) as u8;
+            match self.trunk_colors[branch_index % len] {
+                c if wraps == 0 => c,
+                c @ Color::Rgb(..) => dim_color(c, wraps * 40),
+                named => darker_named(named),
+            }
         } else {
             self.branch_colors[branch_index.saturating_sub(trunk_count) % self.branch_colors.len()]
         }
     }
 
+    pub fn commit_type_color(&self, kind: &str) -> Color {
+        self.commit_type_colors
+            .iter()
+            .find(|(k, _)| *k == kind)
+            .map_or(self.accent, |(_, c)| *c)
+    }
+
     pub fn with_remote_tint(&self) -> ThemePalette {
         let mut p = self.clone();
         p.content_bg = dim_color(p.content_bg, 4);
@@ -68,6 +86,20 @@ fn dim_color(c: Color, amount: u8) -> Color {
     }
 }
 
+fn darker_named(c: Color) -> Color {
+    match c {
+        Color::LightCyan => Color::Cyan,
+        Color::LightYellow => Color::Yellow,
+        Color::LightGreen => Color::Green,
+        Color::LightRed => Color::Red,
+        Color::LightBlue => Color::Blue,
+        Color::LightMagenta => Color::Magenta,
+        Color::White => Color::Gray,
+        Color::Gray => Color::DarkGray,
+        other => other,
+    }
+}
+
 pub fn palette_for_theme(name: Option<&str>) -> ThemePalette {
     match name.unwrap_or("Arachne Purple") {
         "Midnight" => midnight(),
@@ -108,6 +140,15 @@ static PURPLE_BRANCHES: &[Color] = &[
 
 static PURPLE_TRUNKS: &[Color] = &[Color::LightCyan, Color::LightYellow, Color::LightGreen];
 
+static PURPLE_TYPES: &[(&str, Color)] = &[
+    ("feat", Color::Green),
+    ("fix", Color::LightRed),
+    ("chore", Color::Rgb(100, 100, 120)),
+    ("revert", Color::Magenta),
+    ("docs", Color::Cyan),
+    ("perf", Color::Yellow),
+];
+
 fn arachne_purple() -> ThemePalette {
     ThemePalette {
         app_bg: Color::Rgb(22, 22, 34),
@@ -139,9 +180,12 @@ fn arachne_purple() -> ThemePalette {
         fork_dim: Color::DarkGray,
         error_fg: Color::LightRed,
         warn_fg: Color::Yellow,
+        diff_add: Color::Green,
+        diff_del: Color::LightRed,
 
         branch_colors: PURPLE_BRANCHES,
         trunk_colors: PURPLE_TRUNKS,
+        commit_type_colors: PURPLE_TYPES,
     }
 }
 
@@ -162,6 +206,15 @@ static MIDNIGHT_TRUNKS: &[Color] = &[
     Color::Rgb(140, 240, 200),
 ];
 
+static MIDNIGHT_TYPES: &[(&str, Color)] = &[
+    ("feat", Color::Rgb(80, 200, 160)),
+    ("fix", Color::Rgb(220, 120, 120)),
+    ("chore", Color::Rgb(80, 95, 120)),
+    ("revert", Color::Rgb(180, 130, 220)),
+    ("docs", Color::Rgb(100, 180, 255)),
+    ("perf", Color::Rgb(200, 180, 100)),
+];
+
 fn midnight() -> ThemePalette {
     ThemePalette {
         app_bg: Color::Rgb(12, 16, 28),
@@ -193,9 +246,12 @@ fn midnight() -> ThemePalette {
         fork_dim: Color::DarkGray,
         error_fg: Color::LightRed,
         warn_fg: Color::Yellow,
+        diff_add: Color::Rgb(80, 200, 160),
+        diff_del: Color::Rgb(220, 120, 120),
 
         branch_colors: MIDNIGHT_BRANCHES,
         trunk_colors: MIDNIGHT_TRUNKS,
+        commit_type_colors: MIDNIGHT_TYPES,
     }
 }
 
@@ -216,6 +272,15 @@ static EMERALD_TRUNKS: &[Color] = &[
     Color::Rgb(100, 220, 220),
 ];
 
+static EMERALD_TYPES: &[(&str, Color)] = &[
+    ("feat", Color::Rgb(100, 220, 140)),
+    ("fix", Color::Rgb(220, 130, 110)),
+    ("chore", Color::Rgb(80, 110, 90)),
+    ("revert", Color::Rgb(180, 140, 200)),
+    ("docs", Color::Rgb(80, 200, 200)),
+    ("perf", Color::Rgb(200, 200, 100)),
+];
+
 fn emerald() -> ThemePalette {
     ThemePalette {
         app_bg: Color::Rgb(14, 24, 18),
@@ -247,9 +312,12 @@ fn emerald() -> ThemePalette {
         fork_dim: Color::DarkGray,
         error_fg: Color::LightRed,
         warn_fg: Color::Yellow,
+        diff_add: Color::Rgb(100, 220, 140),
+        diff_del: Color::Rgb(220, 130, 110),
 
         branch_colors: EMERALD_BRANCHES,
         trunk_colors: EMERALD_TRUNKS,
+        commit_type_colors: EMERALD_TYPES,
     }
 }
 
@@ -270,6 +338,15 @@ static AMBER_TRUNKS: &[Color] = &[
     Color::Rgb(160, 220, 180),
 ];
 
+static AMBER_TYPES: &[(&str, Color)] = &[
+    ("feat", Color::Rgb(140, 200, 80)),
+    ("fix", Color::Rgb(220, 120, 100)),
+    ("chore", Color::Rgb(120, 105, 80)),
+    ("revert", Color::Rgb(200, 140, 180)),
+    ("docs", Color::Rgb(100, 190, 180)),
+    ("perf", Color::Rgb(220, 190, 80)),
+];
+
 fn amber() -> ThemePalette {
     ThemePalette {
         app_bg: Color::Rgb(26, 20, 14),
@@ -301,9 +378,12 @@ fn amber() -> ThemePalette {
         fork_dim: Color::DarkGray,
         error_fg: Color::LightRed,
         warn_fg: Color::Rgb(240, 200, 80),
+        diff_add: Color::Rgb(140, 200, 80),
+        diff_del: Color::Rgb(220, 120, 100),
 
         branch_colors: AMBER_BRANCHES,
         trunk_colors: AMBER_TRUNKS,
+        commit_type_colors: AMBER_TYPES,
     }
 }
 
@@ -324,6 +404,15 @@ static FROST_TRUNKS: &[Color] = &[
     Color::Rgb(160, 230, 200),
 ];
 
+static FROST_TYPES: &[(&str, Color)] = &[
+    ("feat", Color::Rgb(100, 210, 160)),
+    ("fix", Color::Rgb(210, 130, 130)),
+    ("chore", Color::Rgb(100, 115, 130)),
+    ("revert", Color::Rgb(190, 150, 210)),
+    ("docs", Color::Rgb(120, 190, 230)),
+    ("perf", Color::Rgb(210, 200, 120)),
+];
+
 fn frost() -> ThemePalette {
     ThemePalette {
         app_bg: Color::Rgb(28, 32, 38),
@@ -355,8 +444,11 @@ fn frost() -> ThemePalette {
         fork_dim: Color::DarkGray,
         error_fg: Color::LightRed,
         warn_fg: Color::Yellow,
+        diff_add: Color::Rgb(100, 210, 160),
+        diff_del: Color::Rgb(210, 130, 130),
 
         branch_colors: FROST_BRANCHES,
         trunk_colors: FROST_TRUNKS,
+        commit_type_colors: FROST_TYPES,
     }
 }
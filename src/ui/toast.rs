@@ -12,7 +12,6 @@ use unicode_width::UnicodeWidthStr;
 pub enum NotifyLevel {
     Error,
     Warn,
-    #[allow(dead_code)]
     Info,
 }
 
@@ -2,17 +2,17 @@ use crate::event::AppEvent;
 use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
 use std::path::Path;
 use tokio::sync::mpsc;
-use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
 pub struct FsWatcherHandle {
     pub _watcher: RecommendedWatcher,
-    pub debounce_task: JoinHandle<()>,
 }
 
 pub fn start_fs_watcher(
     repo_path: &Path,
     project_idx: usize,
     tx: mpsc::UnboundedSender<AppEvent>,
+    cancel: CancellationToken,
 ) -> notify::Result<FsWatcherHandle> {
     let git_dir = repo_path.join(".git");
 
@@ -38,16 +38,21 @@ pub fn start_fs_watcher(
         let _ = watcher.watch(&packed_refs, RecursiveMode::NonRecursive);
     }
 
-    let debounce_task = tokio::spawn(async move {
-        while raw_rx.recv().await.is_some() {
-            tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    // Exits on cancel or when the watcher (and with it raw_tx) is dropped
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                msg = raw_rx.recv() => if msg.is_none() { break },
+            }
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = tokio::time::sleep(std::time::Duration::from_millis(300)) => {}
+            }
             while raw_rx.try_recv().is_ok() {}
             let _ = tx.send(AppEvent::FsChanged(project_idx));
         }
     });
 
-    Ok(FsWatcherHandle {
-        _watcher: watcher,
-        debounce_task,
-    })
+    Ok(FsWatcherHandle { _watcher: watcher })
 }